@@ -13,6 +13,8 @@ use admission_control_proto::{
     },
     AdmissionControlStatus,
 };
+use crypto::hash::CryptoHash;
+use debug_interface::trace_span;
 use failure::prelude::*;
 use futures::future::Future;
 use futures03::executor::block_on;
@@ -111,6 +113,12 @@ where
             }
         };
 
+        let _span = trace_span!(
+            "admission_control::submit_transaction",
+            signed_txn.hash().as_ref(),
+            "sender": signed_txn.sender().short_str(),
+            "sequence_number": signed_txn.sequence_number(),
+        );
         let gas_cost = signed_txn.max_gas_amount();
         let validation_status = self
             .vm_validator
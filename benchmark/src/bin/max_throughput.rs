@@ -3,9 +3,11 @@ use benchmark::{
         create_benchmarker_from_opt, linear_search_max_throughput, try_start_metrics_server,
     },
     cli_opt::SearchOpt,
-    load_generator::PairwiseTransferTxnGenerator,
+    load_generator::{LoadGenerator, PairwiseTransferTxnGenerator},
+    workload::MixedWorkloadGenerator,
 };
 use logger::{self, prelude::*};
+use std::ops::DerefMut;
 
 /// During linear search, both submission rate and number of TXNs are increased.
 /// With 32 new accounts and 2 rounds, we add extra 2K TXNs as rate increases.
@@ -19,11 +21,16 @@ fn main() {
     try_start_metrics_server(&args.bench_opt);
     let mut bm = create_benchmarker_from_opt(&args.bench_opt);
     let mut faucet_account = bm.load_faucet_account(&args.bench_opt.faucet_key_file_path);
-    let mut generator = PairwiseTransferTxnGenerator::new();
+    let mut generator: Box<dyn LoadGenerator> = match &args.workload_config {
+        Some(workload_config) => Box::new(
+            MixedWorkloadGenerator::load(workload_config).expect("invalid workload config"),
+        ),
+        None => Box::new(PairwiseTransferTxnGenerator::new()),
+    };
     for _ in 0..args.num_searches {
         linear_search_max_throughput(
             &mut bm,
-            &mut generator,
+            generator.deref_mut(),
             &mut faucet_account,
             args.lower_bound,
             args.upper_bound,
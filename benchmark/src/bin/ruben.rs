@@ -28,6 +28,7 @@ use benchmark::{
     bin_utils::{create_benchmarker_from_opt, measure_throughput, try_start_metrics_server},
     cli_opt::{RubenOpt, TransactionPattern},
     load_generator::{LoadGenerator, PairwiseTransferTxnGenerator, RingTransferTxnGenerator},
+    workload::MixedWorkloadGenerator,
 };
 use logger::{self, prelude::*};
 use std::ops::DerefMut;
@@ -40,9 +41,12 @@ fn main() {
     try_start_metrics_server(&args.bench_opt);
     let mut bm = create_benchmarker_from_opt(&args.bench_opt);
     let mut faucet_account = bm.load_faucet_account(&args.bench_opt.faucet_key_file_path);
-    let mut generator: Box<dyn LoadGenerator> = match args.txn_pattern {
-        TransactionPattern::Ring => Box::new(RingTransferTxnGenerator::new()),
-        TransactionPattern::Pairwise => Box::new(PairwiseTransferTxnGenerator::new()),
+    let mut generator: Box<dyn LoadGenerator> = match (&args.workload_config, &args.txn_pattern) {
+        (Some(workload_config), _) => Box::new(
+            MixedWorkloadGenerator::load(workload_config).expect("invalid workload config"),
+        ),
+        (None, TransactionPattern::Ring) => Box::new(RingTransferTxnGenerator::new()),
+        (None, TransactionPattern::Pairwise) => Box::new(PairwiseTransferTxnGenerator::new()),
     };
     measure_throughput(
         &mut bm,
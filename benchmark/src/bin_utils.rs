@@ -113,6 +113,7 @@ pub fn measure_throughput<T: LoadGenerator + ?Sized>(
             1, /* record result epoch by epoch */
         );
         throughput_seq.push((result.req_throughput(), result.txn_throughput()));
+        generator.report(&result);
         results.push(result);
     }
     info!(
@@ -136,6 +137,7 @@ fn run_benchmarker_at_const_rate<T: LoadGenerator + ?Sized>(
 ) -> BenchSummary {
     let (mut total_submitted, mut total_accepted, mut total_committed) = (0, 0, 0);
     let (mut total_submit_duration, mut total_wait_duration) = (0, 0);
+    let mut total_txn_outcomes = vec![];
     for _ in 0..num_epochs {
         let mut txn_reqs = vec![];
         let now = time::Instant::now();
@@ -155,6 +157,7 @@ fn run_benchmarker_at_const_rate<T: LoadGenerator + ?Sized>(
         total_committed += result.num_committed;
         total_submit_duration += result.submit_duration_ms;
         total_wait_duration += result.wait_duration_ms;
+        total_txn_outcomes.extend(result.txn_outcomes);
     }
     BenchSummary {
         num_submitted: total_submitted,
@@ -162,6 +165,7 @@ fn run_benchmarker_at_const_rate<T: LoadGenerator + ?Sized>(
         num_committed: total_committed,
         submit_duration_ms: total_submit_duration,
         wait_duration_ms: total_wait_duration,
+        txn_outcomes: total_txn_outcomes,
     }
 }
 
@@ -208,6 +212,7 @@ pub fn linear_search_max_throughput<T: LoadGenerator + ?Sized>(
             "#submitted = {}, #committed = {}, avg REQ/TXN throughput = {:.2}/{:.2}.",
             num_submitted, num_committed, req_throughput, txn_throughput
         );
+        generator.report(&result);
         let commit_ratio = num_committed as f64 / num_submitted as f64;
         info!(
             "Commit ratio at submit rate {} per client is {:.4}.",
@@ -88,6 +88,10 @@ pub struct RubenOpt {
         default_value = "Ring"
     )]
     pub txn_pattern: TransactionPattern,
+    /// TOML file describing a mixed workload to play instead of txn_pattern,
+    /// see the workload module.
+    #[structopt(short = "w", long = "workload_config")]
+    pub workload_config: Option<String>,
 }
 
 /// CLI options for linear search max throughput.
@@ -116,6 +120,10 @@ pub struct SearchOpt {
     /// How many times to repeat the same linear search. Each time with new accounts/TXNs.
     #[structopt(short = "b", long = "num_searches", default_value = "10")]
     pub num_searches: u64,
+    /// TOML file describing a mixed workload to play instead of pairwise transfers,
+    /// see the workload module.
+    #[structopt(short = "w", long = "workload_config")]
+    pub workload_config: Option<String>,
 }
 
 /// Helper that checks if address is valid, and converts unspecified address to localhost.
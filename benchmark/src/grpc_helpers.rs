@@ -59,37 +59,29 @@ pub fn divide_items<T>(items: &[T], num_chunks: usize) -> Chunks<T> {
 /// ---------------------------------------------------------- ///
 
 /// By checking 1) ac status, 2) vm status, and 3) mempool status, decide whether the reponse
-/// from AC is accepted. If not, classify what the error type is.
-fn check_ac_response(resp: &ProtoSubmitTransactionResponse) -> bool {
-    if resp.has_ac_status() {
+/// from AC is accepted. If not, classify what the error type is and return it as the reason of
+/// the rejection, e.g. `mempool.MempoolIsFull`.
+fn check_ac_response(resp: &ProtoSubmitTransactionResponse) -> std::result::Result<(), String> {
+    let reason = if resp.has_ac_status() {
         let status = resp.get_ac_status().get_code();
         if status == AdmissionControlStatusCode::Accepted {
             OP_COUNTER.inc("submit_txns.success");
-            true
-        } else {
-            OP_COUNTER.inc(&format!("submit_txns.failure.ac.{:?}", status));
-            debug!("Request rejected by AC: {:?}", resp);
-            false
+            return Ok(());
         }
+        debug!("Request rejected by AC: {:?}", resp);
+        format!("ac.{:?}", status)
     } else if resp.has_vm_status() {
-        OP_COUNTER.inc(&format!(
-            "submit_txns.failure.vm.{:?}",
-            resp.get_vm_status()
-        ));
         debug!("Request causes error on VM: {:?}", resp);
-        false
+        format!("vm.{:?}", resp.get_vm_status())
     } else if resp.has_mempool_status() {
-        OP_COUNTER.inc(&format!(
-            "submit_txns.failure.mempool.{:?}",
-            resp.get_mempool_status().get_code()
-        ));
         debug!("Request causes error on mempool: {:?}", resp);
-        false
+        format!("mempool.{:?}", resp.get_mempool_status().get_code())
     } else {
-        OP_COUNTER.inc("submit_txns.failure.Unknown");
         debug!("Request rejected by AC for unknown error: {:?}", resp);
-        false
-    }
+        "Unknown".to_string()
+    };
+    OP_COUNTER.inc(&format!("submit_txns.failure.{}", reason));
+    Err(reason)
 }
 
 /// Process read requests' responses in a separate thread.
@@ -119,50 +111,85 @@ fn wait_read_requests<
     });
 }
 
-/// Wait and exam responses from AC and return only accepted responses.
-/// TODO: only return #accepted TXNs since main thread only used length of the current ret value.
+/// Result of the submission of a TXN to AC.
+#[derive(Clone, Debug)]
+pub struct SubmittedTxn {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    /// When the TXN was sent to AC.
+    pub submitted_at: time::Instant,
+    /// Why the TXN was not accepted, e.g. `ac.RejectedWriteRequest` or `grpc.RpcFailure(..)`,
+    /// the same as the suffix of the `submit_txns.failure` counters. None if accepted.
+    pub rejection: Option<String>,
+}
+
+impl SubmittedTxn {
+    fn new(sender: AccountAddress, sequence_number: u64) -> Self {
+        SubmittedTxn {
+            sender,
+            sequence_number,
+            submitted_at: time::Instant::now(),
+            rejection: None,
+        }
+    }
+}
+
+/// Wait and exam responses from AC, and return the submitted TXNs with their rejection reasons.
 fn wait_write_requests(
-    write_futures: Vec<impl Future<Item = ProtoSubmitTransactionResponse, Error = Error>>,
-) -> Vec<ProtoSubmitTransactionResponse> {
+    write_futures: Vec<(
+        SubmittedTxn,
+        impl Future<Item = ProtoSubmitTransactionResponse, Error = Error>,
+    )>,
+) -> Vec<SubmittedTxn> {
+    // Keep each TXN with the response to its submission.
+    let write_futures = write_futures.into_iter().map(|(txn, future)| {
+        future.then(move |result| -> std::result::Result<_, ()> { Ok((txn, result)) })
+    });
     stream::futures_unordered(write_futures)
         .wait()
-        .filter_map(|future_result| match future_result {
-            Ok(proto_resp) => {
-                if check_ac_response(&proto_resp) {
-                    Some(proto_resp)
-                } else {
-                    None
+        .map(|future_result| {
+            let (mut txn, result) = future_result.expect("Wrapped future never fails");
+            txn.rejection = match result {
+                Ok(proto_resp) => check_ac_response(&proto_resp).err(),
+                Err(e) => {
+                    OP_COUNTER.inc(&format!("submit_txns.failure.grpc.{:?}", e));
+                    debug!("Failed to receive gRPC response: {:?}", e);
+                    Some(format!("grpc.{:?}", e))
                 }
-            }
-            Err(e) => {
-                OP_COUNTER.inc(&format!("submit_txns.failure.grpc.{:?}", e));
-                debug!("Failed to receive gRPC response: {:?}", e);
-                None
-            }
+            };
+            txn
         })
         .collect()
 }
 
 /// Send requests using specified rate to AC async,
 /// wait for and check the responses (currently only for write requests).
-/// Return only the responses of accepted TXNs.
-/// Ignore but count both gRPC-failed submissions and AC-rejected requests.
+/// Return all the submitted TXNs, along with why they were not accepted if so.
+/// Both gRPC-failed submissions and AC-rejected requests are counted.
 pub fn submit_and_wait_requests(
     client: &AdmissionControlClient,
     requests: Vec<Request>,
     submit_rate: u64,
-) -> Vec<ProtoSubmitTransactionResponse> {
+) -> Vec<SubmittedTxn> {
     let mut read_futures = vec![];
     let mut write_futures = vec![];
+    let mut submitted_txns = vec![];
     for request in ConstantRate::new(submit_rate, requests.into_iter()) {
         match request {
-            Request::WriteRequest(txn_req) => {
+            Request::WriteRequest {
+                sender,
+                sequence_number,
+                request: txn_req,
+            } => {
+                let mut txn = SubmittedTxn::new(sender, sequence_number);
                 match client.submit_transaction_async_opt(&txn_req, get_default_grpc_call_option())
                 {
-                    Ok(future) => write_futures.push(future),
+                    Ok(future) => write_futures.push((txn, future)),
                     Err(e) => {
                         OP_COUNTER.inc(&format!("submit_txns.failure.grpc.{:?}", e));
                         debug!("Failed to send gRPC request: {:?}", e);
+                        txn.rejection = Some(format!("grpc.{:?}", e));
+                        submitted_txns.push(txn);
                     }
                 }
             }
@@ -182,8 +209,9 @@ pub fn submit_and_wait_requests(
     }
     // Spawn thread for read requests first and main thread won't join/blocked by this thread.
     wait_read_requests(read_futures);
-    // Wait all the write futures unorderedly, then pick only accepted responses.
-    wait_write_requests(write_futures)
+    // Wait all the write futures unorderedly.
+    submitted_txns.extend(wait_write_requests(write_futures));
+    submitted_txns
 }
 
 /// ------------------------------------------------------------ ///
@@ -272,14 +300,21 @@ pub fn get_account_states(
     states
 }
 
+/// The increasing sequence numbers synchronized for an account, each with when it was first
+/// observed. A TXN is committed at most when the first sequence number above its own one is.
+pub type CommitProgress = Vec<(u64, time::Instant)>;
+
 /// For each sender account, synchronize its persisted sequence number from validator.
 /// When this sync sequence number equals the account's local sequence number,
 /// all its transactions are committed. Timeout if such condition is never met for all senders.
-/// Return sender accounts' most recent persisted sequence numbers.
+/// Return sender accounts' most recent persisted sequence numbers, and how they progressed.
 pub fn sync_account_sequence_number(
     client: &AdmissionControlClient,
     senders_and_sequence_numbers: &[(AccountAddress, u64)],
-) -> HashMap<AccountAddress, u64> {
+) -> (
+    HashMap<AccountAddress, u64>,
+    HashMap<AccountAddress, CommitProgress>,
+) {
     // Invariants for the keys in targets (T), unfinished (U) and finished (F):
     // (1) T = U union F, and (2) U and F are disjoint.
     let targets: HashMap<AccountAddress, u64> =
@@ -289,13 +324,24 @@ pub fn sync_account_sequence_number(
         .map(|(sender, _)| (*sender, 0))
         .collect();
     let mut finished = HashMap::new();
-    // We start to wait when all TXNs are submitted.
+    let mut progress: HashMap<AccountAddress, CommitProgress> = HashMap::new();
+    // We start to wait once all TXNs are generated, before they are submitted.
     // So the longest reasonable waiting duration is the duration until the last TXN expired.
     let start_wait = time::Instant::now();
     while start_wait.elapsed().as_secs() < TXN_EXPIRATION as u64 {
         let unfinished_addresses: Vec<_> = unfinished.keys().copied().collect();
         let states = get_account_states(client, &unfinished_addresses);
+        let now = time::Instant::now();
         for (address, (sequence_number, _status)) in states.iter() {
+            let observations = progress.entry(*address).or_default();
+            if observations
+                .last()
+                .map_or(true, |(last_sequence_number, _)| {
+                    last_sequence_number < sequence_number
+                })
+            {
+                observations.push((*sequence_number, now));
+            }
             if let Some(target) = targets.get(address) {
                 if sequence_number == target {
                     debug!("All TXNs from {:?} are committed", address);
@@ -320,7 +366,7 @@ pub fn sync_account_sequence_number(
     }
     // Merging won't have conflict because F and U are disjoint.
     finished.extend(unfinished);
-    finished
+    (finished, progress)
 }
 
 #[cfg(test)]
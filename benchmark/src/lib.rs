@@ -1,10 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use admission_control_proto::proto::{
-    admission_control::SubmitTransactionResponse as ProtoSubmitTransactionResponse,
-    admission_control_grpc::AdmissionControlClient,
-};
+use admission_control_proto::proto::admission_control_grpc::AdmissionControlClient;
 use client::{AccountData, AccountStatus};
 use crypto::{ed25519::*, test_utils::KeyPair};
 use generate_keypair::load_key_from_file;
@@ -20,12 +17,20 @@ pub mod cli_opt;
 pub mod grpc_helpers;
 pub mod load_generator;
 pub mod submit_rate;
+pub mod workload;
 
 use grpc_helpers::{
     divide_items, get_account_states, submit_and_wait_requests, sync_account_sequence_number,
+    CommitProgress, SubmittedTxn,
 };
 use load_generator::Request;
 
+/// Thread synchronizing a chunk of senders' sequence numbers, see `sync_account_sequence_number`.
+type WaitThread = thread::JoinHandle<(
+    HashMap<AccountAddress, u64>,
+    HashMap<AccountAddress, CommitProgress>,
+)>;
+
 lazy_static! {
     pub static ref OP_COUNTER: OpMetrics = OpMetrics::new_and_registered("benchmark");
 }
@@ -61,6 +66,19 @@ pub struct Benchmarker {
     submit_rate: u64,
 }
 
+/// What happened to a TXN played by Benchmarker.
+#[derive(Clone, Debug)]
+pub struct TxnOutcome {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    /// Why the TXN was not accepted by AC, e.g. `mempool.MempoolIsFull`. None if accepted.
+    pub rejection: Option<String>,
+    /// Duration from the submission of the TXN until its commit was observed, None if it was not
+    /// committed in time. Commits are polled concurrently with the submission, so this exceeds
+    /// the actual latency by at most the time between two polls of the sender.
+    pub latency: Option<time::Duration>,
+}
+
 /// Summary of the results of playing TXNs with Benchmarker.
 #[derive(Debug)]
 pub struct BenchSummary {
@@ -74,6 +92,8 @@ pub struct BenchSummary {
     submit_duration_ms: u128,
     /// Duration to wait TXNs committed.
     wait_duration_ms: u128,
+    /// Outcomes of the submitted TXNs.
+    txn_outcomes: Vec<TxnOutcome>,
 }
 
 impl BenchSummary {
@@ -111,6 +131,10 @@ impl BenchSummary {
     pub fn has_uncommitted_txns(&self) -> bool {
         self.num_accepted - self.num_committed > 0
     }
+
+    pub fn txn_outcomes(&self) -> &[TxnOutcome] {
+        &self.txn_outcomes
+    }
 }
 
 impl Benchmarker {
@@ -215,8 +239,12 @@ impl Benchmarker {
 
     /// Send both TXNs and read requests to AC async, wait for TXNs' responses from AC.
     /// Read requests are handled in a separate thread.
-    /// Return #accepted TXNs and submission duration.
-    pub fn submit_requests(&mut self, requests: &[Request], submit_rate: u64) -> (usize, u128) {
+    /// Return the submitted TXNs and submission duration.
+    pub fn submit_requests(
+        &mut self,
+        requests: &[Request],
+        submit_rate: u64,
+    ) -> (Vec<SubmittedTxn>, u128) {
         let req_chunks = divide_items(requests, self.clients.len());
         let now = time::Instant::now();
         // Zip req_chunks with clients: when first iter returns none,
@@ -229,9 +257,9 @@ impl Benchmarker {
                 let stagger_range_ms = self.stagger_range_ms;
                 // Spawn threads with corresponding client.
                 thread::spawn(
-                    // Dispatch requests to client and submit, return the list of responses
-                    // that are accepted by AC, and how long the client is delayed.
-                    move || -> (Vec<ProtoSubmitTransactionResponse>, u16) {
+                    // Dispatch requests to client and submit, return the list of submitted
+                    // TXNs, and how long the client is delayed.
+                    move || -> (Vec<SubmittedTxn>, u16) {
                         let delay_duration_ms = Self::stagger_client(stagger_range_ms);
                         debug!(
                             "Dispatch {} requests to client after staggered {} ms.",
@@ -246,13 +274,12 @@ impl Benchmarker {
                 )
             })
             .collect();
-        // Wait for threads and gather reponses.
-        // TODO: Group response by error type and report staticstics.
-        let mut txn_resps: Vec<ProtoSubmitTransactionResponse> = vec![];
+        // Wait for threads and gather submitted TXNs.
+        let mut submitted_txns: Vec<SubmittedTxn> = vec![];
         let mut delay_duration_ms = self.stagger_range_ms;
         for child in children {
             let resp_tuple = child.join().expect("failed to join a request thread");
-            txn_resps.extend(resp_tuple.0.into_iter());
+            submitted_txns.extend(resp_tuple.0.into_iter());
             // Start counting time as soon as the first client starts to submit requests.
             delay_duration_ms = std::cmp::min(delay_duration_ms, resp_tuple.1);
         }
@@ -262,24 +289,43 @@ impl Benchmarker {
             request_duration_ms -= u128::from(delay_duration_ms);
         }
         info!(
-            "Submitted and accepted {} TXNs within {} ms.",
-            txn_resps.len(),
+            "Submitted {} TXNs and {} accepted within {} ms.",
+            submitted_txns.len(),
+            submitted_txns
+                .iter()
+                .filter(|txn| txn.rejection.is_none())
+                .count(),
             request_duration_ms,
         );
-        (txn_resps.len(), request_duration_ms)
+        (submitted_txns, request_duration_ms)
     }
 
     /// Wait for accepted TXNs to commit or time out: for any account, if its sequence number
     /// (bumpped during TXN generation) equals the one synchronized from validator,
     /// denoted as sync sequence number, then all its TXNs are committed.
-    /// Return senders' most up-to-date sync sequence numbers and how long we have waited.
+    /// Return senders' most up-to-date sync sequence numbers, how they progressed while waiting,
+    /// and how long we have waited.
     pub fn wait_txns_committed(
         &self,
         senders: &[AccountData],
-    ) -> (HashMap<AccountAddress, u64>, u128) {
-        let account_chunks = divide_items(senders, self.clients.len());
+    ) -> (
+        HashMap<AccountAddress, u64>,
+        HashMap<AccountAddress, CommitProgress>,
+        u128,
+    ) {
         let now = time::Instant::now();
-        let children: Vec<thread::JoinHandle<HashMap<_, _>>> = account_chunks
+        let (sequence_numbers, progress) =
+            Self::join_wait_threads(self.spawn_wait_threads(senders));
+        let wait_duration_ms = now.elapsed().as_millis();
+        info!("Waited for TXNs for {} ms", wait_duration_ms);
+        (sequence_numbers, progress, wait_duration_ms)
+    }
+
+    /// Spawn the threads that synchronize senders' sequence numbers from validator until their
+    /// TXNs are committed or time out, one per chunk of senders.
+    fn spawn_wait_threads(&self, senders: &[AccountData]) -> Vec<WaitThread> {
+        let account_chunks = divide_items(senders, self.clients.len());
+        account_chunks
             .zip(self.clients.iter().cycle())
             .map(|(chunk, client)| {
                 let local_chunk: Vec<(AccountAddress, u64)> = chunk
@@ -291,19 +337,32 @@ impl Benchmarker {
                     "Dispatch a chunk of {} accounts to client.",
                     local_chunk.len()
                 );
-                thread::spawn(move || -> HashMap<AccountAddress, u64> {
-                    sync_account_sequence_number(&local_client, &local_chunk)
-                })
+                thread::spawn(
+                    move || -> (
+                        HashMap<AccountAddress, u64>,
+                        HashMap<AccountAddress, CommitProgress>,
+                    ) { sync_account_sequence_number(&local_client, &local_chunk) },
+                )
             })
-            .collect();
+            .collect()
+    }
+
+    /// Wait for the threads spawned by spawn_wait_threads and merge what they synchronized.
+    fn join_wait_threads(
+        children: Vec<WaitThread>,
+    ) -> (
+        HashMap<AccountAddress, u64>,
+        HashMap<AccountAddress, CommitProgress>,
+    ) {
         let mut sequence_numbers: HashMap<AccountAddress, u64> = HashMap::new();
+        let mut progress: HashMap<AccountAddress, CommitProgress> = HashMap::new();
         for child in children {
-            let sequence_number_chunk = child.join().expect("failed to join a wait thread");
+            let (sequence_number_chunk, progress_chunk) =
+                child.join().expect("failed to join a wait thread");
             sequence_numbers.extend(sequence_number_chunk);
+            progress.extend(progress_chunk);
         }
-        let wait_duration_ms = now.elapsed().as_millis();
-        info!("Waited for TXNs for {} ms", wait_duration_ms);
-        (sequence_numbers, wait_duration_ms)
+        (sequence_numbers, progress)
     }
 
     /// -------------------------------------------------- ///
@@ -379,18 +438,58 @@ impl Benchmarker {
         submit_rate: Option<u64>,
     ) -> BenchSummary {
         let rate = submit_rate.unwrap_or(self.submit_rate);
-        let (num_accepted, submit_duration_ms) = self.submit_requests(requests, rate);
-        let (sync_sequence_numbers, wait_duration_ms) = self.wait_txns_committed(senders);
+        // Poll sequence numbers while submitting, so that TXNs committed during the submission
+        // are observed when they commit rather than once the submission is over.
+        let wait_threads = self.spawn_wait_threads(senders);
+        let (submitted_txns, submit_duration_ms) = self.submit_requests(requests, rate);
+        let now = time::Instant::now();
+        let (sync_sequence_numbers, progress) = Self::join_wait_threads(wait_threads);
+        let wait_duration_ms = now.elapsed().as_millis();
+        info!("Waited for TXNs for {} ms", wait_duration_ms);
         let (num_committed, _) = self.check_txn_results(senders, &sync_sequence_numbers);
         BenchSummary {
             num_submitted: requests.len(),
-            num_accepted,
+            num_accepted: submitted_txns
+                .iter()
+                .filter(|txn| txn.rejection.is_none())
+                .count(),
             num_committed,
             submit_duration_ms,
             wait_duration_ms,
+            txn_outcomes: Self::get_txn_outcomes(submitted_txns, &progress),
         }
     }
 
+    /// Match the submitted TXNs with the commit progress of their senders to find out when they
+    /// were committed, if ever.
+    fn get_txn_outcomes(
+        submitted_txns: Vec<SubmittedTxn>,
+        progress: &HashMap<AccountAddress, CommitProgress>,
+    ) -> Vec<TxnOutcome> {
+        submitted_txns
+            .into_iter()
+            .map(|txn| {
+                let latency = match txn.rejection {
+                    Some(_) => None,
+                    None => progress
+                        .get(&txn.sender)
+                        .and_then(|observations| {
+                            observations
+                                .iter()
+                                .find(|(sequence_number, _)| *sequence_number > txn.sequence_number)
+                        })
+                        .map(|(_, committed_at)| committed_at.duration_since(txn.submitted_at)),
+                };
+                TxnOutcome {
+                    sender: txn.sender,
+                    sequence_number: txn.sequence_number,
+                    rejection: txn.rejection,
+                    latency,
+                }
+            })
+            .collect()
+    }
+
     /// Similar to submit_requests_and_wait_txns_committed but with timing.
     /// How given TXNs are played and how time durations (submission, commit and running)
     /// are defined are illustrated as follows:
@@ -4,7 +4,7 @@
 /// ---------------------------------------------------------------------------------- ///
 ///  Definition of LoadGenerator trait and several example structs that implement it.  ///
 /// ---------------------------------------------------------------------------------- ///
-use crate::OP_COUNTER;
+use crate::{BenchSummary, OP_COUNTER};
 use admission_control_proto::proto::admission_control::SubmitTransactionRequest;
 use client::{AccountData, AccountStatus};
 use failure::prelude::*;
@@ -20,7 +20,7 @@ use types::{
 };
 
 /// Placeholder values used to generate offline TXNs.
-const MAX_GAS_AMOUNT: u64 = 1_000_000;
+pub(crate) const MAX_GAS_AMOUNT: u64 = 1_000_000;
 const GAS_UNIT_PRICE: u64 = 0;
 pub const TXN_EXPIRATION: i64 = 100;
 /// The amount of coins initially minted to all generated accounts.
@@ -33,8 +33,13 @@ const FREE_LUNCH: u64 = 1_000_000;
 /// Current Benchmarker only support submitting and verifying WriteRequest.
 #[derive(PartialEq, Clone)]
 pub enum Request {
-    // Both write and read requests are protobuf struct.
-    WriteRequest(SubmitTransactionRequest),
+    // Both write and read requests are protobuf struct. A write request also carries the sender
+    // and sequence number of its TXN, so that the TXN can be tracked without decoding it.
+    WriteRequest {
+        sender: AccountAddress,
+        sequence_number: u64,
+        request: SubmitTransactionRequest,
+    },
     ReadRequest(ProtoUpdateToLatestLedgerRequest),
 }
 
@@ -54,6 +59,9 @@ pub trait LoadGenerator {
     ) -> Vec<Request>;
     /// 3. Generate arbitrary read/write requests from subset of accounts from step 1.
     fn gen_requests(&self, accounts: &mut [AccountData]) -> Vec<Request>;
+    /// 4. Optionally report statistics specific to the generated requests, e.g. per kind of TXN,
+    ///    from the summary of playing them. Called once per measurement.
+    fn report(&self, _summary: &BenchSummary) {}
 }
 
 /// ------------------------------------------------------------ ///
@@ -85,10 +93,11 @@ pub fn gen_accounts_from_wallet(wallet: &mut WalletLibrary, num_accounts: u64) -
 /// ---------------------------------------------------------------------------------- ///
 
 /// Craft a generic signed transaction request.
-fn gen_submit_transaction_request<T: TransactionSigner>(
+pub(crate) fn gen_submit_transaction_request<T: TransactionSigner>(
     program: Program,
     sender_account: &mut AccountData,
     signer: &T,
+    max_gas_amount: u64,
 ) -> Result<Request> {
     // If generation fails here, sequence number will not be increased,
     // so it is fine to continue later generation.
@@ -97,7 +106,7 @@ fn gen_submit_transaction_request<T: TransactionSigner>(
         program,
         sender_account.address,
         sender_account.sequence_number,
-        MAX_GAS_AMOUNT,
+        max_gas_amount,
         GAS_UNIT_PRICE,
         TXN_EXPIRATION,
     )
@@ -107,9 +116,14 @@ fn gen_submit_transaction_request<T: TransactionSigner>(
     })?;
     let mut req = SubmitTransactionRequest::new();
     req.set_signed_txn(signed_txn.into_proto());
+    let request = Request::WriteRequest {
+        sender: sender_account.address,
+        sequence_number: sender_account.sequence_number,
+        request: req,
+    };
     sender_account.sequence_number += 1;
     OP_COUNTER.inc("create_txn_request.success");
-    Ok(Request::WriteRequest(req))
+    Ok(request)
 }
 
 /// Craft TXN that mints receiver with some libra coins.
@@ -123,18 +137,18 @@ fn gen_mint_txn_request(
         .as_ref()
         .expect("Failed to load keypair from faucet")
         .clone();
-    gen_submit_transaction_request(program, faucet_account, &signer)
+    gen_submit_transaction_request(program, faucet_account, &signer, MAX_GAS_AMOUNT)
 }
 
 /// Craft TXN that transfers coins from sender to receiver.
-fn gen_transfer_txn_request(
+pub(crate) fn gen_transfer_txn_request(
     sender: &mut AccountData,
     receiver: &AccountAddress,
     wallet: &WalletLibrary,
     num_coins: u64,
 ) -> Result<Request> {
     let program = vm_genesis::encode_transfer_program(&receiver, num_coins);
-    gen_submit_transaction_request(program, sender, wallet)
+    gen_submit_transaction_request(program, sender, wallet, MAX_GAS_AMOUNT)
 }
 
 /// For each account, generate a mint TXN with the valid faucet account.
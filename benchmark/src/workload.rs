@@ -0,0 +1,625 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+/// ------------------------------------------------------------------------------------ ///
+///  Mixed workloads: a LoadGenerator playing several profiles of TXNs described in TOML.  ///
+/// ------------------------------------------------------------------------------------ ///
+///
+/// A workload config lists profiles, each with the kind of TXNs it generates, how many of them
+/// per round and how their senders are picked. For example:
+///
+///     [[profiles]]
+///     name = "payments"
+///     accounts_share = 3
+///     txns_per_round = 64
+///     senders = { distribution = "zipf", exponent = 1.1 }
+///     kind = { type = "transfer", amount = 1 }
+///
+///     [[profiles]]
+///     name = "signups"
+///     txns_per_round = 200
+///     every_rounds = 5
+///     kind = { type = "create_account", initial_balance = 10 }
+///
+/// The generated accounts are split among the profiles according to their accounts_share, and
+/// every profile only sends TXNs from its own accounts, so that the results of the TXNs can be
+/// reported per profile. Besides the log, the reports are exported as the counters
+/// profile.{name}.submit_txns.failure.{reason} and the gauges
+/// profile.{name}.latency_p{50,90,99}_ms.
+use crate::{
+    load_generator::{
+        gen_accounts_from_wallet, gen_mint_txn_requests, gen_submit_transaction_request,
+        gen_transfer_txn_request, LoadGenerator, Request, MAX_GAS_AMOUNT,
+    },
+    BenchSummary, TxnOutcome, OP_COUNTER,
+};
+use client::AccountData;
+use failure::prelude::*;
+use libra_wallet::wallet_library::WalletLibrary;
+use logger::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{self, Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use types::{
+    account_address::AccountAddress,
+    transaction::{parse_as_transaction_argument, Program},
+};
+
+/// Profiles of the TXNs of a mixed workload.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct WorkloadConfig {
+    pub profiles: Vec<ProfileConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// Name of the profile in reports.
+    pub name: String,
+    /// Weight of the profile when splitting the generated accounts among profiles.
+    #[serde(default = "default_one")]
+    pub accounts_share: u64,
+    /// Number of TXNs generated in every round the profile is active.
+    pub txns_per_round: u64,
+    /// The profile is active every `every_rounds` rounds of requests generation, starting with
+    /// the first one, which allows bursts of TXNs.
+    #[serde(default = "default_one")]
+    pub every_rounds: u64,
+    /// How senders are picked among the accounts of the profile for each TXN.
+    #[serde(default)]
+    pub senders: SenderDistribution,
+    pub kind: ProfileKind,
+}
+
+fn default_one() -> u64 {
+    1
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum SenderDistribution {
+    Uniform,
+    /// The k-th account of the profile sends a share of TXNs proportional to 1 / k^exponent.
+    Zipf {
+        exponent: f64,
+    },
+}
+
+impl Default for SenderDistribution {
+    fn default() -> Self {
+        SenderDistribution::Uniform
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ProfileKind {
+    /// Transfers to receivers picked uniformly among all generated accounts.
+    Transfer { amount: u64 },
+    /// Creates a new account per TXN.
+    CreateAccount { initial_balance: u64 },
+    /// Transfers to the first `num_hot_accounts` generated accounts only, so that TXNs contend on
+    /// the same account resources.
+    HotAccount {
+        num_hot_accounts: usize,
+        amount: u64,
+    },
+    /// Publishes the module of a program compiled with `compiler --module`, as a JSON file.
+    /// Publishing the same module twice from an account fails.
+    PublishModule { program_path: PathBuf },
+    /// Runs a script compiled with `compiler`, as a JSON file, with the given arguments in the
+    /// format of the client, e.g. `b"0a0b"` or `42`, so heavy scripts can be given more gas.
+    /// Note that validators only accept custom scripts and modules if their VM publishing option
+    /// allows them.
+    Script {
+        program_path: PathBuf,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default = "default_max_gas_amount")]
+        max_gas_amount: u64,
+    },
+}
+
+fn default_max_gas_amount() -> u64 {
+    MAX_GAS_AMOUNT
+}
+
+impl WorkloadConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let config_string = fs::read_to_string(path)
+            .map_err(|e| format_err!("Failed to read workload config {:?}: {}", path, e))?;
+        Self::parse(&config_string)
+    }
+
+    pub fn parse(config_string: &str) -> Result<Self> {
+        let config: WorkloadConfig = toml::from_str(config_string)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(!self.profiles.is_empty(), "No profile in workload config.");
+        let mut names = HashSet::new();
+        for profile in &self.profiles {
+            ensure!(
+                names.insert(&profile.name),
+                "Duplicate profile name {}.",
+                profile.name
+            );
+            ensure!(
+                profile.accounts_share > 0 && profile.every_rounds > 0,
+                "Profile {}: accounts_share and every_rounds must be positive.",
+                profile.name
+            );
+            if let SenderDistribution::Zipf { exponent } = profile.senders {
+                ensure!(
+                    exponent.is_finite() && exponent >= 0.0,
+                    "Profile {}: invalid Zipf exponent {}.",
+                    profile.name,
+                    exponent
+                );
+            }
+            if let ProfileKind::HotAccount {
+                num_hot_accounts, ..
+            } = profile.kind
+            {
+                ensure!(
+                    num_hot_accounts > 0,
+                    "Profile {}: num_hot_accounts must be positive.",
+                    profile.name
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Picks the index of the sender of a TXN among the accounts of a profile.
+enum SenderSampler {
+    Uniform(usize),
+    /// Cumulative weights of the accounts.
+    Weighted(Vec<f64>),
+}
+
+impl SenderSampler {
+    fn new(distribution: &SenderDistribution, num_senders: usize) -> Self {
+        assert!(num_senders > 0);
+        match distribution {
+            SenderDistribution::Uniform => SenderSampler::Uniform(num_senders),
+            SenderDistribution::Zipf { exponent } => SenderSampler::Weighted(
+                (1..=num_senders)
+                    .scan(0.0, |total, rank| {
+                        *total += 1.0 / (rank as f64).powf(*exponent);
+                        Some(*total)
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> usize {
+        match self {
+            SenderSampler::Uniform(num_senders) => rng.gen_range(0, *num_senders),
+            SenderSampler::Weighted(cumulative_weights) => {
+                let total = *cumulative_weights.last().expect("At least one sender");
+                let x = rng.gen_range(0.0, total);
+                // The first account whose cumulative weight is above x.
+                let index = match cumulative_weights
+                    .binary_search_by(|weight| weight.partial_cmp(&x).expect("Finite weights"))
+                {
+                    Ok(index) => index + 1,
+                    Err(index) => index,
+                };
+                std::cmp::min(index, cumulative_weights.len() - 1)
+            }
+        }
+    }
+}
+
+/// A profile along with the program its TXNs run, if not a built-in one.
+struct Profile {
+    config: ProfileConfig,
+    program: Option<Program>,
+}
+
+impl Profile {
+    fn new(config: ProfileConfig) -> Result<Self> {
+        let read_program = |path: &Path| -> Result<Program> {
+            let program = serde_json::from_slice(&fs::read(path)?)
+                .map_err(|e| format_err!("Invalid program {:?}: {}", path, e))?;
+            Ok(program)
+        };
+        let program = match &config.kind {
+            ProfileKind::PublishModule { program_path } => Some(read_program(program_path)?),
+            ProfileKind::Script {
+                program_path, args, ..
+            } => {
+                let (script, _, modules) = read_program(program_path)?.into_inner();
+                let args = args
+                    .iter()
+                    .map(|arg| parse_as_transaction_argument(arg))
+                    .collect::<Result<Vec<_>>>()?;
+                Some(Program::new(script, modules, args))
+            }
+            _ => None,
+        };
+        Ok(Profile { config, program })
+    }
+}
+
+/// Generates the TXNs of the profiles of a WorkloadConfig.
+pub struct MixedWorkloadGenerator {
+    /// Use the WalletLibrary to generate accounts and sign TXNs.
+    wallet: WalletLibrary,
+    profiles: Vec<Profile>,
+    /// Index of the profile of each generated account.
+    account_profiles: HashMap<AccountAddress, usize>,
+    /// All generated accounts, in order of generation.
+    addresses: Vec<AccountAddress>,
+    /// Number of calls to gen_requests so far.
+    num_rounds: Cell<u64>,
+}
+
+impl MixedWorkloadGenerator {
+    pub fn new(config: WorkloadConfig) -> Result<Self> {
+        Ok(MixedWorkloadGenerator {
+            wallet: WalletLibrary::new(),
+            profiles: config
+                .profiles
+                .into_iter()
+                .map(Profile::new)
+                .collect::<Result<_>>()?,
+            account_profiles: HashMap::new(),
+            addresses: vec![],
+            num_rounds: Cell::new(0),
+        })
+    }
+
+    /// Create the generator of the workload described in the TOML file at path.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::new(WorkloadConfig::load(path)?)
+    }
+
+    /// Split accounts among profiles proportionally to their shares, interleaving them with
+    /// smooth weighted round-robin so that a few accounts are already split fairly.
+    fn assign_profiles(&mut self, accounts: &[AccountData]) {
+        let shares: Vec<i64> = self
+            .profiles
+            .iter()
+            .map(|profile| profile.config.accounts_share as i64)
+            .collect();
+        let total_share: i64 = shares.iter().sum();
+        let mut current_weights = vec![0i64; shares.len()];
+        for account in accounts {
+            for (weight, share) in current_weights.iter_mut().zip(shares.iter()) {
+                *weight += share;
+            }
+            let (index, _) = current_weights
+                .iter()
+                .enumerate()
+                .rev()
+                .max_by_key(|(_, weight)| **weight)
+                .expect("At least one profile");
+            current_weights[index] -= total_share;
+            self.account_profiles.insert(account.address, index);
+            self.addresses.push(account.address);
+        }
+    }
+
+    fn gen_profile_request(
+        &self,
+        profile: &Profile,
+        sender: &mut AccountData,
+        rng: &mut impl Rng,
+    ) -> Result<Request> {
+        match &profile.config.kind {
+            ProfileKind::Transfer { amount } => {
+                let receiver = &self.addresses[rng.gen_range(0, self.addresses.len())];
+                gen_transfer_txn_request(sender, receiver, &self.wallet, *amount)
+            }
+            ProfileKind::CreateAccount { initial_balance } => {
+                let program = vm_genesis::encode_create_account_program(
+                    &AccountAddress::random(),
+                    *initial_balance,
+                );
+                gen_submit_transaction_request(program, sender, &self.wallet, MAX_GAS_AMOUNT)
+            }
+            ProfileKind::HotAccount {
+                num_hot_accounts,
+                amount,
+            } => {
+                let num_hot_accounts = std::cmp::min(*num_hot_accounts, self.addresses.len());
+                let receiver = &self.addresses[rng.gen_range(0, num_hot_accounts)];
+                gen_transfer_txn_request(sender, receiver, &self.wallet, *amount)
+            }
+            ProfileKind::PublishModule { .. } => gen_submit_transaction_request(
+                profile.program.clone().expect("Program is loaded"),
+                sender,
+                &self.wallet,
+                MAX_GAS_AMOUNT,
+            ),
+            ProfileKind::Script { max_gas_amount, .. } => gen_submit_transaction_request(
+                profile.program.clone().expect("Program is loaded"),
+                sender,
+                &self.wallet,
+                *max_gas_amount,
+            ),
+        }
+    }
+
+    /// Break down the outcomes of the TXNs sent by the generated accounts per profile.
+    pub fn gen_reports(&self, txn_outcomes: &[TxnOutcome]) -> Vec<ProfileReport> {
+        let mut reports: Vec<ProfileReport> = self
+            .profiles
+            .iter()
+            .map(|profile| ProfileReport::new(&profile.config.name))
+            .collect();
+        let mut latencies = vec![vec![]; reports.len()];
+        for outcome in txn_outcomes {
+            let index = match self.account_profiles.get(&outcome.sender) {
+                Some(index) => *index,
+                // E.g. mint TXNs from the faucet account.
+                None => continue,
+            };
+            let report = &mut reports[index];
+            report.num_submitted += 1;
+            match &outcome.rejection {
+                Some(reason) => *report.rejections.entry(reason.clone()).or_insert(0) += 1,
+                None => report.num_accepted += 1,
+            }
+            if let Some(latency) = outcome.latency {
+                report.num_committed += 1;
+                latencies[index].push(latency);
+            }
+        }
+        for (report, mut latencies) in reports.iter_mut().zip(latencies.into_iter()) {
+            latencies.sort();
+            report.latency_percentiles = [0.5, 0.9, 0.99, 1.0]
+                .iter()
+                .filter_map(|percentile| get_percentile(&latencies, *percentile))
+                .collect();
+        }
+        reports
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn get_percentile(sorted_values: &[Duration], percentile: f64) -> Option<Duration> {
+    if sorted_values.is_empty() {
+        return None;
+    }
+    let rank = (percentile * sorted_values.len() as f64).ceil() as usize;
+    Some(sorted_values[std::cmp::max(rank, 1) - 1])
+}
+
+impl LoadGenerator for MixedWorkloadGenerator {
+    fn gen_accounts(&mut self, num_accounts: u64) -> Vec<AccountData> {
+        let accounts = gen_accounts_from_wallet(&mut self.wallet, num_accounts);
+        self.assign_profiles(&accounts);
+        accounts
+    }
+
+    fn gen_setup_requests(
+        &self,
+        faucet_account: &mut AccountData,
+        accounts: &mut [AccountData],
+    ) -> Vec<Request> {
+        gen_mint_txn_requests(faucet_account, accounts)
+    }
+
+    fn gen_requests(&self, accounts: &mut [AccountData]) -> Vec<Request> {
+        let round = self.num_rounds.get();
+        self.num_rounds.set(round + 1);
+        let mut rng = rand::thread_rng();
+        let mut requests = vec![];
+        for (index, profile) in self.profiles.iter().enumerate() {
+            if round % profile.config.every_rounds != 0 {
+                continue;
+            }
+            let senders: Vec<usize> = accounts
+                .iter()
+                .enumerate()
+                .filter(|(_, account)| self.account_profiles.get(&account.address) == Some(&index))
+                .map(|(position, _)| position)
+                .collect();
+            if senders.is_empty() {
+                continue;
+            }
+            let sampler = SenderSampler::new(&profile.config.senders, senders.len());
+            for _ in 0..profile.config.txns_per_round {
+                let sender = &mut accounts[senders[sampler.sample(&mut rng)]];
+                match self.gen_profile_request(profile, sender, &mut rng) {
+                    Ok(request) => requests.push(request),
+                    Err(e) => error!(
+                        "failed to generate {} TXN from {:?}: {:?}",
+                        profile.config.name, sender.address, e
+                    ),
+                }
+            }
+        }
+        requests
+    }
+
+    fn report(&self, summary: &BenchSummary) {
+        for report in self.gen_reports(summary.txn_outcomes()) {
+            report.update_metrics();
+            info!("{}", report);
+        }
+    }
+}
+
+/// Results of the TXNs of a profile.
+#[derive(Debug, Default, PartialEq)]
+pub struct ProfileReport {
+    pub name: String,
+    pub num_submitted: usize,
+    pub num_accepted: usize,
+    pub num_committed: usize,
+    /// Number of TXNs rejected for each reason, e.g. `mempool.MempoolIsFull`.
+    pub rejections: BTreeMap<String, usize>,
+    /// p50, p90, p99 and max latencies of committed TXNs, empty if none was committed.
+    pub latency_percentiles: Vec<Duration>,
+}
+
+impl ProfileReport {
+    fn new(name: &str) -> Self {
+        ProfileReport {
+            name: name.to_string(),
+            ..ProfileReport::default()
+        }
+    }
+
+    fn update_metrics(&self) {
+        for (reason, count) in &self.rejections {
+            OP_COUNTER.inc_by(
+                &format!("profile.{}.submit_txns.failure.{}", self.name, reason),
+                *count,
+            );
+        }
+        for (percentile, latency) in ["50", "90", "99"].iter().zip(&self.latency_percentiles) {
+            OP_COUNTER.set(
+                &format!("profile.{}.latency_p{}_ms", self.name, percentile),
+                latency.as_millis() as usize,
+            );
+        }
+    }
+}
+
+impl Display for ProfileReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Profile {}: #submitted = {}, #accepted = {}, #committed = {}",
+            self.name, self.num_submitted, self.num_accepted, self.num_committed
+        )?;
+        if let [p50, p90, p99, max] = self.latency_percentiles[..] {
+            write!(
+                f,
+                ", latency p50/p90/p99/max = {}/{}/{}/{} ms",
+                p50.as_millis(),
+                p90.as_millis(),
+                p99.as_millis(),
+                max.as_millis()
+            )?;
+        }
+        if !self.rejections.is_empty() {
+            write!(f, ", rejections = {:?}", self.rejections)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [[profiles]]
+        name = "payments"
+        accounts_share = 3
+        txns_per_round = 12
+        senders = { distribution = "zipf", exponent = 1.5 }
+        kind = { type = "transfer", amount = 1 }
+
+        [[profiles]]
+        name = "signups"
+        txns_per_round = 5
+        every_rounds = 2
+        kind = { type = "create_account", initial_balance = 10 }
+    "#;
+
+    #[test]
+    fn test_parse_workload_config() {
+        let config = WorkloadConfig::parse(CONFIG).unwrap();
+        assert_eq!(config.profiles.len(), 2);
+        assert_eq!(
+            config.profiles[0].senders,
+            SenderDistribution::Zipf { exponent: 1.5 }
+        );
+        assert_eq!(config.profiles[1].accounts_share, 1);
+        assert_eq!(config.profiles[1].senders, SenderDistribution::Uniform);
+        assert_eq!(
+            config.profiles[1].kind,
+            ProfileKind::CreateAccount {
+                initial_balance: 10
+            }
+        );
+
+        assert!(WorkloadConfig::parse("profiles = []").is_err());
+        assert!(WorkloadConfig::parse(&CONFIG.replace("signups", "payments")).is_err());
+        assert!(WorkloadConfig::parse(&CONFIG.replace("amount", "amonut")).is_err());
+    }
+
+    #[test]
+    fn test_zipf_sampler() {
+        let sampler = SenderSampler::new(&SenderDistribution::Zipf { exponent: 1.0 }, 4);
+        let mut rng = rand::thread_rng();
+        let mut counts = [0; 4];
+        for _ in 0..10_000 {
+            counts[sampler.sample(&mut rng)] += 1;
+        }
+        // Expected shares are 48%, 24%, 16% and 12%.
+        assert!(counts[0] > counts[1] && counts[1] > counts[3]);
+        assert!(counts[0] > 4_000 && counts[3] > 800);
+    }
+
+    #[test]
+    fn test_mixed_workload_generator() {
+        let mut generator =
+            MixedWorkloadGenerator::new(WorkloadConfig::parse(CONFIG).unwrap()).unwrap();
+        let mut accounts = generator.gen_accounts(8);
+        let num_accounts_per_profile = |index| {
+            generator
+                .account_profiles
+                .values()
+                .filter(|i| **i == index)
+                .count()
+        };
+        assert_eq!(num_accounts_per_profile(0), 6);
+        assert_eq!(num_accounts_per_profile(1), 2);
+
+        // The signups profile is only active every other round.
+        assert_eq!(generator.gen_requests(&mut accounts).len(), 17);
+        assert_eq!(generator.gen_requests(&mut accounts).len(), 12);
+        let num_txns: u64 = accounts.iter().map(|account| account.sequence_number).sum();
+        assert_eq!(num_txns, 29);
+
+        let outcome = |account: &AccountData, rejection: Option<&str>, latency_ms| TxnOutcome {
+            sender: account.address,
+            sequence_number: 0,
+            rejection: rejection.map(ToString::to_string),
+            latency: latency_ms.map(Duration::from_millis),
+        };
+        let payer = accounts
+            .iter()
+            .find(|account| generator.account_profiles[&account.address] == 0)
+            .unwrap();
+        let mut outcomes: Vec<_> = (1..=100)
+            .map(|latency_ms| outcome(payer, None, Some(latency_ms)))
+            .collect();
+        outcomes.push(outcome(payer, None, None));
+        outcomes.push(outcome(payer, Some("mempool.MempoolIsFull"), None));
+        let reports = generator.gen_reports(&outcomes);
+        assert_eq!(reports[0].num_submitted, 102);
+        assert_eq!(reports[0].num_accepted, 101);
+        assert_eq!(reports[0].num_committed, 100);
+        assert_eq!(reports[0].rejections["mempool.MempoolIsFull"], 1);
+        assert_eq!(
+            reports[0].latency_percentiles,
+            vec![50, 90, 99, 100]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(reports[1], ProfileReport::new("signups"));
+    }
+}
@@ -5,6 +5,7 @@ use crate::{commands::*, grpc_client::GRPCClient, AccountData, AccountStatus};
 use admission_control_proto::proto::admission_control::SubmitTransactionRequest;
 use config::{config::PersistableConfig, trusted_peers::ConsensusPeersConfig};
 use crypto::{ed25519::*, test_utils::KeyPair};
+use disassembler::disassembler::{disassemble_module, DisassemblerOptions};
 use failure::prelude::*;
 use futures::{future::Future, stream::Stream};
 use hyper;
@@ -34,17 +35,21 @@ use types::{
     access_path::AccessPath,
     account_address::{AccountAddress, ADDRESS_LENGTH},
     account_config::{
-        association_address, core_code_address, get_account_resource_or_default, AccountResource,
+        association_address, core_code_address, get_account_resource_or_default,
+        received_event_struct_tag, sent_event_struct_tag, AccountResource,
         ACCOUNT_RECEIVED_EVENT_PATH, ACCOUNT_SENT_EVENT_PATH,
     },
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
     contract_event::{ContractEvent, EventWithProof},
+    identifier::Identifier,
+    language_storage::ModuleId,
     transaction::{
         parse_as_transaction_argument, Program, RawTransaction, SignedTransaction, Version,
     },
     transaction_helpers::{create_signed_txn, create_unsigned_txn, TransactionSigner},
     validator_verifier::ValidatorVerifier,
 };
+use vm::{access::ModuleAccess, file_format::CompiledModule};
 
 const CLIENT_WALLET_MNEMONIC_FILE: &str = "client.mnemonic";
 const GAS_UNIT_PRICE: u64 = 0;
@@ -550,6 +555,30 @@ impl ClientProxy {
         Ok(Some(path))
     }
 
+    /// Print a module published under an account as Move IR
+    pub fn disassemble_module(&mut self, space_delim_strings: &[&str]) -> Result<()> {
+        let address = self.get_account_address_from_parameter(space_delim_strings[1])?;
+        let module_name = space_delim_strings[2];
+        let path =
+            AccessPath::code_access_path(&ModuleId::new(address, Identifier::new(module_name)?));
+        let blob = match self.client.get_account_blob(address)? {
+            (Some(blob), _) => blob,
+            (None, _) => bail!("No account exists at {}", address),
+        };
+        let map = BTreeMap::<Vec<u8>, Vec<u8>>::try_from(&blob)?;
+        let code = map.get(&path.path).ok_or_else(|| {
+            format_err!("Module {} is not published under {}", module_name, address)
+        })?;
+
+        let module = CompiledModule::deserialize(code)
+            .map_err(|err| format_err!("Unable to deserialize module: {:?}", err))?;
+        print!(
+            "{}",
+            disassemble_module(&module, &DisassemblerOptions::default())?
+        );
+        Ok(())
+    }
+
     /// Submit a transaction to the network given the unsigned raw transaction, sender public key
     /// and signature
     pub fn submit_signed_transaction(
@@ -593,13 +622,40 @@ impl ClientProxy {
         self.submit_program(space_delim_strings, program)
     }
 
+    /// Publish the modules of a bundle built by `compiler --package`, one transaction per module.
+    /// Returns the number of modules published.
+    pub fn publish_bundle(&mut self, space_delim_strings: &[&str]) -> Result<usize> {
+        let bundle: Vec<Program> = serde_json::from_slice(&fs::read(space_delim_strings[2])?)?;
+        // Modules can only be published under the address of the sender, check it before
+        // submitting anything so that the bundle is not left partially published.
+        let sender_address = self.get_account_address_from_parameter(space_delim_strings[1])?;
+        for module in bundle.iter().flat_map(|program| program.modules()) {
+            let module_id = CompiledModule::deserialize(module)
+                .map_err(|err| format_err!("Invalid module in bundle: {:?}", err))?
+                .self_id();
+            ensure!(
+                *module_id.address() == sender_address,
+                "Module {} is published under {}, which is not the sender {}",
+                module_id.name(),
+                module_id.address(),
+                sender_address
+            );
+        }
+        let count = bundle.len();
+        for (idx, program) in bundle.into_iter().enumerate() {
+            self.submit_program(space_delim_strings, program)
+                .map_err(|err| format_err!("Failed to publish module {}: {}", idx, err))?;
+        }
+        Ok(count)
+    }
+
     /// Execute custom script
     pub fn execute_script(&mut self, space_delim_strings: &[&str]) -> Result<()> {
         let program: Program = serde_json::from_slice(&fs::read(space_delim_strings[2])?)?;
-        let arguments: Vec<_> = space_delim_strings[3..]
+        let arguments = space_delim_strings[3..]
             .iter()
-            .filter_map(|arg| parse_as_transaction_argument(arg).ok())
-            .collect();
+            .map(|arg| parse_as_transaction_argument(arg))
+            .collect::<Result<Vec<_>>>()?;
         let (script, _, modules) = program.into_inner();
         self.submit_program(
             space_delim_strings,
@@ -758,6 +814,52 @@ impl ClientProxy {
             .get_events_by_access_path(access_path, start_seq_number, ascending, limit)
     }
 
+    /// Get events of the given type (sent|received) emitted by any account, starting with the
+    /// event at the given index of the transaction at the given version.
+    pub fn get_events_by_type(
+        &mut self,
+        space_delim_strings: &[&str],
+    ) -> Result<Vec<EventWithProof>> {
+        ensure!(
+            space_delim_strings.len() == 5,
+            "Invalid number of arguments to get events by type"
+        );
+        let event_type = match space_delim_strings[1] {
+            "sent" => sent_event_struct_tag(),
+            "received" => received_event_struct_tag(),
+            _ => bail!(
+                "Unknown event type: {:?}, only sent and received are supported",
+                space_delim_strings[1]
+            ),
+        };
+        let start_version = space_delim_strings[2].parse::<u64>().map_err(|error| {
+            format_parse_data_error(
+                "start_version",
+                InputType::UnsignedInt,
+                space_delim_strings[2],
+                error,
+            )
+        })?;
+        let start_event_index = space_delim_strings[3].parse::<u64>().map_err(|error| {
+            format_parse_data_error(
+                "start_event_index",
+                InputType::UnsignedInt,
+                space_delim_strings[3],
+                error,
+            )
+        })?;
+        let limit = space_delim_strings[4].parse::<u64>().map_err(|error| {
+            format_parse_data_error(
+                "limit",
+                InputType::UnsignedInt,
+                space_delim_strings[4],
+                error,
+            )
+        })?;
+        self.client
+            .get_events_by_type(event_type, start_version, start_event_index, limit)
+    }
+
     /// Write mnemonic recover to the file specified.
     pub fn write_recovery(&self, space_delim_strings: &[&str]) -> Result<()> {
         ensure!(
@@ -17,7 +17,9 @@ impl Command for DevCommand {
         let commands: Vec<Box<dyn Command>> = vec![
             Box::new(DevCommandCompile {}),
             Box::new(DevCommandPublish {}),
+            Box::new(DevCommandPublishBundle {}),
             Box::new(DevCommandExecute {}),
+            Box::new(DevCommandDisassemble {}),
         ];
         subcommand_execute(&params[0], commands, client, &params[1..]);
     }
@@ -77,6 +79,34 @@ impl Command for DevCommandPublish {
     }
 }
 
+/// Sub command to publish a bundle of move modules built from a package
+pub struct DevCommandPublishBundle {}
+
+impl Command for DevCommandPublishBundle {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["publish_bundle", "pb"]
+    }
+
+    fn get_params_help(&self) -> &'static str {
+        "<sender_account_address>|<sender_account_ref_id> <bundle_path>"
+    }
+
+    fn get_description(&self) -> &'static str {
+        "Publish the modules of a package bundle on-chain, in order"
+    }
+
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        if params.len() != 3 {
+            println!("Invalid number of arguments to publish bundle");
+            return;
+        }
+        match client.publish_bundle(params) {
+            Ok(count) => println!("Successfully published {} modules", count),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
 /// Sub command to execute custom move script
 pub struct DevCommandExecute {}
 
@@ -86,7 +116,9 @@ impl Command for DevCommandExecute {
     }
 
     fn get_params_help(&self) -> &'static str {
-        "<sender_account_address>|<sender_account_ref_id> <compiled_module_path> [parameters]"
+        "<sender_account_address>|<sender_account_ref_id> <compiled_module_path> [parameters]. \
+         Parameters can be u64 (42), bool (true), address (0x1), bytearray (b\"cafe\") or \
+         vector<address> ([0x1,0x2])"
     }
 
     fn get_description(&self) -> &'static str {
@@ -104,3 +136,30 @@ impl Command for DevCommandExecute {
         }
     }
 }
+
+/// Sub command to print a published move module as IR
+pub struct DevCommandDisassemble {}
+
+impl Command for DevCommandDisassemble {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["disassemble", "d"]
+    }
+
+    fn get_params_help(&self) -> &'static str {
+        "<account_address>|<account_ref_id> <module_name>"
+    }
+
+    fn get_description(&self) -> &'static str {
+        "Print a module published on-chain as Move IR"
+    }
+
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        if params.len() != 3 {
+            println!("Invalid number of arguments to disassemble module");
+            return;
+        }
+        if let Err(e) = client.disassemble_module(params) {
+            println!("{}", e);
+        }
+    }
+}
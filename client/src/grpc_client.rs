@@ -18,6 +18,7 @@ use grpcio::{CallOption, ChannelBuilder, EnvBuilder};
 use logger::prelude::*;
 use proto_conv::{FromProto, IntoProto};
 use std::sync::Arc;
+use stdlib::error_codes::explain_vm_status;
 use types::{
     access_path::AccessPath,
     account_address::AccountAddress,
@@ -27,9 +28,10 @@ use types::{
     get_with_proof::{
         RequestItem, ResponseItem, UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse,
     },
+    language_storage::StructTag,
     transaction::{SignedTransaction, Version},
     validator_verifier::ValidatorVerifier,
-    vm_error::StatusCode,
+    vm_error::{StatusCode, VMStatus},
 };
 
 const MAX_GRPC_RETRY_COUNT: u64 = 1;
@@ -91,12 +93,15 @@ impl GRPCClient {
                     sender_account.sequence_number =
                         self.get_sequence_number(sender_account.address)?;
                     bail!(
-                        "Transaction failed with vm status: {:?}, please retry your transaction.",
-                        vm_error
+                        "Transaction failed with vm status: {}, please retry your transaction.",
+                        Self::format_vm_status(&vm_error)
                     );
                 }
             }
-            bail!("Transaction failed with vm status: {:?}", vm_error);
+            bail!(
+                "Transaction failed with vm status: {}",
+                Self::format_vm_status(&vm_error)
+            );
         } else if let Some(mempool_error) = completed_resp.mempool_error {
             bail!(
                 "Transaction failed with mempool status: {:?}",
@@ -111,6 +116,14 @@ impl GRPCClient {
         Ok(())
     }
 
+    /// Formats a VM status, explaining its abort code if it was raised by a stdlib module.
+    fn format_vm_status(vm_status: &VMStatus) -> String {
+        match explain_vm_status(vm_status) {
+            Some(explanation) => format!("{:?} ({})", vm_status, explanation),
+            None => format!("{:?}", vm_status),
+        }
+    }
+
     /// Async version of submit_transaction
     pub fn submit_transaction_async(
         &self,
@@ -290,8 +303,35 @@ impl GRPCClient {
         match value_with_proof {
             ResponseItem::GetEventsByEventAccessPath {
                 events_with_proof,
-                proof_of_latest_event,
-            } => Ok((events_with_proof, proof_of_latest_event)),
+                proof_of_event_handle,
+            } => Ok((events_with_proof, proof_of_event_handle)),
+            _ => bail!(
+                "Incorrect type of response returned: {:?}",
+                value_with_proof
+            ),
+        }
+    }
+
+    /// Get events of the given type emitted by any account from validator, starting with the
+    /// event at index `start_event_index` of the transaction at `start_version`.
+    pub fn get_events_by_type(
+        &self,
+        event_type: StructTag,
+        start_version: Version,
+        start_event_index: u64,
+        limit: u64,
+    ) -> Result<Vec<EventWithProof>> {
+        let req_item = RequestItem::GetEventsByEventType {
+            event_type,
+            start_version,
+            start_event_index,
+            limit,
+        };
+
+        let mut response = self.get_with_proof_sync(vec![req_item])?;
+        let value_with_proof = response.response_items.remove(0);
+        match value_with_proof {
+            ResponseItem::GetEventsByEventType { events_with_proof } => Ok(events_with_proof),
             _ => bail!(
                 "Incorrect type of response returned: {:?}",
                 value_with_proof
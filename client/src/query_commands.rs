@@ -23,6 +23,7 @@ impl Command for QueryCommand {
             Box::new(QueryCommandGetTxnByAccountSeq {}),
             Box::new(QueryCommandGetTxnByRange {}),
             Box::new(QueryCommandGetEvent {}),
+            Box::new(QueryCommandGetEventsByType {}),
         ];
 
         subcommand_execute(&params[0], commands, client, &params[1..]);
@@ -224,8 +225,55 @@ impl Command for QueryCommandGetEvent {
                     }
                 }
                 println!("Last event state: {:#?}", last_event_state);
+                // The response is verified against the event counter in the account state, so
+                // no event up to this count was left out.
+                match get_account_resource_or_default(&last_event_state.blob) {
+                    Ok(account_resource) => {
+                        let event_handle = if params[2] == "sent" {
+                            account_resource.sent_events()
+                        } else {
+                            account_resource.received_events()
+                        };
+                        println!(
+                            "Total number of events as of version {}: {}",
+                            last_event_state.version,
+                            event_handle.count()
+                        );
+                    }
+                    Err(e) => report_error("Error decoding the event counter", e),
+                }
             }
             Err(e) => report_error("Error getting events by access path", e),
         }
     }
 }
+
+/// Command to query events of a given type across all accounts.
+pub struct QueryCommandGetEventsByType {}
+
+impl Command for QueryCommandGetEventsByType {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["events_by_type", "et"]
+    }
+    fn get_params_help(&self) -> &'static str {
+        "<sent|received> <start_version> <start_event_index> <limit>"
+    }
+    fn get_description(&self) -> &'static str {
+        "Get events of a type (sent|received) emitted by any account, in ledger order."
+    }
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        println!(">> Getting events by type.");
+        match client.get_events_by_type(&params) {
+            Ok(events) => {
+                if events.is_empty() {
+                    println!("No events returned");
+                } else {
+                    for event in events {
+                        println!("{}", event);
+                    }
+                }
+            }
+            Err(e) => report_error("Error getting events by type", e),
+        }
+    }
+}
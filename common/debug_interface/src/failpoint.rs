@@ -0,0 +1,313 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Named failpoints for injecting faults into a running node.
+//!
+//! A subsystem declares a failpoint by calling `fail_point("libradb::commit")?` on the path it
+//! wants to make fallible. Failpoints are inactive until configured, either from the
+//! `failpoints` section of NodeConfig at startup or through the `SetFailpoint` call of the debug
+//! interface at runtime.
+//!
+//! A configuration is a chain of actions separated by `->`, each optionally prefixed with the
+//! number of times it fires before moving on to the next one. Once every action is exhausted
+//! the failpoint turns off. Supported actions are:
+//!   * `off` - do nothing
+//!   * `return` or `return(msg)` - make `fail_point` return an error
+//!   * `panic` or `panic(msg)` - panic, which crashes the node through the crash handler
+//!   * `sleep(ms)` - block the calling thread for `ms` milliseconds
+//!
+//! Example: `"2*return->sleep(100)"` fails the first two calls and delays every later one.
+//!
+//! Failpoints are compiled out unless the `failpoints` feature is enabled, e.g. by test crates:
+//! `fail_point` then always succeeds and configuring a failpoint is refused.
+
+use failure::prelude::*;
+use lazy_static::lazy_static;
+use logger::prelude::*;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FailAction {
+    Off,
+    Return(Option<String>),
+    Panic(Option<String>),
+    Sleep(u64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct FailTask {
+    action: FailAction,
+    // None means the action fires forever
+    remaining: Option<usize>,
+}
+
+struct FailPoint {
+    actions: String,
+    tasks: VecDeque<FailTask>,
+}
+
+lazy_static! {
+    static ref FAILPOINTS: Mutex<HashMap<String, FailPoint>> = Mutex::new(HashMap::new());
+}
+
+// Number of configured failpoints, so that `fail_point` does not take the lock in the common
+// case where none are configured.
+static NUM_FAILPOINTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether failpoints can be configured, i.e. the `failpoints` feature is enabled.
+pub fn is_enabled() -> bool {
+    cfg!(feature = "failpoints")
+}
+
+/// Evaluates the failpoint `name`. Returns an error if the failpoint is configured to `return`.
+#[inline]
+pub fn fail_point(name: &str) -> Result<()> {
+    if !is_enabled() || NUM_FAILPOINTS.load(Ordering::Relaxed) == 0 {
+        return Ok(());
+    }
+    let action = match next_action(name) {
+        Some(action) => action,
+        None => return Ok(()),
+    };
+    match action {
+        FailAction::Off => Ok(()),
+        FailAction::Return(msg) => {
+            warn!("Failpoint {} returns an error", name);
+            bail!(
+                "Injected failure at failpoint {}: {}",
+                name,
+                msg.unwrap_or_default()
+            )
+        }
+        FailAction::Panic(msg) => panic!(
+            "Injected panic at failpoint {}: {}",
+            name,
+            msg.unwrap_or_default()
+        ),
+        FailAction::Sleep(ms) => {
+            warn!("Failpoint {} sleeps for {} ms", name, ms);
+            thread::sleep(Duration::from_millis(ms));
+            Ok(())
+        }
+    }
+}
+
+fn next_action(name: &str) -> Option<FailAction> {
+    let mut failpoints = FAILPOINTS.lock().unwrap();
+    let failpoint = failpoints.get_mut(name)?;
+    let action = loop {
+        let task = match failpoint.tasks.front_mut() {
+            Some(task) => task,
+            None => break None,
+        };
+        match task.remaining {
+            Some(0) => (),
+            Some(ref mut remaining) => {
+                *remaining -= 1;
+                break Some(task.action.clone());
+            }
+            None => break Some(task.action.clone()),
+        }
+        failpoint.tasks.pop_front();
+    };
+    while failpoint
+        .tasks
+        .front()
+        .map_or(false, |task| task.remaining == Some(0))
+    {
+        failpoint.tasks.pop_front();
+    }
+    // Exhausted failpoints are removed, so that `fail_point` goes back to the fast path once
+    // they all are.
+    if failpoint.tasks.is_empty() {
+        info!("Failpoint {} is exhausted", name);
+        failpoints.remove(name);
+        NUM_FAILPOINTS.store(failpoints.len(), Ordering::Relaxed);
+    }
+    action
+}
+
+/// Configures the failpoint `name` with `actions`. An empty configuration or `off` removes it.
+pub fn configure(name: &str, actions: &str) -> Result<()> {
+    ensure!(
+        is_enabled(),
+        "Cannot set failpoint {}: failpoints are disabled in this build",
+        name
+    );
+    let tasks = parse_actions(actions)?;
+    let mut failpoints = FAILPOINTS.lock().unwrap();
+    if tasks.is_empty() || tasks == [FailTask::forever(FailAction::Off)] {
+        failpoints.remove(name);
+    } else {
+        info!("Failpoint {} set to {}", name, actions);
+        failpoints.insert(
+            name.to_string(),
+            FailPoint {
+                actions: actions.to_string(),
+                tasks: tasks.into_iter().collect(),
+            },
+        );
+    }
+    NUM_FAILPOINTS.store(failpoints.len(), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Configures every failpoint in `failpoints`, e.g. the ones from NodeConfig.
+pub fn configure_all(failpoints: &HashMap<String, String>) -> Result<()> {
+    for (name, actions) in failpoints {
+        configure(name, actions).with_context(|_| format!("Invalid failpoint {}", name))?;
+    }
+    Ok(())
+}
+
+/// Removes all failpoints.
+pub fn clear_all() {
+    let mut failpoints = FAILPOINTS.lock().unwrap();
+    failpoints.clear();
+    NUM_FAILPOINTS.store(0, Ordering::Relaxed);
+}
+
+/// Returns every configured failpoint with the configuration it was set to.
+pub fn list() -> HashMap<String, String> {
+    FAILPOINTS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, failpoint)| (name.clone(), failpoint.actions.clone()))
+        .collect()
+}
+
+impl FailTask {
+    fn forever(action: FailAction) -> Self {
+        Self {
+            action,
+            remaining: None,
+        }
+    }
+}
+
+fn parse_actions(actions: &str) -> Result<Vec<FailTask>> {
+    let actions = actions.trim();
+    if actions.is_empty() {
+        return Ok(vec![]);
+    }
+    actions.split("->").map(parse_task).collect()
+}
+
+fn parse_task(task: &str) -> Result<FailTask> {
+    let task = task.trim();
+    let (remaining, action) = match task.find('*') {
+        Some(pos) => {
+            let count = task[..pos]
+                .trim()
+                .parse::<usize>()
+                .map_err(|e| format_err!("Invalid count in {}: {}", task, e))?;
+            (Some(count), &task[pos + 1..])
+        }
+        None => (None, task),
+    };
+    Ok(FailTask {
+        action: parse_action(action.trim())?,
+        remaining,
+    })
+}
+
+fn parse_action(action: &str) -> Result<FailAction> {
+    let (kind, arg) = match action.find('(') {
+        Some(pos) => {
+            ensure!(action.ends_with(')'), "Unclosed argument in {}", action);
+            (
+                &action[..pos],
+                Some(action[pos + 1..action.len() - 1].to_string()),
+            )
+        }
+        None => (action, None),
+    };
+    Ok(match kind {
+        "off" => FailAction::Off,
+        "return" => FailAction::Return(arg),
+        "panic" => FailAction::Panic(arg),
+        "sleep" => {
+            let ms = arg
+                .ok_or_else(|| format_err!("sleep requires a duration in ms"))?
+                .parse::<u64>()
+                .map_err(|e| format_err!("Invalid sleep duration in {}: {}", action, e))?;
+            FailAction::Sleep(ms)
+        }
+        _ => bail!("Unknown failpoint action: {}", action),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_actions() {
+        assert_eq!(parse_actions("").unwrap(), vec![]);
+        assert_eq!(
+            parse_actions("2*return(boom) -> sleep(10)").unwrap(),
+            vec![
+                FailTask {
+                    action: FailAction::Return(Some("boom".to_string())),
+                    remaining: Some(2),
+                },
+                FailTask::forever(FailAction::Sleep(10)),
+            ]
+        );
+        assert!(parse_actions("explode").is_err());
+        assert!(parse_actions("sleep").is_err());
+        assert!(parse_actions("x*return").is_err());
+        assert!(parse_actions("return(boom").is_err());
+    }
+
+    #[cfg(feature = "failpoints")]
+    #[test]
+    fn test_fail_point() {
+        let name = "debug_interface::test_fail_point";
+        assert!(fail_point(name).is_ok());
+
+        configure(name, "2*return->1*off->return(boom)").unwrap();
+        assert_eq!(
+            list().get(name),
+            Some(&"2*return->1*off->return(boom)".to_string())
+        );
+        assert!(fail_point(name).is_err());
+        assert!(fail_point(name).is_err());
+        assert!(fail_point(name).is_ok());
+        let err = fail_point(name).unwrap_err();
+        assert!(err.to_string().contains("boom"));
+
+        configure(name, "off").unwrap();
+        assert!(fail_point(name).is_ok());
+        assert!(list().get(name).is_none());
+    }
+
+    #[cfg(feature = "failpoints")]
+    #[test]
+    fn test_fail_point_exhausted() {
+        let name = "debug_interface::test_fail_point_exhausted";
+        configure(name, "1*return").unwrap();
+        assert!(fail_point(name).is_err());
+        assert!(list().get(name).is_none());
+        assert!(fail_point(name).is_ok());
+        assert!(fail_point(name).is_ok());
+    }
+
+    #[cfg(not(feature = "failpoints"))]
+    #[test]
+    fn test_configure_disabled() {
+        let name = "debug_interface::test_configure_disabled";
+        assert!(configure(name, "return").is_err());
+        assert!(list().get(name).is_none());
+        assert!(fail_point(name).is_ok());
+    }
+}
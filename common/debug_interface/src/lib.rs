@@ -2,20 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::proto::{
-    node_debug_interface::GetNodeDetailsRequest,
+    node_debug_interface::{
+        GetFailpointsRequest, GetLogLevelsRequest, GetNodeDetailsRequest, GetTracesRequest,
+        SetFailpointRequest, SetLogLevelRequest, StreamLogsRequest,
+    },
     node_debug_interface_grpc::NodeDebugInterfaceClient,
 };
 use failure::prelude::*;
+use futures::Stream;
 use grpcio::{ChannelBuilder, EnvBuilder};
 use std::{collections::HashMap, sync::Arc};
 
 // Generated
 pub mod proto;
 
+pub mod failpoint;
 pub mod node_debug_helpers;
 pub mod node_debug_service;
 #[macro_use]
 pub mod json_log;
+#[macro_use]
+pub mod libra_trace;
 
 /// Implement default utility client for NodeDebugInterface
 pub struct NodeDebugClient {
@@ -60,4 +67,67 @@ impl NodeDebugClient {
             })
             .collect()
     }
+
+    /// Drains the spans recorded by the node and returns them as OTLP JSON.
+    pub fn get_traces<S: Into<String>>(&self, service_name: S) -> Result<String> {
+        let mut request = GetTracesRequest::new();
+        request.set_service_name(service_name.into());
+        let response = self
+            .client
+            .get_traces(&request)
+            .context("Unable to query Node traces")?;
+        Ok(response.otlp_json)
+    }
+
+    /// Configures a failpoint of the node, see `failpoint` for the syntax of `actions`.
+    pub fn set_failpoint<S: Into<String>>(&self, name: S, actions: S) -> Result<()> {
+        let mut request = SetFailpointRequest::new();
+        request.set_name(name.into());
+        request.set_actions(actions.into());
+        self.client
+            .set_failpoint(&request)
+            .context("Unable to set Node failpoint")?;
+        Ok(())
+    }
+
+    pub fn get_failpoints(&self) -> Result<HashMap<String, String>> {
+        let response = self
+            .client
+            .get_failpoints(&GetFailpointsRequest::new())
+            .context("Unable to query Node failpoints")?;
+        Ok(response.failpoints)
+    }
+
+    /// Sets the log level of a module path prefix of the node, or its default level if `module`
+    /// is empty. An empty `level` removes the level of `module`.
+    pub fn set_log_level<S: Into<String>>(&self, module: S, level: S) -> Result<()> {
+        let mut request = SetLogLevelRequest::new();
+        request.set_module(module.into());
+        request.set_level(level.into());
+        self.client
+            .set_log_level(&request)
+            .context("Unable to set Node log level")?;
+        Ok(())
+    }
+
+    /// Returns the default log level of the node and the levels of the modules overriding it.
+    pub fn get_log_levels(&self) -> Result<(String, HashMap<String, String>)> {
+        let response = self
+            .client
+            .get_log_levels(&GetLogLevelsRequest::new())
+            .context("Unable to query Node log levels")?;
+        Ok((response.default_level, response.modules))
+    }
+
+    /// Returns the recent log lines of the node followed by the new ones, blocking until they
+    /// are logged.
+    pub fn stream_logs(&self) -> Result<impl Iterator<Item = Result<String>>> {
+        let lines = self
+            .client
+            .stream_logs(&StreamLogsRequest::new())
+            .context("Unable to stream Node logs")?;
+        Ok(lines
+            .wait()
+            .map(|line| Ok(line.context("Node log stream failed")?.line)))
+    }
 }
@@ -0,0 +1,392 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lightweight distributed tracing of transactions and blocks as they move through the node.
+//!
+//! Every span belongs to a trace identified by the first 16 bytes of a transaction hash or a
+//! block id, so spans recorded by admission control, mempool, consensus, execution and storage
+//! for the same object line up into a single timeline. A span may also link to other traces,
+//! e.g. a block execution span links to the traces of the transactions in the block. A link
+//! points to the latest span recorded in the linked trace, and is dropped if that trace has no
+//! recent span.
+//!
+//! Finished spans are kept in a bounded in-memory queue drained through the `GetTraces` call of
+//! the debug interface. If a file exporter is started, it gets its own bounded queue, from which
+//! it periodically appends the spans as OpenTelemetry (OTLP) JSON lines, so both consumers see
+//! every span.
+//!
+//! Example:
+//!   let _span = trace_span!("mempool::add_txn", txn_hash.as_ref(), "sender": sender.short_str());
+
+use lazy_static::lazy_static;
+use logger::prelude::*;
+use serde_json::Value;
+// Re-exported so that crates using `trace_span!` need not depend on serde_json themselves.
+#[doc(hidden)]
+pub use serde_json::json;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+const MAX_SPANS_IN_QUEUE: usize = 10_000;
+const TRACE_ID_LENGTH: usize = 16;
+
+/// Starts a span that ends when the returned guard is dropped. Neither the trace key nor the
+/// attributes are evaluated unless tracing has been enabled with `set_enabled`.
+/// Example:
+///   let _span = trace_span!("executor::execute_block", block_id.as_ref(), "txns": 10);
+#[macro_export]
+macro_rules! trace_span {
+    ($name:expr, $trace_key:expr) => {
+        $crate::trace_span!($name, $trace_key,)
+    };
+    ($name:expr, $trace_key:expr, $($json:tt)*) => {
+        if $crate::libra_trace::is_enabled() {
+            $crate::libra_trace::SpanGuard::new(
+                $name,
+                $trace_key,
+                $crate::libra_trace::json!({$($json)*}),
+            )
+        } else {
+            $crate::libra_trace::SpanGuard::disabled()
+        }
+    };
+}
+
+lazy_static! {
+    static ref SPAN_QUEUE: Mutex<VecDeque<Span>> =
+        Mutex::new(VecDeque::with_capacity(MAX_SPANS_IN_QUEUE));
+    static ref EXPORT_QUEUE: Mutex<VecDeque<Span>> = Mutex::new(VecDeque::new());
+    static ref LATEST_SPAN_IDS: Mutex<LatestSpanIds> = Mutex::new(LatestSpanIds::default());
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static EXPORTER_STARTED: AtomicBool = AtomicBool::new(false);
+static NEXT_SPAN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Turns span collection on or off for the whole process.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Hex encoded trace id derived from the first 16 bytes of a transaction hash or block id.
+pub fn trace_id(trace_key: &[u8]) -> String {
+    let mut id = [0u8; TRACE_ID_LENGTH];
+    let len = std::cmp::min(trace_key.len(), TRACE_ID_LENGTH);
+    id[..len].copy_from_slice(&trace_key[..len]);
+    id.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn now_unix_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("now > UNIX_EPOCH")
+        .as_nanos()
+}
+
+/// A reference from a span to a span of another trace.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    pub trace_id: String,
+    pub span_id: u64,
+}
+
+/// A finished unit of work within a trace.
+#[derive(Clone, Debug)]
+pub struct Span {
+    pub trace_id: String,
+    pub span_id: u64,
+    pub name: &'static str,
+    pub start_time_unix_nano: u128,
+    pub end_time_unix_nano: u128,
+    pub attributes: Value,
+    pub links: Vec<Link>,
+}
+
+/// Records a span into the queue when dropped.
+pub struct SpanGuard {
+    span: Option<Span>,
+}
+
+impl SpanGuard {
+    pub fn new(name: &'static str, trace_key: &[u8], attributes: Value) -> Self {
+        if !is_enabled() {
+            return Self::disabled();
+        }
+        Self {
+            span: Some(Span {
+                trace_id: trace_id(trace_key),
+                span_id: NEXT_SPAN_ID.fetch_add(1, Ordering::Relaxed),
+                name,
+                start_time_unix_nano: now_unix_nanos(),
+                end_time_unix_nano: 0,
+                attributes,
+                links: vec![],
+            }),
+        }
+    }
+
+    /// A guard that records nothing, used while tracing is off.
+    pub fn disabled() -> Self {
+        Self { span: None }
+    }
+
+    /// Links this span to the latest span of the trace identified by `trace_key`, if any.
+    pub fn add_link(&mut self, trace_key: &[u8]) {
+        if let Some(span) = self.span.as_mut() {
+            let trace_id = trace_id(trace_key);
+            let span_id = LATEST_SPAN_IDS.lock().unwrap().get(&trace_id);
+            if let Some(span_id) = span_id {
+                span.links.push(Link { trace_id, span_id });
+            }
+        }
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        if let Some(mut span) = self.span.take() {
+            span.end_time_unix_nano = now_unix_nanos();
+            record_span(span);
+        }
+    }
+}
+
+/// The id of the latest span of each of the most recent traces, which links point to.
+#[derive(Default)]
+struct LatestSpanIds {
+    span_ids: HashMap<String, u64>,
+    // The traces in `span_ids`, oldest first.
+    trace_ids: VecDeque<String>,
+}
+
+impl LatestSpanIds {
+    fn get(&self, trace_id: &str) -> Option<u64> {
+        self.span_ids.get(trace_id).cloned()
+    }
+
+    fn insert(&mut self, trace_id: &str, span_id: u64) {
+        if self
+            .span_ids
+            .insert(trace_id.to_string(), span_id)
+            .is_none()
+        {
+            self.trace_ids.push_back(trace_id.to_string());
+            if self.trace_ids.len() > MAX_SPANS_IN_QUEUE {
+                if let Some(oldest) = self.trace_ids.pop_front() {
+                    self.span_ids.remove(&oldest);
+                }
+            }
+        }
+    }
+}
+
+/// Adds a finished span to the queues, evicting the oldest span of a queue if it is full.
+pub fn record_span(span: Span) {
+    LATEST_SPAN_IDS
+        .lock()
+        .unwrap()
+        .insert(&span.trace_id, span.span_id);
+    if EXPORTER_STARTED.load(Ordering::Relaxed) {
+        push_span(&EXPORT_QUEUE, span.clone());
+    }
+    push_span(&SPAN_QUEUE, span);
+}
+
+fn push_span(queue: &Mutex<VecDeque<Span>>, span: Span) {
+    let mut queue = queue.lock().unwrap();
+    if queue.len() >= MAX_SPANS_IN_QUEUE {
+        queue.pop_front();
+    }
+    queue.push_back(span);
+}
+
+/// Get up to MAX_SPANS_IN_QUEUE last spans and clears the queue. Spans exported to a file are
+/// still returned.
+pub fn pop_spans() -> Vec<Span> {
+    let mut queue = SPAN_QUEUE.lock().unwrap();
+    queue.drain(..).collect()
+}
+
+/// Converts spans into an OTLP `ExportTraceServiceRequest` in its JSON encoding.
+pub fn to_otlp_json(service_name: &str, spans: &[Span]) -> Value {
+    let spans: Vec<_> = spans
+        .iter()
+        .map(|span| {
+            let attributes: Vec<_> = span
+                .attributes
+                .as_object()
+                .map(|attributes| {
+                    attributes
+                        .iter()
+                        .map(|(key, value)| json!({"key": key, "value": otlp_value(value)}))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let links: Vec<_> = span
+                .links
+                .iter()
+                .map(|link| {
+                    json!({
+                        "traceId": link.trace_id,
+                        "spanId": format!("{:016x}", link.span_id),
+                    })
+                })
+                .collect();
+            json!({
+                "traceId": span.trace_id,
+                "spanId": format!("{:016x}", span.span_id),
+                "name": span.name,
+                "kind": 1,
+                "startTimeUnixNano": span.start_time_unix_nano.to_string(),
+                "endTimeUnixNano": span.end_time_unix_nano.to_string(),
+                "attributes": attributes,
+                "links": links,
+            })
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    {"key": "service.name", "value": {"stringValue": service_name}}
+                ]
+            },
+            "instrumentationLibrarySpans": [{
+                "instrumentationLibrary": {"name": "libra_trace"},
+                "spans": spans,
+            }]
+        }]
+    })
+}
+
+fn otlp_value(value: &Value) -> Value {
+    match value {
+        Value::Bool(b) => json!({ "boolValue": b }),
+        Value::Number(n) if n.is_i64() || n.is_u64() => json!({ "intValue": n.to_string() }),
+        Value::Number(n) => json!({ "doubleValue": n }),
+        Value::String(s) => json!({ "stringValue": s }),
+        other => json!({ "stringValue": other.to_string() }),
+    }
+}
+
+/// Spawns a thread that drains the span queue every `interval` and appends the spans to `path`
+/// as one OTLP JSON document per line.
+pub fn start_file_exporter(service_name: String, path: PathBuf, interval: Duration) {
+    EXPORTER_STARTED.store(true, Ordering::Relaxed);
+    thread::Builder::new()
+        .name("trace-exporter".to_string())
+        .spawn(move || loop {
+            thread::sleep(interval);
+            let spans: Vec<_> = EXPORT_QUEUE.lock().unwrap().drain(..).collect();
+            if spans.is_empty() {
+                continue;
+            }
+            let line = to_otlp_json(&service_name, &spans).to_string();
+            let result = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| writeln!(file, "{}", line));
+            if let Err(e) = result {
+                error!(
+                    "Failed to export {} spans to {:?}: {}",
+                    spans.len(),
+                    path,
+                    e
+                );
+            }
+        })
+        .expect("Failed to spawn trace exporter thread");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_id() {
+        assert_eq!(trace_id(&[0xab; 32]), "ab".repeat(16));
+        assert_eq!(trace_id(&[1, 2]), format!("0102{}", "0".repeat(28)));
+    }
+
+    #[test]
+    fn test_to_otlp_json() {
+        let span = Span {
+            trace_id: trace_id(&[1; 32]),
+            span_id: 42,
+            name: "executor::execute_block",
+            start_time_unix_nano: 10,
+            end_time_unix_nano: 20,
+            attributes: json!({"txns": 3, "mode": "normal"}),
+            links: vec![Link {
+                trace_id: trace_id(&[2; 32]),
+                span_id: 7,
+            }],
+        };
+        let json = to_otlp_json("validator", &[span]);
+        let otlp_span = &json["resourceSpans"][0]["instrumentationLibrarySpans"][0]["spans"][0];
+        assert_eq!(otlp_span["traceId"], "01".repeat(16));
+        assert_eq!(otlp_span["spanId"], "000000000000002a");
+        assert_eq!(otlp_span["name"], "executor::execute_block");
+        assert_eq!(otlp_span["startTimeUnixNano"], "10");
+        assert_eq!(otlp_span["endTimeUnixNano"], "20");
+        assert_eq!(otlp_span["links"][0]["traceId"], "02".repeat(16));
+        assert_eq!(otlp_span["links"][0]["spanId"], "0000000000000007");
+        let attributes = otlp_span["attributes"].as_array().unwrap();
+        assert!(attributes.contains(&json!({"key": "txns", "value": {"intValue": "3"}})));
+        assert!(attributes.contains(&json!({"key": "mode", "value": {"stringValue": "normal"}})));
+    }
+
+    #[test]
+    fn test_links() {
+        set_enabled(true);
+        let linked_key = [3; 32];
+        drop(SpanGuard::new("mempool::add_txn", &linked_key, json!({})));
+        let linked_span_id = LATEST_SPAN_IDS
+            .lock()
+            .unwrap()
+            .get(&trace_id(&linked_key))
+            .unwrap();
+
+        let mut span = SpanGuard::new("executor::execute_block", &[4; 32], json!({}));
+        span.add_link(&linked_key);
+        // Traces without any recorded span are not linked to.
+        span.add_link(&[5; 32]);
+        assert_eq!(
+            span.span.as_ref().unwrap().links,
+            vec![Link {
+                trace_id: trace_id(&linked_key),
+                span_id: linked_span_id,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_spans_fan_out_to_exporter() {
+        set_enabled(true);
+        EXPORTER_STARTED.store(true, Ordering::Relaxed);
+        let trace_key = [6; 32];
+        drop(SpanGuard::new(
+            "storage::save_transactions",
+            &trace_key,
+            json!({}),
+        ));
+        let is_recorded = |span: &Span| span.trace_id == trace_id(&trace_key);
+        assert!(pop_spans().iter().any(is_recorded));
+        assert!(EXPORT_QUEUE.lock().unwrap().iter().any(is_recorded));
+    }
+}
@@ -4,19 +4,26 @@
 //! Debug interface to access information in a specific node.
 
 use crate::{
-    json_log,
+    failpoint, json_log, libra_trace,
     proto::{
         node_debug_interface::{
-            Event, GetEventsRequest, GetEventsResponse, GetNodeDetailsRequest,
-            GetNodeDetailsResponse,
+            Event, GetEventsRequest, GetEventsResponse, GetFailpointsRequest,
+            GetFailpointsResponse, GetLogLevelsRequest, GetLogLevelsResponse,
+            GetNodeDetailsRequest, GetNodeDetailsResponse, GetTracesRequest, GetTracesResponse,
+            LogLine, SetFailpointRequest, SetFailpointResponse, SetLogLevelRequest,
+            SetLogLevelResponse, StreamLogsRequest,
         },
         node_debug_interface_grpc::NodeDebugInterface,
     },
 };
-use futures::Future;
+use futures::{stream, Future, Sink, Stream};
+use grpcio::{RpcStatus, RpcStatusCode, WriteFlags};
 use logger::prelude::*;
 use metrics::counters::COUNTER_ADMISSION_CONTROL_CANNOT_SEND_REPLY;
 
+/// Number of new log lines buffered for a log stream before it starts missing lines.
+const LOG_STREAM_BUFFER: usize = 1_024;
+
 #[derive(Clone, Default)]
 pub struct NodeDebugService {}
 
@@ -57,6 +64,115 @@ impl NodeDebugInterface for NodeDebugService {
         }
         ctx.spawn(sink.success(response).map_err(default_reply_error_logger))
     }
+
+    fn get_traces(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        req: GetTracesRequest,
+        sink: ::grpcio::UnarySink<GetTracesResponse>,
+    ) {
+        let spans = libra_trace::pop_spans();
+        let mut response = GetTracesResponse::new();
+        response.set_otlp_json(libra_trace::to_otlp_json(&req.service_name, &spans).to_string());
+        ctx.spawn(sink.success(response).map_err(default_reply_error_logger))
+    }
+
+    fn set_failpoint(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        req: SetFailpointRequest,
+        sink: ::grpcio::UnarySink<SetFailpointResponse>,
+    ) {
+        info!("[GRPC] set_failpoint {} to {}", req.name, req.actions);
+        if !failpoint::is_enabled() {
+            let status = RpcStatus::new(
+                RpcStatusCode::FailedPrecondition,
+                Some("Failpoints are disabled in this build".to_string()),
+            );
+            ctx.spawn(sink.fail(status).map_err(default_reply_error_logger));
+            return;
+        }
+        match failpoint::configure(&req.name, &req.actions) {
+            Ok(()) => ctx.spawn(
+                sink.success(SetFailpointResponse::new())
+                    .map_err(default_reply_error_logger),
+            ),
+            Err(e) => {
+                let status = RpcStatus::new(RpcStatusCode::InvalidArgument, Some(e.to_string()));
+                ctx.spawn(sink.fail(status).map_err(default_reply_error_logger))
+            }
+        }
+    }
+
+    fn get_failpoints(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        _req: GetFailpointsRequest,
+        sink: ::grpcio::UnarySink<GetFailpointsResponse>,
+    ) {
+        let mut response = GetFailpointsResponse::new();
+        response.failpoints = failpoint::list();
+        ctx.spawn(sink.success(response).map_err(default_reply_error_logger))
+    }
+
+    fn set_log_level(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        req: SetLogLevelRequest,
+        sink: ::grpcio::UnarySink<SetLogLevelResponse>,
+    ) {
+        info!(
+            "[GRPC] set_log_level of '{}' to '{}'",
+            req.module, req.level
+        );
+        match logger::set_log_level(&req.module, &req.level) {
+            Ok(()) => ctx.spawn(
+                sink.success(SetLogLevelResponse::new())
+                    .map_err(default_reply_error_logger),
+            ),
+            Err(e) => {
+                let status = RpcStatus::new(RpcStatusCode::InvalidArgument, Some(e.to_string()));
+                ctx.spawn(sink.fail(status).map_err(default_reply_error_logger))
+            }
+        }
+    }
+
+    fn get_log_levels(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        _req: GetLogLevelsRequest,
+        sink: ::grpcio::UnarySink<GetLogLevelsResponse>,
+    ) {
+        let (default_level, modules) = logger::log_levels();
+        let mut response = GetLogLevelsResponse::new();
+        response.set_default_level(default_level);
+        response.modules = modules.into_iter().collect();
+        ctx.spawn(sink.success(response).map_err(default_reply_error_logger))
+    }
+
+    fn stream_logs(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        _req: StreamLogsRequest,
+        sink: ::grpcio::ServerStreamingSink<LogLine>,
+    ) {
+        info!("[GRPC] stream_logs");
+        let (recent_lines, new_lines) = logger::subscribe_to_logs(LOG_STREAM_BUFFER);
+        // The stream ends when the client goes away, which fails the last send.
+        let lines = stream::iter_ok(recent_lines)
+            .chain(new_lines)
+            .map(|line| {
+                let mut log_line = LogLine::new();
+                log_line.set_line(line);
+                (log_line, WriteFlags::default())
+            })
+            .map_err(|()| ::grpcio::Error::RemoteStopped);
+        ctx.spawn(
+            sink.send_all(lines)
+                .map(|_| ())
+                .map_err(|e| debug!("Log stream closed: {:?}", e)),
+        )
+    }
 }
 
 fn default_reply_error_logger<T: ::std::fmt::Debug>(e: T) {
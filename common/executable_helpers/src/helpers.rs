@@ -3,7 +3,7 @@
 
 use clap::{value_t, App, Arg, ArgMatches};
 use config::config::{NodeConfig, NodeConfigHelpers};
-use logger::prelude::*;
+use logger::{prelude::*, LogFormat};
 use slog_scope::GlobalLoggerGuard;
 
 // General args
@@ -57,17 +57,23 @@ pub fn setup_executable(
 
     let args = get_arg_matches(app_name, arg_names);
     let is_logging_disabled = args.is_present(ARG_DISABLE_LOGGING);
-    let mut _logger = set_default_global_logger(is_logging_disabled, None);
+    let mut _logger = set_default_global_logger(is_logging_disabled, None, LogFormat::default());
 
     let config = load_configs_from_args(&args);
 
-    // Reset the global logger using config (for chan_size currently).
+    // Reset the global logger using config (for chan_size and format currently).
     // We need to drop the global logger guard first before resetting it.
     _logger = None;
     let logger = set_default_global_logger(
         is_logging_disabled,
         Some(config.base.node_async_log_chan_size),
+        config.log_collector.format,
     );
+    if let Some(network) = config.networks.first() {
+        logger::log_context::update_node_log_context(|context| {
+            context.peer_id = Some(network.peer_id.clone())
+        });
+    }
     for network in &config.networks {
         setup_metrics(&network.peer_id, &config);
     }
@@ -77,14 +83,16 @@ pub fn setup_executable(
 fn set_default_global_logger(
     is_logging_disabled: bool,
     chan_size: Option<usize>,
+    format: LogFormat,
 ) -> Option<GlobalLoggerGuard> {
     if is_logging_disabled {
         return None;
     }
 
-    Some(logger::set_default_global_logger(
+    Some(logger::set_global_logger_with_format(
         true,      /* async */
         chan_size, /* chan_size */
+        format,
     ))
 }
 
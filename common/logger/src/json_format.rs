@@ -0,0 +1,252 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides a slog drain that writes every record as a JSON object on its own line.
+//!
+//! A record looks like:
+//! `{"timestamp":"2019-10-01T12:00:00.000000Z","level":"INFO","module":"consensus",
+//! "file":"consensus/src/lib.rs","line":10,"thread":1,"message":"Committed",
+//! "peer_id":"8deeeaed","round":5,"version":42,"data":{"block_id":"e27c8d7a"}}`.
+//! The standard fields of `log_context` are written at the top level, from the node context and
+//! from the record, and the other key values of the record are written in `data`.
+
+use crate::log_context::{NODE_LOG_CONTEXT, STANDARD_KEYS};
+use chrono::{SecondsFormat, Utc};
+use serde_json::{Map, Number, Value};
+use slog::{Drain, Key, Level, OwnedKVList, Record, Serializer, KV};
+use std::{fmt::Arguments, io, sync::Mutex};
+use thread_id;
+
+/// A slog `Drain` for JSON-formatted logs.
+pub struct JsonFormat<W: io::Write> {
+    writer: Mutex<W>,
+}
+
+impl<W: io::Write> JsonFormat<W> {
+    /// Create a JSON-formatted `Drain` writing to `writer`
+    pub fn new(writer: W) -> JsonFormat<W> {
+        JsonFormat {
+            writer: Mutex::new(writer),
+        }
+    }
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Critical => "CRITICAL",
+        Level::Error => "ERROR",
+        Level::Warning => "WARN",
+        Level::Info => "INFO",
+        Level::Debug => "DEBUG",
+        Level::Trace => "TRACE",
+    }
+}
+
+impl<W: io::Write> Drain for JsonFormat<W> {
+    type Ok = ();
+    type Err = io::Error;
+
+    fn log(&self, record: &Record<'_>, values: &OwnedKVList) -> io::Result<Self::Ok> {
+        // The first value of a key wins: the record ones override the logger ones, which
+        // override the node context.
+        let mut serializer = JsonSerializer::default();
+        record.kv().serialize(record, &mut serializer)?;
+        values.serialize(record, &mut serializer)?;
+        NODE_LOG_CONTEXT.load().serialize(record, &mut serializer)?;
+
+        let mut object = Map::new();
+        object.insert(
+            "timestamp".to_string(),
+            Value::from(Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true)),
+        );
+        object.insert("level".to_string(), Value::from(level_name(record.level())));
+        object.insert("module".to_string(), Value::from(record.module()));
+        object.insert("file".to_string(), Value::from(record.file()));
+        object.insert("line".to_string(), Value::from(record.line()));
+        object.insert("thread".to_string(), Value::from(thread_id::get()));
+        object.insert("message".to_string(), Value::from(record.msg().to_string()));
+        let mut data = Map::new();
+        for (key, value) in serializer.0 {
+            if STANDARD_KEYS.contains(&key) {
+                object.insert(key.to_string(), value);
+            } else {
+                data.insert(key.to_string(), value);
+            }
+        }
+        if !data.is_empty() {
+            object.insert("data".to_string(), Value::Object(data));
+        }
+
+        let mut line = serde_json::to_vec(&Value::Object(object))?;
+        line.push(b'\n');
+        let mut writer = self.writer.lock().unwrap();
+        writer.write_all(&line)?;
+        writer.flush()
+    }
+}
+
+/// This serializer collects the KV pairs as JSON values, keeping numbers and booleans as such.
+#[derive(Default)]
+struct JsonSerializer(Vec<(Key, Value)>);
+
+impl JsonSerializer {
+    fn insert(&mut self, key: Key, value: Value) {
+        if self.0.iter().all(|(k, _)| *k != key) {
+            self.0.push((key, value));
+        }
+    }
+}
+
+/// Define a macro to implement serializer emit functions for numbers.
+macro_rules! impl_emit_number(
+    ($name:ident, $t:ty) => {
+        /// Emit $t
+        fn $name(&mut self, key: Key, val: $t) -> slog::Result {
+            self.insert(key, Value::from(val));
+            Ok(())
+        }
+    };
+);
+
+impl Serializer for JsonSerializer {
+    impl_emit_number!(emit_usize, usize);
+    impl_emit_number!(emit_isize, isize);
+    impl_emit_number!(emit_u8, u8);
+    impl_emit_number!(emit_i8, i8);
+    impl_emit_number!(emit_u16, u16);
+    impl_emit_number!(emit_i16, i16);
+    impl_emit_number!(emit_u32, u32);
+    impl_emit_number!(emit_i32, i32);
+    impl_emit_number!(emit_u64, u64);
+    impl_emit_number!(emit_i64, i64);
+
+    /// Emit f32
+    fn emit_f32(&mut self, key: Key, val: f32) -> slog::Result {
+        self.emit_f64(key, f64::from(val))
+    }
+
+    /// Emit f64, non finite numbers are written as strings
+    fn emit_f64(&mut self, key: Key, val: f64) -> slog::Result {
+        let value = Number::from_f64(val).map_or_else(|| Value::from(val.to_string()), Value::from);
+        self.insert(key, value);
+        Ok(())
+    }
+
+    /// Emit bool
+    fn emit_bool(&mut self, key: Key, val: bool) -> slog::Result {
+        self.insert(key, Value::from(val));
+        Ok(())
+    }
+
+    /// Emit ()
+    fn emit_unit(&mut self, key: Key) -> slog::Result {
+        self.insert(key, Value::Null);
+        Ok(())
+    }
+
+    /// Emit None
+    fn emit_none(&mut self, key: Key) -> slog::Result {
+        self.insert(key, Value::Null);
+        Ok(())
+    }
+
+    /// Emit str
+    fn emit_str(&mut self, key: Key, val: &str) -> slog::Result {
+        self.insert(key, Value::from(val));
+        Ok(())
+    }
+
+    /// Emit Arguments
+    fn emit_arguments(&mut self, key: Key, val: &Arguments<'_>) -> slog::Result {
+        self.insert(key, Value::from(val.to_string()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonFormat;
+    use crate::log_context::{update_node_log_context, LogContext, NODE_LOG_CONTEXT};
+    use serde_json::Value;
+    use slog::{info, o, Drain, Logger, OwnedKV};
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    /// Restores the node context when dropped, so that a test changing it leaves it as it was.
+    struct NodeLogContextGuard(Arc<LogContext>);
+
+    impl NodeLogContextGuard {
+        fn new() -> Self {
+            Self(NODE_LOG_CONTEXT.load_full())
+        }
+    }
+
+    impl Drop for NodeLogContextGuard {
+        fn drop(&mut self) {
+            NODE_LOG_CONTEXT.store(Arc::clone(&self.0));
+        }
+    }
+
+    /// Wrap a buffer so that it can be used by slog as a log output.
+    #[derive(Clone, Default)]
+    struct TestBuffer {
+        buffer: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl TestBuffer {
+        fn get_lines(&self) -> Vec<Value> {
+            let buffer = self.buffer.lock().unwrap();
+            String::from_utf8(buffer.clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    impl io::Write for TestBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.buffer.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_format() {
+        let test_buffer = TestBuffer::default();
+        let log = Logger::root(
+            JsonFormat::new(test_buffer.clone()).fuse(),
+            o!("mode" => "test"),
+        )
+        .new(OwnedKV(LogContext::new().round(2).txn_hash("ab12")));
+        let _guard = NodeLogContextGuard::new();
+        update_node_log_context(|context| {
+            context.peer_id = Some("8deeeaed".to_string());
+            context.round = Some(1);
+        });
+
+        let line = line!() + 1;
+        info!(log, "Test log {}", 1; "tau" => 6.28, "ok" => true);
+
+        let lines = test_buffer.get_lines();
+        assert_eq!(lines.len(), 1);
+        let record = &lines[0];
+        assert_eq!(record["level"], "INFO");
+        assert_eq!(record["message"], "Test log 1");
+        assert_eq!(record["file"], file!());
+        assert_eq!(record["line"], line);
+        assert!(record["timestamp"].as_str().unwrap().ends_with('Z'));
+        assert_eq!(record["peer_id"], "8deeeaed");
+        assert_eq!(record["round"], 2);
+        assert_eq!(record["txn_hash"], "ab12");
+        assert!(record.get("version").is_none());
+        assert_eq!(record["data"]["mode"], "test");
+        assert_eq!(record["data"]["tau"], 6.28);
+        assert_eq!(record["data"]["ok"], true);
+    }
+}
@@ -26,18 +26,24 @@ mod collector_serializer;
 mod glog_format;
 mod http_local_slog_drain;
 mod http_log_client;
+mod json_format;
 mod kv_categorizer;
+pub mod log_context;
+mod log_filter;
+mod recent_logs;
 mod security;
 mod simple_logger;
 
 use crate::{
     http_local_slog_drain::HttpLocalSlogDrain, http_log_client::HttpLogClient,
-    kv_categorizer::ErrorCategorizer,
+    json_format::JsonFormat, kv_categorizer::ErrorCategorizer, log_filter::RuntimeLevelFilter,
+    recent_logs::RecentLogsWriter,
 };
 use arc_swap::ArcSwap;
 use failure::prelude::*;
 use glog_format::GlogFormat;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use slog::{o, Discard, Drain, FilterLevel, Logger, Never};
 pub use slog::{slog_crit, slog_debug, slog_error, slog_info, slog_trace, slog_warn};
 use slog_async::Async;
@@ -58,20 +64,66 @@ pub mod prelude {
     pub use slog_scope::{crit, debug, error, info, trace, warn};
 }
 
+pub use log_filter::{log_levels, set_log_level};
+pub use recent_logs::subscribe_to_logs;
 pub use simple_logger::{set_simple_logger, set_simple_logger_prefix};
 
+/// Output format of the default logger.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// glog-formatted text lines.
+    Glog,
+    /// One JSON object per line, with the standard fields of `log_context`.
+    Json,
+}
+
+impl Default for LogFormat {
+    fn default() -> LogFormat {
+        LogFormat::Glog
+    }
+}
+
 /// Creates and sets default global logger.
-/// Caller must keep the returned guard alive.
+/// Its verbosity starts from the RUST_LOG environment variable and can be changed at runtime
+/// with `set_log_level`. Caller must keep the returned guard alive.
 pub fn set_default_global_logger(async_drain: bool, chan_size: Option<usize>) -> GlobalLoggerGuard {
-    let logger = create_default_root_logger(async_drain, chan_size);
+    set_global_logger_with_format(async_drain, chan_size, LogFormat::default())
+}
+
+/// Creates and sets default global logger, writing logs in the given format.
+/// Caller must keep the returned guard alive.
+pub fn set_global_logger_with_format(
+    async_drain: bool,
+    chan_size: Option<usize>,
+    format: LogFormat,
+) -> GlobalLoggerGuard {
+    let logger = create_default_root_logger(async_drain, chan_size, format);
     set_global_logger(logger)
 }
 
 /// Creates a root logger with default settings.
-fn create_default_root_logger(async_drain: bool, chan_size: Option<usize>) -> Logger {
-    let drain = GlogFormat::new(PlainDecorator::new(::std::io::stderr()), ErrorCategorizer).fuse();
-    let logger = create_env_logger(drain);
-    get_logger(async_drain, chan_size, logger)
+fn create_default_root_logger(
+    async_drain: bool,
+    chan_size: Option<usize>,
+    format: LogFormat,
+) -> Logger {
+    // Filter before the async drain, so that disabled records are not sent to its thread.
+    let logger = match format {
+        LogFormat::Glog => {
+            let drain = GlogFormat::new(
+                PlainDecorator::new(RecentLogsWriter::new(::std::io::stderr())),
+                ErrorCategorizer,
+            )
+            .fuse();
+            get_logger(async_drain, chan_size, drain)
+        }
+        LogFormat::Json => {
+            let drain = JsonFormat::new(RecentLogsWriter::new(::std::io::stderr())).fuse();
+            get_logger(async_drain, chan_size, drain)
+        }
+    };
+    Logger::root(RuntimeLevelFilter(logger), o!())
 }
 
 /// Creates a logger that respects RUST_LOG environment variable
@@ -90,15 +142,6 @@ where
     builder.build()
 }
 
-/// Creates a logger that respects RUST_LOG environment variable
-fn create_env_logger<D>(drain: D) -> EnvLogger<D>
-where
-    D: Drain<Err = Never, Ok = ()> + Send + 'static,
-{
-    // Have the default logging level be 'Info'
-    create_env_logger_with_level(drain, FilterLevel::Info)
-}
-
 /// Creates a root logger with test settings: does not do output if test passes.
 /// Caveat: cargo test does not capture output for non main thread. So this logger is not
 /// very useful for multithreading scenarios.
@@ -113,7 +156,11 @@ lazy_static! {
     static ref TESTING_ENVLOGGER_GUARD: GlobalLoggerGuard = {
         let logger = {
             if ::std::env::var("RUST_LOG").is_ok() {
-                create_default_root_logger(false /* async */, None /* chan_size */)
+                create_default_root_logger(
+                    false, /* async */
+                    None,  /* chan_size */
+                    LogFormat::default(),
+                )
             } else {
                 Logger::root(Discard, o!())
             }
@@ -171,7 +218,11 @@ fn get_log_collector(
             let drain = HttpLocalSlogDrain::new(client?);
             Ok(get_logger(is_async, chan_size, drain))
         }
-        LoggerType::StdOutput => Ok(create_default_root_logger(is_async, chan_size)),
+        LoggerType::StdOutput => Ok(create_default_root_logger(
+            is_async,
+            chan_size,
+            LogFormat::default(),
+        )),
     }
 }
 
@@ -0,0 +1,111 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Standard fields of structured logs.
+//!
+//! The node context holds the fields that describe the state of the whole node, e.g. its peer id
+//! or the last committed version, and is added to every record of the JSON logger. A `LogContext`
+//! can also be attached to a single record or to the records of a scope, e.g. for the hash of the
+//! transaction being processed. Fields of a record override the ones of the node context.
+
+use arc_swap::ArcSwap;
+use lazy_static::lazy_static;
+use slog::{OwnedKV, Record, Serializer, KV};
+use std::{fmt::Display, sync::Arc};
+
+pub const PEER_ID: &str = "peer_id";
+pub const EPOCH: &str = "epoch";
+pub const ROUND: &str = "round";
+pub const VERSION: &str = "version";
+pub const TXN_HASH: &str = "txn_hash";
+
+/// Keys of the standard fields, which the JSON logger writes at the top level of a record.
+pub const STANDARD_KEYS: [&str; 5] = [PEER_ID, EPOCH, ROUND, VERSION, TXN_HASH];
+
+lazy_static! {
+    pub(crate) static ref NODE_LOG_CONTEXT: ArcSwap<LogContext> =
+        ArcSwap::from(Arc::new(LogContext::default()));
+}
+
+/// Standard fields of a log record, unset fields are not logged.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogContext {
+    pub peer_id: Option<String>,
+    pub epoch: Option<u64>,
+    pub round: Option<u64>,
+    pub version: Option<u64>,
+    pub txn_hash: Option<String>,
+}
+
+impl LogContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn peer_id(mut self, peer_id: impl Display) -> Self {
+        self.peer_id = Some(peer_id.to_string());
+        self
+    }
+
+    pub fn epoch(mut self, epoch: u64) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    pub fn round(mut self, round: u64) -> Self {
+        self.round = Some(round);
+        self
+    }
+
+    pub fn version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn txn_hash(mut self, txn_hash: impl Display) -> Self {
+        self.txn_hash = Some(txn_hash.to_string());
+        self
+    }
+
+    /// Runs `f` with the fields of this context added to the records it logs from the current
+    /// thread through the global logger.
+    pub fn scope<F, R>(self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        slog_scope::scope(&slog_scope::logger().new(OwnedKV(self)), f)
+    }
+}
+
+impl KV for LogContext {
+    fn serialize(&self, _record: &Record<'_>, serializer: &mut dyn Serializer) -> slog::Result {
+        if let Some(peer_id) = &self.peer_id {
+            serializer.emit_str(PEER_ID, peer_id)?;
+        }
+        if let Some(epoch) = self.epoch {
+            serializer.emit_u64(EPOCH, epoch)?;
+        }
+        if let Some(round) = self.round {
+            serializer.emit_u64(ROUND, round)?;
+        }
+        if let Some(version) = self.version {
+            serializer.emit_u64(VERSION, version)?;
+        }
+        if let Some(txn_hash) = &self.txn_hash {
+            serializer.emit_str(TXN_HASH, txn_hash)?;
+        }
+        Ok(())
+    }
+}
+
+/// Updates the node context, e.g. `update_node_log_context(|ctx| ctx.round = Some(round))`.
+pub fn update_node_log_context<F>(f: F)
+where
+    F: Fn(&mut LogContext),
+{
+    NODE_LOG_CONTEXT.rcu(|context| {
+        let mut context = LogContext::clone(context);
+        f(&mut context);
+        context
+    });
+}
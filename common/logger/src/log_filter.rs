@@ -0,0 +1,208 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Log verbosity that can be changed while the process is running.
+//!
+//! The default logger filters records through the global `LogFilter`. It starts from the
+//! directives of the RUST_LOG environment variable, e.g. `info,consensus=trace`, and can then be
+//! changed at runtime, e.g. through the debug interface of a node. A record is logged if its
+//! level is at least as severe as the level of the longest module prefix that matches its module
+//! path, or as the default level if no module prefix matches. Release builds compile out trace
+//! records, so raising a module to trace there logs its debug records.
+
+use arc_swap::ArcSwap;
+use failure::prelude::*;
+use lazy_static::lazy_static;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::{collections::BTreeMap, sync::Arc};
+
+lazy_static! {
+    static ref LOG_FILTER: ArcSwap<LogFilter> = ArcSwap::from(Arc::new(LogFilter::from_env()));
+}
+
+/// Levels of the logger: None turns logging off.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogFilter {
+    default_level: Option<Level>,
+    modules: BTreeMap<String, Option<Level>>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            default_level: Some(Level::Info),
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+impl LogFilter {
+    fn from_env() -> Self {
+        let mut filter = Self::default();
+        if let Ok(directives) = ::std::env::var("RUST_LOG") {
+            filter.parse_directives(&directives);
+        }
+        filter
+    }
+
+    /// Applies comma separated env-logger directives: `level`, `module` or `module=level`.
+    /// Invalid directives and regex filters are ignored.
+    fn parse_directives(&mut self, directives: &str) {
+        let directives = directives.split('/').next().unwrap_or_default();
+        for directive in directives.split(',').map(str::trim) {
+            let mut parts = directive.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(""), None) => (),
+                (Some(module), None) => match parse_level(module) {
+                    Ok(level) => self.default_level = level,
+                    Err(_) => {
+                        self.modules.insert(module.to_string(), Some(Level::Trace));
+                    }
+                },
+                (Some(module), Some(level)) => {
+                    if let Ok(level) = parse_level(level) {
+                        self.modules.insert(module.to_string(), level);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn level(&self, module: &str) -> Option<Level> {
+        self.modules
+            .iter()
+            .rev()
+            .find(|(prefix, _)| module.starts_with(prefix.as_str()))
+            .map_or(self.default_level, |(_, level)| *level)
+    }
+
+    fn is_enabled(&self, module: &str, level: Level) -> bool {
+        match self.level(module) {
+            Some(max_level) => level.as_usize() <= max_level.as_usize(),
+            None => false,
+        }
+    }
+}
+
+fn parse_level(level: &str) -> Result<Option<Level>> {
+    Ok(Some(match level.to_lowercase().as_str() {
+        "off" => return Ok(None),
+        "critical" | "crit" => Level::Critical,
+        "error" => Level::Error,
+        "warning" | "warn" => Level::Warning,
+        "info" => Level::Info,
+        "debug" => Level::Debug,
+        "trace" => Level::Trace,
+        _ => bail!("Invalid log level: {}", level),
+    }))
+}
+
+fn level_name(level: Option<Level>) -> &'static str {
+    match level {
+        None => "off",
+        Some(Level::Critical) => "critical",
+        Some(Level::Error) => "error",
+        Some(Level::Warning) => "warn",
+        Some(Level::Info) => "info",
+        Some(Level::Debug) => "debug",
+        Some(Level::Trace) => "trace",
+    }
+}
+
+/// Sets the level of the module path prefix `module`, or the default level if `module` is empty.
+/// An empty `level` removes the level of `module`, so that it falls back to the default one.
+pub fn set_log_level(module: &str, level: &str) -> Result<()> {
+    let level = if level.is_empty() {
+        ensure!(
+            !module.is_empty(),
+            "The default log level cannot be removed"
+        );
+        None
+    } else {
+        Some(parse_level(level)?)
+    };
+    LOG_FILTER.rcu(|filter| {
+        let mut filter = LogFilter::clone(filter);
+        match level {
+            Some(level) if module.is_empty() => filter.default_level = level,
+            Some(level) => {
+                filter.modules.insert(module.to_string(), level);
+            }
+            None => {
+                filter.modules.remove(module);
+            }
+        }
+        filter
+    });
+    Ok(())
+}
+
+/// Returns the default log level and the levels of the modules that override it.
+pub fn log_levels() -> (String, BTreeMap<String, String>) {
+    let filter = LOG_FILTER.load();
+    let modules = filter
+        .modules
+        .iter()
+        .map(|(module, level)| (module.clone(), level_name(*level).to_string()))
+        .collect();
+    (level_name(filter.default_level).to_string(), modules)
+}
+
+/// A drain that drops the records disabled by the global `LogFilter`.
+pub struct RuntimeLevelFilter<D>(pub D);
+
+impl<D: Drain<Ok = ()>> Drain for RuntimeLevelFilter<D> {
+    type Ok = ();
+    type Err = D::Err;
+
+    fn log(&self, record: &Record<'_>, values: &OwnedKVList) -> ::std::result::Result<(), D::Err> {
+        if LOG_FILTER
+            .load()
+            .is_enabled(record.module(), record.level())
+        {
+            self.0.log(record, values)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_directives() {
+        let mut filter = LogFilter::default();
+        filter.parse_directives("warn, consensus=trace,network,storage=off/regex");
+        assert_eq!(filter.default_level, Some(Level::Warning));
+        assert_eq!(filter.modules["consensus"], Some(Level::Trace));
+        assert_eq!(filter.modules["network"], Some(Level::Trace));
+        assert_eq!(filter.modules["storage"], None);
+
+        filter.parse_directives("consensus=invalid");
+        assert_eq!(filter.modules["consensus"], Some(Level::Trace));
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let mut filter = LogFilter::default();
+        filter.parse_directives("consensus=debug,consensus::chained_bft::sync_manager=off");
+        assert!(filter.is_enabled("consensus::chained_bft", Level::Debug));
+        assert!(!filter.is_enabled("consensus::chained_bft", Level::Trace));
+        assert!(!filter.is_enabled("consensus::chained_bft::sync_manager", Level::Critical));
+        assert!(filter.is_enabled("mempool", Level::Info));
+        assert!(!filter.is_enabled("mempool", Level::Debug));
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("TRACE").unwrap(), Some(Level::Trace));
+        assert_eq!(parse_level("off").unwrap(), None);
+        assert!(parse_level("verbose").is_err());
+        for level in &["off", "critical", "error", "warn", "info", "debug", "trace"] {
+            assert_eq!(level_name(parse_level(level).unwrap()), *level);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keeps the most recent lines written by the default logger and streams new ones to
+//! subscribers, e.g. the log stream of the debug interface of a node.
+
+use futures::sync::mpsc;
+use lazy_static::lazy_static;
+use std::{collections::VecDeque, io, mem, sync::Mutex};
+
+const MAX_RECENT_LINES: usize = 1_000;
+
+lazy_static! {
+    static ref RECENT_LOGS: Mutex<RecentLogs> = Mutex::new(RecentLogs::default());
+}
+
+#[derive(Default)]
+struct RecentLogs {
+    lines: VecDeque<String>,
+    subscribers: Vec<mpsc::Sender<String>>,
+}
+
+impl RecentLogs {
+    fn push(&mut self, line: String) {
+        // A subscriber that does not keep up misses lines rather than blocking the logger.
+        self.subscribers = mem::replace(&mut self.subscribers, vec![])
+            .into_iter()
+            .filter_map(|mut subscriber| match subscriber.try_send(line.clone()) {
+                Err(ref e) if e.is_disconnected() => None,
+                _ => Some(subscriber),
+            })
+            .collect();
+        if self.lines.len() == MAX_RECENT_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+}
+
+/// Returns the most recent log lines, oldest first, and a receiver of the lines logged after
+/// them. The receiver buffers up to `buffer` lines and drops the next ones until it is read.
+pub fn subscribe_to_logs(buffer: usize) -> (Vec<String>, mpsc::Receiver<String>) {
+    let (tx, rx) = mpsc::channel(buffer);
+    let mut recent_logs = RECENT_LOGS.lock().unwrap();
+    recent_logs.subscribers.push(tx);
+    (recent_logs.lines.iter().cloned().collect(), rx)
+}
+
+/// Output of the default logger: writes every line to `inner` and records it in the recent logs,
+/// so that each record is formatted only once for both.
+pub struct RecentLogsWriter<W> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: io::Write> RecentLogsWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buffer: vec![],
+        }
+    }
+}
+
+impl<W: io::Write> io::Write for RecentLogsWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        let mut rest = &buf[..written];
+        while let Some(end) = rest.iter().position(|byte| *byte == b'\n') {
+            self.buffer.extend_from_slice(&rest[..end]);
+            let line = mem::replace(&mut self.buffer, vec![]);
+            RECENT_LOGS
+                .lock()
+                .unwrap()
+                .push(String::from_utf8_lossy(&line).into_owned());
+            rest = &rest[end + 1..];
+        }
+        self.buffer.extend_from_slice(rest);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
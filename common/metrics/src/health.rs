@@ -0,0 +1,103 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Health checks of the node, served by the metric server on `/health` and `/ready`.
+//!
+//! Components register named checks at startup. `/health` runs the liveness checks, which fail
+//! when the node is broken, e.g. its storage is unreachable. `/ready` runs every check, including
+//! the readiness ones, which fail while the node is not keeping up, e.g. consensus is not making
+//! progress. Both answer 200 if all their checks pass and 503 otherwise, with a JSON body that
+//! lists the passing checks and the error of every failing one.
+
+use failure::prelude::*;
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CheckKind {
+    /// Served by both `/health` and `/ready`.
+    Liveness,
+    /// Served by `/ready` only.
+    Readiness,
+}
+
+type Check = Box<dyn Fn() -> Result<()> + Send + Sync>;
+
+lazy_static! {
+    static ref HEALTH_CHECKS: RwLock<BTreeMap<String, (CheckKind, Check)>> =
+        RwLock::new(BTreeMap::new());
+}
+
+/// Registers a check under `name`, replacing the previous check with that name.
+pub fn register_health_check<F>(name: &str, kind: CheckKind, check: F)
+where
+    F: Fn() -> Result<()> + Send + Sync + 'static,
+{
+    HEALTH_CHECKS
+        .write()
+        .unwrap()
+        .insert(name.to_string(), (kind, Box::new(check)));
+}
+
+/// Runs the liveness checks, and the readiness ones if `readiness` is set. Returns whether they
+/// all passed, and the JSON report of the metric server.
+pub fn run_health_checks(readiness: bool) -> (bool, Value) {
+    let mut passing = vec![];
+    let mut failing = BTreeMap::new();
+    for (name, (kind, check)) in HEALTH_CHECKS.read().unwrap().iter() {
+        if *kind == CheckKind::Readiness && !readiness {
+            continue;
+        }
+        match check() {
+            Ok(()) => passing.push(name.clone()),
+            Err(e) => {
+                failing.insert(name.clone(), e.to_string());
+            }
+        }
+    }
+    let healthy = failing.is_empty();
+    let report = json!({
+        "healthy": healthy,
+        "passing": passing,
+        "failing": failing,
+    });
+    (healthy, report)
+}
+
+/// Returns a check that fails once `value` has not increased for longer than `window`. The window
+/// starts when the check is created, so a node that never makes progress fails it even if it is
+/// only checked late.
+pub fn progress_check<F>(
+    name: &'static str,
+    value: F,
+    window: Duration,
+) -> impl Fn() -> Result<()> + Send + Sync
+where
+    F: Fn() -> i64 + Send + Sync,
+{
+    let last_progress = Mutex::new((value(), Instant::now()));
+    move || {
+        let current = value();
+        let now = Instant::now();
+        let mut last_progress = last_progress.lock().unwrap();
+        let (last_value, since) = *last_progress;
+        if current > last_value {
+            *last_progress = (current, now);
+        } else {
+            let stalled = now.duration_since(since);
+            ensure!(
+                stalled <= window,
+                "{} has been stuck at {} for {} s",
+                name,
+                current,
+                stalled.as_secs()
+            );
+        }
+        Ok(())
+    }
+}
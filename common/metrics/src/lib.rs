@@ -7,6 +7,7 @@
 extern crate prometheus;
 
 pub mod counters;
+pub mod health;
 mod json_encoder;
 pub mod metric_server;
 
@@ -1,7 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::json_encoder::JsonEncoder;
+use crate::{health, json_encoder::JsonEncoder};
 use futures::future;
 use hyper::{
     rt::{self, Future},
@@ -34,6 +34,13 @@ fn serve_metrics(req: Request<Body>) -> impl Future<Item = Response<Body>, Error
             let buffer = encode_metrics(encoder);
             *resp.body_mut() = Body::from(buffer);
         }
+        (&Method::GET, path @ "/health") | (&Method::GET, path @ "/ready") => {
+            let (healthy, report) = health::run_health_checks(path == "/ready");
+            if !healthy {
+                *resp.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+            }
+            *resp.body_mut() = Body::from(report.to_string());
+        }
         _ => {
             *resp.status_mut() = StatusCode::NOT_FOUND;
         }
@@ -0,0 +1,54 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::health::*;
+use failure::prelude::*;
+use rusty_fork::{rusty_fork_id, rusty_fork_test, rusty_fork_test_name};
+use std::{
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+rusty_fork_test! {
+#[test]
+fn health_and_readiness_test() {
+    register_health_check("storage", CheckKind::Liveness, || Ok(()));
+    register_health_check("consensus", CheckKind::Readiness, || bail!("no progress"));
+
+    let (healthy, report) = run_health_checks(false);
+    assert!(healthy);
+    assert_eq!(report["passing"][0], "storage");
+    assert!(report["failing"].as_object().unwrap().is_empty());
+
+    let (healthy, report) = run_health_checks(true);
+    assert!(!healthy);
+    assert_eq!(report["healthy"], false);
+    assert_eq!(report["failing"]["consensus"], "no progress");
+
+    // Registering a check again replaces it.
+    register_health_check("consensus", CheckKind::Readiness, || Ok(()));
+    assert!(run_health_checks(true).0);
+}
+}
+
+#[test]
+fn progress_check_test() {
+    let value = Arc::new(AtomicI64::new(0));
+    let value_clone = Arc::clone(&value);
+    let check = progress_check(
+        "round",
+        move || value_clone.load(Ordering::SeqCst),
+        Duration::from_millis(50),
+    );
+    // The window starts when the check is created, not at its first run.
+    thread::sleep(Duration::from_millis(100));
+    assert!(check().is_err());
+
+    value.store(1, Ordering::SeqCst);
+    assert!(check().is_ok());
+    assert!(check().is_ok());
+}
@@ -196,6 +196,8 @@ impl SwarmConfig {
             execution: template.execution.clone(),
             admission_control: template.admission_control.clone(),
             debug_interface: template.debug_interface.clone(),
+            trace: template.trace.clone(),
+            failpoints: template.failpoints.clone(),
             storage: template.storage.clone(),
             mempool: template.mempool.clone(),
             state_sync: template.state_sync.clone(),
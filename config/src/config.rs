@@ -12,7 +12,7 @@ use crate::{
 };
 use crypto::{ed25519::Ed25519PublicKey, ValidKey};
 use failure::prelude::*;
-use logger::LoggerType;
+use logger::{LogFormat, LoggerType};
 use parity_multiaddr::{Multiaddr, Protocol};
 use proto_conv::FromProtoBytes;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -59,6 +59,12 @@ pub struct NodeConfig {
     pub admission_control: AdmissionControlConfig,
     #[serde(default)]
     pub debug_interface: DebugInterfaceConfig,
+    #[serde(default)]
+    pub trace: TraceConfig,
+    // Failpoints to configure at startup, keyed by failpoint name. Requires a node built with the
+    // `failpoints` feature
+    #[serde(default)]
+    pub failpoints: HashMap<String, String>,
 
     #[serde(default)]
     pub storage: StorageConfig,
@@ -213,6 +219,8 @@ pub struct LoggerConfig {
     pub is_async: bool,
     pub chan_size: Option<usize>,
     pub use_std_output: bool,
+    // Output format of the node logs: "glog" or "json"
+    pub format: LogFormat,
 }
 
 impl Default for LoggerConfig {
@@ -222,6 +230,7 @@ impl Default for LoggerConfig {
             is_async: true,
             chan_size: None,
             use_std_output: true,
+            format: LogFormat::Glog,
         }
     }
 }
@@ -286,6 +295,12 @@ pub struct DebugInterfaceConfig {
     // This has similar use to the core-node-debug-server itself
     pub metrics_server_port: u16,
     pub address: String,
+    // The node is not ready if consensus has not committed a block for this long
+    pub consensus_progress_window_ms: u64,
+    // The node is not ready if state sync is more versions behind than this
+    pub max_state_sync_lag: u64,
+    // Health checks that call another service fail if it does not answer within this time
+    pub health_check_timeout_ms: u64,
 }
 
 impl Default for DebugInterfaceConfig {
@@ -296,6 +311,30 @@ impl Default for DebugInterfaceConfig {
             secret_service_node_debug_port: 6195,
             metrics_server_port: 9101,
             address: "localhost".to_string(),
+            consensus_progress_window_ms: 60_000,
+            max_state_sync_lag: 1_000,
+            health_check_timeout_ms: 1_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TraceConfig {
+    // Whether spans of transactions and blocks are recorded at all
+    pub enabled: bool,
+    // If set, recorded spans are appended to this file as OTLP JSON lines instead of being kept
+    // for the GetTraces call of the debug interface
+    pub output_path: Option<PathBuf>,
+    pub flush_interval_ms: u64,
+}
+
+impl Default for TraceConfig {
+    fn default() -> TraceConfig {
+        TraceConfig {
+            enabled: false,
+            output_path: None,
+            flush_interval_ms: 1000,
         }
     }
 }
@@ -118,7 +118,7 @@ impl<T: Payload> BlockStore<T> {
             .collect::<HashMap<_, _>>();
         for block in blocks {
             let compute_res = state_computer
-                .compute(block.parent_id(), block.id(), block.get_payload())
+                .compute(&block)
                 .await
                 .expect("fail to rebuild scratchpad");
             // if this block is certified, ensure we agree with the certified state.
@@ -181,24 +181,17 @@ impl<T: Payload> BlockStore<T> {
         if let Some(existing_block) = self.get_block(block.id()) {
             return Ok(existing_block);
         }
-        let parent_id = match self.verify_and_get_parent_id(&block) {
-            Ok(t) => t,
-            Err(e) => {
-                security_log(SecurityEvent::InvalidBlock)
-                    .error(&e)
-                    .data(&block)
-                    .log();
-                return Err(e);
-            }
-        };
-        let compute_res = self
-            .state_computer
-            .compute(parent_id, block.id(), block.get_payload())
-            .await
-            .map_err(|e| {
-                error!("Execution failure for block {}: {:?}", block, e);
-                InsertError::StateComputerError
-            })?;
+        if let Err(e) = self.verify_and_get_parent_id(&block) {
+            security_log(SecurityEvent::InvalidBlock)
+                .error(&e)
+                .data(&block)
+                .log();
+            return Err(e);
+        }
+        let compute_res = self.state_computer.compute(&block).await.map_err(|e| {
+            error!("Execution failure for block {}: {:?}", block, e);
+            InsertError::StateComputerError
+        })?;
 
         self.storage
             .save_tree(vec![block.clone()], vec![])
@@ -11,13 +11,14 @@ use crate::{
             vote_msg::VoteMsg,
         },
         network::ConsensusNetworkImpl,
-        network_tests::NetworkPlayground,
+        network_router::{LinkChange, LinkCondition, NetworkRouter},
+        network_tests::{NetworkPlayground, TestNetwork},
         test_utils::{MockStateComputer, MockStorage, MockTransactionManager, TestPayload},
     },
     state_replication::StateMachineReplication,
 };
 use channel;
-use crypto::hash::CryptoHash;
+use crypto::{hash::CryptoHash, HashValue};
 use futures::{channel::mpsc, executor::block_on, prelude::*};
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
 use proto_conv::FromProto;
@@ -32,7 +33,11 @@ use crate::chained_bft::{
 use config::config::ConsensusProposerType::{
     self, FixedProposer, MultipleOrderedProposers, RotatingProposer,
 };
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    thread,
+    time::{Duration, Instant},
+};
 use tokio::runtime;
 use types::crypto_proxies::{LedgerInfoWithSignatures, ValidatorSigner, ValidatorVerifier};
 
@@ -53,7 +58,7 @@ struct SMRNode {
 
 impl SMRNode {
     fn start(
-        playground: &mut NetworkPlayground,
+        playground: &mut impl TestNetwork,
         signer: ValidatorSigner,
         epoch_mgr: Arc<EpochManager>,
         proposer: Vec<Author>,
@@ -123,7 +128,7 @@ impl SMRNode {
         }
     }
 
-    fn restart(mut self, playground: &mut NetworkPlayground) -> Self {
+    fn restart(mut self, playground: &mut impl TestNetwork) -> Self {
         self.smr.stop();
         let recover_data = self
             .storage
@@ -144,7 +149,7 @@ impl SMRNode {
     fn start_num_nodes(
         num_nodes: usize,
         quorum_size: usize,
-        playground: &mut NetworkPlayground,
+        playground: &mut impl TestNetwork,
         proposer_type: ConsensusProposerType,
     ) -> Vec<Self> {
         let mut signers = vec![];
@@ -871,3 +876,173 @@ fn secondary_proposers() {
         assert_eq!(secondary_proposal_committed, true);
     });
 }
+
+// The partition tests below wait on real round timeouts and take a while to run, so they are
+// ignored by default: run them with `cargo test -- --ignored`.
+
+/// How long the nodes may go without committing a block before a test gives up on them.
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Drains the commit notifications of every node into `committed` until `done` holds for the
+/// blocks committed by each node. Returns false once no node has committed a block for
+/// `STALL_TIMEOUT`: a slow machine only makes a test slower as long as the nodes make progress.
+fn collect_commits(
+    nodes: &mut [SMRNode],
+    committed: &mut [Vec<HashValue>],
+    done: impl Fn(usize, &[HashValue]) -> bool,
+) -> bool {
+    let mut deadline = Instant::now() + STALL_TIMEOUT;
+    loop {
+        for (node, ids) in nodes.iter_mut().zip(committed.iter_mut()) {
+            while let Ok(Some(li)) = node.commit_cb_receiver.try_next() {
+                ids.push(li.ledger_info().consensus_block_id());
+                deadline = Instant::now() + STALL_TIMEOUT;
+            }
+        }
+        if committed.iter().enumerate().all(|(i, ids)| done(i, ids)) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Returns a predicate that holds once a node has committed a block of a round above `round`.
+fn committed_after(
+    router: &NetworkRouter,
+    round: u64,
+) -> impl Fn(usize, &[HashValue]) -> bool + '_ {
+    move |_, ids| {
+        ids.iter()
+            .any(|id| router.block_round(*id).map_or(false, |r| r > round))
+    }
+}
+
+/// Returns the highest round among the blocks committed so far, or 0 if nothing was committed.
+fn highest_committed_round(router: &NetworkRouter, committed: &[Vec<HashValue>]) -> u64 {
+    committed
+        .iter()
+        .flatten()
+        .filter_map(|id| router.block_round(*id))
+        .max()
+        .unwrap_or(0)
+}
+
+fn all_commits(committed: &[Vec<HashValue>]) -> Vec<HashValue> {
+    committed.iter().flatten().cloned().collect()
+}
+
+#[test]
+#[ignore]
+/// Split 4 nodes into two halves, neither of which has a quorum, then heal the partition: the
+/// nodes never commit conflicting blocks and all of them make progress after the heal.
+fn partition_and_heal() {
+    let runtime = consensus_runtime();
+    let mut router = NetworkRouter::new(runtime.executor(), 1);
+    let mut nodes = SMRNode::start_num_nodes(4, 3, &mut router, RotatingProposer);
+    let authors: Vec<_> = nodes.iter().map(|node| node.author).collect();
+
+    let mut committed = vec![vec![]; nodes.len()];
+    assert!(
+        collect_commits(&mut nodes, &mut committed, |_, ids| !ids.is_empty()),
+        "Nodes did not commit before the partition"
+    );
+    router.apply(LinkChange::Partition(vec![
+        authors[..2].to_vec(),
+        authors[2..].to_vec(),
+    ]));
+    let round_at_partition = router.highest_round();
+    // Every node has to time out while partitioned; no new block can be certified meanwhile.
+    assert!(
+        collect_commits(&mut nodes, &mut committed, |_, _| router
+            .cut_timeout_senders()
+            .len()
+            == authors.len()),
+        "Nodes did not time out during the partition"
+    );
+    assert!(
+        highest_committed_round(&router, &committed) <= round_at_partition,
+        "Nodes committed a block proposed during the partition"
+    );
+
+    router.apply(LinkChange::Heal);
+    let round_at_heal = router.highest_round();
+    assert!(
+        collect_commits(
+            &mut nodes,
+            &mut committed,
+            committed_after(&router, round_at_heal),
+        ),
+        "Nodes did not commit after the partition healed"
+    );
+    router.assert_no_conflicting_commits(&all_commits(&committed));
+}
+
+#[test]
+#[ignore]
+/// Isolate one of 4 nodes: the remaining quorum keeps committing and the isolated node catches
+/// up once it is reconnected.
+fn isolated_minority_catches_up() {
+    let runtime = consensus_runtime();
+    let mut router = NetworkRouter::new(runtime.executor(), 2);
+    let mut nodes = SMRNode::start_num_nodes(4, 3, &mut router, RotatingProposer);
+    let authors: Vec<_> = nodes.iter().map(|node| node.author).collect();
+
+    let mut committed = vec![vec![]; nodes.len()];
+    assert!(
+        collect_commits(&mut nodes, &mut committed, |_, ids| !ids.is_empty()),
+        "Nodes did not commit before the partition"
+    );
+    router.apply(LinkChange::Partition(vec![
+        authors[..3].to_vec(),
+        authors[3..].to_vec(),
+    ]));
+    // Let the majority get well ahead, so that node 3 has to retrieve what it missed.
+    let round_at_partition = router.highest_round();
+    let majority_progress = committed_after(&router, round_at_partition + 10);
+    assert!(
+        collect_commits(&mut nodes, &mut committed, |i, ids| i == 3
+            || majority_progress(i, ids)),
+        "The majority did not commit while node 3 was isolated"
+    );
+    assert!(
+        !committed_after(&router, round_at_partition)(3, &committed[3]),
+        "Node 3 committed a block proposed while it was isolated"
+    );
+
+    router.apply(LinkChange::Heal);
+    let round_at_heal = router.highest_round();
+    assert!(
+        collect_commits(
+            &mut nodes,
+            &mut committed,
+            committed_after(&router, round_at_heal),
+        ),
+        "Node 3 did not catch up after the partition healed"
+    );
+    router.assert_no_conflicting_commits(&all_commits(&committed));
+}
+
+#[test]
+#[ignore]
+/// Run 4 nodes over links that drop, duplicate and reorder messages: the nodes keep committing
+/// and never commit conflicting blocks.
+fn lossy_links() {
+    let runtime = consensus_runtime();
+    let mut router = NetworkRouter::new(runtime.executor(), 3);
+    router.apply(LinkChange::SetAll(LinkCondition {
+        drop_probability: 0.05,
+        duplicate_probability: 0.1,
+        ..LinkCondition::with_latency(Duration::from_millis(0), Duration::from_millis(50))
+    }));
+    let mut nodes = SMRNode::start_num_nodes(4, 3, &mut router, RotatingProposer);
+
+    let mut committed = vec![vec![]; nodes.len()];
+    assert!(
+        collect_commits(&mut nodes, &mut committed, committed_after(&router, 10)),
+        "Nodes did not reach round 10 over lossy links"
+    );
+    router.assert_no_conflicting_commits(&all_commits(&committed));
+}
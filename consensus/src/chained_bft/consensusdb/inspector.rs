@@ -0,0 +1,188 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read-only inspection of the ConsensusDB of a node that is not running, e.g. to understand why
+//! a validator got stuck.
+
+use crate::chained_bft::{
+    common::Payload,
+    consensus_types::{block::Block, quorum_cert::QuorumCert},
+    consensusdb::ConsensusDB,
+    liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
+    safety::safety_rules::ConsensusState,
+};
+use crypto::HashValue;
+use failure::prelude::*;
+use rmp_serde::from_slice;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display, Formatter},
+    path::Path,
+};
+use types::transaction::SignedTransaction;
+
+/// Summary of the data persisted in the ConsensusDB of a node.
+#[derive(Debug)]
+pub struct ConsensusDbSummary {
+    /// The safety rules state, including the round of the last vote.
+    pub consensus_state: Option<String>,
+    /// The highest timeout certificate, locally generated or received.
+    pub highest_timeout_certificate: Option<String>,
+    /// The quorum certificate with the highest certified round.
+    pub highest_quorum_cert: Option<String>,
+    /// The ledger info of the quorum certificate that commits the highest round.
+    pub highest_commit_ledger_info: Option<String>,
+    /// The persisted blocks in depth first order, indented by their depth in the tree of blocks.
+    /// Certified blocks are marked as such.
+    pub block_tree: Vec<String>,
+}
+
+impl Display for ConsensusDbSummary {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let none = "None".to_string();
+        writeln!(f, "{}", self.consensus_state.as_ref().unwrap_or(&none))?;
+        writeln!(
+            f,
+            "Highest timeout certificate: {}",
+            self.highest_timeout_certificate.as_ref().unwrap_or(&none)
+        )?;
+        writeln!(
+            f,
+            "Highest quorum cert: {}",
+            self.highest_quorum_cert.as_ref().unwrap_or(&none)
+        )?;
+        writeln!(
+            f,
+            "Highest commit: {}",
+            self.highest_commit_ledger_info.as_ref().unwrap_or(&none)
+        )?;
+        writeln!(f, "Block tree ({} blocks):", self.block_tree.len())?;
+        for line in &self.block_tree {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Opens the ConsensusDB under `db_root_path` read-only and summarizes its content.
+pub fn inspect_consensus_db<P: AsRef<Path>>(db_root_path: P) -> Result<ConsensusDbSummary> {
+    let db = ConsensusDB::open_readonly(db_root_path)?;
+    summarize::<Vec<SignedTransaction>>(&db)
+}
+
+fn summarize<T: Payload>(db: &ConsensusDB) -> Result<ConsensusDbSummary> {
+    let (consensus_state, highest_timeout_certificates, blocks, quorum_certs) = db.get_data()?;
+    let consensus_state = consensus_state
+        .map(|s| from_slice::<ConsensusState>(&s[..]))
+        .transpose()?
+        .map(|state| state.to_string());
+    let highest_timeout_certificate = highest_timeout_certificates
+        .map(|s| from_slice::<HighestTimeoutCertificates>(&s[..]))
+        .transpose()?
+        .and_then(|certs| certs.highest_timeout_certificate().map(ToString::to_string));
+    let highest_quorum_cert = quorum_certs
+        .iter()
+        .max_by_key(|qc| qc.certified_block_round())
+        .map(ToString::to_string);
+    let highest_commit_ledger_info = quorum_certs
+        .iter()
+        .filter(|qc| qc.committed_block_id().is_some())
+        .max_by_key(|qc| qc.certified_block_round())
+        .map(|qc| qc.ledger_info().to_string());
+
+    Ok(ConsensusDbSummary {
+        consensus_state,
+        highest_timeout_certificate,
+        highest_quorum_cert,
+        highest_commit_ledger_info,
+        block_tree: block_tree(blocks, &quorum_certs),
+    })
+}
+
+/// Renders the blocks as a forest: the blocks whose parent is not persisted are the roots.
+fn block_tree<T: Payload>(mut blocks: Vec<Block<T>>, quorum_certs: &[QuorumCert]) -> Vec<String> {
+    blocks.sort_by_key(Block::round);
+    let ids: HashSet<HashValue> = blocks.iter().map(Block::id).collect();
+    let certified: HashSet<HashValue> = quorum_certs
+        .iter()
+        .map(QuorumCert::certified_block_id)
+        .collect();
+    let mut children: HashMap<HashValue, Vec<&Block<T>>> = HashMap::new();
+    let mut roots = vec![];
+    for block in &blocks {
+        if block.round() > 0 && ids.contains(&block.parent_id()) {
+            children.entry(block.parent_id()).or_default().push(block);
+        } else {
+            roots.push(block);
+        }
+    }
+
+    let mut lines = vec![];
+    let mut stack: Vec<(&Block<T>, usize)> = roots.into_iter().rev().map(|b| (b, 0)).collect();
+    while let Some((block, depth)) = stack.pop() {
+        lines.push(format!(
+            "{}{}{}",
+            "  ".repeat(depth),
+            block,
+            if certified.contains(&block.id()) {
+                " (certified)"
+            } else {
+                ""
+            }
+        ));
+        if let Some(block_children) = children.get(&block.id()) {
+            stack.extend(block_children.iter().rev().map(|b| (*b, depth + 1)));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chained_bft::test_utils::placeholder_certificate_for_block;
+    use tools::tempdir::TempPath;
+    use types::validator_signer::ValidatorSigner;
+
+    #[test]
+    fn test_inspect_consensus_db() {
+        let tmp_dir = TempPath::new();
+        let db = ConsensusDB::new(&tmp_dir);
+        let signer = ValidatorSigner::random(None);
+
+        let genesis = Block::<i64>::make_genesis_block();
+        let genesis_qc = QuorumCert::certificate_for_genesis();
+        let block_1 = Block::make_block(&genesis, 1, 1, 1, genesis_qc.clone(), &signer);
+        let block_2 = Block::make_block(&genesis, 2, 2, 2, genesis_qc.clone(), &signer);
+        let qc_1 = placeholder_certificate_for_block(
+            vec![&signer],
+            block_1.id(),
+            block_1.round(),
+            genesis.id(),
+            genesis.round(),
+            HashValue::zero(),
+            0,
+        );
+        db.save_blocks_and_quorum_certificates(
+            vec![genesis.clone(), block_1.clone(), block_2.clone()],
+            vec![genesis_qc, qc_1],
+        )
+        .unwrap();
+
+        let summary = summarize::<i64>(&db).unwrap();
+        assert!(summary.consensus_state.is_none());
+        assert!(summary.highest_timeout_certificate.is_none());
+        assert!(summary
+            .highest_quorum_cert
+            .unwrap()
+            .contains(&format!("{}", block_1.id())));
+        assert_eq!(
+            summary.block_tree,
+            vec![
+                format!("{} (certified)", genesis),
+                format!("  {} (certified)", block_1),
+                format!("  {}", block_2),
+            ]
+        );
+    }
+}
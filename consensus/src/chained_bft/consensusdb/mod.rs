@@ -3,6 +3,7 @@
 
 #[cfg(test)]
 mod consensusdb_test;
+mod inspector;
 mod schema;
 
 use crate::chained_bft::{
@@ -16,6 +17,7 @@ use crate::chained_bft::{
 };
 use crypto::HashValue;
 use failure::prelude::*;
+pub use inspector::{inspect_consensus_db, ConsensusDbSummary};
 use logger::prelude::*;
 use schema::{BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
 use schemadb::{
@@ -31,8 +33,8 @@ pub struct ConsensusDB {
 }
 
 impl ConsensusDB {
-    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let cf_opts_map: ColumnFamilyOptionsMap = [
+    fn column_families() -> ColumnFamilyOptionsMap {
+        [
             (
                 /* UNUSED CF = */ DEFAULT_CF_NAME,
                 ColumnFamilyOptions::default(),
@@ -43,11 +45,13 @@ impl ConsensusDB {
         ]
         .iter()
         .cloned()
-        .collect();
+        .collect()
+    }
 
+    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
         let path = db_root_path.as_ref().join("consensusdb");
         let instant = Instant::now();
-        let db = DB::open(path.clone(), cf_opts_map).unwrap_or_else(|e| {
+        let db = DB::open(path.clone(), Self::column_families()).unwrap_or_else(|e| {
             panic!("ConsensusDB open failed due to {:?}, unable to continue", e)
         });
 
@@ -60,6 +64,14 @@ impl ConsensusDB {
         Self { db }
     }
 
+    /// Opens an existing ConsensusDB read-only, e.g. to inspect the DB of a node that is not
+    /// running.
+    pub fn open_readonly<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let path = db_root_path.as_ref().join("consensusdb");
+        let db = DB::open_readonly(path, Self::column_families())?;
+        Ok(Self { db })
+    }
+
     pub fn get_data<T: Payload>(
         &self,
     ) -> Result<(
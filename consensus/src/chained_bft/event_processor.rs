@@ -35,7 +35,7 @@ use crate::{
         duration_since_epoch, wait_if_possible, TimeService, WaitingError, WaitingSuccess,
     },
 };
-use logger::prelude::*;
+use logger::{log_context::update_node_log_context, prelude::*};
 use mirai_annotations::{
     debug_checked_precondition, debug_checked_precondition_eq, debug_checked_verify,
     debug_checked_verify_eq,
@@ -133,6 +133,7 @@ impl<T: Payload> EventProcessor<T> {
     async fn process_new_round_event(&self, new_round_event: NewRoundEvent) {
         debug!("Processing {}", new_round_event);
         counters::CURRENT_ROUND.set(new_round_event.round as i64);
+        update_node_log_context(|context| context.round = Some(new_round_event.round));
         counters::ROUND_TIMEOUT_MS.set(new_round_event.timeout.as_millis() as i64);
         match new_round_event.reason {
             NewRoundReason::QCReady => {
@@ -514,6 +515,12 @@ impl<T: Payload> EventProcessor<T> {
             .block_store
             .get_quorum_cert_for_block(proposal.parent_id())
             .is_some());
+        let _span = trace_span!(
+            "consensus::process_proposed_block",
+            proposal.id().as_ref(),
+            "round": proposal.round(),
+            "parent_id": proposal.parent_id().short_str(),
+        );
 
         if let Some(time_to_receival) =
             duration_since_epoch().checked_sub(Duration::from_micros(proposal.timestamp_usecs()))
@@ -775,6 +782,11 @@ impl<T: Payload> EventProcessor<T> {
         if committed_block.round() <= self.block_store.root().round() {
             return;
         }
+        let _span = trace_span!(
+            "consensus::process_commit",
+            committed_block.id().as_ref(),
+            "round": committed_block.round(),
+        );
 
         // Verify that the ledger info is indeed for the block we're planning to
         // commit.
@@ -782,6 +794,7 @@ impl<T: Payload> EventProcessor<T> {
             finality_proof.ledger_info().consensus_block_id(),
             committed_block.id()
         );
+        let epoch = finality_proof.ledger_info().epoch_num();
 
         if let Err(e) = self.state_computer.commit(finality_proof).await {
             // We assume that state computer cannot enter an inconsistent state that might
@@ -794,6 +807,7 @@ impl<T: Payload> EventProcessor<T> {
             );
             return;
         }
+        update_node_log_context(|context| context.epoch = Some(epoch));
         // At this moment the new state is persisted and we can notify the clients.
         // Multiple blocks might be committed at once: notify about all the transactions in the
         // path from the old root to the new root.
@@ -885,4 +899,10 @@ impl<T: Payload> EventProcessor<T> {
     pub fn consensus_state(&self) -> ConsensusState {
         self.safety_rules.consensus_state()
     }
+
+    /// Makes the choice of peers for block retrieval reproducible.
+    #[cfg(test)]
+    pub fn set_sync_rng_seed(&mut self, seed: u64) {
+        self.sync_manager.set_rng_seed(seed);
+    }
 }
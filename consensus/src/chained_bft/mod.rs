@@ -8,7 +8,8 @@ mod safety;
 
 mod block_storage;
 pub mod chained_bft_consensus_provider;
-pub use consensus_types::quorum_cert::QuorumCert;
+pub use consensus_types::{block::Block, quorum_cert::QuorumCert};
+pub use consensusdb::{inspect_consensus_db, ConsensusDbSummary};
 mod chained_bft_smr;
 mod network;
 
@@ -19,9 +20,13 @@ mod sync_manager;
 #[cfg(test)]
 mod chained_bft_smr_test;
 #[cfg(test)]
+mod network_router;
+#[cfg(test)]
 mod network_tests;
 #[cfg(test)]
 mod proto_test;
+#[cfg(test)]
+mod simulator;
 
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod test_utils;
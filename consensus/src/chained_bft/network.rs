@@ -175,6 +175,17 @@ impl ConsensusNetworkImpl {
         }
     }
 
+    /// Takes the receiver of the messages this node sends to itself, so that a test can deliver
+    /// them without starting the network task.
+    #[cfg(test)]
+    pub fn take_self_receiver(
+        &mut self,
+    ) -> channel::Receiver<Result<Event<ConsensusMsg>, failure::Error>> {
+        self.self_receiver
+            .take()
+            .expect("[consensus]: self receiver is already taken")
+    }
+
     /// Tries to retrieve num of blocks backwards starting from id from the given peer: the function
     /// returns a future that is either fulfilled with BlockRetrievalResponse, or with a
     /// BlockRetrievalFailure.
@@ -0,0 +1,415 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Round},
+    consensus_types::proposal_msg::{ProposalMsg, ProposalUncheckedSignatures},
+    network_tests::TestNetwork,
+    test_utils::TestPayload,
+};
+use channel;
+use crypto::HashValue;
+use failure::prelude::*;
+use futures::{compat::Future01CompatExt, FutureExt, SinkExt, StreamExt, TryFutureExt};
+use network::{
+    interface::{NetworkNotification, NetworkRequest},
+    proto::ConsensusMsg,
+    protocols::{direct_send::Message, rpc::InboundRpcRequest},
+};
+use proto_conv::FromProto;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{runtime::TaskExecutor, timer::Delay};
+
+/// `NetworkRouter` connects in-process consensus nodes like `NetworkPlayground` does, but delivers
+/// messages on its own instead of waiting for the test to pull them. Every message goes through
+/// the `LinkCondition` of its (sender, receiver) pair, which can drop, delay, duplicate it or cut
+/// the link entirely. Delays are drawn per message, so a link with jitter also reorders messages.
+///
+/// Link conditions can be changed while the nodes run, e.g. to partition the network once the
+/// nodes have committed and heal it once they have timed out.
+///
+/// The router records every proposal it routes, so it can check that the blocks committed by
+/// the nodes all lie on a single chain, and the nodes whose timeout messages a partition cut, so
+/// that tests can wait for the nodes to time out instead of waiting for a fixed time.
+pub struct NetworkRouter {
+    node_consensus_txs: Arc<Mutex<HashMap<Author, channel::Sender<NetworkNotification>>>>,
+    state: Arc<Mutex<RouterState>>,
+    executor: TaskExecutor,
+}
+
+/// How messages from one node to another are delivered.
+#[derive(Clone, Debug, Default)]
+pub struct LinkCondition {
+    /// Drop every message, including rpc requests.
+    pub partitioned: bool,
+    /// Probability of dropping a message.
+    pub drop_probability: f64,
+    /// Probability of delivering a direct-send message twice. Rpc requests are never duplicated.
+    pub duplicate_probability: f64,
+    /// Each delivery is delayed by a duration drawn uniformly from this range.
+    pub min_delay: Duration,
+    pub max_delay: Duration,
+}
+
+/// A change to the link conditions.
+#[derive(Clone, Debug)]
+pub enum LinkChange {
+    /// Sets the condition of all links.
+    SetAll(LinkCondition),
+    /// Sets the condition of the link from the first node to the second one.
+    Set(Author, Author, LinkCondition),
+    /// Cuts every link between nodes in different groups, in both directions.
+    Partition(Vec<Vec<Author>>),
+    /// Restores every cut link, keeping the other link conditions.
+    Heal,
+}
+
+struct RouterState {
+    default_condition: LinkCondition,
+    conditions: HashMap<(Author, Author), LinkCondition>,
+    rng: StdRng,
+    /// Parent id and round of every proposed block seen by the router.
+    blocks: HashMap<HashValue, (HashValue, Round)>,
+    /// Nodes that sent a timeout message over a cut link.
+    cut_timeout_senders: HashSet<Author>,
+}
+
+impl LinkCondition {
+    /// A link with the given latency and jitter and no losses.
+    pub fn with_latency(min_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            min_delay,
+            max_delay,
+            ..Self::default()
+        }
+    }
+}
+
+impl RouterState {
+    fn condition(&self, src: Author, dst: Author) -> &LinkCondition {
+        self.conditions
+            .get(&(src, dst))
+            .unwrap_or(&self.default_condition)
+    }
+
+    fn apply(&mut self, change: LinkChange) {
+        match change {
+            LinkChange::SetAll(condition) => {
+                self.default_condition = condition;
+                self.conditions.clear();
+            }
+            LinkChange::Set(src, dst, condition) => {
+                self.conditions.insert((src, dst), condition);
+            }
+            LinkChange::Partition(groups) => {
+                for (i, group) in groups.iter().enumerate() {
+                    for other in groups.iter().skip(i + 1) {
+                        for src in group {
+                            for dst in other {
+                                self.cut(*src, *dst);
+                                self.cut(*dst, *src);
+                            }
+                        }
+                    }
+                }
+            }
+            LinkChange::Heal => {
+                self.default_condition.partitioned = false;
+                for condition in self.conditions.values_mut() {
+                    condition.partitioned = false;
+                }
+            }
+        }
+    }
+
+    fn cut(&mut self, src: Author, dst: Author) {
+        let mut condition = self.condition(src, dst).clone();
+        condition.partitioned = true;
+        self.conditions.insert((src, dst), condition);
+    }
+
+    /// Returns the delays of the copies of a message to deliver, empty if it is dropped.
+    fn plan_delivery(&mut self, src: Author, dst: Author, allow_duplicate: bool) -> Vec<Duration> {
+        let condition = self.condition(src, dst).clone();
+        if condition.partitioned || self.rng.gen_bool(condition.drop_probability) {
+            return vec![];
+        }
+        let num_copies = if allow_duplicate && self.rng.gen_bool(condition.duplicate_probability) {
+            2
+        } else {
+            1
+        };
+        (0..num_copies)
+            .map(|_| {
+                if condition.max_delay > condition.min_delay {
+                    self.rng.gen_range(condition.min_delay, condition.max_delay)
+                } else {
+                    condition.min_delay
+                }
+            })
+            .collect()
+    }
+
+    fn record_message(&mut self, src: Author, dst: Author, msg: &Message) {
+        let mut consensus_msg: ConsensusMsg = match ::protobuf::parse_from_bytes(msg.mdata.as_ref())
+        {
+            Ok(consensus_msg) => consensus_msg,
+            Err(_) => return,
+        };
+        if consensus_msg.has_timeout_msg() && self.condition(src, dst).partitioned {
+            self.cut_timeout_senders.insert(src);
+        }
+        if !consensus_msg.has_proposal() {
+            return;
+        }
+        if let Ok(proposal) =
+            ProposalUncheckedSignatures::<TestPayload>::from_proto(consensus_msg.take_proposal())
+        {
+            let proposal: ProposalMsg<TestPayload> = proposal.into();
+            let block = proposal.proposal();
+            self.blocks
+                .insert(block.id(), (block.parent_id(), block.round()));
+        }
+    }
+}
+
+impl NetworkRouter {
+    /// Creates a router with lossless, instant links. `seed` makes the random drops, duplicates
+    /// and delays reproducible.
+    pub fn new(executor: TaskExecutor, seed: u64) -> Self {
+        Self {
+            node_consensus_txs: Arc::new(Mutex::new(HashMap::new())),
+            state: Arc::new(Mutex::new(RouterState {
+                default_condition: LinkCondition::default(),
+                conditions: HashMap::new(),
+                rng: StdRng::seed_from_u64(seed),
+                blocks: HashMap::new(),
+                cut_timeout_senders: HashSet::new(),
+            })),
+            executor,
+        }
+    }
+
+    /// Applies a change to the link conditions immediately.
+    pub fn apply(&self, change: LinkChange) {
+        self.state.lock().unwrap().apply(change);
+    }
+
+    /// Returns the round of a proposed block, if the router has seen its proposal.
+    pub fn block_round(&self, block_id: HashValue) -> Option<Round> {
+        self.state
+            .lock()
+            .unwrap()
+            .blocks
+            .get(&block_id)
+            .map(|(_, round)| *round)
+    }
+
+    /// Returns the highest round of any proposal the router has seen so far.
+    pub fn highest_round(&self) -> Round {
+        self.state
+            .lock()
+            .unwrap()
+            .blocks
+            .values()
+            .map(|(_, round)| *round)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the nodes that sent a timeout message over a link cut by a partition, i.e. that
+    /// timed out while partitioned.
+    pub fn cut_timeout_senders(&self) -> HashSet<Author> {
+        self.state.lock().unwrap().cut_timeout_senders.clone()
+    }
+
+    /// Panics unless all the committed blocks, gathered from any number of nodes, lie on a
+    /// single chain of proposals. Blocks that were never proposed, i.e. genesis, are ignored.
+    pub fn assert_no_conflicting_commits(&self, committed: &[HashValue]) {
+        if let Err(e) = check_commit_chain(&self.state.lock().unwrap().blocks, committed) {
+            panic!("{}", e);
+        }
+    }
+
+    async fn start_node_outbound_handler(
+        state: Arc<Mutex<RouterState>>,
+        src: Author,
+        mut network_reqs_rx: channel::Receiver<NetworkRequest>,
+        node_consensus_txs: Arc<Mutex<HashMap<Author, channel::Sender<NetworkNotification>>>>,
+        executor: TaskExecutor,
+    ) {
+        while let Some(net_req) = network_reqs_rx.next().await {
+            match net_req {
+                NetworkRequest::SendMessage(dst, msg) => {
+                    let delays = {
+                        let mut state = state.lock().unwrap();
+                        state.record_message(src, dst, &msg);
+                        state.plan_delivery(src, dst, true /* allow_duplicate */)
+                    };
+                    for delay in delays {
+                        let notification = NetworkNotification::RecvMessage(src, msg.clone());
+                        let fut = Self::deliver(
+                            Arc::clone(&node_consensus_txs),
+                            dst,
+                            notification,
+                            delay,
+                        );
+                        executor.spawn(fut.boxed().unit_error().compat());
+                    }
+                }
+                NetworkRequest::SendRpc(dst, outbound_req) => {
+                    let delays = state
+                        .lock()
+                        .unwrap()
+                        .plan_delivery(src, dst, false /* allow_duplicate */);
+                    // A dropped request drops its response channel, which the sender observes
+                    // as a failed rpc.
+                    if let Some(delay) = delays.first() {
+                        let inbound_req = InboundRpcRequest {
+                            protocol: outbound_req.protocol,
+                            data: outbound_req.data,
+                            res_tx: outbound_req.res_tx,
+                        };
+                        let fut = Self::deliver(
+                            Arc::clone(&node_consensus_txs),
+                            dst,
+                            NetworkNotification::RecvRpc(src, inbound_req),
+                            *delay,
+                        );
+                        executor.spawn(fut.boxed().unit_error().compat());
+                    }
+                }
+                NetworkRequest::UpdateEligibleNodes(_) => (),
+            }
+        }
+    }
+
+    async fn deliver(
+        node_consensus_txs: Arc<Mutex<HashMap<Author, channel::Sender<NetworkNotification>>>>,
+        dst: Author,
+        notification: NetworkNotification,
+        delay: Duration,
+    ) {
+        if delay > Duration::from_millis(0) {
+            let _ = Delay::new(Instant::now() + delay).compat().await;
+        }
+        let node_consensus_tx = node_consensus_txs.lock().unwrap().get(&dst).cloned();
+        // The destination may have been stopped in the meantime.
+        if let Some(mut node_consensus_tx) = node_consensus_tx {
+            let _ = node_consensus_tx.send(notification).await;
+        }
+    }
+}
+
+/// Checks that the `committed` blocks lie on a single chain, given the parent id and round of
+/// every known block. Committed blocks missing from `blocks`, i.e. genesis, are ignored.
+pub fn check_commit_chain(
+    blocks: &HashMap<HashValue, (HashValue, Round)>,
+    committed: &[HashValue],
+) -> Result<()> {
+    let mut committed: Vec<_> = committed
+        .iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter_map(|id| blocks.get(id).map(|(_, round)| (*round, *id)))
+        .collect();
+    committed.sort();
+    for pair in committed.windows(2) {
+        let (ancestor_round, ancestor_id) = pair[0];
+        let (round, id) = pair[1];
+        ensure!(
+            ancestor_round != round,
+            "Conflicting commits {} and {} at round {}",
+            ancestor_id,
+            id,
+            round
+        );
+        // Walk back from the later block until the round of the earlier one.
+        let mut current = id;
+        while current != ancestor_id {
+            match blocks.get(&current) {
+                Some((parent_id, current_round)) if *current_round > ancestor_round => {
+                    current = *parent_id;
+                }
+                _ => bail!(
+                    "Committed block {} (round {}) does not extend committed block {} (round {})",
+                    id,
+                    round,
+                    ancestor_id,
+                    ancestor_round
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+impl TestNetwork for NetworkRouter {
+    fn add_node(
+        &mut self,
+        author: Author,
+        consensus_tx: channel::Sender<NetworkNotification>,
+        network_reqs_rx: channel::Receiver<NetworkRequest>,
+    ) {
+        self.node_consensus_txs
+            .lock()
+            .unwrap()
+            .insert(author, consensus_tx);
+        let fut = Self::start_node_outbound_handler(
+            Arc::clone(&self.state),
+            author,
+            network_reqs_rx,
+            Arc::clone(&self.node_consensus_txs),
+            self.executor.clone(),
+        );
+        self.executor.spawn(fut.boxed().unit_error().compat());
+    }
+}
+
+#[test]
+fn test_link_changes() {
+    let runtime = crate::chained_bft::test_utils::consensus_runtime();
+    let router = NetworkRouter::new(runtime.executor(), 0);
+    let a = Author::random();
+    let b = Author::random();
+    let c = Author::random();
+    let lossy = LinkCondition {
+        drop_probability: 1.0,
+        ..LinkCondition::default()
+    };
+
+    router.apply(LinkChange::Set(a, b, lossy));
+    router.apply(LinkChange::Partition(vec![vec![a], vec![b, c]]));
+    {
+        let mut state = router.state.lock().unwrap();
+        assert!(state.plan_delivery(a, b, true).is_empty());
+        assert!(state.plan_delivery(c, a, true).is_empty());
+        assert_eq!(state.plan_delivery(b, c, true).len(), 1);
+    }
+
+    router.apply(LinkChange::Heal);
+    {
+        let mut state = router.state.lock().unwrap();
+        // Healing keeps the lossy link.
+        assert!(state.plan_delivery(a, b, true).is_empty());
+        assert_eq!(state.plan_delivery(b, a, true).len(), 1);
+        assert_eq!(state.plan_delivery(c, a, true).len(), 1);
+    }
+
+    router.apply(LinkChange::SetAll(LinkCondition {
+        duplicate_probability: 1.0,
+        ..LinkCondition::with_latency(Duration::from_millis(10), Duration::from_millis(20))
+    }));
+    let mut state = router.state.lock().unwrap();
+    let delays = state.plan_delivery(a, b, true);
+    assert_eq!(delays.len(), 2);
+    for delay in delays {
+        assert!(delay >= Duration::from_millis(10) && delay < Duration::from_millis(20));
+    }
+    assert_eq!(state.plan_delivery(a, b, false).len(), 1);
+}
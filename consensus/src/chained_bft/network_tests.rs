@@ -281,6 +281,29 @@ impl NetworkPlayground {
     }
 }
 
+/// A simulated network that in-process consensus nodes can be attached to.
+pub trait TestNetwork {
+    /// Attaches a node: `consensus_tx` receives the node's inbound network events and
+    /// `network_reqs_rx` yields the requests it sends.
+    fn add_node(
+        &mut self,
+        author: Author,
+        consensus_tx: channel::Sender<NetworkNotification>,
+        network_reqs_rx: channel::Receiver<NetworkRequest>,
+    );
+}
+
+impl TestNetwork for NetworkPlayground {
+    fn add_node(
+        &mut self,
+        author: Author,
+        consensus_tx: channel::Sender<NetworkNotification>,
+        network_reqs_rx: channel::Receiver<NetworkRequest>,
+    ) {
+        NetworkPlayground::add_node(self, author, consensus_tx, network_reqs_rx)
+    }
+}
+
 struct DropConfig(HashMap<Author, HashSet<Author>>);
 
 impl DropConfig {
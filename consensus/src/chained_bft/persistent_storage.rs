@@ -13,6 +13,7 @@ use crate::{
 };
 use config::config::NodeConfig;
 use crypto::HashValue;
+use debug_interface::failpoint::fail_point;
 use failure::Result;
 use logger::prelude::*;
 use rmp_serde::{from_slice, to_vec_named};
@@ -279,6 +280,7 @@ impl PersistentLivenessStorage for StorageWriteProxy {
         &self,
         highest_timeout_certs: HighestTimeoutCertificates,
     ) -> Result<()> {
+        fail_point("consensus::save_highest_timeout_cert")?;
         self.db
             .save_highest_timeout_certificates(to_vec_named(&highest_timeout_certs)?)
     }
@@ -290,6 +292,7 @@ impl<T: Payload> PersistentStorage<T> for StorageWriteProxy {
     }
 
     fn save_tree(&self, blocks: Vec<Block<T>>, quorum_certs: Vec<QuorumCert>) -> Result<()> {
+        fail_point("consensus::save_tree")?;
         self.db
             .save_blocks_and_quorum_certificates(blocks, quorum_certs)
     }
@@ -304,6 +307,7 @@ impl<T: Payload> PersistentStorage<T> for StorageWriteProxy {
     }
 
     fn save_consensus_state(&self, state: ConsensusState) -> Result<()> {
+        fail_point("consensus::save_consensus_state")?;
         self.db.save_state(to_vec_named(&state)?)
     }
 
@@ -0,0 +1,917 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic simulation of chained BFT.
+//!
+//! The simulator drives the EventProcessors of a set of nodes from a single thread. Nothing runs
+//! on its own: message deliveries and pacemaker timers are events in a queue ordered by a virtual
+//! clock, and every random choice (message delays, drops and duplicates, failed block retrievals,
+//! byzantine nodes) is drawn from a generator seeded with the seed of the execution, so a seed
+//! always replays the same execution.
+//!
+//! After every event the simulator checks that the blocks committed by the honest nodes lie on a
+//! single chain. On a violation `simulate_random_executions` reports the seed together with the
+//! smallest number of events that reproduces it, which can be replayed with its event trace:
+//!   CONSENSUS_SIM_SEED=<seed> CONSENSUS_SIM_STEPS=<steps> \
+//!       cargo test -p consensus replay_simulation -- --ignored --nocapture
+//! CONSENSUS_SIM_SEED, CONSENSUS_SIM_RUNS and CONSENSUS_SIM_STEPS also select the executions that
+//! `simulate_random_executions` explores, e.g. CONSENSUS_SIM_RUNS=5000 for a long search.
+//!
+//! The pacemaker still computes the round deadlines that bound block retrieval from the real
+//! clock. Virtual round timeouts are therefore a minute long, so that these deadlines never expire
+//! during an execution, and message delays are scaled accordingly.
+
+use crate::{
+    chained_bft::{
+        block_storage::{BlockReader, BlockStore},
+        common::{Author, Round},
+        consensus_types::{
+            block::Block,
+            proposal_msg::{ProposalMsg, ProposalUncheckedSignatures},
+            sync_info::SyncInfo,
+            timeout_msg::TimeoutMsg,
+            vote_data::VoteData,
+            vote_msg::VoteMsg,
+        },
+        epoch_manager::EpochManager,
+        event_processor::EventProcessor,
+        liveness::{
+            pacemaker::{ExponentialTimeInterval, Pacemaker},
+            pacemaker_timeout_manager::HighestTimeoutCertificates,
+            proposal_generator::ProposalGenerator,
+            rotating_proposer_election::RotatingProposer,
+        },
+        network::{BlockRetrievalRequest, BlockRetrievalResponse, ConsensusNetworkImpl},
+        network_router::check_commit_chain,
+        persistent_storage::PersistentStorage,
+        safety::safety_rules::SafetyRules,
+        test_utils::{MockStateComputer, MockStorage, MockTransactionManager, TestPayload},
+    },
+    util::time_service::{ScheduledTask, TimeService},
+};
+use bytes::Bytes;
+use channel;
+use crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
+use failure::prelude::*;
+use futures::{
+    channel::{mpsc, oneshot},
+    executor::block_on,
+    task::{noop_waker, Context, Poll},
+    Future, Stream, StreamExt,
+};
+use logger::{prelude::*, set_simple_logger};
+use network::{
+    interface::NetworkRequest,
+    proto::{ConsensusMsg, RespondBlock},
+    validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender, Event, RpcError},
+};
+use proto_conv::{FromProto, IntoProto};
+use protobuf::Message;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    env,
+    pin::Pin,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use types::crypto_proxies::{LedgerInfoWithSignatures, ValidatorSigner, ValidatorVerifier};
+
+const NUM_NODES: usize = 4;
+const ROUND_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_RUNS: u64 = 20;
+const DEFAULT_STEPS: usize = 300;
+
+/// How a byzantine node deviates from the protocol. Apart from that it runs an honest
+/// EventProcessor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Byzantine {
+    /// Sends nothing, as if it crashed.
+    Silent,
+    /// When proposing, sends a conflicting proposal to every other node.
+    Equivocate,
+    /// Votes for every proposal it receives, ignoring the safety rules.
+    DoubleVote,
+}
+
+/// Faults injected into an execution.
+#[derive(Clone, Debug)]
+struct SimConfig {
+    /// Byzantine behavior of every node, None for honest nodes.
+    byzantine: Vec<Option<Byzantine>>,
+    drop_probability: f64,
+    duplicate_probability: f64,
+    /// Probability that a block retrieval request fails.
+    rpc_failure_probability: f64,
+    /// Message delays are drawn uniformly up to this value.
+    max_delay: Duration,
+}
+
+impl SimConfig {
+    fn fault_free() -> Self {
+        Self {
+            byzantine: vec![None; NUM_NODES],
+            drop_probability: 0.0,
+            duplicate_probability: 0.0,
+            rpc_failure_probability: 0.0,
+            max_delay: Duration::from_secs(1),
+        }
+    }
+
+    fn random(rng: &mut StdRng) -> Self {
+        let mut byzantine = vec![None; NUM_NODES];
+        // With NUM_NODES = 3f + 1 = 4, at most one node may be byzantine.
+        if rng.gen_bool(0.5) {
+            let node = rng.gen_range(0, NUM_NODES);
+            byzantine[node] = Some(match rng.gen_range(0, 3) {
+                0 => Byzantine::Silent,
+                1 => Byzantine::Equivocate,
+                _ => Byzantine::DoubleVote,
+            });
+        }
+        Self {
+            byzantine,
+            drop_probability: rng.gen_range(0.0, 0.2),
+            duplicate_probability: rng.gen_range(0.0, 0.2),
+            rpc_failure_probability: rng.gen_range(0.0, 0.3),
+            max_delay: Duration::from_millis(rng.gen_range(1, 30_000)),
+        }
+    }
+}
+
+/// Virtual time shared by the simulator and the time services of the nodes.
+struct VirtualClock {
+    now: Duration,
+    /// Timers started since the simulator last collected them: node, deadline and task.
+    new_timers: Vec<(usize, Duration, Box<dyn ScheduledTask>)>,
+}
+
+/// TimeService of a simulated node: it reads the virtual clock and hands its timers over to the
+/// simulator.
+struct VirtualTimeService {
+    node: usize,
+    clock: Arc<Mutex<VirtualClock>>,
+}
+
+impl TimeService for VirtualTimeService {
+    fn run_after(&self, timeout: Duration, task: Box<dyn ScheduledTask>) {
+        let mut clock = self.clock.lock().unwrap();
+        let deadline = clock.now + timeout;
+        clock.new_timers.push((self.node, deadline, task));
+    }
+
+    fn get_current_timestamp(&self) -> Duration {
+        self.clock.lock().unwrap().now
+    }
+
+    fn sleep(&self, _t: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        // Nodes run without enforce_increasing_timestamps, so they never wait for the clock.
+        unimplemented!("Sleeping is not supported by the simulator")
+    }
+}
+
+enum SimEvent {
+    /// Delivers a message sent by the node with the given index.
+    Deliver { from: usize, msg: ConsensusMsg },
+    /// Fires a timer of the node.
+    Timer(Box<dyn ScheduledTask>),
+}
+
+struct Scheduled {
+    at: Duration,
+    // Breaks ties between events scheduled at the same time in the order they were scheduled.
+    seq: u64,
+    node: usize,
+    event: SimEvent,
+}
+
+// BinaryHeap is a max-heap, so the earliest event has to compare as the greatest.
+impl Ord for Scheduled {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.at, other.seq).cmp(&(self.at, self.seq))
+    }
+}
+
+impl PartialOrd for Scheduled {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Scheduled {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scheduled {}
+
+/// An event for the EventProcessor of a node.
+enum Input {
+    Start,
+    Proposal(ProposalMsg<TestPayload>),
+    Vote(VoteMsg),
+    Timeout(TimeoutMsg),
+    SyncInfo(SyncInfo, Author),
+    LocalTimeout(Round),
+}
+
+async fn process_input(event_processor: &mut EventProcessor<TestPayload>, input: Input) {
+    match input {
+        Input::Start => event_processor.start().await,
+        Input::Proposal(proposal) => event_processor.process_proposal_msg(proposal).await,
+        Input::Vote(vote) => event_processor.process_vote(vote).await,
+        Input::Timeout(timeout_msg) => {
+            event_processor
+                .process_remote_timeout_msg(timeout_msg)
+                .await
+        }
+        Input::SyncInfo(sync_info, peer) => {
+            event_processor.process_sync_info_msg(sync_info, peer).await
+        }
+        Input::LocalTimeout(round) => event_processor.process_local_timeout(round).await,
+    }
+}
+
+/// Returns the next item of the stream if it is immediately available.
+fn try_recv<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    match stream.poll_next_unpin(&mut cx) {
+        Poll::Ready(item) => item,
+        Poll::Pending => None,
+    }
+}
+
+struct SimNode {
+    author: Author,
+    signer: ValidatorSigner,
+    byzantine: Option<Byzantine>,
+    block_store: Arc<BlockStore<TestPayload>>,
+    event_processor: EventProcessor<TestPayload>,
+    network_reqs_rx: channel::Receiver<NetworkRequest>,
+    self_rx: channel::Receiver<Result<Event<ConsensusMsg>>>,
+    timeout_rx: channel::Receiver<Round>,
+    commit_rx: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
+    mempool_commit_rx: mpsc::Receiver<usize>,
+    /// Ids of the blocks committed so far.
+    committed: Vec<HashValue>,
+}
+
+impl SimNode {
+    fn new(
+        index: usize,
+        signer: ValidatorSigner,
+        byzantine: Option<Byzantine>,
+        proposers: Vec<Author>,
+        epoch_mgr: Arc<EpochManager>,
+        clock: Arc<Mutex<VirtualClock>>,
+        sync_rng_seed: u64,
+    ) -> Self {
+        let author = signer.author();
+        let (network_reqs_tx, network_reqs_rx) = channel::new_test(1_024);
+        let (_, consensus_rx) = channel::new_test(1);
+        let mut network = ConsensusNetworkImpl::new(
+            author,
+            ConsensusNetworkSender::new(network_reqs_tx),
+            ConsensusNetworkEvents::new(consensus_rx),
+            Arc::clone(&epoch_mgr),
+        );
+        let self_rx = network.take_self_receiver();
+
+        let (storage, initial_data) = MockStorage::<TestPayload>::start_for_testing();
+        let consensus_state = initial_data.state();
+        let (commit_tx, commit_rx) = mpsc::unbounded::<LedgerInfoWithSignatures>();
+        let state_computer = Arc::new(MockStateComputer::new(commit_tx));
+        let block_store = Arc::new(block_on(BlockStore::new(
+            storage.clone(),
+            initial_data,
+            signer.clone(),
+            state_computer.clone(),
+            false,
+            10, // max pruned blocks in mem
+        )));
+        let time_service: Arc<dyn TimeService> =
+            Arc::new(VirtualTimeService { node: index, clock });
+        let mut txn_manager = MockTransactionManager::new();
+        let mempool_commit_rx = txn_manager.take_commit_receiver();
+        let txn_manager = Arc::new(txn_manager);
+        let proposal_generator = ProposalGenerator::new(
+            block_store.clone(),
+            txn_manager.clone(),
+            time_service.clone(),
+            1,
+            false,
+        );
+        let (timeout_tx, timeout_rx) = channel::new_test(1_024);
+        let pacemaker = Pacemaker::new(
+            storage.persistent_liveness_storage(),
+            Box::new(ExponentialTimeInterval::new(ROUND_TIMEOUT, 1.2, 6)),
+            time_service.clone(),
+            timeout_tx,
+            HighestTimeoutCertificates::default(),
+        );
+        let mut event_processor = EventProcessor::new(
+            author,
+            Arc::clone(&block_store),
+            pacemaker,
+            Box::new(RotatingProposer::new(proposers, 1)),
+            proposal_generator,
+            SafetyRules::new(consensus_state),
+            state_computer,
+            txn_manager,
+            network,
+            storage,
+            time_service,
+            false,
+            epoch_mgr,
+        );
+        event_processor.set_sync_rng_seed(sync_rng_seed);
+        Self {
+            author,
+            signer,
+            byzantine,
+            block_store,
+            event_processor,
+            network_reqs_rx,
+            self_rx,
+            timeout_rx,
+            commit_rx,
+            mempool_commit_rx,
+            committed: vec![],
+        }
+    }
+
+    /// Signs a vote for `block` without consulting the safety rules.
+    fn unchecked_vote(&self, block: &Block<TestPayload>) -> VoteMsg {
+        let qc = block.quorum_cert();
+        // The 3-chain commit rule of SafetyRules.
+        let potential_commit_id = if qc.parent_block_round() + 1 == qc.certified_block_round()
+            && qc.certified_block_round() + 1 == block.round()
+        {
+            Some(qc.parent_block_id())
+        } else {
+            None
+        };
+        VoteMsg::new(
+            VoteData::new(
+                block.id(),
+                *ACCUMULATOR_PLACEHOLDER_HASH,
+                block.round(),
+                qc.certified_block_id(),
+                qc.certified_block_round(),
+                qc.parent_block_id(),
+                qc.parent_block_round(),
+            ),
+            self.author,
+            self.block_store
+                .ledger_info_placeholder(potential_commit_id),
+            &self.signer,
+        )
+    }
+
+    /// Replaces the block of a proposal by a conflicting block of the same round.
+    fn equivocate(&self, msg: ConsensusMsg) -> ConsensusMsg {
+        let proposal: ProposalMsg<TestPayload> =
+            match ProposalUncheckedSignatures::from_proto(msg.get_proposal().clone()) {
+                Ok(proposal) => proposal.into(),
+                Err(_) => return msg,
+            };
+        let block = proposal.proposal();
+        let parent = match self.block_store.get_block(block.parent_id()) {
+            Some(parent) => parent,
+            None => return msg,
+        };
+        let conflicting_block = Block::make_block(
+            parent.as_ref(),
+            vec![std::usize::MAX],
+            block.round(),
+            block.timestamp_usecs(),
+            block.quorum_cert().clone(),
+            &self.signer,
+        );
+        let mut equivocation = ConsensusMsg::new();
+        equivocation.set_proposal(
+            ProposalMsg::new(conflicting_block, proposal.sync_info().clone()).into_proto(),
+        );
+        equivocation
+    }
+
+    /// Answers a block retrieval request like the network task of the node does.
+    fn respond_block(&self, request: &[u8]) -> Result<Bytes> {
+        let mut msg: ConsensusMsg = ::protobuf::parse_from_bytes(request)?;
+        ensure!(msg.has_request_block(), "Unexpected rpc {:?}", msg);
+        let request = msg.take_request_block();
+        let (response_sender, response_rx) = oneshot::channel();
+        block_on(
+            self.event_processor
+                .process_block_retrieval(BlockRetrievalRequest {
+                    block_id: HashValue::from_slice(request.get_block_id())?,
+                    num_blocks: request.get_num_blocks(),
+                    response_sender,
+                }),
+        );
+        let BlockRetrievalResponse { status, blocks } = block_on(response_rx)?;
+        let mut response = RespondBlock::new();
+        response.set_status(status);
+        response.set_blocks(blocks.into_iter().map(IntoProto::into_proto).collect());
+        let mut response_msg = ConsensusMsg::new();
+        response_msg.set_respond_block(response);
+        Ok(Bytes::from(response_msg.write_to_bytes()?))
+    }
+}
+
+/// State of the simulation besides the nodes, so that it can be updated while a node is
+/// processing an event.
+struct SimNetwork {
+    config: SimConfig,
+    rng: StdRng,
+    clock: Arc<Mutex<VirtualClock>>,
+    queue: BinaryHeap<Scheduled>,
+    next_seq: u64,
+    authors: Vec<Author>,
+    epoch_mgr: Arc<EpochManager>,
+    /// Parent id and round of every block seen in a proposal or a vote.
+    blocks: HashMap<HashValue, (HashValue, Round)>,
+    /// One line per processed event.
+    trace: Vec<String>,
+}
+
+impl SimNetwork {
+    fn now(&self) -> Duration {
+        self.clock.lock().unwrap().now
+    }
+
+    fn index_of(&self, author: Author) -> usize {
+        self.authors
+            .iter()
+            .position(|a| *a == author)
+            .expect("Unknown author")
+    }
+
+    fn schedule(&mut self, at: Duration, node: usize, event: SimEvent) {
+        self.queue.push(Scheduled {
+            at,
+            seq: self.next_seq,
+            node,
+            event,
+        });
+        self.next_seq += 1;
+    }
+
+    fn collect_timers(&mut self) {
+        let timers = std::mem::replace(&mut self.clock.lock().unwrap().new_timers, vec![]);
+        for (node, deadline, task) in timers {
+            self.schedule(deadline, node, SimEvent::Timer(task));
+        }
+    }
+
+    /// Sends a message from node `from` to node `to` through the faulty network.
+    fn send(&mut self, from: usize, sender: &SimNode, to: usize, mut msg: ConsensusMsg) {
+        self.record_blocks(&msg);
+        let now = self.now();
+        if from == to {
+            // Messages to self do not go through the network.
+            self.schedule(now, to, SimEvent::Deliver { from, msg });
+            return;
+        }
+        match sender.byzantine {
+            Some(Byzantine::Silent) => return,
+            Some(Byzantine::Equivocate) if to % 2 == 1 && msg.has_proposal() => {
+                msg = sender.equivocate(msg);
+                self.record_blocks(&msg);
+            }
+            _ => (),
+        }
+        if self.rng.gen_bool(self.config.drop_probability) {
+            return;
+        }
+        let num_copies = if self.rng.gen_bool(self.config.duplicate_probability) {
+            2
+        } else {
+            1
+        };
+        for _ in 0..num_copies {
+            let delay = Duration::from_millis(
+                self.rng
+                    .gen_range(0, self.config.max_delay.as_millis() as u64 + 1),
+            );
+            self.schedule(
+                now + delay,
+                to,
+                SimEvent::Deliver {
+                    from,
+                    msg: msg.clone(),
+                },
+            );
+        }
+    }
+
+    /// Answers a block retrieval rpc sent to `target`, unless the rpc fails.
+    fn answer_rpc(
+        &mut self,
+        target: &SimNode,
+        request: &[u8],
+        res_tx: oneshot::Sender<std::result::Result<Bytes, RpcError>>,
+    ) {
+        let response = if target.byzantine == Some(Byzantine::Silent)
+            || self.rng.gen_bool(self.config.rpc_failure_probability)
+        {
+            Err(RpcError::TimedOut)
+        } else {
+            target
+                .respond_block(request)
+                .map_err(RpcError::ApplicationError)
+        };
+        let _ = res_tx.send(response);
+    }
+
+    fn record_blocks(&mut self, msg: &ConsensusMsg) {
+        let vote = if msg.has_proposal() {
+            if let Ok(proposal) =
+                ProposalUncheckedSignatures::<TestPayload>::from_proto(msg.get_proposal().clone())
+            {
+                let proposal: ProposalMsg<TestPayload> = proposal.into();
+                let block = proposal.proposal();
+                self.blocks
+                    .insert(block.id(), (block.parent_id(), block.round()));
+            }
+            None
+        } else if msg.has_vote() {
+            VoteMsg::from_proto(msg.get_vote().clone()).ok()
+        } else if msg.has_timeout_msg() {
+            TimeoutMsg::from_proto(msg.get_timeout_msg().clone())
+                .ok()
+                .and_then(|timeout_msg| timeout_msg.pacemaker_timeout().vote_msg().cloned())
+        } else {
+            None
+        };
+        // Votes also reveal the NIL blocks, which are never proposed.
+        if let Some(vote) = vote {
+            self.blocks.insert(
+                vote.block_id(),
+                (vote.parent_block_id(), vote.block_round()),
+            );
+        }
+    }
+
+    /// Verifies a message like the network task of a node does and turns it into an input for
+    /// the EventProcessor. Invalid messages are dropped.
+    fn parse_message(&self, from: Author, mut msg: ConsensusMsg) -> Option<Input> {
+        let validators = self.epoch_mgr.validators();
+        let input = if msg.has_proposal() {
+            ProposalUncheckedSignatures::<TestPayload>::from_proto(msg.take_proposal())
+                .and_then(|proposal| proposal.validate_signatures(validators.as_ref()))
+                .and_then(ProposalMsg::verify_well_formed)
+                .map(Input::Proposal)
+        } else if msg.has_vote() {
+            VoteMsg::from_proto(msg.take_vote()).and_then(|vote| {
+                vote.verify(validators.as_ref())?;
+                Ok(Input::Vote(vote))
+            })
+        } else if msg.has_timeout_msg() {
+            TimeoutMsg::from_proto(msg.take_timeout_msg()).and_then(|timeout_msg| {
+                timeout_msg.verify(validators.as_ref())?;
+                Ok(Input::Timeout(timeout_msg))
+            })
+        } else if msg.has_sync_info() {
+            SyncInfo::from_proto(msg.take_sync_info()).and_then(|sync_info| {
+                sync_info.verify(validators.as_ref())?;
+                Ok(Input::SyncInfo(sync_info, from))
+            })
+        } else {
+            Err(format_err!("Unexpected message {:?}", msg))
+        };
+        input
+            .map_err(|e| debug!("Dropping message from {}: {:?}", from.short_str(), e))
+            .ok()
+    }
+}
+
+fn msg_kind(msg: &ConsensusMsg) -> &'static str {
+    if msg.has_proposal() {
+        "proposal"
+    } else if msg.has_vote() {
+        "vote"
+    } else if msg.has_timeout_msg() {
+        "timeout"
+    } else if msg.has_sync_info() {
+        "sync_info"
+    } else {
+        "unknown"
+    }
+}
+
+/// Honest nodes committed blocks that do not lie on a single chain.
+#[derive(Debug)]
+struct SafetyViolation {
+    /// Number of events processed when the violation was detected.
+    step: usize,
+    error: Error,
+}
+
+struct Simulator {
+    nodes: Vec<SimNode>,
+    net: SimNetwork,
+}
+
+impl Simulator {
+    /// Creates an execution whose faults and schedule are all drawn from `seed`.
+    fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let config = SimConfig::random(&mut rng);
+        Self::with_config(rng, config)
+    }
+
+    fn with_config(mut rng: StdRng, config: SimConfig) -> Self {
+        let clock = Arc::new(Mutex::new(VirtualClock {
+            now: Duration::from_secs(0),
+            new_timers: vec![],
+        }));
+        let mut signers = vec![];
+        let mut author_to_public_keys = HashMap::new();
+        for i in 0..NUM_NODES {
+            let signer = ValidatorSigner::random([i as u8; 32]);
+            author_to_public_keys.insert(signer.author(), signer.public_key());
+            signers.push(signer);
+        }
+        let validator_verifier = ValidatorVerifier::new(author_to_public_keys);
+        let epoch_mgr = Arc::new(EpochManager::new(0, validator_verifier));
+        let proposers = epoch_mgr.validators().get_ordered_account_addresses();
+        let nodes: Vec<_> = signers
+            .into_iter()
+            .enumerate()
+            .map(|(index, signer)| {
+                SimNode::new(
+                    index,
+                    signer,
+                    config.byzantine[index],
+                    proposers.clone(),
+                    Arc::clone(&epoch_mgr),
+                    Arc::clone(&clock),
+                    rng.gen(),
+                )
+            })
+            .collect();
+        let authors = nodes.iter().map(|node| node.author).collect();
+        let mut simulator = Self {
+            nodes,
+            net: SimNetwork {
+                config,
+                rng,
+                clock,
+                queue: BinaryHeap::new(),
+                next_seq: 0,
+                authors,
+                epoch_mgr,
+                blocks: HashMap::new(),
+                trace: vec![],
+            },
+        };
+        for index in 0..NUM_NODES {
+            simulator.process(index, Input::Start);
+        }
+        simulator.net.collect_timers();
+        simulator
+    }
+
+    /// Processes up to `max_steps` events, checking safety after each of them.
+    fn run(&mut self, max_steps: usize) -> std::result::Result<(), SafetyViolation> {
+        for step in 1..=max_steps {
+            if !self.step() {
+                break;
+            }
+            if let Err(error) = self.check_safety() {
+                return Err(SafetyViolation { step, error });
+            }
+        }
+        Ok(())
+    }
+
+    /// Processes the next event. Returns false if there is none.
+    fn step(&mut self) -> bool {
+        let Scheduled {
+            at,
+            node: index,
+            event,
+            ..
+        } = match self.net.queue.pop() {
+            Some(scheduled) => scheduled,
+            None => return false,
+        };
+        self.net.clock.lock().unwrap().now = at;
+        match event {
+            SimEvent::Deliver { from, msg } => {
+                self.net.trace.push(format!(
+                    "{}ms: node {} receives {} from node {}",
+                    at.as_millis(),
+                    index,
+                    msg_kind(&msg),
+                    from
+                ));
+                let from_author = self.net.authors[from];
+                if let Some(input) = self.net.parse_message(from_author, msg) {
+                    let node = &self.nodes[index];
+                    let extra_vote = match (&input, node.byzantine) {
+                        (Input::Proposal(proposal), Some(Byzantine::DoubleVote)) => {
+                            Some(node.unchecked_vote(proposal.proposal()))
+                        }
+                        _ => None,
+                    };
+                    self.process(index, input);
+                    if let Some(vote) = extra_vote {
+                        let mut msg = ConsensusMsg::new();
+                        msg.set_vote(vote.into_proto());
+                        for to in 0..NUM_NODES {
+                            self.net.send(index, &self.nodes[index], to, msg.clone());
+                        }
+                    }
+                }
+            }
+            SimEvent::Timer(mut task) => {
+                self.net
+                    .trace
+                    .push(format!("{}ms: timer of node {}", at.as_millis(), index));
+                block_on(task.run());
+                while let Some(round) = try_recv(&mut self.nodes[index].timeout_rx) {
+                    self.process(index, Input::LocalTimeout(round));
+                }
+            }
+        }
+        self.net.collect_timers();
+        true
+    }
+
+    /// Runs the EventProcessor of a node on an input until it is done, answering the block
+    /// retrieval rpcs it sends on the way, then routes the messages it sent.
+    fn process(&mut self, index: usize, input: Input) {
+        let (nodes_before, rest) = self.nodes.split_at_mut(index);
+        let (node, nodes_after) = rest.split_first_mut().expect("Node index out of range");
+        let mut outbox = vec![];
+        {
+            let waker = noop_waker();
+            let mut cx = Context::from_waker(&waker);
+            let mut fut = Box::pin(process_input(&mut node.event_processor, input));
+            loop {
+                let done = fut.as_mut().poll(&mut cx).is_ready();
+                let mut made_progress = false;
+                while let Some(request) = try_recv(&mut node.network_reqs_rx) {
+                    made_progress = true;
+                    match request {
+                        NetworkRequest::SendMessage(peer, msg) => outbox.push((peer, msg.mdata)),
+                        NetworkRequest::SendRpc(peer, rpc) => {
+                            let target = self.net.index_of(peer);
+                            if target == index {
+                                // Like the real network, nodes cannot send rpcs to themselves.
+                                let _ = rpc.res_tx.send(Err(RpcError::NotConnected(peer)));
+                                continue;
+                            }
+                            let target = if target < index {
+                                &nodes_before[target]
+                            } else {
+                                &nodes_after[target - index - 1]
+                            };
+                            self.net.answer_rpc(target, rpc.data.as_ref(), rpc.res_tx);
+                        }
+                        NetworkRequest::UpdateEligibleNodes(_) => (),
+                    }
+                }
+                if done {
+                    break;
+                }
+                assert!(
+                    made_progress,
+                    "Node {} is blocked on something other than the network",
+                    index
+                );
+            }
+        }
+
+        let node = &self.nodes[index];
+        for (peer, data) in outbox {
+            match ::protobuf::parse_from_bytes::<ConsensusMsg>(data.as_ref()) {
+                Ok(msg) => {
+                    let to = self.net.index_of(peer);
+                    self.net.send(index, node, to, msg);
+                }
+                Err(e) => error!("Node {} sent an invalid message: {:?}", index, e),
+            }
+        }
+        let self_msgs: Vec<_> =
+            std::iter::from_fn(|| try_recv(&mut self.nodes[index].self_rx)).collect();
+        for self_msg in self_msgs {
+            if let Ok(Event::Message((_, msg))) = self_msg {
+                self.net.send(index, &self.nodes[index], index, msg);
+            }
+        }
+    }
+
+    /// Checks that the blocks committed by the honest nodes lie on a single chain.
+    fn check_safety(&mut self) -> Result<()> {
+        let mut committed = vec![];
+        for node in self.nodes.iter_mut() {
+            while let Ok(Some(ledger_info)) = node.commit_rx.try_next() {
+                node.committed
+                    .push(ledger_info.ledger_info().consensus_block_id());
+            }
+            while let Ok(Some(_)) = node.mempool_commit_rx.try_next() {}
+            if node.byzantine.is_none() {
+                committed.extend(node.committed.iter().cloned());
+            }
+        }
+        check_commit_chain(&self.net.blocks, &committed)
+    }
+}
+
+fn env_var<T: FromStr>(name: &str) -> Option<T> {
+    env::var(name).ok().and_then(|value| value.parse().ok())
+}
+
+#[test]
+fn simulate_random_executions() {
+    let first_seed = env_var("CONSENSUS_SIM_SEED").unwrap_or(0);
+    let runs = env_var("CONSENSUS_SIM_RUNS").unwrap_or(DEFAULT_RUNS);
+    let max_steps = env_var("CONSENSUS_SIM_STEPS").unwrap_or(DEFAULT_STEPS);
+    for seed in first_seed..first_seed + runs {
+        let mut simulator = Simulator::new(seed);
+        if let Err(violation) = simulator.run(max_steps) {
+            // Safety is checked after every event, so no shorter prefix of this execution fails.
+            panic!(
+                "Safety violation in execution {} after {} events: {}\nFaults: {:?}\n\
+                 Replay with: CONSENSUS_SIM_SEED={} CONSENSUS_SIM_STEPS={} \
+                 cargo test -p consensus replay_simulation -- --ignored --nocapture",
+                seed, violation.step, violation.error, simulator.net.config, seed, violation.step
+            );
+        }
+    }
+}
+
+#[test]
+#[ignore]
+fn replay_simulation() {
+    set_simple_logger("consensus");
+    let seed = env_var("CONSENSUS_SIM_SEED").expect("CONSENSUS_SIM_SEED is not set");
+    let max_steps = env_var("CONSENSUS_SIM_STEPS").unwrap_or(DEFAULT_STEPS);
+    let mut simulator = Simulator::new(seed);
+    println!("Faults: {:?}", simulator.net.config);
+    let result = simulator.run(max_steps);
+    for line in &simulator.net.trace {
+        println!("{}", line);
+    }
+    if let Err(violation) = result {
+        panic!(
+            "Safety violation after {} events: {}",
+            violation.step, violation.error
+        );
+    }
+}
+
+#[test]
+fn test_same_seed_same_execution() {
+    let run = |seed| {
+        let mut simulator = Simulator::new(seed);
+        simulator.run(DEFAULT_STEPS).unwrap();
+        let committed: Vec<_> = simulator
+            .nodes
+            .iter()
+            .map(|node| node.committed.clone())
+            .collect();
+        (simulator.net.trace, committed)
+    };
+    assert_eq!(run(7), run(7));
+}
+
+#[test]
+fn test_fault_free_execution_commits() {
+    let mut simulator = Simulator::with_config(StdRng::seed_from_u64(0), SimConfig::fault_free());
+    simulator.run(DEFAULT_STEPS).unwrap();
+    for node in &simulator.nodes {
+        assert!(
+            node.committed.len() >= 10,
+            "Node {} committed only {} blocks",
+            node.author.short_str(),
+            node.committed.len()
+        );
+    }
+}
+
+#[test]
+fn test_single_byzantine_node() {
+    for behavior in &[
+        Byzantine::Silent,
+        Byzantine::Equivocate,
+        Byzantine::DoubleVote,
+    ] {
+        let mut config = SimConfig::fault_free();
+        config.byzantine[1] = Some(*behavior);
+        let mut simulator = Simulator::with_config(StdRng::seed_from_u64(0), config);
+        if let Err(violation) = simulator.run(DEFAULT_STEPS) {
+            panic!(
+                "{:?} node broke safety after {} events: {}",
+                behavior, violation.step, violation.error
+            );
+        }
+    }
+}
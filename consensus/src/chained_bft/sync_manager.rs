@@ -19,7 +19,7 @@ use rand::{prelude::*, Rng};
 use std::{
     clone::Clone,
     result::Result,
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 use termion::color::*;
@@ -31,6 +31,8 @@ pub struct SyncManager<T> {
     storage: Arc<dyn PersistentStorage<T>>,
     network: ConsensusNetworkImpl,
     state_computer: Arc<dyn StateComputer<Payload = T>>,
+    // Seeds the random choice of peers that block retrieval falls back on.
+    rng: Mutex<StdRng>,
 }
 
 /// Keeps the necessary context for `SyncMgr` to bring the missing information.
@@ -73,6 +75,22 @@ where
             storage,
             network,
             state_computer,
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    /// Makes the choice of peers for block retrieval reproducible.
+    #[cfg(test)]
+    pub fn set_rng_seed(&mut self, seed: u64) {
+        self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+    }
+
+    fn new_retriever(&self, preferred_peer: Author, deadline: Instant) -> BlockRetriever {
+        BlockRetriever {
+            network: self.network.clone(),
+            deadline,
+            preferred_peer,
+            rng: StdRng::seed_from_u64(self.rng.lock().unwrap().gen()),
         }
     }
 
@@ -130,12 +148,7 @@ where
         deadline: Instant,
     ) -> Result<(), InsertError> {
         let mut pending = vec![];
-        let network = self.network.clone();
-        let mut retriever = BlockRetriever {
-            network,
-            deadline,
-            preferred_peer,
-        };
+        let mut retriever = self.new_retriever(preferred_peer, deadline);
         let mut retrieve_qc = qc.clone();
         loop {
             if self
@@ -189,12 +202,7 @@ where
             highest_ledger_info.certified_block_round() - 2,
             self.block_store.root()
         );
-        let network = self.network.clone();
-        let mut retriever = BlockRetriever {
-            network,
-            deadline,
-            preferred_peer: peer,
-        };
+        let mut retriever = self.new_retriever(peer, deadline);
         let mut blocks = retriever
             .retrieve_block_for_qc(&highest_ledger_info, 3)
             .await?;
@@ -248,6 +256,7 @@ struct BlockRetriever {
     network: ConsensusNetworkImpl,
     deadline: Instant,
     preferred_peer: Author,
+    rng: StdRng,
 }
 
 #[derive(Debug, Fail)]
@@ -287,6 +296,8 @@ impl BlockRetriever {
     {
         let block_id = qc.certified_block_id();
         let mut peers: Vec<&AccountAddress> = qc.ledger_info().signatures().keys().collect();
+        // Signatures are kept in a HashMap: sort them so that the choice only depends on the rng.
+        peers.sort();
         let mut attempt = 0_u32;
         loop {
             if peers.is_empty() {
@@ -341,7 +352,7 @@ impl BlockRetriever {
         }
     }
 
-    fn pick_peer(&self, attempt: u32, peers: &mut Vec<&AccountAddress>) -> AccountAddress {
+    fn pick_peer(&mut self, attempt: u32, peers: &mut Vec<&AccountAddress>) -> AccountAddress {
         assert!(!peers.is_empty(), "pick_peer on empty peer list");
 
         if attempt == 0 {
@@ -356,7 +367,7 @@ impl BlockRetriever {
             return self.preferred_peer;
         }
 
-        let peer_idx = thread_rng().gen_range(0, peers.len());
+        let peer_idx = self.rng.gen_range(0, peers.len());
         *peers.remove(peer_idx)
     }
 }
@@ -2,10 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    chained_bft::{consensus_types::quorum_cert::QuorumCert, test_utils::TestPayload},
+    chained_bft::{
+        consensus_types::{block::Block, quorum_cert::QuorumCert},
+        test_utils::TestPayload,
+    },
     state_replication::{ExecutedState, StateComputeResult, StateComputer},
 };
-use crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
+use crypto::hash::ACCUMULATOR_PLACEHOLDER_HASH;
 use failure::Result;
 use futures::{channel::mpsc, future, Future, FutureExt};
 use logger::prelude::*;
@@ -27,9 +30,7 @@ impl StateComputer for MockStateComputer {
     type Payload = Vec<usize>;
     fn compute(
         &self,
-        _parent_id: HashValue,
-        _block_id: HashValue,
-        _transactions: &Self::Payload,
+        _block: &Block<Self::Payload>,
     ) -> Pin<Box<dyn Future<Output = Result<StateComputeResult>> + Send>> {
         future::ok(StateComputeResult {
             executed_state: ExecutedState {
@@ -72,9 +73,7 @@ impl StateComputer for EmptyStateComputer {
     type Payload = TestPayload;
     fn compute(
         &self,
-        _parent_id: HashValue,
-        _block_id: HashValue,
-        _transactions: &Self::Payload,
+        _block: &Block<Self::Payload>,
     ) -> Pin<Box<dyn Future<Output = Result<StateComputeResult>> + Send>> {
         future::ok(StateComputeResult {
             executed_state: ExecutedState {
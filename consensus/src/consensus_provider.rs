@@ -5,7 +5,7 @@ use config::config::NodeConfig;
 use failure::prelude::*;
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
 
-use crate::chained_bft::chained_bft_consensus_provider::ChainedBftProvider;
+use crate::{chained_bft::chained_bft_consensus_provider::ChainedBftProvider, counters};
 use execution_proto::proto::execution_grpc::ExecutionClient;
 use grpcio::{ChannelBuilder, EnvBuilder};
 use mempool::proto::mempool_grpc::MempoolClient;
@@ -26,6 +26,11 @@ pub trait ConsensusProvider {
     fn stop(&mut self);
 }
 
+/// Round of the last block committed by consensus on this node.
+pub fn last_committed_round() -> i64 {
+    counters::LAST_COMMITTED_ROUND.get()
+}
+
 /// Helper function to create a ConsensusProvider based on configuration
 pub fn make_consensus_provider(
     node_config: &mut NodeConfig,
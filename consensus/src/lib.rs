@@ -30,6 +30,8 @@ pub mod util;
 /// use in the Libra Core blockchain.
 pub mod consensus_provider;
 
+pub use chained_bft::{inspect_consensus_db, ConsensusDbSummary};
+
 mod counters;
 
 mod state_computer;
@@ -2,16 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    chained_bft::QuorumCert,
+    chained_bft::{Block, QuorumCert},
     counters,
     state_replication::{ExecutedState, StateComputeResult, StateComputer},
 };
-use crypto::HashValue;
 use execution_proto::proto::{
     execution::{CommitBlockRequest, CommitBlockStatus, ExecuteBlockRequest, ExecuteBlockResponse},
     execution_grpc::ExecutionClient,
 };
-use failure::Result;
+use failure::prelude::*;
 use futures::{compat::Future01CompatExt, future, Future, FutureExt};
 use logger::prelude::*;
 use proto_conv::{FromProto, IntoProto};
@@ -23,8 +22,10 @@ use std::{
     time::{Duration, Instant},
 };
 use types::{
+    account_address::AccountAddress,
+    block_metadata::BlockMetadata,
     crypto_proxies::LedgerInfoWithSignatures,
-    transaction::{SignedTransaction, TransactionStatus},
+    transaction::{SignedTransaction, TransactionPayload, TransactionStatus},
 };
 
 /// Basic communication with the Execution module;
@@ -42,6 +43,24 @@ impl ExecutionProxy {
         }
     }
 
+    /// Builds the metadata the VM records on chain before executing the payload of `block`.
+    fn block_metadata(block: &Block<Vec<SignedTransaction>>) -> BlockMetadata {
+        let previous_block_votes = block
+            .quorum_cert()
+            .ledger_info()
+            .signatures()
+            .iter()
+            .map(|(voter, signature)| (*voter, signature.clone()))
+            .collect();
+        BlockMetadata::new(
+            block.id(),
+            block.timestamp_usecs(),
+            previous_block_votes,
+            // NIL blocks are not proposed by anyone.
+            block.author().unwrap_or_else(AccountAddress::default),
+        )
+    }
+
     fn process_exec_response(
         response: ExecuteBlockResponse,
         pre_execution_instant: Instant,
@@ -49,7 +68,9 @@ impl ExecutionProxy {
         let execution_block_response = execution_proto::ExecuteBlockResponse::from_proto(response)
             .expect("Couldn't decode ExecutionBlockResponse from protobuf");
         let execution_duration = pre_execution_instant.elapsed();
-        let num_txns = execution_block_response.status().len();
+        // The first transaction of every block is the block metadata, which is not part of the
+        // payload.
+        let num_txns = execution_block_response.status().len().saturating_sub(1);
         if num_txns == 0 {
             // no txns in that block
             counters::EMPTY_BLOCK_EXECUTION_DURATION_S.observe_duration(execution_duration);
@@ -65,7 +86,7 @@ impl ExecutionProxy {
             }
         }
         let mut compute_status = vec![];
-        for vm_status in execution_block_response.status() {
+        for vm_status in execution_block_response.status().iter().skip(1) {
             let status = match vm_status {
                 TransactionStatus::Keep(_) => true,
                 TransactionStatus::Discard(_) => false,
@@ -89,20 +110,30 @@ impl StateComputer for ExecutionProxy {
 
     fn compute(
         &self,
-        // The id of a parent block, on top of which the given transactions should be executed.
-        parent_block_id: HashValue,
-        // The id of a current block.
-        block_id: HashValue,
-        // Transactions to execute.
-        transactions: &Self::Payload,
+        // The block whose payload should be executed.
+        block: &Block<Self::Payload>,
     ) -> Pin<Box<dyn Future<Output = Result<StateComputeResult>> + Send>> {
+        // Block metadata is only ever derived from the block itself. A proposer smuggling its own
+        // metadata into the payload could tamper with the on-chain time.
+        let has_block_metadata = block.get_payload().iter().any(|txn| match txn.payload() {
+            TransactionPayload::BlockMetadata(_) => true,
+            _ => false,
+        });
+        if has_block_metadata {
+            return future::err(format_err!(
+                "Block {} carries block metadata in its payload",
+                block.id()
+            ))
+            .boxed();
+        }
+
+        let block_metadata_txn = Self::block_metadata(block).into_signed_transaction();
         let mut exec_req = ExecuteBlockRequest::new();
-        exec_req.set_parent_block_id(parent_block_id.to_vec());
-        exec_req.set_block_id(block_id.to_vec());
+        exec_req.set_parent_block_id(block.parent_id().to_vec());
+        exec_req.set_block_id(block.id().to_vec());
         exec_req.set_transactions(::protobuf::RepeatedField::from_vec(
-            transactions
-                .clone()
-                .into_iter()
+            std::iter::once(block_metadata_txn)
+                .chain(block.get_payload().iter().cloned())
                 .map(IntoProto::into_proto)
                 .collect(),
         ));
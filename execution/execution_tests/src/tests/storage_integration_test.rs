@@ -15,7 +15,9 @@ use storage_client::{StorageRead, StorageReadServiceClient};
 use types::{
     access_path::AccessPath,
     account_address::AccountAddress,
-    account_config::{association_address, get_account_resource_or_default},
+    account_config::{
+        association_address, get_account_resource_or_default, received_event_struct_tag,
+    },
     account_state_blob::AccountStateWithProof,
     get_with_proof::{verify_update_to_latest_ledger_response, RequestItem},
     test_helpers::transaction_test_helpers::get_test_signed_txn as get_test_signed_txn_proto,
@@ -234,6 +236,12 @@ fn test_execution_with_storage() {
             ascending: false,
             limit: 10,
         },
+        RequestItem::GetEventsByEventType {
+            event_type: received_event_struct_tag(),
+            start_version: 0,
+            start_event_index: 0,
+            limit: 100,
+        },
     ];
 
     let (mut response_items, ledger_info_with_sigs, _validator_change_events) = storage_read_client
@@ -375,6 +383,24 @@ fn test_execution_with_storage() {
         .unwrap();
     assert_eq!(account3_received_events.len(), 3);
 
+    // All events received by account3 are found among the received events of all accounts.
+    let received_events = response_items
+        .pop()
+        .unwrap()
+        .into_get_events_by_type_response()
+        .unwrap();
+    let account3_received_key = *get_account_resource_or_default(&account3_state_with_proof.blob)
+        .unwrap()
+        .received_events()
+        .key();
+    assert_eq!(
+        received_events
+            .iter()
+            .filter(|e| *e.event.key() == account3_received_key)
+            .count(),
+        3
+    );
+
     // Execution the 2nd block.
     let execute_block_request = ExecuteBlockRequest::new(block2.clone(), block1_id, block2_id);
     let execute_block_response = execution_client
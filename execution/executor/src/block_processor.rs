@@ -13,6 +13,7 @@ use crypto::{
     hash::{CryptoHash, EventAccumulatorHasher},
     HashValue,
 };
+use debug_interface::{failpoint::fail_point, libra_trace, trace_span};
 use execution_proto::{CommitBlockResponse, ExecuteBlockResponse, ExecuteChunkResponse};
 use failure::prelude::*;
 use futures::channel::oneshot;
@@ -508,6 +509,24 @@ where
             }
         }
 
+        // The commit is traced under the id of the block carrying the signatures, linked to the
+        // other blocks of the batch.
+        let (last_block_id, other_blocks) = block_batch
+            .split_last()
+            .map(|(last, others)| (last.id(), others))
+            .expect("There must be at least one block with signatures.");
+        let mut span = trace_span!(
+            "executor::commit_blocks",
+            last_block_id.as_ref(),
+            "blocks": block_batch.len(),
+            "txns": txns_to_commit.len(),
+        );
+        if libra_trace::is_enabled() {
+            for block in other_blocks {
+                span.add_link(block.id().as_ref());
+            }
+        }
+
         let last_block = block_batch
             .last_mut()
             .expect("There must be at least one block with signatures.");
@@ -594,6 +613,16 @@ where
             .block_tree
             .get_block_mut(id)
             .expect("Block to execute should exist.");
+        let mut span = trace_span!(
+            "executor::execute_block",
+            id.as_ref(),
+            "txns": block_to_execute.transactions().len(),
+        );
+        if libra_trace::is_enabled() {
+            for txn in block_to_execute.transactions() {
+                span.add_link(txn.hash().as_ref());
+            }
+        }
 
         // Construct a StateView and pass the transactions to VM.
         let state_view = VerifiedStateView::new(
@@ -620,13 +649,15 @@ where
         }
 
         let (account_to_btree, account_to_proof) = state_view.into();
-        match Self::process_vm_outputs(
-            account_to_btree,
-            account_to_proof,
-            block_to_execute.transactions(),
-            vm_outputs,
-            &parent_trees,
-        ) {
+        match fail_point("executor::execute_block").and_then(|()| {
+            Self::process_vm_outputs(
+                account_to_btree,
+                account_to_proof,
+                block_to_execute.transactions(),
+                vm_outputs,
+                &parent_trees,
+            )
+        }) {
             Ok(output) => {
                 let accu_root_hash = output.executed_trees().txn_accumulator().root_hash();
                 let version = output.executed_trees().txn_accumulator().num_leaves() - 1;
@@ -790,7 +821,8 @@ where
                     match transaction.payload() {
                         TransactionPayload::Program(_)
                         | TransactionPayload::Module(_)
-                        | TransactionPayload::Script(_) => {
+                        | TransactionPayload::Script(_)
+                        | TransactionPayload::BlockMetadata(_) => {
                             bail!("Write set should be a subset of read set.")
                         }
                         TransactionPayload::WriteSet(_) => (),
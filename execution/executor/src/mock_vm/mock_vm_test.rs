@@ -1,13 +1,18 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{balance_ap, encode_mint_transaction, encode_transfer_transaction, seqnum_ap, MockVM};
+use super::{
+    balance_ap, block_timestamp_ap, encode_mint_transaction, encode_transfer_transaction,
+    seqnum_ap, MockVM,
+};
 use config::config::VMConfig;
+use crypto::HashValue;
 use failure::Result;
 use state_view::StateView;
 use types::{
     access_path::AccessPath,
     account_address::{AccountAddress, ADDRESS_LENGTH},
+    block_metadata::BlockMetadata,
     write_set::WriteOp,
 };
 use vm_runtime::VMExecutor;
@@ -140,3 +145,33 @@ fn test_mock_vm_payment() {
         ]
     );
 }
+
+#[test]
+fn test_mock_vm_block_metadata() {
+    let timestamp_usecs = 42;
+    let block_metadata = BlockMetadata::new(
+        HashValue::zero(),
+        timestamp_usecs,
+        Default::default(), /* previous_block_votes */
+        gen_address(0),
+    );
+    let txns = vec![
+        block_metadata.into_signed_transaction(),
+        encode_mint_transaction(gen_address(1), 100),
+    ];
+
+    let outputs = MockVM::execute_block(
+        txns,
+        &VMConfig::empty_whitelist_FOR_TESTING(),
+        &MockStateView,
+    );
+
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(
+        outputs[0].write_set().iter().cloned().collect::<Vec<_>>(),
+        vec![(
+            block_timestamp_ap(),
+            WriteOp::Value(timestamp_usecs.to_le_bytes().to_vec())
+        )]
+    );
+}
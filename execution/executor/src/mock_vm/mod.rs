@@ -14,6 +14,7 @@ use types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
     contract_event::ContractEvent,
     event::EventKey,
+    language_storage::TypeTag,
     transaction::{
         Program, RawTransaction, SignedTransaction, TransactionArgument, TransactionOutput,
         TransactionPayload, TransactionStatus,
@@ -34,6 +35,9 @@ enum Transaction {
         recipient: AccountAddress,
         amount: u64,
     },
+    BlockMetadata {
+        timestamp_usecs: u64,
+    },
 }
 
 lazy_static! {
@@ -130,6 +134,14 @@ impl VMExecutor for MockVM {
                         TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
                     ));
                 }
+                Transaction::BlockMetadata { timestamp_usecs } => {
+                    outputs.push(TransactionOutput::new(
+                        gen_block_metadata_writeset(timestamp_usecs),
+                        vec![],
+                        0,
+                        KEEP_STATUS.clone(),
+                    ));
+                }
             }
         }
 
@@ -193,6 +205,14 @@ fn seqnum_ap(account: AccountAddress) -> AccessPath {
     AccessPath::new(account, b"seqnum".to_vec())
 }
 
+/// The mock counterpart of the on-chain `Block.T` resource: the timestamp of the latest block.
+fn block_timestamp_ap() -> AccessPath {
+    AccessPath::new(
+        AccountAddress::new([0xff; ADDRESS_LENGTH]),
+        b"block_timestamp".to_vec(),
+    )
+}
+
 fn gen_genesis_writeset() -> WriteSet {
     let address = AccountAddress::new([0xff; ADDRESS_LENGTH]);
     let path = b"hello".to_vec();
@@ -244,10 +264,22 @@ fn gen_payment_writeset(
         .expect("payment write set should be valid")
 }
 
+fn gen_block_metadata_writeset(timestamp_usecs: u64) -> WriteSet {
+    let mut write_set = WriteSetMut::default();
+    write_set.push((
+        block_timestamp_ap(),
+        WriteOp::Value(timestamp_usecs.to_le_bytes().to_vec()),
+    ));
+    write_set
+        .freeze()
+        .expect("block metadata write set should be valid")
+}
+
 fn gen_events(sender: AccountAddress) -> Vec<ContractEvent> {
     vec![ContractEvent::new(
         EventKey::new_from_address(&sender, 0),
         0,
+        TypeTag::ByteArray,
         b"event_data".to_vec(),
     )]
 }
@@ -324,5 +356,8 @@ fn decode_transaction(txn: &SignedTransaction) -> Transaction {
         TransactionPayload::Module(_) => {
             unimplemented!("MockVM does not support Module transaction payload.")
         }
+        TransactionPayload::BlockMetadata(block_metadata) => Transaction::BlockMetadata {
+            timestamp_usecs: block_metadata.timestamp_usecs(),
+        },
     }
 }
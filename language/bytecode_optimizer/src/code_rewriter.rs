@@ -0,0 +1,70 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers shared by the passes to edit a code unit while keeping its branches consistent.
+
+use std::collections::BTreeSet;
+use vm::file_format::{Bytecode, CodeOffset};
+
+/// Returns the target of a branch instruction.
+pub fn branch_target(instr: &Bytecode) -> Option<CodeOffset> {
+    match instr {
+        Bytecode::Branch(target) | Bytecode::BrTrue(target) | Bytecode::BrFalse(target) => {
+            Some(*target)
+        }
+        _ => None,
+    }
+}
+
+fn branch_target_mut(instr: &mut Bytecode) -> Option<&mut CodeOffset> {
+    match instr {
+        Bytecode::Branch(target) | Bytecode::BrTrue(target) | Bytecode::BrFalse(target) => {
+            Some(target)
+        }
+        _ => None,
+    }
+}
+
+/// Whether control can flow from the instruction to the one following it.
+pub fn falls_through(instr: &Bytecode) -> bool {
+    match instr {
+        Bytecode::Branch(_) | Bytecode::Ret | Bytecode::Abort => false,
+        _ => true,
+    }
+}
+
+/// The offsets some branch of `code` jumps to.
+pub fn branch_targets(code: &[Bytecode]) -> BTreeSet<usize> {
+    code.iter()
+        .filter_map(branch_target)
+        .map(|target| target as usize)
+        .collect()
+}
+
+/// Removes the instructions at `offsets` from `code`. A branch to a removed instruction jumps to
+/// the first instruction following it that is kept.
+pub fn remove_instructions(code: &mut Vec<Bytecode>, offsets: &BTreeSet<usize>) {
+    if offsets.is_empty() {
+        return;
+    }
+    // new_offsets[i] is the offset the instruction at i, or the next kept one, moves to
+    let mut new_offsets = Vec::with_capacity(code.len() + 1);
+    let mut num_removed = 0;
+    for offset in 0..=code.len() {
+        new_offsets.push((offset - num_removed) as CodeOffset);
+        if offsets.contains(&offset) {
+            num_removed += 1;
+        }
+    }
+
+    let old_code = std::mem::replace(code, vec![]);
+    for (offset, mut instr) in old_code.into_iter().enumerate() {
+        if offsets.contains(&offset) {
+            continue;
+        }
+        if let Some(target) = branch_target_mut(&mut instr) {
+            *target = new_offsets[*target as usize];
+        }
+        code.push(instr);
+    }
+}
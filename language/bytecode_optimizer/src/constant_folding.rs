@@ -0,0 +1,140 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Constant folding: operators applied to constants are replaced by their result, and
+//! conditional branches on a constant become unconditional or are removed.
+//!
+//! Operations that would abort at runtime, like an overflowing addition or a division by zero,
+//! are left in place so that the transaction still aborts.
+
+use crate::{code_rewriter, optimizer::OptimizationPass};
+use std::collections::BTreeSet;
+use vm::file_format::{Bytecode, SignatureToken};
+
+pub struct ConstantFolding;
+
+impl OptimizationPass for ConstantFolding {
+    fn name(&self) -> &'static str {
+        "constant folding"
+    }
+
+    fn optimize(&self, code: &mut Vec<Bytecode>, _locals: &[SignatureToken]) -> bool {
+        let mut changed = false;
+        loop {
+            let targets = code_rewriter::branch_targets(code);
+            let mut removed = BTreeSet::new();
+            let mut offset = 0;
+            while offset < code.len() {
+                // Only the first instruction of a folded sequence may be a branch target
+                let window_len = (offset + 1..code.len())
+                    .take(2)
+                    .take_while(|next| !targets.contains(next))
+                    .count()
+                    + 1;
+                let window = &code[offset..offset + window_len];
+                match fold(window) {
+                    Some((len, replacement)) => {
+                        match replacement {
+                            Some(instr) => {
+                                code[offset] = instr;
+                                removed.extend(offset + 1..offset + len);
+                            }
+                            None => removed.extend(offset..offset + len),
+                        }
+                        offset += len;
+                    }
+                    None => offset += 1,
+                }
+            }
+            if removed.is_empty() {
+                return changed;
+            }
+            code_rewriter::remove_instructions(code, &removed);
+            changed = true;
+        }
+    }
+}
+
+enum Constant {
+    U64(u64),
+    Bool(bool),
+}
+
+fn constant(instr: &Bytecode) -> Option<Constant> {
+    match instr {
+        Bytecode::LdConst(value) => Some(Constant::U64(*value)),
+        Bytecode::LdTrue => Some(Constant::Bool(true)),
+        Bytecode::LdFalse => Some(Constant::Bool(false)),
+        _ => None,
+    }
+}
+
+fn load(constant: Constant) -> Bytecode {
+    match constant {
+        Constant::U64(value) => Bytecode::LdConst(value),
+        Constant::Bool(true) => Bytecode::LdTrue,
+        Constant::Bool(false) => Bytecode::LdFalse,
+    }
+}
+
+/// Folds the instructions at the start of `window`. Returns the number of instructions folded
+/// and the instruction replacing them, if any.
+fn fold(window: &[Bytecode]) -> Option<(usize, Option<Bytecode>)> {
+    match window {
+        [first, second, op, ..] => {
+            if let (Some(lhs), Some(rhs)) = (constant(first), constant(second)) {
+                if let Some(result) = fold_binary(lhs, rhs, op) {
+                    return Some((3, Some(load(result))));
+                }
+            }
+            fold_unary(first, second)
+        }
+        [first, second] => fold_unary(first, second),
+        _ => None,
+    }
+}
+
+fn fold_unary(first: &Bytecode, second: &Bytecode) -> Option<(usize, Option<Bytecode>)> {
+    use Bytecode::*;
+    match (constant(first)?, second) {
+        (Constant::Bool(value), Not) => Some((2, Some(load(Constant::Bool(!value))))),
+        (Constant::Bool(value), BrTrue(target)) => {
+            Some((2, if value { Some(Branch(*target)) } else { None }))
+        }
+        (Constant::Bool(value), BrFalse(target)) => {
+            Some((2, if value { None } else { Some(Branch(*target)) }))
+        }
+        _ => None,
+    }
+}
+
+fn fold_binary(lhs: Constant, rhs: Constant, op: &Bytecode) -> Option<Constant> {
+    use Bytecode::*;
+    match (lhs, rhs) {
+        (Constant::U64(lhs), Constant::U64(rhs)) => match op {
+            Add => lhs.checked_add(rhs).map(Constant::U64),
+            Sub => lhs.checked_sub(rhs).map(Constant::U64),
+            Mul => lhs.checked_mul(rhs).map(Constant::U64),
+            Div => lhs.checked_div(rhs).map(Constant::U64),
+            Mod => lhs.checked_rem(rhs).map(Constant::U64),
+            BitOr => Some(Constant::U64(lhs | rhs)),
+            BitAnd => Some(Constant::U64(lhs & rhs)),
+            Xor => Some(Constant::U64(lhs ^ rhs)),
+            Eq => Some(Constant::Bool(lhs == rhs)),
+            Neq => Some(Constant::Bool(lhs != rhs)),
+            Lt => Some(Constant::Bool(lhs < rhs)),
+            Gt => Some(Constant::Bool(lhs > rhs)),
+            Le => Some(Constant::Bool(lhs <= rhs)),
+            Ge => Some(Constant::Bool(lhs >= rhs)),
+            _ => None,
+        },
+        (Constant::Bool(lhs), Constant::Bool(rhs)) => match op {
+            And => Some(Constant::Bool(lhs && rhs)),
+            Or => Some(Constant::Bool(lhs || rhs)),
+            Eq => Some(Constant::Bool(lhs == rhs)),
+            Neq => Some(Constant::Bool(lhs != rhs)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
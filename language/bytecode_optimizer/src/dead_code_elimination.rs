@@ -0,0 +1,48 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dead code elimination: instructions that cannot be reached from the entry of the function are
+//! removed.
+
+use crate::{code_rewriter, optimizer::OptimizationPass};
+use std::collections::BTreeSet;
+use vm::file_format::{Bytecode, SignatureToken};
+
+pub struct DeadCodeElimination;
+
+impl OptimizationPass for DeadCodeElimination {
+    fn name(&self) -> &'static str {
+        "dead code elimination"
+    }
+
+    fn optimize(&self, code: &mut Vec<Bytecode>, _locals: &[SignatureToken]) -> bool {
+        let reachable = reachable_offsets(code);
+        let unreachable: BTreeSet<_> = (0..code.len())
+            .filter(|offset| !reachable.contains(offset))
+            .collect();
+        if unreachable.is_empty() {
+            return false;
+        }
+        // Only unreachable code can branch to unreachable code, so no kept branch is remapped
+        code_rewriter::remove_instructions(code, &unreachable);
+        true
+    }
+}
+
+fn reachable_offsets(code: &[Bytecode]) -> BTreeSet<usize> {
+    let mut reachable = BTreeSet::new();
+    let mut worklist = vec![0];
+    while let Some(offset) = worklist.pop() {
+        if offset >= code.len() || !reachable.insert(offset) {
+            continue;
+        }
+        let instr = &code[offset];
+        if let Some(target) = code_rewriter::branch_target(instr) {
+            worklist.push(target as usize);
+        }
+        if code_rewriter::falls_through(instr) {
+            worklist.push(offset + 1);
+        }
+    }
+    reachable
+}
@@ -0,0 +1,59 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Jump threading: a branch to an unconditional branch jumps directly to the final target, and an
+//! unconditional branch to a return returns directly.
+
+use crate::{code_rewriter, optimizer::OptimizationPass};
+use std::collections::BTreeSet;
+use vm::file_format::{Bytecode, CodeOffset, SignatureToken};
+
+pub struct JumpThreading;
+
+impl OptimizationPass for JumpThreading {
+    fn name(&self) -> &'static str {
+        "jump threading"
+    }
+
+    fn optimize(&self, code: &mut Vec<Bytecode>, _locals: &[SignatureToken]) -> bool {
+        let mut changed = false;
+        for offset in 0..code.len() {
+            let target = match code_rewriter::branch_target(&code[offset]) {
+                Some(target) => target,
+                None => continue,
+            };
+            let final_target = final_target(code, target);
+            if let Bytecode::Branch(_) = code[offset] {
+                if code[final_target as usize] == Bytecode::Ret {
+                    code[offset] = Bytecode::Ret;
+                    changed = true;
+                    continue;
+                }
+            }
+            if final_target != target {
+                match &mut code[offset] {
+                    Bytecode::Branch(target)
+                    | Bytecode::BrTrue(target)
+                    | Bytecode::BrFalse(target) => *target = final_target,
+                    _ => unreachable!("only branches have a target"),
+                }
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Follows the chain of unconditional branches starting at `target`. A chain that loops forever
+/// stops at the first branch seen twice.
+fn final_target(code: &[Bytecode], target: CodeOffset) -> CodeOffset {
+    let mut visited = BTreeSet::new();
+    let mut target = target;
+    while let Bytecode::Branch(next) = code[target as usize] {
+        if !visited.insert(target) {
+            break;
+        }
+        target = next;
+    }
+    target
+}
@@ -0,0 +1,17 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optimizes the function bodies of verified modules and scripts.
+//!
+//! The optimizer runs a pipeline of passes over the bytecode of every function until none of them
+//! changes the code. The bytecode verifier runs again after every pass, so a pass producing
+//! invalid code is reported instead of being published.
+
+pub mod code_rewriter;
+pub mod constant_folding;
+pub mod dead_code_elimination;
+pub mod jump_threading;
+pub mod optimizer;
+pub mod peephole;
+
+pub use optimizer::{OptimizationPass, Optimizer};
@@ -0,0 +1,113 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The optimization pipeline.
+
+use crate::{
+    constant_folding::ConstantFolding, dead_code_elimination::DeadCodeElimination,
+    jump_threading::JumpThreading, peephole::Peephole,
+};
+use bytecode_verifier::{VerifiedModule, VerifiedScript};
+use failure::prelude::*;
+use vm::file_format::{Bytecode, FunctionDefinition, LocalsSignature, SignatureToken};
+
+/// The maximum number of times the pipeline runs over a module. Every pass only ever shrinks or
+/// simplifies the code, so this bound is only reached by a pass that keeps reporting changes.
+const MAX_ROUNDS: usize = 16;
+
+/// A transformation of a function body that preserves its behavior.
+pub trait OptimizationPass {
+    /// The name of the pass, used in error messages.
+    fn name(&self) -> &'static str;
+
+    /// Rewrites `code`, the body of a function whose locals have the types `locals`. Returns
+    /// whether the code changed.
+    fn optimize(&self, code: &mut Vec<Bytecode>, locals: &[SignatureToken]) -> bool;
+}
+
+pub struct Optimizer {
+    passes: Vec<Box<dyn OptimizationPass>>,
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new(vec![
+            Box::new(ConstantFolding),
+            Box::new(Peephole),
+            Box::new(JumpThreading),
+            Box::new(DeadCodeElimination),
+        ])
+    }
+}
+
+impl Optimizer {
+    pub fn new(passes: Vec<Box<dyn OptimizationPass>>) -> Self {
+        Self { passes }
+    }
+
+    /// Optimizes the functions of `module`. The module is verified again after every pass.
+    pub fn optimize_module(&self, module: VerifiedModule) -> Result<VerifiedModule> {
+        let mut module = module;
+        for _ in 0..MAX_ROUNDS {
+            let mut changed = false;
+            for pass in &self.passes {
+                let mut inner = module.into_inner().into_inner();
+                for function_def in &mut inner.function_defs {
+                    changed |= run_pass(pass.as_ref(), function_def, &inner.locals_signatures);
+                }
+                let compiled_module = inner.freeze().map_err(|errors| {
+                    format_err!("{} pass broke the module bounds: {:?}", pass.name(), errors)
+                })?;
+                module = VerifiedModule::new(compiled_module).map_err(|(_, errors)| {
+                    format_err!(
+                        "{} pass produced an invalid module: {:?}",
+                        pass.name(),
+                        errors
+                    )
+                })?;
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok(module)
+    }
+
+    /// Optimizes the main function of `script`. The script is verified again after every pass.
+    pub fn optimize_script(&self, script: VerifiedScript) -> Result<VerifiedScript> {
+        let mut script = script;
+        for _ in 0..MAX_ROUNDS {
+            let mut changed = false;
+            for pass in &self.passes {
+                let mut inner = script.into_inner().into_inner();
+                changed |= run_pass(pass.as_ref(), &mut inner.main, &inner.locals_signatures);
+                let compiled_script = inner.freeze().map_err(|errors| {
+                    format_err!("{} pass broke the script bounds: {:?}", pass.name(), errors)
+                })?;
+                script = VerifiedScript::new(compiled_script).map_err(|(_, errors)| {
+                    format_err!(
+                        "{} pass produced an invalid script: {:?}",
+                        pass.name(),
+                        errors
+                    )
+                })?;
+            }
+            if !changed {
+                break;
+            }
+        }
+        Ok(script)
+    }
+}
+
+fn run_pass(
+    pass: &dyn OptimizationPass,
+    function_def: &mut FunctionDefinition,
+    locals_signatures: &[LocalsSignature],
+) -> bool {
+    if function_def.is_native() {
+        return false;
+    }
+    let locals = &locals_signatures[function_def.code.locals.0 as usize].0;
+    pass.optimize(&mut function_def.code.code, locals)
+}
@@ -0,0 +1,102 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Peephole simplification of pairs of adjacent instructions:
+//!
+//! - a value loaded and immediately popped is never loaded;
+//! - a value stored into a local and immediately moved out of it stays on the stack;
+//! - a double negation is removed, and a negation before a conditional branch flips the branch;
+//! - a branch to the next instruction is removed.
+//!
+//! The second instruction of a pair must not be a branch target, since the code jumping there
+//! relies on the first instruction having run.
+
+use crate::{code_rewriter, optimizer::OptimizationPass};
+use std::collections::BTreeSet;
+use vm::file_format::{Bytecode, SignatureToken};
+
+pub struct Peephole;
+
+impl OptimizationPass for Peephole {
+    fn name(&self) -> &'static str {
+        "peephole"
+    }
+
+    fn optimize(&self, code: &mut Vec<Bytecode>, locals: &[SignatureToken]) -> bool {
+        let mut changed = false;
+        loop {
+            let targets = code_rewriter::branch_targets(code);
+            let mut removed = BTreeSet::new();
+            let mut offset = 0;
+            while offset < code.len() {
+                let next_is_target = targets.contains(&(offset + 1));
+                match (&code[offset], code.get(offset + 1)) {
+                    (Bytecode::Branch(target), _) if *target as usize == offset + 1 => {
+                        removed.insert(offset);
+                    }
+                    (first, Some(second)) if !next_is_target => {
+                        match simplify_pair(first, second, locals) {
+                            Some(PairRewrite::RemoveBoth) => {
+                                removed.insert(offset);
+                                removed.insert(offset + 1);
+                                offset += 1;
+                            }
+                            Some(PairRewrite::Replace(instr)) => {
+                                code[offset + 1] = instr;
+                                removed.insert(offset);
+                                offset += 1;
+                            }
+                            None => (),
+                        }
+                    }
+                    _ => (),
+                }
+                offset += 1;
+            }
+            if removed.is_empty() {
+                return changed;
+            }
+            code_rewriter::remove_instructions(code, &removed);
+            changed = true;
+        }
+    }
+}
+
+enum PairRewrite {
+    RemoveBoth,
+    /// Replace the pair with a single instruction
+    Replace(Bytecode),
+}
+
+fn simplify_pair(
+    first: &Bytecode,
+    second: &Bytecode,
+    locals: &[SignatureToken],
+) -> Option<PairRewrite> {
+    use Bytecode::*;
+    match (first, second) {
+        (CopyLoc(_), Pop)
+        | (LdConst(_), Pop)
+        | (LdTrue, Pop)
+        | (LdFalse, Pop)
+        | (LdAddr(_), Pop)
+        | (LdByteArray(_), Pop)
+        | (LdStr(_), Pop)
+        | (Not, Not) => Some(PairRewrite::RemoveBoth),
+        // Leaving a reference in the local would extend its borrow, so only values are kept on
+        // the stack
+        (StLoc(stored), MoveLoc(moved)) if stored == moved && !is_reference(locals, *stored) => {
+            Some(PairRewrite::RemoveBoth)
+        }
+        (Not, BrFalse(target)) => Some(PairRewrite::Replace(BrTrue(*target))),
+        (Not, BrTrue(target)) => Some(PairRewrite::Replace(BrFalse(*target))),
+        _ => None,
+    }
+}
+
+fn is_reference(locals: &[SignatureToken], local: u8) -> bool {
+    match locals[local as usize] {
+        SignatureToken::Reference(_) | SignatureToken::MutableReference(_) => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use bytecode_optimizer::Optimizer;
+use bytecode_verifier::{VerifiedModule, VerifiedScript};
+use ir_to_bytecode::{
+    compiler::{compile_module, compile_script},
+    parser::{parse_module, parse_script},
+};
+use stdlib::stdlib_modules;
+use types::account_address::AccountAddress;
+use vm::{
+    access::{ModuleAccess, ScriptAccess},
+    file_format::Bytecode,
+};
+
+fn optimize_module(code: &str) -> VerifiedModule {
+    let module = compile_module(
+        AccountAddress::default(),
+        parse_module(code).unwrap(),
+        stdlib_modules(),
+    )
+    .unwrap();
+    let module = VerifiedModule::new(module).unwrap();
+    Optimizer::default().optimize_module(module).unwrap()
+}
+
+fn function_code<'a>(module: &'a VerifiedModule, name: &str) -> &'a [Bytecode] {
+    module
+        .function_defs()
+        .iter()
+        .find(|function_def| {
+            let handle = module.function_handle_at(function_def.function);
+            module.identifier_at(handle.name).as_str() == name
+        })
+        .map(|function_def| function_def.code.code.as_slice())
+        .unwrap()
+}
+
+#[test]
+fn folds_constant_arithmetic() {
+    let module = optimize_module(
+        "
+        module M {
+            public f(): u64 {
+                let x: u64;
+                x = 1 + 2 * 3;
+                return move(x);
+            }
+        }
+        ",
+    );
+    assert_eq!(
+        function_code(&module, "f"),
+        &[Bytecode::LdConst(7), Bytecode::Ret]
+    );
+}
+
+#[test]
+fn keeps_aborting_arithmetic() {
+    let module = optimize_module(
+        "
+        module M {
+            public f(): u64 {
+                return 1 / 0;
+            }
+        }
+        ",
+    );
+    assert!(function_code(&module, "f").contains(&Bytecode::Div));
+}
+
+#[test]
+fn removes_constant_branches_and_dead_code() {
+    let module = optimize_module(
+        "
+        module M {
+            public f(x: u64): u64 {
+                if (true) {
+                    return move(x);
+                } else {
+                    return 0;
+                }
+            }
+        }
+        ",
+    );
+    assert_eq!(
+        function_code(&module, "f"),
+        &[Bytecode::MoveLoc(0), Bytecode::Ret]
+    );
+}
+
+#[test]
+fn threads_branches() {
+    let module = optimize_module(
+        "
+        module M {
+            public f(x: u64): u64 {
+                let y: u64;
+                if (copy(x) > 10) {
+                    y = 1;
+                } else {
+                    y = 2;
+                }
+                return move(y);
+            }
+        }
+        ",
+    );
+    let code = function_code(&module, "f");
+    // The branches around the else block survive, but none of them jumps to a branch
+    for instr in code {
+        if let Bytecode::Branch(target) | Bytecode::BrFalse(target) = instr {
+            if let Bytecode::Branch(_) = code[*target as usize] {
+                panic!("branch to a branch in {:?}", code);
+            }
+        }
+    }
+}
+
+#[test]
+fn optimizes_scripts() {
+    let script = compile_script(
+        AccountAddress::default(),
+        parse_script(
+            "
+            main() {
+                let x: u64;
+                x = 5;
+                if (!(copy(x) > 3)) {
+                    abort 1;
+                }
+                return;
+            }
+            ",
+        )
+        .unwrap(),
+        stdlib_modules(),
+    )
+    .unwrap();
+    let script = VerifiedScript::new(script).unwrap();
+    let original_len = script.main().code.code.len();
+    let script = Optimizer::default().optimize_script(script).unwrap();
+    let code = &script.main().code.code;
+    assert!(code.len() < original_len);
+    assert!(!code.contains(&Bytecode::Not));
+}
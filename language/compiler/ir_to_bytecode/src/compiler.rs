@@ -5,12 +5,12 @@ use crate::{
     context::{Context, MaterializedPools},
     errors::*,
     parser::ast::{
-        self, BinOp, Block, Builtin, Cmd, Cmd_, CopyableVal, Exp, Exp_, Function, FunctionBody,
-        FunctionCall, FunctionName, FunctionSignature as AstFunctionSignature, FunctionVisibility,
-        IfElse, ImportDefinition, LValue, LValue_, Loop, ModuleDefinition, ModuleIdent, ModuleName,
-        Program, QualifiedModuleIdent, QualifiedStructIdent, Script, Statement,
-        StructDefinition as MoveStruct, StructDefinitionFields, Type, TypeVar, UnaryOp, Var, Var_,
-        While,
+        self, BinOp, Block, Builtin, Cmd, Cmd_, Condition, CopyableVal, Exp, Exp_, Function,
+        FunctionBody, FunctionCall, FunctionName, FunctionSignature as AstFunctionSignature,
+        FunctionVisibility, IfElse, ImportDefinition, LValue, LValue_, Loc, Loop, ModuleDefinition,
+        ModuleIdent, ModuleName, Program, QualifiedModuleIdent, QualifiedStructIdent, Script,
+        SpecExp, Statement, StructDefinition as MoveStruct, StructDefinitionFields, Type, TypeVar,
+        UnaryOp, Var, Var_, While,
     },
 };
 
@@ -19,14 +19,14 @@ use std::{
     clone::Clone,
     collections::{
         hash_map::Entry::{Occupied, Vacant},
-        HashMap, VecDeque,
+        BTreeMap, HashMap, VecDeque,
     },
 };
 use types::{account_address::AccountAddress, identifier::Identifier};
 use vm::{
     access::ModuleAccess,
     file_format::{
-        self, Bytecode, CodeUnit, CompiledModule, CompiledModuleMut, CompiledProgram,
+        self, Bytecode, CodeOffset, CodeUnit, CompiledModule, CompiledModuleMut, CompiledProgram,
         CompiledScript, CompiledScriptMut, FieldDefinition, FieldDefinitionIndex,
         FunctionDefinition, FunctionSignature, Kind, LocalsSignature, MemberCount, SignatureToken,
         StructDefinition, StructFieldInformation, StructHandleIndex, TableIndex,
@@ -141,6 +141,8 @@ struct FunctionFrame {
     max_stack_depth: i64,
     cur_stack_depth: i64,
     loops: Vec<LoopInfo>,
+    // The location of the statement each instruction was compiled from
+    locations: BTreeMap<CodeOffset, Loc>,
 }
 
 impl FunctionFrame {
@@ -148,6 +150,18 @@ impl FunctionFrame {
         FunctionFrame::default()
     }
 
+    // Attribute the instructions in `start..end` that don't have a location yet to `loc`. Nested
+    // statements are compiled first, so they keep their own location.
+    fn record_location(&mut self, start: usize, end: usize, loc: Loc) {
+        if loc.start() == loc.end() {
+            // The statement was not parsed from source
+            return;
+        }
+        for offset in start..end {
+            self.locations.entry(offset as CodeOffset).or_insert(loc);
+        }
+    }
+
     // Manage the stack info for the function
     fn push(&mut self) -> Result<()> {
         if self.cur_stack_depth == i64::max_value() {
@@ -283,7 +297,7 @@ pub fn compile_script<'a, T: 'a + ModuleAccess>(
 
     let sig = function_signature(&mut context, &function.signature)?;
     context.declare_function(self_name.clone(), main_name.clone(), sig)?;
-    let main = compile_function(&mut context, &self_name, main_name, function)?;
+    let (main, _) = compile_function(&mut context, &self_name, main_name, function)?;
 
     let MaterializedPools {
         module_handles,
@@ -321,6 +335,15 @@ pub fn compile_module<'a, T: 'a + ModuleAccess>(
     module: ModuleDefinition,
     dependencies: impl IntoIterator<Item = &'a T>,
 ) -> Result<CompiledModule> {
+    Ok(compile_module_with_source_map(address, module, dependencies)?.0)
+}
+
+/// Compile a module, and return the locations in the source of its instructions alongside it.
+pub fn compile_module_with_source_map<'a, T: 'a + ModuleAccess>(
+    address: AccountAddress,
+    module: ModuleDefinition,
+    dependencies: impl IntoIterator<Item = &'a T>,
+) -> Result<(CompiledModule, ModuleSourceMap)> {
     let current_module = QualifiedModuleIdent {
         address,
         name: module.name,
@@ -349,7 +372,8 @@ pub fn compile_module<'a, T: 'a + ModuleAccess>(
 
     let (struct_defs, field_defs) = compile_structs(&mut context, &self_name, module.structs)?;
 
-    let function_defs = compile_functions(&mut context, &self_name, module.functions)?;
+    let (function_defs, function_source_maps) =
+        compile_functions(&mut context, &self_name, module.functions)?;
 
     let MaterializedPools {
         module_handles,
@@ -378,9 +402,93 @@ pub fn compile_module<'a, T: 'a + ModuleAccess>(
         field_defs,
         function_defs,
     };
-    compiled_module
+    let compiled_module = compiled_module
         .freeze()
-        .map_err(|errs| InternalCompilerError::BoundsCheckErrors(errs).into())
+        .map_err(|errs| InternalCompilerError::BoundsCheckErrors(errs))?;
+    let source_map = ModuleSourceMap {
+        functions: function_source_maps,
+    };
+    Ok((compiled_module, source_map))
+}
+
+/// The locations in the source of the instructions of a module.
+///
+/// `functions` is indexed like the function definitions of the compiled module.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModuleSourceMap {
+    pub functions: Vec<FunctionSourceMap>,
+}
+
+/// The location of the statement each instruction of a function was compiled from. Instructions
+/// added by the compiler on its own, like the return ending a function without one, have no
+/// location.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FunctionSourceMap {
+    pub locations: BTreeMap<CodeOffset, Loc>,
+}
+
+/// The specifications of a module. They are not part of the bytecode, so they are returned
+/// alongside the compiled module.
+///
+/// `functions` and `structs` are indexed like the function and struct definitions of the compiled
+/// module.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModuleSpec {
+    pub functions: Vec<FunctionSpec>,
+    pub structs: Vec<StructSpec>,
+}
+
+/// The specification of a function.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionSpec {
+    /// The names of the formals of the function, which the conditions refer to.
+    pub formals: Vec<String>,
+    pub conditions: Vec<Condition>,
+}
+
+/// The specification of a struct.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructSpec {
+    /// Properties of every instance of the struct, stated over its fields.
+    pub invariants: Vec<SpecExp>,
+}
+
+impl ModuleSpec {
+    /// Collects the specifications declared in a module.
+    pub fn new(module: &ModuleDefinition) -> Self {
+        let functions = module
+            .functions
+            .iter()
+            .map(|(_, function)| FunctionSpec {
+                formals: function
+                    .signature
+                    .formals
+                    .iter()
+                    .map(|(var, _)| var.name().to_string())
+                    .collect(),
+                conditions: function.specifications.clone(),
+            })
+            .collect();
+        let structs = module
+            .structs
+            .iter()
+            .map(|s| StructSpec {
+                invariants: s.invariants.clone(),
+            })
+            .collect();
+        ModuleSpec { functions, structs }
+    }
+}
+
+/// Compile a module, and return its specifications alongside it.
+pub fn compile_module_with_spec<'a, T: 'a + ModuleAccess>(
+    address: AccountAddress,
+    module: ModuleDefinition,
+    dependencies: impl IntoIterator<Item = &'a T>,
+) -> Result<(CompiledModule, ModuleSpec)> {
+    let spec = ModuleSpec::new(&module);
+    let compiled_module = compile_module(address, module, dependencies)?;
+    Ok((compiled_module, spec))
 }
 
 fn compile_imports(
@@ -533,11 +641,12 @@ fn compile_functions(
     context: &mut Context,
     self_name: &ModuleName,
     functions: Vec<(FunctionName, Function)>,
-) -> Result<Vec<FunctionDefinition>> {
-    functions
+) -> Result<(Vec<FunctionDefinition>, Vec<FunctionSourceMap>)> {
+    let compiled_functions = functions
         .into_iter()
         .map(|(name, ast_function)| compile_function(context, self_name, name, ast_function))
-        .collect()
+        .collect::<Result<Vec<_>>>()?;
+    Ok(compiled_functions.into_iter().unzip())
 }
 
 fn compile_function(
@@ -545,7 +654,7 @@ fn compile_function(
     self_name: &ModuleName,
     name: FunctionName,
     ast_function: Function,
-) -> Result<FunctionDefinition> {
+) -> Result<(FunctionDefinition, FunctionSourceMap)> {
     let fh_idx = context.function_handle(self_name.clone(), name)?.1;
 
     let flags = match ast_function.visibility {
@@ -561,20 +670,21 @@ fn compile_function(
         .map(|name| context.struct_definition_index(name))
         .collect::<Result<_>>()?;
 
-    let code = match ast_function.body {
+    let (code, source_map) = match ast_function.body {
         FunctionBody::Move { locals, code } => {
             let (m, _) = type_formals(&ast_function.signature.type_formals)?;
             context.bind_type_formals(m)?;
             compile_function_body(context, ast_function.signature.formals, locals, code)?
         }
-        FunctionBody::Native => CodeUnit::default(),
+        FunctionBody::Native => (CodeUnit::default(), FunctionSourceMap::default()),
     };
-    Ok(FunctionDefinition {
+    let function_def = FunctionDefinition {
         function: fh_idx,
         flags,
         acquires_global_resources,
         code,
-    })
+    };
+    Ok((function_def, source_map))
 }
 
 fn compile_function_body(
@@ -582,7 +692,7 @@ fn compile_function_body(
     formals: Vec<(Var, Type)>,
     locals: Vec<(Var_, Type)>,
     block: Block,
-) -> Result<CodeUnit> {
+) -> Result<(CodeUnit, FunctionSourceMap)> {
     let mut function_frame = FunctionFrame::new();
     let mut locals_signature = LocalsSignature(vec![]);
     for (var, t) in formals {
@@ -606,11 +716,15 @@ fn compile_function_body(
     } else {
         function_frame.max_stack_depth as u16
     };
-    Ok(CodeUnit {
+    let code_unit = CodeUnit {
         locals: sig_idx,
         max_stack_size,
         code,
-    })
+    };
+    let source_map = FunctionSourceMap {
+        locations: function_frame.locations,
+    };
+    Ok((code_unit, source_map))
 }
 
 fn compile_block(
@@ -624,6 +738,13 @@ fn compile_block(
         terminal_node: false,
     };
     for stmt in block.stmts {
+        let start = code.len();
+        let loc = match &stmt {
+            Statement::CommandStatement(command) => Some(command.span),
+            Statement::WhileStatement(while_) => Some(while_.cond.span),
+            Statement::IfElseStatement(if_else) => Some(if_else.cond.span),
+            Statement::LoopStatement(_) | Statement::EmptyStatement => None,
+        };
         let stmt_info = match stmt {
             Statement::CommandStatement(command) => {
                 compile_command(context, function_frame, code, command)?
@@ -638,6 +759,9 @@ fn compile_block(
             }
             Statement::EmptyStatement => continue,
         };
+        if let Some(loc) = loc {
+            function_frame.record_location(start, code.len(), loc);
+        }
         cf_info = ControlFlowInfo::successor(cf_info, stmt_info);
     }
     Ok(cf_info)
@@ -214,6 +214,8 @@ pub struct StructDefinition {
     pub type_formals: Vec<(TypeVar, Kind)>,
     /// the fields each instance has
     pub fields: StructDefinitionFields,
+    /// Properties that hold for every instance, stated over its fields
+    pub invariants: Vec<SpecExp>,
 }
 
 /// The fields of a Move struct definition
@@ -269,9 +271,22 @@ pub enum FunctionBody {
     Native,
 }
 
+/// An attribute written before a function declaration, like `#[test]`
+#[derive(PartialEq, Debug, Clone)]
+pub enum FunctionAttribute {
+    /// The procedure is a unit test, run by the Move test runner
+    /// `#[test]`
+    Test,
+    /// The test passes only if it aborts, with the given code if any
+    /// `#[expected_failure]` or `#[expected_failure(abort_code = c)]`
+    ExpectedFailure(Option<u64>),
+}
+
 /// A Move function/procedure
 #[derive(PartialEq, Debug, Clone)]
 pub struct Function {
+    /// The attributes of the procedure. They are not used by the compiler or the VM
+    pub attributes: Vec<FunctionAttribute>,
     /// The visibility (public or internal)
     pub visibility: FunctionVisibility,
     /// The type signature
@@ -281,6 +296,9 @@ pub struct Function {
     /// This list of acquires grants the borrow checker the ability to statically verify the safety
     /// of references into global storage
     pub acquires: Vec<StructName>,
+    /// Pre- and postconditions of the procedure. They are not checked by the compiler or the VM,
+    /// only by the prover
+    pub specifications: Vec<Condition>,
     /// The code for the procedure
     pub body: FunctionBody,
 }
@@ -537,6 +555,89 @@ pub enum Exp {
 /// The type for a `Exp` and its location
 pub type Exp_ = Spanned<Exp>;
 
+//**************************************************************************************************
+// Specifications
+//**************************************************************************************************
+
+/// A location in memory that specification expressions can refer to
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageLocation {
+    /// A formal of the function, or a field of the struct in a struct invariant
+    /// `x`
+    Formal(String),
+    /// The resource of the given type stored at the given address
+    /// `global<m.S>(address)`
+    GlobalResource {
+        /// the type of the resource
+        type_: QualifiedStructIdent,
+        /// the type actuals of the resource
+        type_actuals: Vec<Type>,
+        /// the address the resource is stored at
+        address: Box<StorageLocation>,
+    },
+    /// A path of field accesses into a struct
+    /// `base.f_1.f_2 ... .f_j`
+    AccessPath {
+        /// the location of the struct
+        base: Box<StorageLocation>,
+        /// the fields accessed, outermost first
+        fields: Vec<Field>,
+    },
+    /// The sender of the current transaction
+    /// `txn_sender`
+    TxnSenderAddress,
+    /// An address literal
+    /// `0x...`
+    Address(AccountAddress),
+    /// The `i`th return value of the function. Only meaningful in `ensures`
+    /// `RET` (the first return value) or `RET(i)`
+    Ret(usize),
+}
+
+/// Expressions used in specifications. Unlike `Exp`, they have no side effects
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpecExp {
+    /// `v`
+    Constant(CopyableVal),
+    /// The value stored at a location
+    StorageLocation(StorageLocation),
+    /// Whether a resource of the given type is stored at the given address
+    /// `global_exists<m.S>(address)`
+    GlobalExists {
+        /// the type of the resource
+        type_: QualifiedStructIdent,
+        /// the type actuals of the resource
+        type_actuals: Vec<Type>,
+        /// the address the resource would be stored at
+        address: StorageLocation,
+    },
+    /// The value a reference stored at a location points to
+    /// `*x`
+    Dereference(StorageLocation),
+    /// `!e`
+    Not(Box<SpecExp>),
+    /// `e_1 op e_2`
+    Binop(Box<SpecExp>, BinOp, Box<SpecExp>),
+    /// The value of `e` when the function was called. Only meaningful in `ensures`
+    /// `old(e)`
+    Old(Box<SpecExp>),
+}
+
+/// A specification clause of a function
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// The expression must hold when the function is called
+    /// `requires e`
+    Requires(SpecExp),
+    /// The expression holds when the function returns without aborting
+    /// `ensures e`
+    Ensures(SpecExp),
+    /// The function aborts if the expression holds when it is called. If a function has
+    /// `aborts_if` clauses, it aborts only if one of them holds
+    /// `aborts_if e`
+    AbortsIf(SpecExp),
+}
+
 //**************************************************************************************************
 // impls
 //**************************************************************************************************
@@ -715,6 +816,132 @@ impl QualifiedStructIdent {
     }
 }
 
+impl StorageLocation {
+    /// Creates the location a plain name refers to: `RET`, `txn_sender` or a formal. Intended for
+    /// use by syntax.lalrpop.
+    pub fn from_name(name: String) -> Self {
+        match name.as_str() {
+            "RET" => StorageLocation::Ret(0),
+            "txn_sender" => StorageLocation::TxnSenderAddress,
+            _ => StorageLocation::Formal(name),
+        }
+    }
+
+    /// Creates the location reached by accessing `fields` in order from `base`. Intended for use
+    /// by syntax.lalrpop.
+    pub fn with_fields(base: StorageLocation, fields: Vec<Field>) -> Self {
+        if fields.is_empty() {
+            base
+        } else {
+            StorageLocation::AccessPath {
+                base: Box::new(base),
+                fields,
+            }
+        }
+    }
+}
+
+fn spec_error<L, T, R>(message: String) -> Result<R, ParseError<L, T, failure::Error>> {
+    Err(ParseError::User {
+        error: failure::err_msg(message),
+    })
+}
+
+impl SpecExp {
+    /// Creates the expression `name(argument).fields`, which is either `old(e)` or `RET(i)`
+    /// followed by field accesses. Intended for use by syntax.lalrpop.
+    pub fn parse_call<L, T>(
+        name: &str,
+        argument: SpecExp,
+        fields: Vec<Field>,
+    ) -> Result<Self, ParseError<L, T, failure::Error>> {
+        match (name, argument) {
+            ("old", argument) => {
+                if fields.is_empty() {
+                    Ok(SpecExp::Old(Box::new(argument)))
+                } else {
+                    spec_error("Fields cannot be accessed on old(..)".to_string())
+                }
+            }
+            ("RET", SpecExp::Constant(CopyableVal::U64(i))) => Ok(SpecExp::StorageLocation(
+                StorageLocation::with_fields(StorageLocation::Ret(i as usize), fields),
+            )),
+            ("RET", _) => spec_error("The argument of RET(..) must be an integer".to_string()),
+            _ => spec_error(format!("Invalid specification function: {}", name)),
+        }
+    }
+
+    /// Creates the expression `name<type_>(address).fields`, which is either
+    /// `global<type_>(address)` followed by field accesses or `global_exists<type_>(address)`.
+    /// Intended for use by syntax.lalrpop.
+    pub fn parse_global<L, T>(
+        name: &str,
+        type_: QualifiedStructIdent,
+        type_actuals: Vec<Type>,
+        address: StorageLocation,
+        fields: Vec<Field>,
+    ) -> Result<Self, ParseError<L, T, failure::Error>> {
+        match name {
+            "global" => Ok(SpecExp::StorageLocation(StorageLocation::with_fields(
+                StorageLocation::GlobalResource {
+                    type_,
+                    type_actuals,
+                    address: Box::new(address),
+                },
+                fields,
+            ))),
+            "global_exists" if fields.is_empty() => Ok(SpecExp::GlobalExists {
+                type_,
+                type_actuals,
+                address,
+            }),
+            "global_exists" => {
+                spec_error("Fields cannot be accessed on global_exists<..>(..)".to_string())
+            }
+            _ => spec_error(format!("Invalid specification function: {}", name)),
+        }
+    }
+
+    /// Parses the expression of an invariant from its keyword. Intended for use by
+    /// syntax.lalrpop.
+    pub fn parse_invariant<L, T>(
+        keyword: &str,
+        e: SpecExp,
+    ) -> Result<Self, ParseError<L, T, failure::Error>> {
+        match keyword {
+            "invariant" => Ok(e),
+            _ => spec_error(format!("Expected an invariant, found: {}", keyword)),
+        }
+    }
+
+    /// Converts an expression parsed as a call into the location it refers to. Intended for use
+    /// by syntax.lalrpop.
+    pub fn into_storage_location<L, T>(
+        self,
+    ) -> Result<StorageLocation, ParseError<L, T, failure::Error>> {
+        match self {
+            SpecExp::StorageLocation(l) => Ok(l),
+            _ => spec_error("Expected a storage location".to_string()),
+        }
+    }
+}
+
+impl Condition {
+    /// Parses a condition from its keyword and its expression. Intended for use by
+    /// syntax.lalrpop.
+    pub fn parse<L, T>(
+        keyword: &str,
+        e: SpecExp,
+    ) -> Result<Self, ParseError<L, T, failure::Error>> {
+        match keyword {
+            "requires" => Ok(Condition::Requires(e)),
+            "ensures" => Ok(Condition::Ensures(e)),
+            "aborts_if" => Ok(Condition::AbortsIf(e)),
+            _ => spec_error(format!("Invalid condition: {}", keyword)),
+        }
+    }
+}
+
 impl ImportDefinition {
     /// Creates a new import definition from a module identifier and an optional alias
     /// If the alias is `None`, the alias will be a cloned copy of the identifiers module name
@@ -751,8 +978,8 @@ impl StructName {
 
 impl StructDefinition {
     /// Creates a new StructDefinition from the resource kind (true if resource), the string
-    /// representation of the name, and the user specified fields, a map from their names to their
-    /// types
+    /// representation of the name, the user specified fields, a map from their names to their
+    /// types, and the invariants over those fields
     /// Does not verify the correctness of any internal properties, e.g. doesn't check that the
     /// fields do not have reference types
     pub fn move_declared<L, T>(
@@ -760,12 +987,14 @@ impl StructDefinition {
         name: impl Into<Box<str>>,
         type_formals: Vec<(TypeVar, Kind)>,
         fields: Fields<Type>,
+        invariants: Vec<SpecExp>,
     ) -> Result<Self, ParseError<L, T, failure::Error>> {
         Ok(StructDefinition {
             is_nominal_resource,
             name: StructName::parse(name)?,
             type_formals,
             fields: StructDefinitionFields::Move { fields },
+            invariants,
         })
     }
 
@@ -782,6 +1011,7 @@ impl StructDefinition {
             name: StructName::parse(name)?,
             type_formals,
             fields: StructDefinitionFields::Native,
+            invariants: vec![],
         })
     }
 }
@@ -827,23 +1057,46 @@ impl Function {
     /// Creates a new function declaration from the components of the function
     /// See the declaration of the struct `Function` for more details
     pub fn new(
+        attributes: Vec<FunctionAttribute>,
         visibility: FunctionVisibility,
         formals: Vec<(Var, Type)>,
         return_type: Vec<Type>,
         type_formals: Vec<(TypeVar, Kind)>,
         acquires: Vec<StructName>,
+        specifications: Vec<Condition>,
         body: FunctionBody,
     ) -> Self {
         let signature = FunctionSignature::new(formals, return_type, type_formals);
         Function {
+            attributes,
             visibility,
             signature,
             acquires,
+            specifications,
             body,
         }
     }
 }
 
+impl FunctionAttribute {
+    /// Parses an attribute from its name and its argument, if any
+    pub fn parse<L, T>(
+        name: &str,
+        argument: Option<(&str, u64)>,
+    ) -> Result<Self, ParseError<L, T, failure::Error>> {
+        match (name, argument) {
+            ("test", None) => Ok(FunctionAttribute::Test),
+            ("expected_failure", None) => Ok(FunctionAttribute::ExpectedFailure(None)),
+            ("expected_failure", Some(("abort_code", code))) => {
+                Ok(FunctionAttribute::ExpectedFailure(Some(code)))
+            }
+            _ => Err(ParseError::User {
+                error: failure::err_msg(format!("Invalid function attribute: {}", name)),
+            }),
+        }
+    }
+}
+
 impl Var {
     /// Creates a new `Var` from an identifier.
     pub fn new(s: Identifier) -> Self {
@@ -1541,3 +1794,66 @@ impl fmt::Display for Exp {
         }
     }
 }
+
+impl fmt::Display for StorageLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageLocation::Formal(v) => write!(f, "{}", v),
+            StorageLocation::GlobalResource {
+                type_,
+                type_actuals,
+                address,
+            } => write!(
+                f,
+                "global<{}{}>({})",
+                type_,
+                format_type_actuals(type_actuals),
+                address
+            ),
+            StorageLocation::AccessPath { base, fields } => {
+                write!(f, "{}", base)?;
+                for field in fields {
+                    write!(f, ".{}", field)?;
+                }
+                Ok(())
+            }
+            StorageLocation::TxnSenderAddress => write!(f, "txn_sender"),
+            StorageLocation::Address(address) => write!(f, "0x{}", hex::encode(&address)),
+            StorageLocation::Ret(i) => write!(f, "RET({})", i),
+        }
+    }
+}
+
+impl fmt::Display for SpecExp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecExp::Constant(v) => write!(f, "{}", v),
+            SpecExp::StorageLocation(l) => write!(f, "{}", l),
+            SpecExp::GlobalExists {
+                type_,
+                type_actuals,
+                address,
+            } => write!(
+                f,
+                "global_exists<{}{}>({})",
+                type_,
+                format_type_actuals(type_actuals),
+                address
+            ),
+            SpecExp::Dereference(l) => write!(f, "*{}", l),
+            SpecExp::Not(e) => write!(f, "!{}", e),
+            SpecExp::Binop(e1, o, e2) => write!(f, "({} {} {})", e1, o, e2),
+            SpecExp::Old(e) => write!(f, "old({})", e),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Requires(e) => write!(f, "requires {}", e),
+            Condition::Ensures(e) => write!(f, "ensures {}", e),
+            Condition::AbortsIf(e) => write!(f, "aborts_if {}", e),
+        }
+    }
+}
@@ -168,6 +168,40 @@
 //!   | s_1 s_2                     // sequencing
 //! ```
 //!
+//! ## Specifications
+//! ```text
+//! l ∈ StorageLocation ::=
+//!   | x                  // a formal of the procedure, or a field of the struct in an invariant
+//!   | RET                // the first return value of the procedure
+//!   | RET(i)             // the 'i'th return value of the procedure
+//!   | txn_sender         // the sender of the transaction
+//!   | 0xaddr             // an address literal
+//!   | global<d.n>(l)     // the resource 'd.n' stored at the address 'l'
+//!   | l.f                // field 'f' of the struct stored at 'l'
+//!
+//! se ∈ SpecExp ::=
+//!   | v                  // a value that is not an address
+//!   | l                  // the value stored at a location
+//!   | *l                 // the value the reference stored at 'l' points to
+//!   | global_exists<d.n>(l) // whether the resource 'd.n' is stored at the address 'l'
+//!   | old(se)            // the value of 'se' when the procedure was called
+//!   | !se
+//!   | se_1 op se_2       // any operator on expressions except the bitwise ones
+//!                        // '&&' and '||' bind weaker than comparisons
+//!
+//! cond ∈ Condition ::=
+//!   | requires se        // 'se' holds when the procedure is called
+//!   | ensures se         // 'se' holds when the procedure returns without aborting
+//!   | aborts_if se       // the procedure aborts if 'se' holds when it is called
+//!                        // a procedure with 'aborts_if' conditions aborts only if one of them holds
+//!
+//! inv ∈ Invariant ::=
+//!   | invariant se       // 'se' holds for every instance of the struct, stated over its fields
+//! ```
+//!
+//! `requires`, `ensures`, `aborts_if`, `invariant`, `old`, `RET`, `global` and `global_exists`
+//! are only reserved in specifications and can be used as names everywhere else.
+//!
 //! ## Imports
 //!```text
 //! idecl ∈ Import ::=
@@ -180,14 +214,18 @@
 //!   | resource n { f_1: t_1, ..., f_j: t_j } // declaration of a resource struct
 //!   | struct n { f_1: t_1, ..., f_j: t_j }   // declaration of a non-resource (value) struct
 //!                                            // s.t. any 't_i' is not of resource kind
+//!   | resource n { f_1: t_1, ..., f_j: t_j inv_1 ... inv_k } // declaration with invariants
 //!
 //! body ∈ ProcedureBody ::=
 //!  | let x_1; ... let x_j; s // The locals declared in this procedure, and the code for that procedure
 //!
 //! pdecl ∈ ProcedureDecl ::=
-//!   | (public?) p(x_1: 𝛕_1, ..., x_j: 𝛕_j): 𝛕-list { body } // declaration of a defined procedure
+//!   | (public?) p(x_1: 𝛕_1, ..., x_j: 𝛕_j): 𝛕-list cond_1 ... cond_i { body }
+//!                                                          // declaration of a defined procedure
 //!                                                          // the procedure may be public, or internal to the module
-//!   | native (public?) p(x_1: 𝛕_1, ..., x_j: 𝛕_j): 𝛕-list; // declaration of a native procedure
+//!                                                          // the conditions are only checked by the prover
+//!   | native (public?) p(x_1: 𝛕_1, ..., x_j: 𝛕_j): 𝛕-list cond_1 ... cond_i;
+//!                                                         // declaration of a native procedure
 //!                                                         // the implementation is provided by the VM
 //!                                                         // the procedure may be public, or internal to the module
 //!
@@ -1,12 +1,14 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod package;
 pub mod util;
 
 #[cfg(test)]
 mod unit_tests;
 
-use bytecode_verifier::VerifiedModule;
+use bytecode_optimizer::Optimizer;
+use bytecode_verifier::{VerifiedModule, VerifiedScript};
 use failure::prelude::*;
 use ir_to_bytecode::{
     compiler::{compile_module, compile_program},
@@ -33,6 +35,8 @@ pub struct Compiler<'a> {
     pub stdlib_address: AccountAddress,
     /// Extra dependencies to compile with.
     pub extra_deps: Vec<VerifiedModule>,
+    /// Run the bytecode optimizer over the compiled code.
+    pub optimize: bool,
 
     // The typical way this should be used is with functional record update syntax:
     //
@@ -107,7 +111,11 @@ impl<'a> Compiler<'a> {
         let parsed_program = parse_program(self.code)?;
         let deps = self.deps();
         let compiled_program = compile_program(self.address, parsed_program, &deps)?;
-        Ok((compiled_program, deps))
+        if self.optimize {
+            Ok((optimize_program(compiled_program)?, deps))
+        } else {
+            Ok((compiled_program, deps))
+        }
     }
 
     fn compile_mod(&mut self) -> Result<(CompiledModule, Vec<VerifiedModule>)> {
@@ -117,7 +125,11 @@ impl<'a> Compiler<'a> {
         assert_eq!(modules.len(), 1, "Must have single module");
         let module = modules.pop().expect("Module must exist");
         let compiled_module = compile_module(self.address, module, &deps)?;
-        Ok((compiled_module, deps))
+        if self.optimize {
+            Ok((optimize_module(compiled_module)?, deps))
+        } else {
+            Ok((compiled_module, deps))
+        }
     }
 
     fn deps(&mut self) -> Vec<VerifiedModule> {
@@ -131,3 +143,26 @@ impl<'a> Compiler<'a> {
         }
     }
 }
+
+/// Verifies and optimizes `module`.
+pub fn optimize_module(module: CompiledModule) -> Result<CompiledModule> {
+    let verified_module = VerifiedModule::new(module)
+        .map_err(|(_, errors)| format_err!("Module failed to verify: {:?}", errors))?;
+    Ok(Optimizer::default()
+        .optimize_module(verified_module)?
+        .into_inner())
+}
+
+fn optimize_program(program: CompiledProgram) -> Result<CompiledProgram> {
+    let verified_script = VerifiedScript::new(program.script)
+        .map_err(|(_, errors)| format_err!("Script failed to verify: {:?}", errors))?;
+    let script = Optimizer::default()
+        .optimize_script(verified_script)?
+        .into_inner();
+    let modules = program
+        .modules
+        .into_iter()
+        .map(optimize_module)
+        .collect::<Result<_>>()?;
+    Ok(CompiledProgram { script, modules })
+}
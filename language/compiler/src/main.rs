@@ -5,7 +5,7 @@ use bytecode_verifier::{
     verifier::{verify_module_dependencies, VerifiedProgram},
     VerifiedModule,
 };
-use compiler::{util, Compiler};
+use compiler::{package, util, Compiler};
 use ir_to_bytecode::parser::{parse_module, parse_script};
 use serde_json;
 use std::{convert::TryFrom, fs, io::Write, path::PathBuf};
@@ -39,6 +39,14 @@ struct Args {
     /// Do not automatically run the bytecode verifier
     #[structopt(long = "no-verify")]
     pub no_verify: bool,
+    /// Run the bytecode optimizer over the compiled code
+    #[structopt(short = "O", long = "optimize")]
+    pub optimize: bool,
+    /// Treat the source path as a package directory with a Move.toml manifest and build the
+    /// package and its dependencies. The output is a bundle of programs publishing the modules of
+    /// the package in dependency order
+    #[structopt(short = "p", long = "package")]
+    pub package: bool,
     /// Path to the Move IR source to compile
     #[structopt(parse(from_os_str))]
     pub source_path: PathBuf,
@@ -111,6 +119,29 @@ fn main() {
         return;
     }
 
+    if args.package {
+        let built_package = package::build_package(&args.source_path, args.no_stdlib)
+            .unwrap_or_else(|err| {
+                println!("{}", err);
+                std::process::exit(1);
+            });
+        match args.output_path {
+            Some(path) => {
+                let bundle = built_package
+                    .into_bundle()
+                    .expect("Unable to serialize bundle");
+                let bundle_bytes = serde_json::to_vec(&bundle).expect("Unable to serialize bundle");
+                write_output(&path, &bundle_bytes);
+            }
+            None => {
+                for module in built_package.modules {
+                    println!("{}", module);
+                }
+            }
+        }
+        return;
+    }
+
     let deps = {
         if let Some(path) = args.deps_path {
             let deps = fs::read_to_string(path).expect("Unable to read dependency file");
@@ -140,6 +171,7 @@ fn main() {
             code: &source,
             skip_stdlib_deps: args.no_stdlib,
             extra_deps: deps,
+            optimize: args.optimize,
             ..Compiler::default()
         };
         let (compiled_program, dependencies) = compiler
@@ -178,6 +210,11 @@ fn main() {
         }
     } else {
         let compiled_module = util::do_compile_module(&args.source_path, address, &deps);
+        let compiled_module = if args.optimize {
+            compiler::optimize_module(compiled_module).expect("Failed to optimize module")
+        } else {
+            compiled_module
+        };
         let compiled_module = if !args.no_verify {
             let verified_module = do_verify_module(compiled_module, &deps);
             verified_module.into_inner()
@@ -0,0 +1,378 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Building of Move IR packages.
+//!
+//! A package is a directory containing a `Move.toml` manifest next to the Move IR sources of its
+//! modules:
+//!
+//! ```toml
+//! [package]
+//! name = "coins"
+//! # Either a hex literal or one of the named addresses below.
+//! address = "publisher"
+//! sources = ["sources/token.mvir", "sources/vault.mvir"]
+//!
+//! [addresses]
+//! # Occurrences of `{{publisher}}` in the sources are replaced with this address.
+//! publisher = "0xca4e"
+//!
+//! [dependencies]
+//! # Paths to other package directories, relative to this one.
+//! base = "../base"
+//! ```
+//!
+//! Building a package first builds its dependency packages, then compiles the modules of the
+//! package in dependency order and runs the bytecode verifier on each of them. The result can be
+//! turned into a bundle of programs that publish the modules one at a time, in an order in which
+//! every module's dependencies are published before it.
+
+use crate::Compiler;
+use bytecode_verifier::{verify_module_dependencies, VerifiedModule};
+use failure::prelude::*;
+use ir_to_bytecode::{
+    compiler::compile_module,
+    parser::{
+        ast::{ModuleDefinition, ModuleIdent},
+        parse_module,
+    },
+};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use stdlib::stdlib_modules;
+use types::{
+    account_address::{AccountAddress, ADDRESS_LENGTH},
+    transaction::Program,
+};
+
+/// The name of the manifest file at the root of every package.
+pub const MANIFEST_FILE_NAME: &str = "Move.toml";
+
+/// The script of every program in a bundle. Modules are published as part of a program, which
+/// needs a script even if it does nothing.
+const PUBLISH_SCRIPT: &str = "main() { return; }";
+
+/// The contents of a `Move.toml` manifest.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PackageManifest {
+    pub package: PackageInfo,
+    /// Named addresses, substituted for `{{name}}` in the sources of the package.
+    #[serde(default)]
+    pub addresses: BTreeMap<String, String>,
+    /// Dependency packages, by name, with the path of their directory relative to this package.
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, PathBuf>,
+}
+
+/// The `[package]` section of a manifest.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PackageInfo {
+    pub name: String,
+    /// The address the modules are published under: a hex literal or a named address.
+    pub address: String,
+    /// Paths of the module sources, relative to the package directory. Each file holds exactly
+    /// one module.
+    pub sources: Vec<PathBuf>,
+}
+
+impl PackageManifest {
+    /// Parses the contents of a manifest.
+    pub fn parse(manifest: &str) -> Result<Self> {
+        Ok(toml::from_str(manifest)?)
+    }
+
+    /// Loads the manifest of the package in `package_dir`.
+    pub fn load(package_dir: &Path) -> Result<Self> {
+        let path = package_dir.join(MANIFEST_FILE_NAME);
+        let manifest = fs::read_to_string(&path)
+            .map_err(|err| format_err!("Unable to read manifest {:?}: {}", path, err))?;
+        Self::parse(&manifest).map_err(|err| format_err!("Invalid manifest {:?}: {}", path, err))
+    }
+
+    /// Returns the address the modules of the package are published under.
+    pub fn package_address(&self) -> Result<AccountAddress> {
+        match self.addresses.get(&self.package.address) {
+            Some(address) => parse_address(address),
+            None => parse_address(&self.package.address),
+        }
+    }
+
+    /// Replaces every `{{name}}` in `source` with the named address `name`.
+    pub fn substitute_addresses(&self, source: &str) -> Result<String> {
+        let mut source = source.to_string();
+        for (name, address) in &self.addresses {
+            let address = parse_address(address)
+                .map_err(|err| format_err!("Invalid named address {}: {}", name, err))?;
+            source = source.replace(&format!("{{{{{}}}}}", name), &format!("0x{}", address));
+        }
+        Ok(source)
+    }
+}
+
+/// Parses a hex address, with or without a `0x` prefix. Short addresses are zero-extended.
+fn parse_address(address: &str) -> Result<AccountAddress> {
+    let hex = if address.starts_with("0x") {
+        &address[2..]
+    } else {
+        address
+    };
+    ensure!(
+        !hex.is_empty() && hex.len() <= 2 * ADDRESS_LENGTH,
+        "Invalid address: {}",
+        address
+    );
+    AccountAddress::from_str(&format!("{:0>width$}", hex, width = 2 * ADDRESS_LENGTH))
+}
+
+/// A package whose modules have been compiled and verified.
+#[derive(Debug)]
+pub struct BuiltPackage {
+    pub name: String,
+    pub address: AccountAddress,
+    /// The modules of the package, in the order they have to be published in.
+    pub modules: Vec<VerifiedModule>,
+    /// The modules of all dependency packages, each after the modules it depends on.
+    pub dependencies: Vec<VerifiedModule>,
+}
+
+impl BuiltPackage {
+    /// Returns one program per module of the package, each publishing its module. Submitting the
+    /// programs in order publishes the whole package.
+    ///
+    /// The bundle does not contain the modules of the dependency packages: they are usually
+    /// published under other addresses, by other accounts, and have to be published before the
+    /// bundle is submitted.
+    pub fn into_bundle(self) -> Result<Vec<Program>> {
+        let script = Compiler {
+            address: self.address,
+            code: PUBLISH_SCRIPT,
+            skip_stdlib_deps: true,
+            ..Compiler::default()
+        }
+        .into_script_blob()?;
+
+        let mut bundle = vec![];
+        for module in self.modules {
+            let mut serialized_module = vec![];
+            module.as_inner().serialize(&mut serialized_module)?;
+            bundle.push(Program::new(
+                script.clone(),
+                vec![serialized_module],
+                vec![],
+            ));
+        }
+        Ok(bundle)
+    }
+}
+
+/// Builds the package in `package_dir` along with all of its dependency packages.
+///
+/// Unless `skip_stdlib_deps` is set, the modules of every package can also use the stdlib.
+pub fn build_package(package_dir: &Path, skip_stdlib_deps: bool) -> Result<BuiltPackage> {
+    let mut builder = PackageBuilder {
+        base_deps: if skip_stdlib_deps {
+            vec![]
+        } else {
+            stdlib_modules().to_vec()
+        },
+        dependencies: vec![],
+        built: vec![],
+        in_progress: vec![],
+    };
+    let (manifest, address, modules) = builder.compile(package_dir)?;
+    Ok(BuiltPackage {
+        name: manifest.package.name,
+        address,
+        modules,
+        dependencies: builder.dependencies,
+    })
+}
+
+struct PackageBuilder {
+    /// Modules every package can depend on without declaring it.
+    base_deps: Vec<VerifiedModule>,
+    /// The modules of the dependency packages built so far.
+    dependencies: Vec<VerifiedModule>,
+    /// Canonical paths of the dependency packages built so far.
+    built: Vec<PathBuf>,
+    /// Canonical paths of the packages currently being built, used to detect cycles.
+    in_progress: Vec<PathBuf>,
+}
+
+impl PackageBuilder {
+    fn compile(
+        &mut self,
+        package_dir: &Path,
+    ) -> Result<(PackageManifest, AccountAddress, Vec<VerifiedModule>)> {
+        let package_dir = package_dir
+            .canonicalize()
+            .map_err(|err| format_err!("Unable to find package {:?}: {}", package_dir, err))?;
+        if self.in_progress.contains(&package_dir) {
+            bail!("Cyclic dependency on package {:?}", package_dir);
+        }
+        self.in_progress.push(package_dir.clone());
+
+        let manifest = PackageManifest::load(&package_dir)?;
+        for dependency in manifest.dependencies.values() {
+            self.build_dependency(&package_dir.join(dependency))?;
+        }
+
+        let address = manifest.package_address()?;
+        let sources = manifest
+            .package
+            .sources
+            .iter()
+            .map(|path| {
+                let path = package_dir.join(path);
+                let source = fs::read_to_string(&path)
+                    .map_err(|err| format_err!("Unable to read source {:?}: {}", path, err))?;
+                manifest.substitute_addresses(&source)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let deps: Vec<_> = self
+            .base_deps
+            .iter()
+            .chain(self.dependencies.iter())
+            .cloned()
+            .collect();
+        let modules = compile_package_modules(address, &sources, &deps).map_err(|err| {
+            format_err!("Failed to build package {}: {}", manifest.package.name, err)
+        })?;
+
+        self.in_progress.pop();
+        Ok((manifest, address, modules))
+    }
+
+    fn build_dependency(&mut self, package_dir: &Path) -> Result<()> {
+        let canonical_dir = package_dir
+            .canonicalize()
+            .map_err(|err| format_err!("Unable to find package {:?}: {}", package_dir, err))?;
+        if self.built.contains(&canonical_dir) {
+            return Ok(());
+        }
+        let (_, _, modules) = self.compile(&canonical_dir)?;
+        self.dependencies.extend(modules);
+        self.built.push(canonical_dir);
+        Ok(())
+    }
+}
+
+/// Compiles and verifies the modules in `sources`, all published under `address`.
+///
+/// Modules may import each other, either through `address` or `Transaction`, regardless of the
+/// order of `sources`. The returned modules are sorted so that every module comes after the
+/// modules it imports.
+pub fn compile_package_modules(
+    address: AccountAddress,
+    sources: &[String],
+    deps: &[VerifiedModule],
+) -> Result<Vec<VerifiedModule>> {
+    let definitions = sources
+        .iter()
+        .map(|source| parse_module(source))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut modules: Vec<VerifiedModule> = vec![];
+    for definition in sort_modules(address, definitions)? {
+        let name = definition.name.to_string();
+        let compiled_module = compile_module(address, definition, deps.iter().chain(&modules))?;
+        let verified_module = VerifiedModule::new(compiled_module).map_err(|(_, errors)| {
+            format_err!("Module {} failed verification: {:?}", name, errors)
+        })?;
+        let errors = verify_module_dependencies(&verified_module, deps.iter().chain(&modules));
+        ensure!(
+            errors.is_empty(),
+            "Module {} failed dependency verification: {:?}",
+            name,
+            errors
+        );
+        modules.push(verified_module);
+    }
+    Ok(modules)
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum VisitState {
+    Visiting,
+    Visited,
+}
+
+/// Sorts the modules of a package so that every module comes after the package modules it
+/// imports. Modules that don't depend on each other keep their relative order.
+fn sort_modules(
+    address: AccountAddress,
+    definitions: Vec<ModuleDefinition>,
+) -> Result<Vec<ModuleDefinition>> {
+    let mut indices = HashMap::new();
+    for (idx, definition) in definitions.iter().enumerate() {
+        let name = definition.name.to_string();
+        if indices.insert(name.clone(), idx).is_some() {
+            bail!("Module {} is defined more than once", name);
+        }
+    }
+
+    let local_deps: Vec<Vec<usize>> = definitions
+        .iter()
+        .map(|definition| {
+            definition
+                .imports
+                .iter()
+                .filter_map(|import| match &import.ident {
+                    ModuleIdent::Transaction(name) => Some(name.to_string()),
+                    ModuleIdent::Qualified(id) if id.address == address => {
+                        Some(id.name.to_string())
+                    }
+                    ModuleIdent::Qualified(_) => None,
+                })
+                .filter_map(|name| indices.get(&name).cloned())
+                .collect()
+        })
+        .collect();
+
+    fn visit(
+        idx: usize,
+        local_deps: &[Vec<usize>],
+        states: &mut HashMap<usize, VisitState>,
+        order: &mut Vec<usize>,
+        definitions: &[ModuleDefinition],
+    ) -> Result<()> {
+        match states.get(&idx) {
+            Some(VisitState::Visited) => return Ok(()),
+            Some(VisitState::Visiting) => bail!(
+                "Cyclic dependency involving module {}",
+                definitions[idx].name
+            ),
+            None => (),
+        }
+        states.insert(idx, VisitState::Visiting);
+        for dep in &local_deps[idx] {
+            visit(*dep, local_deps, states, order, definitions)?;
+        }
+        states.insert(idx, VisitState::Visited);
+        order.push(idx);
+        Ok(())
+    }
+
+    let mut states = HashMap::new();
+    let mut order = vec![];
+    for idx in 0..definitions.len() {
+        visit(idx, &local_deps, &mut states, &mut order, &definitions)?;
+    }
+
+    let mut definitions: Vec<_> = definitions.into_iter().map(Some).collect();
+    Ok(order
+        .into_iter()
+        .map(|idx| {
+            definitions[idx]
+                .take()
+                .expect("each module is visited once")
+        })
+        .collect())
+}
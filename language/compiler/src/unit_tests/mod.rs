@@ -10,5 +10,7 @@ mod cfg_tests;
 mod expression_tests;
 mod function_tests;
 mod import_tests;
+mod package_tests;
 mod serializer_tests;
+mod spec_tests;
 mod stdlib_scripts;
@@ -0,0 +1,212 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::package::{build_package, compile_package_modules, PackageManifest, MANIFEST_FILE_NAME};
+use std::{fs, path::Path};
+use stdlib::stdlib_modules;
+use types::account_address::AccountAddress;
+use vm::access::ModuleAccess;
+
+const ADDRESS: &str = "0xca4e";
+
+fn package_address() -> AccountAddress {
+    AccountAddress::new([
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0xca, 0x4e,
+    ])
+}
+
+fn token_source() -> String {
+    "
+    module Token {
+        resource T { value: u64 }
+
+        public value(this: &Self.T): u64 {
+            return *&move(this).value;
+        }
+    }
+    "
+    .to_string()
+}
+
+fn vault_source(token_import: &str) -> String {
+    format!(
+        "
+        module Vault {{
+            import {}.Token;
+
+            public balance(token: &Token.T): u64 {{
+                return Token.value(move(token));
+            }}
+        }}
+        ",
+        token_import
+    )
+}
+
+fn module_names(modules: &[bytecode_verifier::VerifiedModule]) -> Vec<String> {
+    modules
+        .iter()
+        .map(|module| module.self_id().name().to_string())
+        .collect()
+}
+
+fn write_package(dir: &Path, manifest: &str, sources: &[(&str, String)]) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join(MANIFEST_FILE_NAME), manifest).unwrap();
+    for (name, source) in sources {
+        fs::write(dir.join(name), source).unwrap();
+    }
+}
+
+#[test]
+fn parse_manifest() {
+    let manifest = PackageManifest::parse(
+        r#"
+        [package]
+        name = "coins"
+        address = "publisher"
+        sources = ["token.mvir"]
+
+        [addresses]
+        publisher = "0xca4e"
+
+        [dependencies]
+        base = "../base"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(manifest.package.name, "coins");
+    assert_eq!(manifest.package_address().unwrap(), package_address());
+    assert_eq!(manifest.dependencies["base"], Path::new("../base"));
+    assert_eq!(
+        manifest
+            .substitute_addresses("import {{publisher}}.Token;")
+            .unwrap(),
+        format!("import 0x{}.Token;", package_address())
+    );
+}
+
+#[test]
+fn parse_manifest_rejects_unknown_fields() {
+    assert!(PackageManifest::parse(
+        r#"
+        [package]
+        name = "coins"
+        address = "0x1"
+        sources = []
+        version = "1.0.0"
+        "#,
+    )
+    .is_err());
+}
+
+#[test]
+fn modules_are_sorted_by_dependencies() {
+    let sources = vec![vault_source(ADDRESS), token_source()];
+    let modules = compile_package_modules(package_address(), &sources, stdlib_modules()).unwrap();
+    assert_eq!(module_names(&modules), vec!["Token", "Vault"]);
+}
+
+#[test]
+fn modules_can_import_through_transaction() {
+    let sources = vec![vault_source("Transaction"), token_source()];
+    let modules = compile_package_modules(package_address(), &sources, stdlib_modules()).unwrap();
+    assert_eq!(module_names(&modules), vec!["Token", "Vault"]);
+}
+
+#[test]
+fn cyclic_modules_are_rejected() {
+    let sources = vec![
+        "
+        module A {
+            import Transaction.B;
+        }
+        "
+        .to_string(),
+        "
+        module B {
+            import Transaction.A;
+        }
+        "
+        .to_string(),
+    ];
+    let err = compile_package_modules(package_address(), &sources, stdlib_modules()).unwrap_err();
+    assert!(err.to_string().contains("Cyclic dependency"));
+}
+
+#[test]
+fn duplicate_modules_are_rejected() {
+    let sources = vec![token_source(), token_source()];
+    let err = compile_package_modules(package_address(), &sources, stdlib_modules()).unwrap_err();
+    assert!(err.to_string().contains("defined more than once"));
+}
+
+#[test]
+fn build_package_with_dependency() {
+    let dir = tempfile::tempdir().unwrap();
+    write_package(
+        &dir.path().join("base"),
+        &format!(
+            r#"
+            [package]
+            name = "base"
+            address = "{}"
+            sources = ["token.mvir"]
+            "#,
+            ADDRESS
+        ),
+        &[("token.mvir", token_source())],
+    );
+    write_package(
+        &dir.path().join("app"),
+        r#"
+        [package]
+        name = "app"
+        address = "base"
+        sources = ["vault.mvir"]
+
+        [addresses]
+        base = "0xca4e"
+
+        [dependencies]
+        base = "../base"
+        "#,
+        &[("vault.mvir", vault_source("{{base}}"))],
+    );
+
+    let built_package = build_package(&dir.path().join("app"), false).unwrap();
+    assert_eq!(built_package.name, "app");
+    assert_eq!(built_package.address, package_address());
+    assert_eq!(module_names(&built_package.modules), vec!["Vault"]);
+    assert_eq!(module_names(&built_package.dependencies), vec!["Token"]);
+
+    let bundle = built_package.into_bundle().unwrap();
+    assert_eq!(bundle.len(), 1);
+}
+
+#[test]
+fn cyclic_packages_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, other) in &[("a", "b"), ("b", "a")] {
+        write_package(
+            &dir.path().join(name),
+            &format!(
+                r#"
+                [package]
+                name = "{}"
+                address = "{}"
+                sources = []
+
+                [dependencies]
+                {} = "../{}"
+                "#,
+                name, ADDRESS, other, other
+            ),
+            &[],
+        );
+    }
+
+    let err = build_package(&dir.path().join("a"), false).unwrap_err();
+    assert!(err.to_string().contains("Cyclic dependency"));
+}
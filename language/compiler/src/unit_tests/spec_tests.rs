@@ -0,0 +1,198 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use ir_to_bytecode::{
+    compiler::{compile_module_with_spec, ModuleSpec},
+    parser::{
+        ast::{BinOp, Condition, CopyableVal, Field, SpecExp, StorageLocation},
+        parse_module,
+    },
+};
+use stdlib::{stdlib::module_defs, stdlib_modules};
+use types::{account_address::AccountAddress, identifier::Identifier};
+use vm::access::ModuleAccess;
+
+fn formal_field(formal: &str, field: &str) -> SpecExp {
+    SpecExp::StorageLocation(StorageLocation::AccessPath {
+        base: Box::new(StorageLocation::Formal(formal.to_string())),
+        fields: vec![Field::new(Identifier::new(field).unwrap())],
+    })
+}
+
+#[test]
+fn parse_conditions() {
+    let module = parse_module(
+        "
+        module Counter {
+            resource T { value: u64 }
+
+            public increment(counter: &mut Self.T, amount: u64): u64
+            requires amount > 0
+            aborts_if counter.value + amount > 18446744073709551615
+            ensures counter.value == old(counter.value) + amount
+            ensures RET == counter.value
+            {
+                let value: u64;
+                value = *(&copy(counter).value) + move(amount);
+                *(&mut move(counter).value) = copy(value);
+                return move(value);
+            }
+        }
+        ",
+    )
+    .unwrap();
+    let spec = ModuleSpec::new(&module);
+    assert_eq!(spec.functions.len(), 1);
+    let function_spec = &spec.functions[0];
+    assert_eq!(function_spec.formals, vec!["counter", "amount"]);
+    assert_eq!(function_spec.conditions.len(), 4);
+    assert_eq!(
+        function_spec.conditions[0],
+        Condition::Requires(SpecExp::Binop(
+            Box::new(SpecExp::StorageLocation(StorageLocation::Formal(
+                "amount".to_string()
+            ))),
+            BinOp::Gt,
+            Box::new(SpecExp::Constant(CopyableVal::U64(0))),
+        ))
+    );
+    assert_eq!(
+        function_spec.conditions[3],
+        Condition::Ensures(SpecExp::Binop(
+            Box::new(SpecExp::StorageLocation(StorageLocation::Ret(0))),
+            BinOp::Eq,
+            Box::new(formal_field("counter", "value")),
+        ))
+    );
+}
+
+#[test]
+fn parse_invariants() {
+    let module = parse_module(
+        "
+        module Bounded {
+            resource T {
+                value: u64,
+                limit: u64,
+                invariant value <= limit
+            }
+        }
+        ",
+    )
+    .unwrap();
+    let spec = ModuleSpec::new(&module);
+    assert_eq!(spec.structs.len(), 1);
+    assert_eq!(
+        spec.structs[0].invariants,
+        vec![SpecExp::Binop(
+            Box::new(SpecExp::StorageLocation(StorageLocation::Formal(
+                "value".to_string()
+            ))),
+            BinOp::Le,
+            Box::new(SpecExp::StorageLocation(StorageLocation::Formal(
+                "limit".to_string()
+            ))),
+        )]
+    );
+}
+
+#[test]
+fn spec_keywords_are_names_outside_specs() {
+    let module = parse_module(
+        "
+        module M {
+            struct invariant { ensures: u64 }
+
+            public old(requires: u64, global: u64): u64
+            ensures RET == requires + global
+            {
+                let aborts_if: u64;
+                aborts_if = move(requires) + move(global);
+                return move(aborts_if);
+            }
+        }
+        ",
+    )
+    .unwrap();
+    let spec = ModuleSpec::new(&module);
+    assert_eq!(
+        spec.functions[0].conditions,
+        vec![Condition::Ensures(SpecExp::Binop(
+            Box::new(SpecExp::StorageLocation(StorageLocation::Ret(0))),
+            BinOp::Eq,
+            Box::new(SpecExp::Binop(
+                Box::new(SpecExp::StorageLocation(StorageLocation::Formal(
+                    "requires".to_string()
+                ))),
+                BinOp::Add,
+                Box::new(SpecExp::StorageLocation(StorageLocation::Formal(
+                    "global".to_string()
+                ))),
+            )),
+        ))]
+    );
+}
+
+#[test]
+fn malformed_specs_are_rejected() {
+    for condition in &[
+        "assumes x > 0",
+        "ensures RET(x) == 0",
+        "ensures new(x) == 0",
+    ] {
+        let module = format!(
+            "
+            module M {{
+                public f(x: u64)
+                {}
+                {{
+                    return;
+                }}
+            }}
+            ",
+            condition
+        );
+        assert!(parse_module(&module).is_err(), "{}", condition);
+    }
+}
+
+#[test]
+fn specs_are_indexed_like_definitions() {
+    let module = parse_module(
+        "
+        module M {
+            struct S { f: u64 }
+
+            public id(x: u64): u64
+            ensures RET == x
+            {
+                return move(x);
+            }
+
+            public get(s: &Self.S): u64
+            ensures RET == s.f
+            {
+                return *&move(s).f;
+            }
+        }
+        ",
+    )
+    .unwrap();
+    let (compiled_module, spec) =
+        compile_module_with_spec(AccountAddress::default(), module, stdlib_modules()).unwrap();
+    assert_eq!(spec.functions.len(), compiled_module.function_defs().len());
+    assert_eq!(spec.structs.len(), compiled_module.struct_defs().len());
+    assert!(spec.functions.iter().all(|f| f.conditions.len() == 1));
+}
+
+#[test]
+fn stdlib_has_specs() {
+    let specs: Vec<_> = module_defs()
+        .iter()
+        .map(|module| ModuleSpec::new(module))
+        .collect();
+    assert!(specs
+        .iter()
+        .flat_map(|spec| spec.functions.iter())
+        .any(|function_spec| !function_spec.conditions.is_empty()));
+}
@@ -17,6 +17,12 @@ fn compile_libra_coin() {
     let _compiled_module = compile_module_string(&code).unwrap();
 }
 
+#[test]
+fn compile_gas_schedule() {
+    let code = include_str!("../../../stdlib/modules/gas_schedule.mvir");
+    let _compiled_module = compile_module_string(&code).unwrap();
+}
+
 #[test]
 fn compile_account_module() {
     let address_util_code = include_str!("../../../stdlib/modules/address_util.mvir");
@@ -82,3 +88,9 @@ fn compile_peer_to_peer_transfer_script() {
     let code = include_str!("../../../stdlib/transaction_scripts/peer_to_peer_transfer.mvir");
     let _compiled_script = compile_script_string_with_stdlib(code).unwrap();
 }
+
+#[test]
+fn compile_update_gas_schedule_script() {
+    let code = include_str!("../../../stdlib/transaction_scripts/update_gas_schedule.mvir");
+    let _compiled_script = compile_script_string_with_stdlib(code).unwrap();
+}
@@ -160,6 +160,9 @@ impl Account {
                 gas_unit_price,
                 Duration::from_secs(u64::max_value()),
             ),
+            TransactionPayload::BlockMetadata(block_metadata) => {
+                RawTransaction::new_block_metadata(block_metadata)
+            }
         };
 
         raw_txn
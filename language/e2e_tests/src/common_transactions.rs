@@ -4,6 +4,7 @@
 //! Support for encoding transactions for common situations.
 
 use crate::{account::Account, compile::compile_script, gas_costs};
+use canonical_serialization::SimpleSerializer;
 use lazy_static::lazy_static;
 use stdlib::transaction_scripts;
 use types::{
@@ -11,6 +12,7 @@ use types::{
     byte_array::ByteArray,
     transaction::{SignedTransaction, TransactionArgument},
 };
+use vm::gas_schedule::CostTable;
 
 lazy_static! {
     /// A serialized transaction to create a new account.
@@ -22,6 +24,8 @@ lazy_static! {
     pub static ref PEER_TO_PEER: Vec<u8> = { peer_to_peer() };
     /// A serialized transaction to change the keys for an account.
     pub static ref ROTATE_KEY: Vec<u8> = { rotate_key() };
+    /// A serialized transaction to replace the gas schedule.
+    pub static ref UPDATE_GAS_SCHEDULE: Vec<u8> = { update_gas_schedule() };
 }
 
 /// Returns a transaction to create a new account with the given arguments.
@@ -105,6 +109,25 @@ pub fn mint_txn(
     )
 }
 
+/// Returns a transaction to replace the gas schedule with `cost_table`.
+pub fn update_gas_schedule_txn(
+    sender: &Account,
+    cost_table: &CostTable,
+    seq_num: u64,
+) -> SignedTransaction {
+    let cost_table_bytes = SimpleSerializer::<Vec<u8>>::serialize(cost_table).unwrap();
+    let args = vec![TransactionArgument::ByteArray(ByteArray::new(
+        cost_table_bytes,
+    ))];
+    sender.create_signed_txn_with_args(
+        UPDATE_GAS_SCHEDULE.clone(),
+        args,
+        seq_num,
+        gas_costs::TXN_RESERVED,
+        1,
+    )
+}
+
 fn create_account() -> Vec<u8> {
     compile_script(transaction_scripts::create_account())
 }
@@ -120,3 +143,7 @@ fn peer_to_peer() -> Vec<u8> {
 fn rotate_key() -> Vec<u8> {
     compile_script(transaction_scripts::rotate_key())
 }
+
+fn update_gas_schedule() -> Vec<u8> {
+    compile_script(transaction_scripts::update_gas_schedule())
+}
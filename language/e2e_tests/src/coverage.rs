@@ -0,0 +1,209 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coverage reports over the instructions executed by the VM.
+//!
+//! Run the transactions of interest under [`record_coverage`], then build a [`CoverageReport`]
+//! from the modules to report on. When the source a module was compiled from is known, the report
+//! can also print it annotated with the number of times each line ran, in the style of `gcov`.
+
+use bytecode_verifier::VerifiedModule;
+use ir_to_bytecode::compiler::ModuleSourceMap;
+use std::{collections::BTreeMap, fmt};
+use types::{identifier::Identifier, language_storage::ModuleId};
+use vm::{access::ModuleAccess, file_format::CodeOffset};
+use vm_runtime::coverage::{self, CoverageMap};
+
+/// Runs `f`, and returns its result along with the instructions executed by the transactions it
+/// ran on the current thread.
+pub fn record_coverage<T>(f: impl FnOnce() -> T) -> (T, CoverageMap) {
+    coverage::start_recording();
+    let result = f();
+    (result, coverage::stop_recording())
+}
+
+/// The coverage of a function. Native functions have no instructions.
+#[derive(Clone, Debug)]
+pub struct FunctionCoverage {
+    pub name: Identifier,
+    /// The number of times each instruction ran, indexed by code offset.
+    pub counts: Vec<u64>,
+}
+
+impl FunctionCoverage {
+    /// The number of instructions that ran at least once.
+    pub fn covered(&self) -> usize {
+        self.counts.iter().filter(|count| **count > 0).count()
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.len()
+    }
+}
+
+/// The coverage of a module, and the source it was compiled from if known.
+#[derive(Clone, Debug)]
+pub struct ModuleCoverage {
+    pub module: ModuleId,
+    pub functions: Vec<FunctionCoverage>,
+    source: Option<(String, Vec<BTreeMap<CodeOffset, usize>>)>,
+}
+
+impl ModuleCoverage {
+    pub fn covered(&self) -> usize {
+        self.functions.iter().map(FunctionCoverage::covered).sum()
+    }
+
+    pub fn total(&self) -> usize {
+        self.functions.iter().map(FunctionCoverage::total).sum()
+    }
+
+    /// Attaches the source the module was compiled from, and where each instruction came from.
+    fn with_source(mut self, source: &str, source_map: &ModuleSourceMap) -> Self {
+        let lines = source_map
+            .functions
+            .iter()
+            .map(|function| {
+                function
+                    .locations
+                    .iter()
+                    .map(|(offset, loc)| {
+                        let start = loc.start().0 as usize;
+                        (*offset, source[..start].matches('\n').count())
+                    })
+                    .collect()
+            })
+            .collect();
+        self.source = Some((source.to_string(), lines));
+        self
+    }
+
+    /// The source of the module, each line prefixed with the number of times it ran. Lines that
+    /// never ran are marked with `#####`, and lines without code with `-`. Returns `None` if the
+    /// source of the module is unknown.
+    pub fn annotated_source(&self) -> Option<String> {
+        let (source, lines) = self.source.as_ref()?;
+        // The largest count of the instructions compiled from each line
+        let mut line_counts = BTreeMap::new();
+        for (function, function_lines) in self.functions.iter().zip(lines) {
+            for (offset, line) in function_lines {
+                let count = line_counts.entry(*line).or_insert(0);
+                *count = function.counts[*offset as usize].max(*count);
+            }
+        }
+
+        let mut annotated = String::new();
+        for (idx, line) in source.lines().enumerate() {
+            let count = match line_counts.get(&idx) {
+                Some(0) => "#####".to_string(),
+                Some(count) => count.to_string(),
+                None => "-".to_string(),
+            };
+            annotated.push_str(&format!("{:>9}:{:>5}:{}\n", count, idx + 1, line));
+        }
+        Some(annotated)
+    }
+}
+
+/// The coverage of a set of modules.
+#[derive(Clone, Debug, Default)]
+pub struct CoverageReport {
+    pub modules: Vec<ModuleCoverage>,
+}
+
+impl CoverageReport {
+    /// Reports the coverage of `modules` by the instructions in `coverage`.
+    pub fn new<'a>(
+        modules: impl IntoIterator<Item = &'a VerifiedModule>,
+        coverage: &CoverageMap,
+    ) -> Self {
+        let modules = modules
+            .into_iter()
+            .map(|module| module_coverage(module, coverage))
+            .collect();
+        Self { modules }
+    }
+
+    /// Reports the coverage of the standard library, annotated with its source.
+    pub fn stdlib(coverage: &CoverageMap) -> Self {
+        let modules = stdlib::stdlib_modules()
+            .iter()
+            .zip(stdlib::stdlib_source_maps())
+            .zip(stdlib::stdlib::module_sources())
+            .map(|((module, source_map), source)| {
+                module_coverage(module, coverage).with_source(source, source_map)
+            })
+            .collect();
+        Self { modules }
+    }
+
+    /// The coverage of the module with the given id, if it is part of this report.
+    pub fn module(&self, module: &ModuleId) -> Option<&ModuleCoverage> {
+        self.modules
+            .iter()
+            .find(|module_coverage| &module_coverage.module == module)
+    }
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for module in &self.modules {
+            writeln!(
+                f,
+                "{}: {}",
+                module.module.name(),
+                percentage(module.covered(), module.total())
+            )?;
+            for function in &module.functions {
+                writeln!(
+                    f,
+                    "  {:<32} {}",
+                    function.name,
+                    percentage(function.covered(), function.total())
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn module_coverage(module: &VerifiedModule, coverage: &CoverageMap) -> ModuleCoverage {
+    let module_id = module.self_id();
+    // Native functions have no instructions, but keep one entry per function definition so that
+    // the source map of the module lines up
+    let functions = module
+        .function_defs()
+        .iter()
+        .map(|function_def| {
+            let handle = module.function_handle_at(function_def.function);
+            let name = module.identifier_at(handle.name);
+            let mut counts = vec![0; function_def.code.code.len()];
+            if let Some(offsets) = coverage.function(&module_id, name) {
+                for (offset, count) in offsets {
+                    counts[*offset as usize] = *count;
+                }
+            }
+            FunctionCoverage {
+                name: name.to_owned(),
+                counts,
+            }
+        })
+        .collect();
+    ModuleCoverage {
+        module: module_id,
+        functions,
+        source: None,
+    }
+}
+
+fn percentage(covered: usize, total: usize) -> String {
+    if total == 0 {
+        return "no instructions".to_string();
+    }
+    format!(
+        "{}/{} instructions ({:.1}%)",
+        covered,
+        total,
+        100.0 * covered as f64 / total as f64
+    )
+}
@@ -8,6 +8,7 @@
 use bytecode_verifier::{VerifiedModule, VerifiedScript};
 use compiler::Compiler;
 use data_store::FakeDataStore;
+use stdlib::error_codes::explain_vm_status;
 use types::{
     access_path::AccessPath,
     account_address::AccountAddress,
@@ -27,6 +28,7 @@ pub mod account;
 pub mod account_universe;
 pub mod common_transactions;
 pub mod compile;
+pub mod coverage;
 pub mod data_store;
 pub mod executor;
 pub mod gas_costs;
@@ -72,9 +74,17 @@ fn verify(
     (verified_script, verified_modules)
 }
 
+/// Formats `status` for test output, explaining its abort code if it was raised by the stdlib.
+pub fn describe_status(status: &VMStatus) -> String {
+    match explain_vm_status(status) {
+        Some(explanation) => format!("{:?} [{}]", status, explanation),
+        None => format!("{:?}", status),
+    }
+}
+
 pub fn assert_status_eq(s1: &VMStatus, s2: &VMStatus) -> bool {
-    assert_eq!(s1.major_status, s2.major_status);
-    assert_eq!(s1.sub_status, s2.sub_status);
+    assert_eq!(s1.major_status, s2.major_status, "{}", describe_status(s1));
+    assert_eq!(s1.sub_status, s2.sub_status, "{}", describe_status(s1));
     true
 }
 
@@ -11,11 +11,15 @@
 
 mod account_universe;
 mod arithmetic;
+mod block_metadata;
+mod coverage;
 mod create_account;
 mod function_call;
+mod gas_schedule;
 mod genesis;
 mod mint;
 mod module_publishing;
+mod optimizer;
 mod pack_unpack;
 mod peer_to_peer;
 mod rotate_key;
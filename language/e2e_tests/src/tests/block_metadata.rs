@@ -0,0 +1,114 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{Account, AccountData},
+    assert_prologue_parity, assert_status_eq,
+    executor::FakeExecutor,
+    transaction_status_eq,
+};
+use crypto::HashValue;
+use std::collections::BTreeMap;
+use types::{
+    block_metadata::BlockMetadata,
+    test_helpers::transaction_test_helpers,
+    transaction::TransactionStatus,
+    vm_error::{StatusCode, VMStatus},
+};
+use vm_genesis::encode_transfer_program;
+
+fn block_metadata(timestamp_usecs: u64, proposer: &Account) -> BlockMetadata {
+    BlockMetadata::new(
+        HashValue::random(),
+        timestamp_usecs,
+        BTreeMap::new(),
+        *proposer.address(),
+    )
+}
+
+#[test]
+fn block_metadata_updates_block_resource() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let proposer = Account::new();
+
+    let txn = block_metadata(1_000, &proposer).into_signed_transaction();
+    let output = executor.execute_transaction(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+    assert_eq!(output.gas_used(), 0);
+    assert!(!output.write_set().is_empty());
+    executor.apply_write_set(output.write_set());
+
+    // Time has to move forward from one block to the next.
+    let stale_txn = block_metadata(1_000, &proposer).into_signed_transaction();
+    let output = executor.execute_transaction(stale_txn);
+    match output.status() {
+        TransactionStatus::Discard(status) => {
+            assert_eq!(status.major_status, StatusCode::ABORTED);
+            assert_eq!(status.sub_status, Some(2));
+        }
+        TransactionStatus::Keep(status) => panic!("stale block metadata was kept: {:?}", status),
+    }
+
+    let next_txn = block_metadata(2_000, &proposer).into_signed_transaction();
+    let output = executor.execute_transaction(next_txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+}
+
+#[test]
+fn block_metadata_rejected_by_validator() {
+    let executor = FakeExecutor::from_genesis_file();
+    let proposer = Account::new();
+
+    let txn = block_metadata(1_000, &proposer).into_signed_transaction();
+    assert_eq!(
+        executor.verify_transaction(txn),
+        Some(VMStatus::new(StatusCode::REJECTED_BLOCK_METADATA))
+    );
+}
+
+#[test]
+fn transaction_expiration_checked_against_block_time() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(900_000, 10);
+    executor.add_account_data(&sender);
+
+    // Move the on-chain time to 2 seconds.
+    let output = executor
+        .execute_transaction(block_metadata(2_000_000, sender.account()).into_signed_transaction());
+    executor.apply_write_set(output.write_set());
+
+    let txn_with_expiration = |expiration_time_secs| {
+        transaction_test_helpers::get_test_unchecked_transaction(
+            *sender.address(),
+            10,
+            sender.account().privkey.clone(),
+            sender.account().pubkey.clone(),
+            Some(encode_transfer_program(sender.address(), 100)),
+            expiration_time_secs,
+            1,
+            None,
+        )
+    };
+
+    // Expired before the current block.
+    let expired_txn = txn_with_expiration(1);
+    assert_prologue_parity!(
+        executor.verify_transaction(expired_txn.clone()),
+        executor.execute_transaction(expired_txn).status(),
+        VMStatus::new(StatusCode::TRANSACTION_EXPIRED)
+    );
+
+    // Expiring exactly at the time of the current block is still fine.
+    let txn = txn_with_expiration(2);
+    assert_eq!(executor.verify_transaction(txn.clone()), None);
+    assert_eq!(
+        executor.execute_transaction(txn).status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+}
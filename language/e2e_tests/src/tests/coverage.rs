@@ -0,0 +1,74 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::AccountData,
+    common_transactions::peer_to_peer_txn,
+    coverage::{record_coverage, CoverageReport},
+    executor::FakeExecutor,
+};
+use types::{
+    account_config,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    transaction::TransactionStatus,
+    vm_error::{StatusCode, VMStatus},
+};
+
+#[test]
+fn peer_to_peer_coverage() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    let (output, coverage) = record_coverage(|| executor.execute_block(vec![txn]));
+    assert_eq!(
+        output[0].status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+
+    let report = CoverageReport::stdlib(&coverage);
+    let account_module = ModuleId::new(
+        account_config::core_code_address(),
+        Identifier::new("LibraAccount").unwrap(),
+    );
+    let account_coverage = report
+        .module(&account_module)
+        .expect("LibraAccount is part of the stdlib");
+    let pay = account_coverage
+        .functions
+        .iter()
+        .find(|function| function.name.as_str() == "pay_from_sender")
+        .expect("LibraAccount defines pay_from_sender");
+    assert!(pay.covered() > 0);
+    // Minting is not part of a payment
+    let mint = account_coverage
+        .functions
+        .iter()
+        .find(|function| function.name.as_str() == "mint_to_address")
+        .expect("LibraAccount defines mint_to_address");
+    assert_eq!(mint.covered(), 0);
+    assert!(account_coverage.covered() < account_coverage.total());
+
+    let annotated = account_coverage
+        .annotated_source()
+        .expect("the stdlib is annotated with its source");
+    assert!(annotated.contains("#####"));
+    assert_eq!(
+        annotated.lines().count(),
+        stdlib::stdlib::module_sources()
+            .last()
+            .unwrap()
+            .lines()
+            .count()
+    );
+
+    // Nothing is recorded outside of `record_coverage`
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 11, 1_000);
+    executor.execute_block(vec![txn]);
+    let (_, coverage) = record_coverage(|| ());
+    assert!(coverage.is_empty());
+}
@@ -0,0 +1,83 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{Account, AccountData},
+    common_transactions::{peer_to_peer_txn, update_gas_schedule_txn},
+    executor::FakeExecutor,
+};
+use types::{
+    transaction::TransactionStatus,
+    vm_error::{StatusCode, VMStatus},
+};
+use vm::gas_schedule::{
+    gas_schedule_access_path, CostTable, GasAlgebra, GasUnits, DEFAULT_GAS_SCHEDULE,
+};
+use vm_genesis::default_config;
+
+fn executor() -> FakeExecutor {
+    FakeExecutor::from_genesis_with_options(default_config().publishing_options)
+}
+
+fn read_gas_schedule(executor: &FakeExecutor) -> CostTable {
+    let blob = executor
+        .read_from_access_path(&gas_schedule_access_path())
+        .expect("gas schedule must be published");
+    CostTable::from_resource_blob(&blob).unwrap()
+}
+
+#[test]
+fn genesis_publishes_default_gas_schedule() {
+    let executor = executor();
+    assert_eq!(read_gas_schedule(&executor), *DEFAULT_GAS_SCHEDULE);
+}
+
+#[test]
+fn association_updates_gas_schedule() {
+    let mut executor = executor();
+    let association = Account::new_association();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let transfer = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    let gas_before = executor.execute_transaction(transfer.clone()).gas_used();
+
+    // Double the compute cost of every instruction.
+    let mut cost_table = DEFAULT_GAS_SCHEDULE.clone();
+    for cost in cost_table.compute_table.values_mut() {
+        *cost = GasUnits::new(cost.get() * 2);
+    }
+    let output =
+        executor.execute_transaction(update_gas_schedule_txn(&association, &cost_table, 1));
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+    executor.apply_write_set(output.write_set());
+    assert_eq!(read_gas_schedule(&executor), cost_table);
+
+    let gas_after = executor.execute_transaction(transfer).gas_used();
+    assert!(gas_after > gas_before);
+}
+
+#[test]
+fn only_association_updates_gas_schedule() {
+    let mut executor = executor();
+    let sender = AccountData::new(1_000_000, 10);
+    executor.add_account_data(&sender);
+
+    let output = executor.execute_transaction(update_gas_schedule_txn(
+        sender.account(),
+        &DEFAULT_GAS_SCHEDULE,
+        10,
+    ));
+    match output.status() {
+        TransactionStatus::Keep(status) => {
+            assert_eq!(status.major_status, StatusCode::ABORTED);
+            assert_eq!(status.sub_status, Some(1));
+        }
+        status => panic!("unexpected status {:?}", status),
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
     compile::compile_program_with_address, executor::FakeExecutor, transaction_status_eq,
 };
 use config::config::VMPublishingOption;
+use stdlib::error_codes::explain_vm_status;
 use types::{
     transaction::TransactionStatus,
     vm_error::{StatusCode, StatusType, VMStatus},
@@ -238,6 +239,8 @@ pub fn test_publishing_with_error() {
         &result[0].status(),
         &TransactionStatus::Keep(VMStatus::new(StatusCode::ABORTED).with_sub_status(42))
     ));
+    // Aborts raised outside of the stdlib are not explained.
+    assert_eq!(explain_vm_status(result[0].status().vm_status()), None);
 
     assert!(transaction_status_eq(
         &result[1].status(),
@@ -0,0 +1,79 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account::AccountData, executor::FakeExecutor};
+use compiler::Compiler;
+use config::config::VMPublishingOption;
+use types::transaction::{TransactionArgument, TransactionOutput};
+
+/// Runs `code` with `args` from a fresh sender on a fresh executor.
+fn run(code: &str, args: Vec<TransactionArgument>, optimize: bool) -> TransactionOutput {
+    let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::CustomScripts);
+    let sender = AccountData::new(1_000_000, 10);
+    let payee = AccountData::new(1_000_000, 0);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&payee);
+
+    let mut args = args;
+    args.insert(0, TransactionArgument::Address(*payee.address()));
+    let program = Compiler {
+        code,
+        optimize,
+        ..Compiler::default()
+    }
+    .into_program(args)
+    .unwrap();
+    // With a gas price of zero the gas used does not show up in the write set
+    let txn = sender
+        .account()
+        .create_signed_txn_impl(*sender.address(), program, 10, 100_000, 0);
+    executor.execute_transaction(txn)
+}
+
+fn assert_same_behavior(code: &str, args: Vec<TransactionArgument>) {
+    let output = run(code, args.clone(), false);
+    let optimized_output = run(code, args, true);
+    assert_eq!(output.status(), optimized_output.status());
+    assert_eq!(output.write_set(), optimized_output.write_set());
+    assert_eq!(output.events(), optimized_output.events());
+    assert!(optimized_output.gas_used() <= output.gas_used());
+}
+
+#[test]
+fn optimized_payment_matches() {
+    assert_same_behavior(
+        "
+        import 0x0.LibraAccount;
+        main(payee: address, extra: u64) {
+            let amount: u64;
+            amount = 10 * 5 + 2;
+            if (!(copy(amount) > 100)) {
+                LibraAccount.pay_from_sender(move(payee), move(amount) + move(extra));
+            } else {
+                abort 1;
+            }
+            return;
+        }
+        ",
+        vec![TransactionArgument::U64(7)],
+    );
+}
+
+#[test]
+fn optimized_abort_matches() {
+    assert_same_behavior(
+        "
+        main(payee: address) {
+            let x: u64;
+            _ = move(payee);
+            x = 3;
+            while (copy(x) > 0) {
+                x = copy(x) - 1;
+            }
+            assert(copy(x) == 1 + 1, 77);
+            return;
+        }
+        ",
+        vec![],
+    );
+}
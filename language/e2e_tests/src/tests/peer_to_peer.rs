@@ -8,6 +8,7 @@ use crate::{
     transaction_status_eq,
 };
 use std::time::Instant;
+use stdlib::error_codes::explain_vm_status;
 use types::{
     account_config::AccountEvent,
     transaction::{SignedTransaction, TransactionOutput, TransactionStatus},
@@ -158,6 +159,14 @@ fn zero_amount_peer_to_peer() {
         &output.status(),
         &TransactionStatus::Keep(VMStatus::new(StatusCode::ABORTED).with_sub_status(7))
     ));
+    assert_eq!(
+        explain_vm_status(output.status().vm_status()),
+        Some(
+            "LibraAccount::EZERO_DEPOSIT (invalid argument): Depositing a coin of value zero is \
+             not allowed"
+                .to_string()
+        )
+    );
 }
 
 #[test]
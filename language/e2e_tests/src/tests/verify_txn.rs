@@ -14,7 +14,11 @@ use compiler::Compiler;
 use config::config::{NodeConfigHelpers, VMPublishingOption};
 use crypto::{ed25519::*, HashValue};
 use std::collections::HashSet;
+use stdlib::error_codes::{error_description, ErrorCategory};
 use types::{
+    account_config,
+    identifier::Identifier,
+    language_storage::ModuleId,
     test_helpers::transaction_test_helpers,
     transaction::{
         Module, Script, TransactionArgument, TransactionPayload, TransactionStatus,
@@ -22,7 +26,13 @@ use types::{
     },
     vm_error::{StatusCode, StatusType, VMStatus},
 };
-use vm::gas_schedule::{self, GasAlgebra};
+use vm::{
+    errors::{
+        EACCOUNT_DOES_NOT_EXIST, EBAD_ACCOUNT_AUTHENTICATION_KEY, ECANT_PAY_GAS_DEPOSIT,
+        ESEQUENCE_NUMBER_TOO_NEW, ESEQUENCE_NUMBER_TOO_OLD, ETRANSACTION_EXPIRED,
+    },
+    gas_schedule::{self, GasAlgebra},
+};
 use vm_genesis::encode_transfer_program;
 
 #[test]
@@ -359,6 +369,49 @@ pub fn test_arbitrary_script_execution() {
     );
 }
 
+#[test]
+pub fn test_bool_and_vector_script_arguments() {
+    let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::CustomScripts);
+    let sender = AccountData::new(1_000_000, 10);
+    executor.add_account_data(&sender);
+
+    let script = compile_script(
+        "
+        import 0x0.Vector;
+        main(flag: bool, addresses: Vector.T<address>) {
+            assert(move(flag), 42);
+            return;
+        }
+        ",
+    );
+    let args = vec![
+        TransactionArgument::Bool(true),
+        TransactionArgument::AddressVector(vec![*sender.address()]),
+    ];
+    let txn = sender
+        .account()
+        .create_signed_txn_with_args(script.clone(), args, 10, 100_000, 1);
+    assert_eq!(executor.verify_transaction(txn.clone()), None);
+    assert_eq!(
+        executor.execute_transaction(txn).status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+
+    // The arguments have to match the signature of main.
+    let args = vec![
+        TransactionArgument::AddressVector(vec![]),
+        TransactionArgument::Bool(true),
+    ];
+    let txn = sender
+        .account()
+        .create_signed_txn_with_args(script, args, 10, 100_000, 1);
+    assert_prologue_parity!(
+        executor.verify_transaction(txn.clone()),
+        executor.execute_transaction(txn).status(),
+        VMStatus::new(StatusCode::TYPE_MISMATCH)
+    );
+}
+
 #[test]
 pub fn test_no_publishing() {
     // create a FakeExecutor with a genesis from file
@@ -568,3 +621,23 @@ fn test_dependency_fails_verification() {
         _ => panic!("Failed to find missing dependency in bytecode verifier"),
     }
 }
+
+#[test]
+fn prologue_error_codes_are_documented() {
+    let account_module = ModuleId::new(
+        account_config::core_code_address(),
+        Identifier::new("LibraAccount").unwrap(),
+    );
+    for code in &[
+        EBAD_ACCOUNT_AUTHENTICATION_KEY,
+        ESEQUENCE_NUMBER_TOO_OLD,
+        ESEQUENCE_NUMBER_TOO_NEW,
+        EACCOUNT_DOES_NOT_EXIST,
+        ECANT_PAY_GAS_DEPOSIT,
+        ETRANSACTION_EXPIRED,
+    ] {
+        let description = error_description(&account_module, *code)
+            .unwrap_or_else(|| panic!("prologue error code {} is not documented", code));
+        assert_eq!(description.category, ErrorCategory::Prologue);
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs functional tests and reports the standard library instructions they executed.
+//!
+//! Usage: `coverage [--annotate] [PATH]...`, where each path is a test file or a directory
+//! searched recursively for .mvir files. Defaults to the whole test suite. With `--annotate`, the
+//! source of each stdlib module is printed with the number of times each of its lines ran.
+
+use functional_tests::{evaluator::eval, utils::parse_input};
+use language_e2e_tests::coverage::{record_coverage, CoverageReport};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+fn collect_tests(path: &Path, tests: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)
+            .unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e))
+            .map(|entry| entry.expect("failed to read directory entry").path())
+            .collect::<Vec<_>>();
+        entries.sort();
+        for entry in entries {
+            collect_tests(&entry, tests);
+        }
+    } else if path.extension().map_or(false, |ext| ext == "mvir") {
+        tests.push(path.to_path_buf());
+    }
+}
+
+fn main() {
+    let mut annotate = false;
+    let mut paths = vec![];
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--annotate" => annotate = true,
+            "-h" | "--help" => {
+                println!("Usage: coverage [--annotate] [PATH]...");
+                return;
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        paths.push(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testsuite"));
+    }
+
+    let mut tests = vec![];
+    for path in &paths {
+        collect_tests(path, &mut tests);
+    }
+
+    let mut failed = 0;
+    let (_, coverage) = record_coverage(|| {
+        for test in &tests {
+            let input = fs::read_to_string(test)
+                .unwrap_or_else(|e| panic!("failed to read {}: {}", test.display(), e));
+            let result = parse_input(&input)
+                .and_then(|(config, _, transactions)| eval(&config, &transactions));
+            if let Err(e) = result {
+                eprintln!("{}: {}", test.display(), e);
+                failed += 1;
+            }
+        }
+    });
+
+    let report = CoverageReport::stdlib(&coverage);
+    println!("Ran {} tests", tests.len());
+    print!("{}", report);
+    if annotate {
+        for module in &report.modules {
+            if let Some(source) = module.annotated_source() {
+                println!();
+                println!("{}:", module.module.name());
+                print!("{}", source);
+            }
+        }
+    }
+    if failed > 0 {
+        eprintln!("{} tests failed to run", failed);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Borrow analysis: computes, for every local or temporary holding a reference, the locations the
+//! reference may point into.
+//!
+//! References are always resolved to the location they were ultimately borrowed from, so the
+//! borrow graph has an edge from every reference to its roots, labeled with the fields borrowed
+//! on the way.
+
+use crate::{
+    dataflow_analysis::{
+        AbstractDomain, AnalysisDirection, DataflowAnalysis, InstructionState, JoinResult,
+        TransferFunctions,
+    },
+    stackless_control_flow_graph::StacklessControlFlowGraph,
+};
+use stackless_bytecode_generator::{
+    stackless_bytecode::{StacklessBytecode, TempIndex},
+    stackless_bytecode_generator::StacklessFunction,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use vm::file_format::{CodeOffset, FieldDefinitionIndex, SignatureToken, StructDefinitionIndex};
+
+/// A location references can be borrowed from.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum BorrowNode {
+    /// A local of the function
+    Local(TempIndex),
+    /// The resource of the given type stored at some address
+    Global(StructDefinitionIndex),
+    /// The location a reference formal points to when the function is called
+    Formal(TempIndex),
+}
+
+/// An edge of the borrow graph: a reference points into `root` at the field path `path`.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct BorrowEdge {
+    pub root: BorrowNode,
+    pub path: Vec<FieldDefinitionIndex>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BorrowGraph {
+    pub edges: BTreeMap<TempIndex, BTreeSet<BorrowEdge>>,
+}
+
+impl BorrowGraph {
+    /// The locations the reference held by `var` may point into
+    pub fn borrows_of(&self, var: TempIndex) -> BTreeSet<BorrowEdge> {
+        self.edges.get(&var).cloned().unwrap_or_default()
+    }
+
+    /// The locals and temporaries holding a reference that may point into `root`
+    pub fn borrowed_by(&self, root: BorrowNode) -> BTreeSet<TempIndex> {
+        self.edges
+            .iter()
+            .filter(|(_, edges)| edges.iter().any(|edge| edge.root == root))
+            .map(|(var, _)| *var)
+            .collect()
+    }
+
+    fn copy_edges(&mut self, dest: TempIndex, src: TempIndex) {
+        let edges = self.borrows_of(src);
+        self.edges.insert(dest, edges);
+    }
+}
+
+impl AbstractDomain for BorrowGraph {
+    fn join(&mut self, other: &Self) -> JoinResult {
+        let mut result = JoinResult::Unchanged;
+        for (var, other_edges) in &other.edges {
+            let edges = self.edges.entry(*var).or_insert_with(BTreeSet::new);
+            for edge in other_edges {
+                if edges.insert(edge.clone()) {
+                    result = JoinResult::Changed;
+                }
+            }
+        }
+        result
+    }
+}
+
+pub struct BorrowAnalysis<'a> {
+    local_types: &'a [SignatureToken],
+}
+
+impl<'a> BorrowAnalysis<'a> {
+    pub fn new(local_types: &'a [SignatureToken]) -> Self {
+        Self { local_types }
+    }
+
+    /// Returns the borrow graphs before and after every instruction of `function`, which has
+    /// `num_formals` formals.
+    pub fn analyze(
+        function: &StacklessFunction,
+        num_formals: usize,
+    ) -> BTreeMap<CodeOffset, InstructionState<BorrowGraph>> {
+        let analysis = BorrowAnalysis::new(&function.local_types);
+        let cfg = StacklessControlFlowGraph::new(&function.code);
+        let mut initial_state = BorrowGraph::default();
+        for formal in 0..num_formals {
+            if analysis.is_reference(formal) {
+                initial_state.edges.insert(
+                    formal,
+                    vec![BorrowEdge {
+                        root: BorrowNode::Formal(formal),
+                        path: vec![],
+                    }]
+                    .into_iter()
+                    .collect(),
+                );
+            }
+        }
+        let state_map = analysis.analyze_function(initial_state, &function.code, &cfg);
+        analysis.state_per_instruction(&state_map, &function.code, &cfg)
+    }
+
+    fn is_reference(&self, var: TempIndex) -> bool {
+        match self.local_types[var] {
+            SignatureToken::Reference(_) | SignatureToken::MutableReference(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<'a> TransferFunctions for BorrowAnalysis<'a> {
+    type State = BorrowGraph;
+    const DIRECTION: AnalysisDirection = AnalysisDirection::Forward;
+
+    fn execute(&self, state: &mut BorrowGraph, instr: &StacklessBytecode, _offset: CodeOffset) {
+        use StacklessBytecode::*;
+        match instr {
+            BorrowLoc(dest, local) => {
+                let edge = BorrowEdge {
+                    root: BorrowNode::Local(*local as TempIndex),
+                    path: vec![],
+                };
+                state.edges.insert(*dest, vec![edge].into_iter().collect());
+            }
+            BorrowGlobal(dest, _, struct_def) => {
+                let edge = BorrowEdge {
+                    root: BorrowNode::Global(*struct_def),
+                    path: vec![],
+                };
+                state.edges.insert(*dest, vec![edge].into_iter().collect());
+            }
+            BorrowField(dest, src, field) => {
+                let edges = state
+                    .borrows_of(*src)
+                    .into_iter()
+                    .map(|mut edge| {
+                        edge.path.push(*field);
+                        edge
+                    })
+                    .collect();
+                state.edges.insert(*dest, edges);
+            }
+            FreezeRef(dest, src) => state.copy_edges(*dest, *src),
+            CopyLoc(dest, local) if self.is_reference(*dest) => {
+                state.copy_edges(*dest, *local as TempIndex)
+            }
+            MoveLoc(dest, local) if self.is_reference(*dest) => {
+                state.copy_edges(*dest, *local as TempIndex);
+                state.edges.remove(&(*local as TempIndex));
+            }
+            StLoc(local, src) if self.is_reference(*src) => {
+                state.copy_edges(*local as TempIndex, *src);
+                state.edges.remove(src);
+            }
+            Call(dests, _, args) => {
+                // References returned by a function can only be derived from its reference
+                // arguments
+                let edges: BTreeSet<BorrowEdge> =
+                    args.iter().flat_map(|arg| state.borrows_of(*arg)).collect();
+                for dest in dests {
+                    if self.is_reference(*dest) {
+                        state.edges.insert(*dest, edges.clone());
+                    } else {
+                        state.edges.remove(dest);
+                    }
+                }
+            }
+            _ => {
+                for dest in instr.destinations() {
+                    state.edges.remove(&dest);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> DataflowAnalysis for BorrowAnalysis<'a> {}
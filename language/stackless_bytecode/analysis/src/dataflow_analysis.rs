@@ -0,0 +1,205 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A generic dataflow framework over stackless bytecode. An analysis defines an abstract domain
+//! and the transfer function of each instruction, and the framework computes a fixpoint over the
+//! control-flow graph, either forward or backward.
+
+use crate::stackless_control_flow_graph::{BlockId, StacklessControlFlowGraph};
+use stackless_bytecode_generator::stackless_bytecode::StacklessBytecode;
+use std::collections::BTreeMap;
+use vm::file_format::CodeOffset;
+
+/// Trait for finite-height abstract domains, where `join` computes the least upper bound.
+pub trait AbstractDomain: Clone + Eq {
+    fn join(&mut self, other: &Self) -> JoinResult;
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JoinResult {
+    Unchanged,
+    Changed,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AnalysisDirection {
+    /// States flow from the entry of the function along its control-flow edges
+    Forward,
+    /// States flow from the exits of the function against its control-flow edges
+    Backward,
+}
+
+/// The states of a block after a fixpoint is reached. `pre` is the state the analysis starts the
+/// block with and `post` the state it ends it with, both in the direction of the analysis: for a
+/// backward analysis, `pre` holds at the end of the block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockState<State> {
+    pub pre: State,
+    pub post: State,
+}
+
+/// A map from block ids to their states after a fixpoint is reached. Blocks a forward analysis
+/// cannot reach from the entry of the function have no state.
+pub type StateMap<State> = BTreeMap<BlockId, BlockState<State>>;
+
+/// The states holding immediately before and after an instruction executes, in program order.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InstructionState<State> {
+    pub before: State,
+    pub after: State,
+}
+
+/// Take the state before an instruction, in the direction of the analysis, and mutate it to
+/// produce the state after it.
+pub trait TransferFunctions {
+    type State: AbstractDomain;
+    const DIRECTION: AnalysisDirection;
+
+    fn execute(&self, state: &mut Self::State, instr: &StacklessBytecode, offset: CodeOffset);
+}
+
+pub trait DataflowAnalysis: TransferFunctions {
+    /// Computes the fixpoint of the analysis over `code`, starting from `initial_state` at the
+    /// entry block (forward) or at every exit block (backward).
+    ///
+    /// A backward analysis also starts from `initial_state` at blocks which cannot reach an exit
+    /// block, like the blocks of an infinite loop.
+    fn analyze_function(
+        &self,
+        initial_state: Self::State,
+        code: &[StacklessBytecode],
+        cfg: &StacklessControlFlowGraph,
+    ) -> StateMap<Self::State> {
+        let mut state_map = StateMap::new();
+        let mut work_list = vec![];
+        let entry_blocks = match Self::DIRECTION {
+            AnalysisDirection::Forward => vec![cfg.entry_block_id()],
+            AnalysisDirection::Backward => cfg.exit_blocks(),
+        };
+        for block_id in entry_blocks {
+            state_map.insert(
+                block_id,
+                BlockState {
+                    pre: initial_state.clone(),
+                    post: initial_state.clone(),
+                },
+            );
+            work_list.push(block_id);
+        }
+
+        loop {
+            while let Some(block_id) = work_list.pop() {
+                let mut state = state_map[&block_id].pre.clone();
+                self.execute_block(block_id, &mut state, code, cfg);
+
+                let next_blocks = match Self::DIRECTION {
+                    AnalysisDirection::Forward => cfg.successors(block_id),
+                    AnalysisDirection::Backward => cfg.predecessors(block_id),
+                };
+                for next_block_id in next_blocks {
+                    match state_map.get_mut(next_block_id) {
+                        Some(next_block_state) => {
+                            if next_block_state.pre.join(&state) == JoinResult::Changed {
+                                work_list.push(*next_block_id);
+                            }
+                        }
+                        None => {
+                            state_map.insert(
+                                *next_block_id,
+                                BlockState {
+                                    pre: state.clone(),
+                                    post: state.clone(),
+                                },
+                            );
+                            work_list.push(*next_block_id);
+                        }
+                    }
+                }
+                state_map
+                    .get_mut(&block_id)
+                    .expect("analyzed blocks have a state")
+                    .post = state;
+            }
+
+            if Self::DIRECTION == AnalysisDirection::Forward {
+                break;
+            }
+            match cfg
+                .blocks()
+                .into_iter()
+                .rev()
+                .find(|block_id| !state_map.contains_key(block_id))
+            {
+                Some(block_id) => {
+                    state_map.insert(
+                        block_id,
+                        BlockState {
+                            pre: initial_state.clone(),
+                            post: initial_state.clone(),
+                        },
+                    );
+                    work_list.push(block_id);
+                }
+                None => break,
+            }
+        }
+        state_map
+    }
+
+    /// Executes the instructions of a block in the direction of the analysis.
+    fn execute_block(
+        &self,
+        block_id: BlockId,
+        state: &mut Self::State,
+        code: &[StacklessBytecode],
+        cfg: &StacklessControlFlowGraph,
+    ) {
+        let instr_indexes = cfg.instr_indexes(block_id);
+        match Self::DIRECTION {
+            AnalysisDirection::Forward => {
+                for offset in instr_indexes {
+                    self.execute(state, &code[offset as usize], offset);
+                }
+            }
+            AnalysisDirection::Backward => {
+                for offset in instr_indexes.rev() {
+                    self.execute(state, &code[offset as usize], offset);
+                }
+            }
+        }
+    }
+
+    /// Computes the states before and after every instruction of the blocks in `state_map`.
+    fn state_per_instruction(
+        &self,
+        state_map: &StateMap<Self::State>,
+        code: &[StacklessBytecode],
+        cfg: &StacklessControlFlowGraph,
+    ) -> BTreeMap<CodeOffset, InstructionState<Self::State>> {
+        let mut result = BTreeMap::new();
+        for (block_id, block_state) in state_map {
+            let mut state = block_state.pre.clone();
+            let instr_indexes = cfg.instr_indexes(*block_id);
+            let offsets: Vec<CodeOffset> = match Self::DIRECTION {
+                AnalysisDirection::Forward => instr_indexes.collect(),
+                AnalysisDirection::Backward => instr_indexes.rev().collect(),
+            };
+            for offset in offsets {
+                let input = state.clone();
+                self.execute(&mut state, &code[offset as usize], offset);
+                let instruction_state = match Self::DIRECTION {
+                    AnalysisDirection::Forward => InstructionState {
+                        before: input,
+                        after: state.clone(),
+                    },
+                    AnalysisDirection::Backward => InstructionState {
+                        before: state.clone(),
+                        after: input,
+                    },
+                };
+                result.insert(offset, instruction_state);
+            }
+        }
+        result
+    }
+}
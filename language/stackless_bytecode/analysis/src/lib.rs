@@ -0,0 +1,12 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Analyses over stackless bytecode: a control-flow graph, a generic dataflow framework and the
+//! analyses built on it, and a lint pass reporting suspicious code in user modules.
+
+pub mod borrow_analysis;
+pub mod dataflow_analysis;
+pub mod lint;
+pub mod livevar_analysis;
+pub mod reaching_def_analysis;
+pub mod stackless_control_flow_graph;
@@ -0,0 +1,184 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lints over the stackless bytecode of user modules: dead stores, unused locals and unreachable
+//! code.
+
+use crate::{
+    livevar_analysis::LiveVarAnalysis, stackless_control_flow_graph::StacklessControlFlowGraph,
+};
+use ir_to_bytecode::parser::ast::{FunctionBody, ModuleDefinition};
+use stackless_bytecode_generator::{
+    stackless_bytecode::{StacklessBytecode, TempIndex},
+    stackless_bytecode_generator::{StacklessFunction, StacklessModuleGenerator},
+};
+use std::{collections::BTreeSet, fmt};
+use vm::{
+    access::ModuleAccess,
+    file_format::{CodeOffset, CompiledModule, FunctionDefinition},
+    views::FunctionDefinitionView,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LintKind {
+    /// A value is stored in a local and never read afterwards
+    DeadStore { local: String, offset: CodeOffset },
+    /// A local is declared but never read
+    UnusedLocal { local: String },
+    /// No execution of the function reaches the code starting at the offset
+    UnreachableCode { offset: CodeOffset },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LintWarning {
+    pub module: String,
+    pub function: String,
+    pub kind: LintKind,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "warning: {}.{}: ", self.module, self.function)?;
+        match &self.kind {
+            LintKind::DeadStore { local, offset } => write!(
+                f,
+                "value stored in local `{}` at offset {} is never read",
+                local, offset
+            ),
+            LintKind::UnusedLocal { local } => write!(f, "local `{}` is never read", local),
+            LintKind::UnreachableCode { offset } => {
+                write!(f, "code at offset {} is unreachable", offset)
+            }
+        }
+    }
+}
+
+/// Lints the functions of `module`. If the source `definition` of the module is given, locals are
+/// reported by name, otherwise by index.
+pub fn lint_module(
+    module: &CompiledModule,
+    definition: Option<&ModuleDefinition>,
+) -> Vec<LintWarning> {
+    let module_name = module.self_id().name().to_string();
+    let functions = StacklessModuleGenerator::new(module).generate_module();
+    let mut warnings = vec![];
+    for (idx, (function_def, function)) in module
+        .function_defs()
+        .iter()
+        .zip(functions.iter())
+        .enumerate()
+    {
+        if function_def.is_native() {
+            continue;
+        }
+        let function_name = module
+            .identifier_at(module.function_handle_at(function_def.function).name)
+            .to_string();
+        let local_names = definition
+            .map(|definition| local_names(definition, idx))
+            .unwrap_or_default();
+        for kind in lint_function(module, function_def, function, &local_names) {
+            warnings.push(LintWarning {
+                module: module_name.clone(),
+                function: function_name.clone(),
+                kind,
+            });
+        }
+    }
+    warnings
+}
+
+/// The names of the formals and locals of the function definition `idx`, in the order of the
+/// locals signature.
+fn local_names(definition: &ModuleDefinition, idx: usize) -> Vec<String> {
+    let (_, function) = &definition.functions[idx];
+    let mut names: Vec<String> = function
+        .signature
+        .formals
+        .iter()
+        .map(|(var, _)| var.name().to_string())
+        .collect();
+    if let FunctionBody::Move { locals, .. } = &function.body {
+        names.extend(locals.iter().map(|(var, _)| var.value.name().to_string()));
+    }
+    names
+}
+
+fn lint_function(
+    module: &CompiledModule,
+    function_def: &FunctionDefinition,
+    function: &StacklessFunction,
+    local_names: &[String],
+) -> Vec<LintKind> {
+    let function_view = FunctionDefinitionView::new(module, function_def);
+    let num_formals = function_view.signature().arg_count();
+    let num_locals = function_view.locals_signature().len();
+    let local_name = |local: TempIndex| {
+        local_names
+            .get(local)
+            .cloned()
+            .unwrap_or_else(|| format!("loc{}", local))
+    };
+
+    let code = &function.code;
+    let cfg = StacklessControlFlowGraph::new(code);
+    let reachable_blocks = cfg.reachable_blocks();
+    let mut lints = vec![];
+
+    let read_locals: BTreeSet<TempIndex> = code
+        .iter()
+        .flat_map(|instr| match instr {
+            StacklessBytecode::MoveLoc(_, local)
+            | StacklessBytecode::CopyLoc(_, local)
+            | StacklessBytecode::BorrowLoc(_, local) => Some(*local as TempIndex),
+            _ => None,
+        })
+        .collect();
+    let unused_locals: BTreeSet<TempIndex> = (num_formals..num_locals)
+        .filter(|local| !read_locals.contains(local))
+        .collect();
+    for local in &unused_locals {
+        lints.push(LintKind::UnusedLocal {
+            local: local_name(*local),
+        });
+    }
+
+    // Stores to unused locals are already reported, and stores in unreachable code are part of
+    // the unreachable code
+    let live_vars = LiveVarAnalysis::analyze(code);
+    for block_id in &reachable_blocks {
+        for offset in cfg.instr_indexes(*block_id) {
+            if let StacklessBytecode::StLoc(local, _) = &code[offset as usize] {
+                let local = *local as TempIndex;
+                if !unused_locals.contains(&local)
+                    && !live_vars[&offset].after.live_vars.contains(&local)
+                {
+                    lints.push(LintKind::DeadStore {
+                        local: local_name(local),
+                        offset,
+                    });
+                }
+            }
+        }
+    }
+
+    // The compiler emits a branch back to the loop head after the body of a loop even if the body
+    // never completes, so unreachable blocks made of a single branch are not reported
+    for block_id in cfg.blocks() {
+        if reachable_blocks.contains(&block_id) {
+            continue;
+        }
+        let is_compiler_branch =
+            cfg.instr_indexes(block_id)
+                .all(|offset| match code[offset as usize] {
+                    StacklessBytecode::Branch(_) => true,
+                    _ => false,
+                });
+        if !is_compiler_branch {
+            lints.push(LintKind::UnreachableCode {
+                offset: cfg.block_start(block_id),
+            });
+        }
+    }
+    lints
+}
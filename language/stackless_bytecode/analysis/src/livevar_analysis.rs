@@ -0,0 +1,60 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Live variable analysis: a local or temporary is live at a point if its current value may be
+//! read later on some path through the function.
+
+use crate::{
+    dataflow_analysis::{
+        AbstractDomain, AnalysisDirection, DataflowAnalysis, InstructionState, JoinResult,
+        TransferFunctions,
+    },
+    stackless_control_flow_graph::StacklessControlFlowGraph,
+};
+use stackless_bytecode_generator::stackless_bytecode::{StacklessBytecode, TempIndex};
+use std::collections::{BTreeMap, BTreeSet};
+use vm::file_format::CodeOffset;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LiveVarState {
+    pub live_vars: BTreeSet<TempIndex>,
+}
+
+impl AbstractDomain for LiveVarState {
+    fn join(&mut self, other: &Self) -> JoinResult {
+        let count = self.live_vars.len();
+        self.live_vars.extend(other.live_vars.iter().cloned());
+        if self.live_vars.len() == count {
+            JoinResult::Unchanged
+        } else {
+            JoinResult::Changed
+        }
+    }
+}
+
+pub struct LiveVarAnalysis;
+
+impl LiveVarAnalysis {
+    /// Returns the variables live before and after every instruction of `code`.
+    pub fn analyze(
+        code: &[StacklessBytecode],
+    ) -> BTreeMap<CodeOffset, InstructionState<LiveVarState>> {
+        let cfg = StacklessControlFlowGraph::new(code);
+        let state_map = LiveVarAnalysis.analyze_function(LiveVarState::default(), code, &cfg);
+        LiveVarAnalysis.state_per_instruction(&state_map, code, &cfg)
+    }
+}
+
+impl TransferFunctions for LiveVarAnalysis {
+    type State = LiveVarState;
+    const DIRECTION: AnalysisDirection = AnalysisDirection::Backward;
+
+    fn execute(&self, state: &mut LiveVarState, instr: &StacklessBytecode, _offset: CodeOffset) {
+        for dest in instr.destinations() {
+            state.live_vars.remove(&dest);
+        }
+        state.live_vars.extend(instr.sources());
+    }
+}
+
+impl DataflowAnalysis for LiveVarAnalysis {}
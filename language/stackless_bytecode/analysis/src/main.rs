@@ -0,0 +1,66 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use bytecode_verifier::VerifiedModule;
+use ir_to_bytecode::{compiler::compile_module, parser::parse_module};
+use stackless_bytecode_analysis::lint::lint_module;
+use std::{fs, path::PathBuf};
+use stdlib::stdlib_modules;
+use structopt::StructOpt;
+use types::account_address::AccountAddress;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "Move Lint",
+    author = "Libra",
+    about = "Reports dead stores, unused locals and unreachable code in Move IR modules."
+)]
+struct Args {
+    /// Account address the modules are published under
+    #[structopt(short = "a", long = "address")]
+    pub address: Option<String>,
+    /// Do not automatically compile stdlib dependencies
+    #[structopt(long = "no-stdlib")]
+    pub no_stdlib: bool,
+    /// Paths to the Move IR modules to lint. Each module can depend on the modules before it
+    #[structopt(parse(from_os_str))]
+    pub source_paths: Vec<PathBuf>,
+}
+
+fn main() {
+    let args = Args::from_args();
+    let address = args
+        .address
+        .map(|address| {
+            AccountAddress::from_hex_literal(&address)
+                .unwrap_or_else(|err| panic!("Invalid address {}: {}", address, err))
+        })
+        .unwrap_or_default();
+    let mut deps = if args.no_stdlib {
+        vec![]
+    } else {
+        stdlib_modules().to_vec()
+    };
+
+    let mut num_warnings = 0;
+    for source_path in &args.source_paths {
+        let source = fs::read_to_string(source_path)
+            .unwrap_or_else(|err| panic!("Unable to read {:?}: {}", source_path, err));
+        let definition = parse_module(&source)
+            .unwrap_or_else(|err| panic!("Unable to parse {:?}: {}", source_path, err));
+        let compiled_module = compile_module(address, definition.clone(), &deps)
+            .unwrap_or_else(|err| panic!("Unable to compile {:?}: {}", source_path, err));
+        for warning in lint_module(&compiled_module, Some(&definition)) {
+            println!("{}", warning);
+            num_warnings += 1;
+        }
+        let verified_module = VerifiedModule::new(compiled_module).unwrap_or_else(|(_, errors)| {
+            panic!("{:?} failed verification: {:?}", source_path, errors)
+        });
+        deps.push(verified_module);
+    }
+
+    if num_warnings > 0 {
+        std::process::exit(1);
+    }
+}
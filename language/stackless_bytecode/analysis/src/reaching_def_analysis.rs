@@ -0,0 +1,79 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reaching definitions analysis: the assignments to a local or temporary whose value may still be
+//! held by it at a point of the function.
+
+use crate::{
+    dataflow_analysis::{
+        AbstractDomain, AnalysisDirection, DataflowAnalysis, InstructionState, JoinResult,
+        TransferFunctions,
+    },
+    stackless_control_flow_graph::StacklessControlFlowGraph,
+};
+use stackless_bytecode_generator::stackless_bytecode::{StacklessBytecode, TempIndex};
+use std::collections::{BTreeMap, BTreeSet};
+use vm::file_format::CodeOffset;
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Definition {
+    /// The value a formal is called with
+    Formal,
+    /// The assignment by the instruction at the offset
+    Offset(CodeOffset),
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReachingDefState {
+    pub defs: BTreeMap<TempIndex, BTreeSet<Definition>>,
+}
+
+impl AbstractDomain for ReachingDefState {
+    fn join(&mut self, other: &Self) -> JoinResult {
+        let mut result = JoinResult::Unchanged;
+        for (var, other_defs) in &other.defs {
+            let defs = self.defs.entry(*var).or_insert_with(BTreeSet::new);
+            for def in other_defs {
+                if defs.insert(*def) {
+                    result = JoinResult::Changed;
+                }
+            }
+        }
+        result
+    }
+}
+
+pub struct ReachingDefAnalysis;
+
+impl ReachingDefAnalysis {
+    /// Returns the definitions reaching every instruction of `code`, for a function with
+    /// `num_formals` formals.
+    pub fn analyze(
+        code: &[StacklessBytecode],
+        num_formals: usize,
+    ) -> BTreeMap<CodeOffset, InstructionState<ReachingDefState>> {
+        let cfg = StacklessControlFlowGraph::new(code);
+        let initial_state = ReachingDefState {
+            defs: (0..num_formals)
+                .map(|formal| (formal, vec![Definition::Formal].into_iter().collect()))
+                .collect(),
+        };
+        let state_map = ReachingDefAnalysis.analyze_function(initial_state, code, &cfg);
+        ReachingDefAnalysis.state_per_instruction(&state_map, code, &cfg)
+    }
+}
+
+impl TransferFunctions for ReachingDefAnalysis {
+    type State = ReachingDefState;
+    const DIRECTION: AnalysisDirection = AnalysisDirection::Forward;
+
+    fn execute(&self, state: &mut ReachingDefState, instr: &StacklessBytecode, offset: CodeOffset) {
+        for dest in instr.destinations() {
+            state
+                .defs
+                .insert(dest, vec![Definition::Offset(offset)].into_iter().collect());
+        }
+    }
+}
+
+impl DataflowAnalysis for ReachingDefAnalysis {}
@@ -0,0 +1,155 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the control-flow graph of a function in stackless bytecode.
+use stackless_bytecode_generator::stackless_bytecode::StacklessBytecode;
+use std::collections::{BTreeMap, BTreeSet};
+use vm::file_format::CodeOffset;
+
+pub type BlockId = CodeOffset;
+
+struct BasicBlock {
+    entry: CodeOffset,
+    exit: CodeOffset,
+    successors: Vec<BlockId>,
+    predecessors: Vec<BlockId>,
+}
+
+/// The control flow graph of a function. Blocks are identified by the offset of their first
+/// instruction.
+pub struct StacklessControlFlowGraph {
+    blocks: BTreeMap<BlockId, BasicBlock>,
+    /// Whether the function has no code at all
+    is_empty: bool,
+}
+
+const ENTRY_BLOCK_ID: BlockId = 0;
+
+impl StacklessControlFlowGraph {
+    pub fn new(code: &[StacklessBytecode]) -> Self {
+        // First collect the offsets that begin basic blocks, so that backward edges are handled
+        let mut block_ids = BTreeSet::new();
+        block_ids.insert(ENTRY_BLOCK_ID);
+        for (pc, bytecode) in code.iter().enumerate() {
+            if let Some(offset) = bytecode.offset() {
+                block_ids.insert(*offset);
+            }
+            if bytecode.is_branch() && pc + 1 < code.len() {
+                block_ids.insert((pc + 1) as CodeOffset);
+            }
+        }
+
+        let mut blocks = BTreeMap::new();
+        let mut entry = 0;
+        for pc in 0..code.len() {
+            let co_pc = pc as CodeOffset;
+            if pc + 1 == code.len() || block_ids.contains(&(co_pc + 1)) {
+                blocks.insert(
+                    entry,
+                    BasicBlock {
+                        entry,
+                        exit: co_pc,
+                        successors: StacklessBytecode::get_successors(co_pc, code),
+                        predecessors: vec![],
+                    },
+                );
+                entry = co_pc + 1;
+            }
+        }
+        // Even a function without code has an (empty) entry block
+        if code.is_empty() {
+            blocks.insert(
+                ENTRY_BLOCK_ID,
+                BasicBlock {
+                    entry: 0,
+                    exit: 0,
+                    successors: vec![],
+                    predecessors: vec![],
+                },
+            );
+        }
+
+        let edges: Vec<(BlockId, BlockId)> = blocks
+            .values()
+            .flat_map(|block| {
+                block
+                    .successors
+                    .iter()
+                    .map(move |successor| (block.entry, *successor))
+            })
+            .collect();
+        for (from, to) in edges {
+            blocks
+                .get_mut(&to)
+                .expect("branch targets start a block")
+                .predecessors
+                .push(from);
+        }
+        StacklessControlFlowGraph {
+            blocks,
+            is_empty: code.is_empty(),
+        }
+    }
+
+    /// Start index of the block in the bytecode vector
+    pub fn block_start(&self, block_id: BlockId) -> CodeOffset {
+        self.blocks[&block_id].entry
+    }
+
+    /// End index of the block in the bytecode vector
+    pub fn block_end(&self, block_id: BlockId) -> CodeOffset {
+        self.blocks[&block_id].exit
+    }
+
+    /// Blocks control can flow to from the end of the block
+    pub fn successors(&self, block_id: BlockId) -> &[BlockId] {
+        &self.blocks[&block_id].successors
+    }
+
+    /// Blocks control can flow from into the start of the block
+    pub fn predecessors(&self, block_id: BlockId) -> &[BlockId] {
+        &self.blocks[&block_id].predecessors
+    }
+
+    /// Indexes of the instructions in the block, in program order. Empty for the entry block of
+    /// a function without code.
+    pub fn instr_indexes(&self, block_id: BlockId) -> impl DoubleEndedIterator<Item = CodeOffset> {
+        let block = &self.blocks[&block_id];
+        let end = if self.is_empty { 0 } else { block.exit + 1 };
+        block.entry..end
+    }
+
+    /// All blocks of the graph, ordered by their start offset
+    pub fn blocks(&self) -> Vec<BlockId> {
+        self.blocks.keys().cloned().collect()
+    }
+
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn entry_block_id(&self) -> BlockId {
+        ENTRY_BLOCK_ID
+    }
+
+    /// Blocks ending the function, i.e. blocks that return or abort
+    pub fn exit_blocks(&self) -> Vec<BlockId> {
+        self.blocks
+            .values()
+            .filter(|block| block.successors.is_empty())
+            .map(|block| block.entry)
+            .collect()
+    }
+
+    /// The blocks reachable from the entry block, including the entry block itself
+    pub fn reachable_blocks(&self) -> BTreeSet<BlockId> {
+        let mut reachable = BTreeSet::new();
+        let mut work_list = vec![ENTRY_BLOCK_ID];
+        while let Some(block_id) = work_list.pop() {
+            if reachable.insert(block_id) {
+                work_list.extend(self.successors(block_id).iter().cloned());
+            }
+        }
+        reachable
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use stackless_bytecode_analysis::{
+    borrow_analysis::{BorrowAnalysis, BorrowEdge, BorrowNode},
+    livevar_analysis::LiveVarAnalysis,
+    reaching_def_analysis::{Definition, ReachingDefAnalysis},
+    stackless_control_flow_graph::StacklessControlFlowGraph,
+};
+use stackless_bytecode_generator::{
+    stackless_bytecode::StacklessBytecode::*, stackless_bytecode_generator::StacklessFunction,
+};
+use std::collections::BTreeSet;
+use vm::file_format::{FieldDefinitionIndex, SignatureToken, StructHandleIndex};
+
+fn set<T: Ord + Clone>(elems: &[T]) -> BTreeSet<T> {
+    elems.iter().cloned().collect()
+}
+
+#[test]
+fn cfg_of_loop() {
+    // 0: t1 = copy(l0)
+    // 1: if (t1) goto 4
+    // 2: l0 = t2
+    // 3: goto 0
+    // 4: return
+    let code = vec![
+        CopyLoc(1, 0),
+        BrTrue(4, 1),
+        StLoc(0, 2),
+        Branch(0),
+        Ret(vec![]),
+    ];
+    let cfg = StacklessControlFlowGraph::new(&code);
+    assert_eq!(cfg.blocks(), vec![0, 2, 4]);
+    assert_eq!(cfg.successors(0), &[4, 2]);
+    assert_eq!(cfg.successors(2), &[0]);
+    assert_eq!(cfg.predecessors(0), &[2]);
+    assert_eq!(cfg.exit_blocks(), vec![4]);
+    assert_eq!(cfg.instr_indexes(2).collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(cfg.reachable_blocks(), set(&[0, 2, 4]));
+}
+
+#[test]
+fn cfg_of_unreachable_code() {
+    let code = vec![LdTrue(0), Ret(vec![0]), LdFalse(1), Ret(vec![1])];
+    let cfg = StacklessControlFlowGraph::new(&code);
+    assert_eq!(cfg.blocks(), vec![0, 2]);
+    assert_eq!(cfg.reachable_blocks(), set(&[0]));
+}
+
+#[test]
+fn cfg_of_empty_function() {
+    let cfg = StacklessControlFlowGraph::new(&[]);
+    assert_eq!(cfg.blocks(), vec![0]);
+    assert_eq!(cfg.instr_indexes(0).count(), 0);
+}
+
+#[test]
+fn live_vars_in_loop() {
+    // 0: t2 = copy(l0)
+    // 1: if (t2) goto 5
+    // 2: t3 = copy(l1)
+    // 3: l0 = t3
+    // 4: goto 0
+    // 5: return
+    let code = vec![
+        CopyLoc(2, 0),
+        BrTrue(5, 2),
+        CopyLoc(3, 1),
+        StLoc(0, 3),
+        Branch(0),
+        Ret(vec![]),
+    ];
+    let live_vars = LiveVarAnalysis::analyze(&code);
+    assert_eq!(live_vars[&0].before.live_vars, set(&[0, 1]));
+    assert_eq!(live_vars[&1].before.live_vars, set(&[1, 2]));
+    assert_eq!(live_vars[&3].before.live_vars, set(&[1, 3]));
+    // l0 is read again at the loop head
+    assert_eq!(live_vars[&3].after.live_vars, set(&[0, 1]));
+    assert!(live_vars[&5].after.live_vars.is_empty());
+}
+
+#[test]
+fn reaching_defs_join_at_merge() {
+    // 0: t1 = copy(l0)
+    // 1: if (t1) goto 4
+    // 2: t2 = 1
+    // 3: l0 = t2
+    // 4: t3 = move(l0)
+    // 5: return t3
+    let code = vec![
+        CopyLoc(1, 0),
+        BrTrue(4, 1),
+        LdConst(2, 1),
+        StLoc(0, 2),
+        MoveLoc(3, 0),
+        Ret(vec![3]),
+    ];
+    let reaching_defs = ReachingDefAnalysis::analyze(&code, 1);
+    assert_eq!(
+        reaching_defs[&0].before.defs[&0],
+        set(&[Definition::Formal])
+    );
+    assert_eq!(
+        reaching_defs[&4].before.defs[&0],
+        set(&[Definition::Formal, Definition::Offset(3)])
+    );
+    assert_eq!(
+        reaching_defs[&4].after.defs[&3],
+        set(&[Definition::Offset(4)])
+    );
+}
+
+#[test]
+fn borrows_through_fields_and_locals() {
+    let struct_type = SignatureToken::Struct(StructHandleIndex::new(0), vec![]);
+    let field = FieldDefinitionIndex::new(0);
+    // l0: &mut S (formal), l1: S, l2: &mut u64
+    // 0: t3 = move(l0)
+    // 1: t4 = &t3.f
+    // 2: l2 = t4
+    // 3: t5 = &mut l1
+    // 4: t6 = &t5.f
+    // 5: return
+    let function = StacklessFunction {
+        local_types: vec![
+            SignatureToken::MutableReference(Box::new(struct_type.clone())),
+            struct_type.clone(),
+            SignatureToken::MutableReference(Box::new(SignatureToken::U64)),
+            SignatureToken::MutableReference(Box::new(struct_type.clone())),
+            SignatureToken::MutableReference(Box::new(SignatureToken::U64)),
+            SignatureToken::MutableReference(Box::new(struct_type)),
+            SignatureToken::MutableReference(Box::new(SignatureToken::U64)),
+        ],
+        code: vec![
+            MoveLoc(3, 0),
+            BorrowField(4, 3, field),
+            StLoc(2, 4),
+            BorrowLoc(5, 1),
+            BorrowField(6, 5, field),
+            Ret(vec![]),
+        ],
+    };
+    let borrows = BorrowAnalysis::analyze(&function, 1);
+    let graph = &borrows[&5].before;
+    assert_eq!(
+        graph.borrows_of(2),
+        set(&[BorrowEdge {
+            root: BorrowNode::Formal(0),
+            path: vec![field],
+        }])
+    );
+    assert_eq!(
+        graph.borrows_of(6),
+        set(&[BorrowEdge {
+            root: BorrowNode::Local(1),
+            path: vec![field],
+        }])
+    );
+    assert_eq!(graph.borrowed_by(BorrowNode::Local(1)), set(&[5, 6]));
+    // moving and storing references transfers their borrows
+    assert!(graph.borrows_of(0).is_empty());
+    assert!(graph.borrows_of(4).is_empty());
+}
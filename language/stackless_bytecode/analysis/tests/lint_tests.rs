@@ -0,0 +1,121 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use ir_to_bytecode::{compiler::compile_module, parser::parse_module};
+use stackless_bytecode_analysis::lint::{lint_module, LintKind};
+use stdlib::stdlib_modules;
+use types::account_address::AccountAddress;
+
+fn lint(code: &str) -> Vec<LintKind> {
+    let definition = parse_module(code).unwrap();
+    let module = compile_module(
+        AccountAddress::default(),
+        definition.clone(),
+        stdlib_modules(),
+    )
+    .unwrap();
+    lint_module(&module, Some(&definition))
+        .into_iter()
+        .map(|warning| warning.kind)
+        .collect()
+}
+
+#[test]
+fn clean_module_has_no_warnings() {
+    let lints = lint(
+        "
+        module M {
+            public sum(n: u64): u64 {
+                let i: u64;
+                let s: u64;
+                i = 0;
+                s = 0;
+                while (copy(i) < copy(n)) {
+                    i = move(i) + 1;
+                    s = move(s) + copy(i);
+                }
+                return move(s);
+            }
+        }
+        ",
+    );
+    assert_eq!(lints, vec![]);
+}
+
+#[test]
+fn dead_store_is_reported() {
+    let lints = lint(
+        "
+        module M {
+            public f(x: u64): u64 {
+                let y: u64;
+                y = copy(x);
+                y = move(x) + 1;
+                return move(y);
+            }
+        }
+        ",
+    );
+    assert_eq!(lints.len(), 1);
+    match &lints[0] {
+        LintKind::DeadStore { local, .. } => assert_eq!(local, "y"),
+        lint => panic!("unexpected lint {:?}", lint),
+    }
+}
+
+#[test]
+fn unused_local_is_reported() {
+    let lints = lint(
+        "
+        module M {
+            public f(): u64 {
+                let unused: u64;
+                unused = 1;
+                return 0;
+            }
+        }
+        ",
+    );
+    assert_eq!(
+        lints,
+        vec![LintKind::UnusedLocal {
+            local: "unused".to_string()
+        }]
+    );
+}
+
+#[test]
+fn unreachable_code_is_reported() {
+    let lints = lint(
+        "
+        module M {
+            public f(): u64 {
+                return 0;
+                return 1;
+            }
+        }
+        ",
+    );
+    assert_eq!(lints.len(), 1);
+    match &lints[0] {
+        LintKind::UnreachableCode { .. } => (),
+        lint => panic!("unexpected lint {:?}", lint),
+    }
+}
+
+#[test]
+fn loop_back_edge_after_return_is_not_reported() {
+    let lints = lint(
+        "
+        module M {
+            public f(b: bool): u64 {
+                while (copy(b)) {
+                    return 1;
+                }
+                return 0;
+            }
+        }
+        ",
+    );
+    assert_eq!(lints, vec![]);
+}
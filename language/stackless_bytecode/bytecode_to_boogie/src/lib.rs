@@ -1,4 +1,5 @@
 //! Translates bytecode to Boogie.
 
 pub mod bytecode_function_generator;
+pub mod spec_translator;
 pub mod translator;
@@ -1,7 +1,7 @@
 use bytecode_to_boogie::translator::BoogieTranslator;
 use bytecode_verifier::VerifiedModule;
 use ir_to_bytecode::{
-    compiler::{compile_module, compile_program},
+    compiler::{compile_module_with_spec, compile_program, ModuleSpec},
     parser::{parse_module, parse_program},
 };
 use std::{
@@ -9,12 +9,16 @@ use std::{
     fs::{self, File},
     io::prelude::*,
 };
-use stdlib::stdlib_modules;
+use stdlib::{stdlib::module_defs, stdlib_modules};
 use types::account_address::AccountAddress;
 
 // mod translator;
-fn compile_files(file_names: Vec<String>) -> Vec<VerifiedModule> {
+fn compile_files(file_names: Vec<String>) -> (Vec<VerifiedModule>, Vec<ModuleSpec>) {
     let mut verified_modules = stdlib_modules().to_vec();
+    let mut specs: Vec<_> = module_defs()
+        .iter()
+        .map(|module| ModuleSpec::new(module))
+        .collect();
     let files_len = file_names.len();
     let dep_files = &file_names[0..files_len - 1];
 
@@ -24,8 +28,8 @@ fn compile_files(file_names: Vec<String>) -> Vec<VerifiedModule> {
     for file_name in dep_files {
         let code = fs::read_to_string(file_name).unwrap();
         let module = parse_module(&code).unwrap();
-        let compiled_module =
-            compile_module(address, module, &verified_modules).expect("module failed to compile");
+        let (compiled_module, spec) = compile_module_with_spec(address, module, &verified_modules)
+            .expect("module failed to compile");
         let verified_module_res = VerifiedModule::new(compiled_module);
 
         match verified_module_res {
@@ -34,12 +38,16 @@ fn compile_files(file_names: Vec<String>) -> Vec<VerifiedModule> {
             }
             Ok(verified_module) => {
                 verified_modules.push(verified_module);
+                specs.push(spec);
             }
         }
     }
     let main_code = fs::read_to_string(main_file).unwrap();
     let program = parse_program(&main_code).unwrap();
     let address = AccountAddress::default();
+    specs.extend(program.modules.iter().map(ModuleSpec::new));
+    // the script has no specification
+    specs.push(ModuleSpec::default());
     let compiled_program =
         compile_program(address, program, &verified_modules).expect("program failed to compile");
     let mut main_modules = compiled_program.modules;
@@ -57,22 +65,22 @@ fn compile_files(file_names: Vec<String>) -> Vec<VerifiedModule> {
         }
     }
 
-    verified_modules
+    (verified_modules, specs)
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let file_names = &args[1..];
     // read files and compile into compiled modules
-    let modules = compile_files(file_names.to_vec());
+    let (modules, specs) = compile_files(file_names.to_vec());
 
-    let mut ts = BoogieTranslator::new(&modules);
+    let mut ts = BoogieTranslator::new(&modules, &specs);
     let mut res = String::new();
 
     // handwritten boogie code
     let written_code = fs::read_to_string("src/bytecode_instrs.bpl").unwrap();
     res.push_str(&written_code);
-    res.push_str(&ts.translate());
+    res.push_str(&ts.translate().expect("specification failed to translate"));
     let mut f = File::create("output.bpl").expect("Unable to create file");
 
     // write resulting code into output.bpl
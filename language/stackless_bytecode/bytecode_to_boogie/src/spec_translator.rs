@@ -0,0 +1,411 @@
+//! This module translates the specifications of functions and structs to Boogie pre- and
+//! postconditions.
+//!
+//! Every specification expression is translated to a Boogie expression of type `Value`. Values
+//! stored at locations of reference type, like reference formals, are dereferenced implicitly.
+//! Specifications that do not type check, like field accesses on integers, are reported as errors.
+
+use crate::translator::{struct_name_from_handle_index, BoogieTranslator};
+use bytecode_verifier::VerifiedModule;
+use failure::prelude::*;
+use ir_to_bytecode::{
+    compiler::FunctionSpec,
+    parser::ast::{
+        BinOp, Condition, CopyableVal, Field, ModuleName, QualifiedStructIdent, SpecExp,
+        StorageLocation,
+    },
+};
+use num::{BigInt, Num};
+use std::collections::BTreeMap;
+use types::{account_address::AccountAddress, identifier::IdentStr};
+use vm::{
+    access::ModuleAccess,
+    file_format::{ModuleHandleIndex, SignatureToken},
+    views::{StructDefinitionView, StructHandleView},
+};
+
+/// The state a specification expression is evaluated in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    /// When the function is called.
+    Pre,
+    /// When the function returns.
+    Post,
+}
+
+/// The type of a specification expression. `token` is relative to `module`.
+#[derive(Clone)]
+struct SpecType<'a> {
+    module: &'a VerifiedModule,
+    token: SignatureToken,
+}
+
+/// A name specification expressions can refer to.
+struct Binding<'a> {
+    /// The Boogie expression for the value when the function is called.
+    pre: String,
+    /// The Boogie expression for the value when the function returns, if it can differ.
+    post: Option<String>,
+    type_: SpecType<'a>,
+}
+
+pub struct SpecTranslator<'a> {
+    translator: &'a BoogieTranslator,
+    module: &'a VerifiedModule,
+    bindings: BTreeMap<String, Binding<'a>>,
+    returns: Vec<SpecType<'a>>,
+}
+
+impl<'a> SpecTranslator<'a> {
+    /// Creates a translator for the conditions of the function definition `idx` of `module`.
+    pub fn for_function(
+        translator: &'a BoogieTranslator,
+        module: &'a VerifiedModule,
+        idx: usize,
+        spec: &FunctionSpec,
+    ) -> Self {
+        let function_def = &module.function_defs()[idx];
+        let function_handle = module.function_handle_at(function_def.function);
+        let signature = module.function_signature_at(function_handle.signature);
+        let bindings = spec
+            .formals
+            .iter()
+            .zip(signature.arg_types.iter())
+            .enumerate()
+            .map(|(i, (name, token))| {
+                // mutable references are returned by the procedure with their updated value
+                let post = if token.is_mutable_reference() {
+                    Some(format!("t{}", i))
+                } else {
+                    None
+                };
+                let binding = Binding {
+                    pre: format!("arg{}", i),
+                    post,
+                    type_: SpecType {
+                        module,
+                        token: token.clone(),
+                    },
+                };
+                (name.clone(), binding)
+            })
+            .collect();
+        let returns = signature
+            .return_types
+            .iter()
+            .map(|token| SpecType {
+                module,
+                token: token.clone(),
+            })
+            .collect();
+        Self {
+            translator,
+            module,
+            bindings,
+            returns,
+        }
+    }
+
+    /// Creates a translator for the invariants of the struct definition `idx` of `module`, where
+    /// `value` is the Boogie expression for the instance of the struct.
+    pub fn for_struct(
+        translator: &'a BoogieTranslator,
+        module: &'a VerifiedModule,
+        idx: usize,
+        value: &str,
+    ) -> Self {
+        let struct_def = &module.struct_defs()[idx];
+        let struct_name = struct_name_from_handle_index(module, struct_def.struct_handle);
+        let bindings = StructDefinitionView::new(module, struct_def)
+            .fields()
+            .into_iter()
+            .flatten()
+            .map(|field| {
+                let field_name = field.name().to_string();
+                let binding = Binding {
+                    pre: format!("m#Map({})[Field({}_{})]", value, struct_name, field_name),
+                    post: None,
+                    type_: SpecType {
+                        module,
+                        token: field.type_signature().token().as_inner().clone(),
+                    },
+                };
+                (field_name, binding)
+            })
+            .collect();
+        Self {
+            translator,
+            module,
+            bindings,
+            returns: vec![],
+        }
+    }
+
+    /// Translates the conditions of a function to Boogie `requires` and `ensures` clauses.
+    pub fn translate_conditions(&self, conditions: &[Condition]) -> Result<Vec<String>> {
+        let mut clauses = vec![];
+        let mut aborts_if = vec![];
+        for condition in conditions {
+            match condition {
+                Condition::Requires(exp) => clauses.push(format!(
+                    "requires b#Boolean({});",
+                    self.translate_exp(exp, State::Pre)?
+                )),
+                Condition::Ensures(exp) => clauses.push(format!(
+                    "ensures !abort_flag ==> b#Boolean({});",
+                    self.translate_exp(exp, State::Post)?
+                )),
+                Condition::AbortsIf(exp) => aborts_if.push(format!(
+                    "b#Boolean({})",
+                    self.translate_exp(exp, State::Pre)?
+                )),
+            }
+        }
+        if !aborts_if.is_empty() {
+            clauses.push(format!(
+                "ensures abort_flag <==> old({});",
+                aborts_if.join(" || ")
+            ));
+        }
+        Ok(clauses)
+    }
+
+    /// Translates the invariants of a struct to a Boogie expression of type `bool`.
+    pub fn translate_invariants(&self, invariants: &[SpecExp]) -> Result<String> {
+        Ok(invariants
+            .iter()
+            .map(|exp| {
+                Ok(format!(
+                    "b#Boolean({})",
+                    self.translate_exp(exp, State::Pre)?
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join(" && "))
+    }
+
+    fn translate_exp(&self, exp: &SpecExp, state: State) -> Result<String> {
+        Ok(match exp {
+            SpecExp::Constant(val) => match val {
+                CopyableVal::U64(i) => format!("Integer({})", i),
+                CopyableVal::Bool(b) => format!("Boolean({})", b),
+                CopyableVal::Address(address) => format!("Address({})", address_to_int(address)),
+                CopyableVal::ByteArray(_) | CopyableVal::String(_) => {
+                    bail!("unsupported constant {} in specification", val)
+                }
+            },
+            SpecExp::StorageLocation(location) => self.translate_value(location, state)?,
+            SpecExp::Dereference(location) => {
+                let (value, type_) = self.translate_location(location, state)?;
+                match type_.token {
+                    SignatureToken::Reference(_) | SignatureToken::MutableReference(_) => {
+                        format!("v#Reference({})", value)
+                    }
+                    _ => bail!(
+                        "cannot dereference non-reference {} in specification",
+                        location
+                    ),
+                }
+            }
+            SpecExp::GlobalExists { type_, address, .. } => {
+                let (struct_name, _) = self.resolve_struct(type_)?;
+                format!(
+                    "Boolean(domain#ResourceStore(rs_{})[a#Address({})])",
+                    struct_name,
+                    self.translate_value(address, state)?
+                )
+            }
+            SpecExp::Not(exp) => {
+                format!("Boolean(!b#Boolean({}))", self.translate_exp(exp, state)?)
+            }
+            SpecExp::Binop(exp1, op, exp2) => {
+                let exp1 = self.translate_exp(exp1, state)?;
+                let exp2 = self.translate_exp(exp2, state)?;
+                match op {
+                    BinOp::Add => format!("Integer(i#Integer({}) + i#Integer({}))", exp1, exp2),
+                    BinOp::Sub => format!("Integer(i#Integer({}) - i#Integer({}))", exp1, exp2),
+                    BinOp::Mul => format!("Integer(i#Integer({}) * i#Integer({}))", exp1, exp2),
+                    BinOp::Div => format!("Integer(i#Integer({}) div i#Integer({}))", exp1, exp2),
+                    BinOp::Mod => format!("Integer(i#Integer({}) mod i#Integer({}))", exp1, exp2),
+                    BinOp::Lt => format!("Boolean(i#Integer({}) < i#Integer({}))", exp1, exp2),
+                    BinOp::Gt => format!("Boolean(i#Integer({}) > i#Integer({}))", exp1, exp2),
+                    BinOp::Le => format!("Boolean(i#Integer({}) <= i#Integer({}))", exp1, exp2),
+                    BinOp::Ge => format!("Boolean(i#Integer({}) >= i#Integer({}))", exp1, exp2),
+                    BinOp::Eq => format!("Boolean({} == {})", exp1, exp2),
+                    BinOp::Neq => format!("Boolean({} != {})", exp1, exp2),
+                    BinOp::And => format!("Boolean(b#Boolean({}) && b#Boolean({}))", exp1, exp2),
+                    BinOp::Or => format!("Boolean(b#Boolean({}) || b#Boolean({}))", exp1, exp2),
+                    BinOp::BitOr | BinOp::BitAnd | BinOp::Xor => {
+                        bail!("bit operation {} not supported in specification", op)
+                    }
+                }
+            }
+            // Boogie's `old` refers to the globals when the procedure was called, formals are
+            // handled by evaluating the expression in the pre state
+            SpecExp::Old(exp) => format!("old({})", self.translate_exp(exp, State::Pre)?),
+        })
+    }
+
+    /// Translates the value stored at `location`, dereferencing it if it is a reference.
+    fn translate_value(&self, location: &StorageLocation, state: State) -> Result<String> {
+        let (value, type_) = self.translate_location(location, state)?;
+        Ok(deref(value, type_).0)
+    }
+
+    fn translate_location(
+        &self,
+        location: &StorageLocation,
+        state: State,
+    ) -> Result<(String, SpecType<'a>)> {
+        Ok(match location {
+            StorageLocation::Formal(name) => {
+                let binding = self
+                    .bindings
+                    .get(name)
+                    .ok_or_else(|| format_err!("unknown name {} in specification", name))?;
+                let value = match (state, &binding.post) {
+                    (State::Post, Some(post)) => post.clone(),
+                    _ => binding.pre.clone(),
+                };
+                (value, binding.type_.clone())
+            }
+            StorageLocation::Ret(i) => {
+                ensure!(
+                    state == State::Post,
+                    "return values can only be used in ensures"
+                );
+                let type_ = self
+                    .returns
+                    .get(*i)
+                    .ok_or_else(|| format_err!("function has no return value {}", i))?;
+                (format!("ret{}", i), type_.clone())
+            }
+            StorageLocation::TxnSenderAddress => (
+                "Address(sender#Transaction_cons(txn))".into(),
+                SpecType {
+                    module: self.module,
+                    token: SignatureToken::Address,
+                },
+            ),
+            StorageLocation::Address(address) => (
+                format!("Address({})", address_to_int(address)),
+                SpecType {
+                    module: self.module,
+                    token: SignatureToken::Address,
+                },
+            ),
+            StorageLocation::GlobalResource { type_, address, .. } => {
+                let (struct_name, struct_type) = self.resolve_struct(type_)?;
+                (
+                    format!(
+                        "contents#ResourceStore(rs_{})[a#Address({})]",
+                        struct_name,
+                        self.translate_value(address, state)?
+                    ),
+                    struct_type,
+                )
+            }
+            StorageLocation::AccessPath { base, fields } => {
+                let (mut value, mut type_) = self.translate_location(base, state)?;
+                for field in fields {
+                    let (struct_value, struct_type) = deref(value, type_);
+                    let (field_name, field_type) = self.resolve_field(&struct_type, field)?;
+                    value = format!("m#Map({})[Field({})]", struct_value, field_name);
+                    type_ = field_type;
+                }
+                (value, type_)
+            }
+        })
+    }
+
+    /// Returns the Boogie name of the struct `ident` and its type.
+    fn resolve_struct(&self, ident: &QualifiedStructIdent) -> Result<(String, SpecType<'a>)> {
+        let module_name = if ident.module().as_inner() == ModuleName::self_name() {
+            self.module
+                .identifier_at(self.module.module_handle_at(ModuleHandleIndex::new(0)).name)
+        } else {
+            ident.module().as_inner()
+        };
+        let struct_name = format!("{}_{}", module_name, ident.name());
+        let def_module = self.defining_module(&struct_name, module_name)?;
+        let def_idx = self.translator.struct_defs[&struct_name];
+        let token = SignatureToken::Struct(def_module.struct_defs()[def_idx].struct_handle, vec![]);
+        Ok((
+            struct_name,
+            SpecType {
+                module: def_module,
+                token,
+            },
+        ))
+    }
+
+    /// Returns the Boogie name of `field` of the struct of type `type_` and the type of the field.
+    fn resolve_field(&self, type_: &SpecType<'a>, field: &Field) -> Result<(String, SpecType<'a>)> {
+        let handle_idx = match &type_.token {
+            SignatureToken::Struct(idx, _) => *idx,
+            token => bail!(
+                "cannot access field {} of {:?} in specification",
+                field,
+                token
+            ),
+        };
+        let struct_name = struct_name_from_handle_index(type_.module, handle_idx);
+        let struct_handle = type_.module.struct_handle_at(handle_idx);
+        let module_name = type_.module.identifier_at(
+            StructHandleView::new(type_.module, struct_handle)
+                .module_handle()
+                .name,
+        );
+        let def_module = self.defining_module(&struct_name, module_name)?;
+        let struct_def = &def_module.struct_defs()[self.translator.struct_defs[&struct_name]];
+        let field_def = StructDefinitionView::new(def_module, struct_def)
+            .fields()
+            .into_iter()
+            .flatten()
+            .find(|field_def| field_def.name() == field.name())
+            .ok_or_else(|| format_err!("struct {} has no field {}", struct_name, field))?;
+        Ok((
+            format!("{}_{}", struct_name, field),
+            SpecType {
+                module: def_module,
+                token: field_def.type_signature().token().as_inner().clone(),
+            },
+        ))
+    }
+
+    fn defining_module(
+        &self,
+        struct_name: &str,
+        module_name: &IdentStr,
+    ) -> Result<&'a VerifiedModule> {
+        ensure!(
+            self.translator.struct_defs.contains_key(struct_name),
+            "no struct named {}",
+            struct_name
+        );
+        let module_idx = self
+            .translator
+            .module_name_to_idx
+            .get(module_name)
+            .ok_or_else(|| format_err!("no module named {}", module_name))?;
+        Ok(&self.translator.modules[*module_idx])
+    }
+}
+
+/// Dereferences `value` if it is a reference.
+fn deref(value: String, type_: SpecType) -> (String, SpecType) {
+    match type_.token {
+        SignatureToken::Reference(token) | SignatureToken::MutableReference(token) => (
+            format!("v#Reference({})", value),
+            SpecType {
+                module: type_.module,
+                token: *token,
+            },
+        ),
+        _ => (value, type_),
+    }
+}
+
+fn address_to_int(address: &AccountAddress) -> BigInt {
+    BigInt::from_str_radix(&format!("{:x}", address), 16).unwrap()
+}
@@ -1,6 +1,9 @@
 //! This module translates the bytecode of a module to Boogie code.
 
+use crate::spec_translator::SpecTranslator;
 use bytecode_verifier::VerifiedModule;
+use failure::prelude::*;
+use ir_to_bytecode::compiler::ModuleSpec;
 use num::{BigInt, Num};
 use stackless_bytecode_generator::{
     stackless_bytecode::StacklessBytecode::{self, *},
@@ -26,16 +29,22 @@ pub struct BoogieTranslator {
     pub struct_defs: BTreeMap<String, usize>,
     pub max_struct_depth: usize,
     pub module_name_to_idx: BTreeMap<Identifier, usize>,
+    pub specs: Vec<ModuleSpec>,
 }
 
 pub struct ModuleTranslator<'a> {
+    pub translator: &'a BoogieTranslator,
     pub module: &'a VerifiedModule,
+    pub spec: &'a ModuleSpec,
+    /// The invariants of the resources of the module, as Boogie clauses that every procedure
+    /// requires and ensures.
+    pub invariant_clauses: Vec<String>,
     pub stackless_bytecode: Vec<StacklessFunction>,
     pub all_type_strs: BTreeSet<String>,
 }
 
 impl BoogieTranslator {
-    pub fn new(modules: &[VerifiedModule]) -> Self {
+    pub fn new(modules: &[VerifiedModule], specs: &[ModuleSpec]) -> Self {
         let mut struct_defs: BTreeMap<String, usize> = BTreeMap::new();
         let mut module_name_to_idx: BTreeMap<Identifier, usize> = BTreeMap::new();
         for (module_idx, module) in modules.iter().enumerate() {
@@ -58,10 +67,11 @@ impl BoogieTranslator {
             struct_defs,
             max_struct_depth: 0,
             module_name_to_idx,
+            specs: specs.to_vec(),
         }
     }
 
-    pub fn translate(&mut self) -> String {
+    pub fn translate(&mut self) -> Result<String> {
         let mut res = String::from("\n\n// everything below is auto generated\n\n");
         // generate names and struct specific functions for all structs
         res.push_str(&self.emit_struct_code());
@@ -72,11 +82,13 @@ impl BoogieTranslator {
         // generate IsPrefix and UpdateValue to the max depth
         res.push_str(&self.emit_stratified_functions());
 
-        for module in self.modules.iter() {
-            let mut mt = ModuleTranslator::new(&module);
-            res.push_str(&mt.translate());
+        let default_spec = ModuleSpec::default();
+        for (idx, module) in self.modules.iter().enumerate() {
+            let spec = self.specs.get(idx).unwrap_or(&default_spec);
+            let mut mt = ModuleTranslator::new(self, &module, spec)?;
+            res.push_str(&mt.translate()?);
         }
-        res
+        Ok(res)
     }
 
     pub fn generate_local_names(&self) -> String {
@@ -167,41 +179,84 @@ impl BoogieTranslator {
 }
 
 impl<'a> ModuleTranslator<'a> {
-    pub fn new(module: &'a VerifiedModule) -> Self {
+    pub fn new(
+        translator: &'a BoogieTranslator,
+        module: &'a VerifiedModule,
+        spec: &'a ModuleSpec,
+    ) -> Result<Self> {
         let stackless_bytecode = StacklessModuleGenerator::new(module.as_inner()).generate_module();
         let mut all_type_strs = BTreeSet::new();
-        for struct_def in module.struct_defs().iter() {
+        let mut invariant_clauses = vec![];
+        for (def_idx, struct_def) in module.struct_defs().iter().enumerate() {
             let struct_name = struct_name_from_handle_index(module, struct_def.struct_handle);
+            let invariants = spec
+                .structs
+                .get(def_idx)
+                .map(|struct_spec| struct_spec.invariants.as_slice())
+                .unwrap_or(&[]);
+            if !invariants.is_empty() {
+                let resource = format!("contents#ResourceStore(rs_{})[a]", struct_name);
+                let invariant = SpecTranslator::for_struct(translator, module, def_idx, &resource)
+                    .translate_invariants(invariants)?;
+                let clause = format!(
+                    "(forall a: Address :: domain#ResourceStore(rs_{})[a] ==> {})",
+                    struct_name, invariant
+                );
+                invariant_clauses.push(format!("requires {};", clause));
+                invariant_clauses.push(format!("ensures !abort_flag ==> {};", clause));
+            }
             all_type_strs.insert(struct_name);
         }
-        Self {
+        Ok(Self {
+            translator,
             module,
+            spec,
+            invariant_clauses,
             stackless_bytecode,
             all_type_strs,
-        }
+        })
     }
 
-    pub fn translate(&mut self) -> String {
+    pub fn translate(&mut self) -> Result<String> {
         let mut res = String::new();
         // translation of stackless bytecode
         for (idx, function_def) in self.module.function_defs().iter().enumerate() {
             if function_def.is_native() {
                 res.push_str(&self.generate_function_sig(idx, false, &None));
-                res.push_str(";\n");
+                res.push_str(";");
+                res.push_str(&self.generate_function_spec(idx)?);
+                res.push_str("\n");
                 continue;
             }
-            res.push_str(&self.translate_function(idx));
+            res.push_str(&self.translate_function(idx)?);
         }
-        res
+        Ok(res)
     }
 
-    pub fn translate_function(&self, idx: usize) -> String {
+    pub fn translate_function(&self, idx: usize) -> Result<String> {
         let mut res = String::new();
         // generate function signature
         res.push_str(&self.generate_function_sig(idx, false, &None)); // no inline
-                                                                      // generate function body
+        res.push_str(&self.generate_function_spec(idx)?);
+        // generate function body
         res.push_str(&self.generate_function_body(idx, false, &None));
-        res
+        Ok(res)
+    }
+
+    /// Generates the `requires` and `ensures` clauses of a procedure from the specification of the
+    /// function and the invariants of the resources of the module.
+    pub fn generate_function_spec(&self, idx: usize) -> Result<String> {
+        let mut clauses = self.invariant_clauses.clone();
+        if let Some(function_spec) = self.spec.functions.get(idx) {
+            clauses.extend(
+                SpecTranslator::for_function(self.translator, self.module, idx, function_spec)
+                    .translate_conditions(&function_spec.conditions)?,
+            );
+        }
+        Ok(clauses
+            .iter()
+            .map(|clause| format!("\n{}", clause))
+            .collect())
     }
 
     pub fn translate_bytecode(
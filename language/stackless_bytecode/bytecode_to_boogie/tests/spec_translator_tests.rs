@@ -0,0 +1,131 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use bytecode_to_boogie::translator::BoogieTranslator;
+use bytecode_verifier::VerifiedModule;
+use failure::prelude::*;
+use ir_to_bytecode::{
+    compiler::{compile_module_with_spec, ModuleSpec},
+    parser::parse_module,
+};
+use stdlib::{stdlib::module_defs, stdlib_modules};
+use types::account_address::AccountAddress;
+
+/// Compiles `code` against the stdlib and returns the Boogie translation of all the modules.
+fn try_translate(code: &str) -> Result<String> {
+    let mut modules = stdlib_modules().to_vec();
+    let mut specs: Vec<_> = module_defs().iter().map(ModuleSpec::new).collect();
+    let (compiled_module, spec) = compile_module_with_spec(
+        AccountAddress::default(),
+        parse_module(code).unwrap(),
+        &modules,
+    )
+    .unwrap();
+    modules.push(VerifiedModule::new(compiled_module).unwrap());
+    specs.push(spec);
+    let mut translator = BoogieTranslator::new(&modules, &specs);
+    translator.translate()
+}
+
+fn translate(code: &str) -> String {
+    try_translate(code).unwrap()
+}
+
+#[test]
+fn translate_conditions() {
+    let boogie = translate(
+        "
+        module Counter {
+            resource T { value: u64 }
+
+            public increment(counter: &mut Self.T, amount: u64): u64
+            requires amount > 0
+            aborts_if counter.value + amount > 18446744073709551615
+            ensures RET == counter.value
+            {
+                let value: u64;
+                value = *(&copy(counter).value) + move(amount);
+                *(&mut move(counter).value) = copy(value);
+                return move(value);
+            }
+        }
+        ",
+    );
+    let value = "m#Map(v#Reference(arg0))[Field(Counter_T_value)]";
+    let new_value = "m#Map(v#Reference(t0))[Field(Counter_T_value)]";
+    let expected_clauses = vec![
+        "requires b#Boolean(Boolean(i#Integer(arg1) > i#Integer(Integer(0))));".to_string(),
+        format!(
+            "ensures !abort_flag ==> b#Boolean(Boolean(ret0 == {}));",
+            new_value
+        ),
+        format!(
+            "ensures abort_flag <==> old(b#Boolean(Boolean(i#Integer(Integer(i#Integer({}) + \
+             i#Integer(arg1))) > i#Integer(Integer(18446744073709551615)))));",
+            value
+        ),
+    ];
+    let procedure = boogie
+        .split("procedure ")
+        .find(|procedure| procedure.starts_with("Counter_increment "))
+        .expect("no procedure for Counter.increment");
+    for clause in expected_clauses {
+        assert!(
+            procedure.contains(&format!("\n{}", clause)),
+            "missing clause {} in:\n{}",
+            clause,
+            procedure
+        );
+    }
+}
+
+#[test]
+fn translate_invariants() {
+    let boogie = translate(
+        "
+        module Bounded {
+            resource T {
+                value: u64,
+                limit: u64,
+                invariant value <= limit
+            }
+
+            public zero(): u64 {
+                return 0;
+            }
+        }
+        ",
+    );
+    let resource = "contents#ResourceStore(rs_Bounded_T)[a]";
+    let invariant = format!(
+        "(forall a: Address :: domain#ResourceStore(rs_Bounded_T)[a] ==> \
+         b#Boolean(Boolean(i#Integer(m#Map({})[Field(Bounded_T_value)]) <= \
+         i#Integer(m#Map({})[Field(Bounded_T_limit)]))))",
+        resource, resource
+    );
+    let procedure = boogie
+        .split("procedure ")
+        .find(|procedure| procedure.starts_with("Bounded_zero "))
+        .expect("no procedure for Bounded.zero");
+    assert!(procedure.contains(&format!("\nrequires {};", invariant)));
+    assert!(procedure.contains(&format!("\nensures !abort_flag ==> {};", invariant)));
+}
+
+#[test]
+fn reject_ill_typed_specs() {
+    for condition in &["ensures x.f == 0", "requires RET == x", "ensures *x == 0"] {
+        let code = format!(
+            "
+            module M {{
+                public f(x: u64): u64
+                {}
+                {{
+                    return move(x);
+                }}
+            }}
+            ",
+            condition
+        );
+        assert!(try_translate(&code).is_err(), "{}", condition);
+    }
+}
@@ -3,7 +3,7 @@ use vm::file_format::{
     LocalIndex, StructDefinitionIndex, UserStringIndex,
 };
 
-type TempIndex = usize;
+pub type TempIndex = usize;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StacklessBytecode {
@@ -69,3 +69,169 @@ pub enum StacklessBytecode {
     Abort(TempIndex), // abort t
     NoOp,
 }
+
+impl StacklessBytecode {
+    /// Return true if this instruction always branches, returns or aborts
+    pub fn is_unconditional_branch(&self) -> bool {
+        match self {
+            StacklessBytecode::Ret(_)
+            | StacklessBytecode::Abort(_)
+            | StacklessBytecode::Branch(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Return true if the branching behavior of this instruction depends on a runtime value
+    pub fn is_conditional_branch(&self) -> bool {
+        match self {
+            StacklessBytecode::BrTrue(_, _) | StacklessBytecode::BrFalse(_, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if this instruction is either a conditional or an unconditional branch
+    pub fn is_branch(&self) -> bool {
+        self.is_conditional_branch() || self.is_unconditional_branch()
+    }
+
+    /// Returns the offset that this instruction branches to, if any.
+    /// Note that return and abort are branch instructions, but have no offset.
+    pub fn offset(&self) -> Option<&CodeOffset> {
+        match self {
+            StacklessBytecode::Branch(offset)
+            | StacklessBytecode::BrTrue(offset, _)
+            | StacklessBytecode::BrFalse(offset, _) => Some(offset),
+            _ => None,
+        }
+    }
+
+    /// Return the successor offsets of the instruction at `pc`.
+    pub fn get_successors(pc: CodeOffset, code: &[StacklessBytecode]) -> Vec<CodeOffset> {
+        let bytecode = &code[pc as usize];
+        let mut v = vec![];
+        if let Some(offset) = bytecode.offset() {
+            v.push(*offset);
+        }
+        let next_pc = pc + 1;
+        if (next_pc as usize) < code.len()
+            && !bytecode.is_unconditional_branch()
+            && !v.contains(&next_pc)
+        {
+            v.push(next_pc);
+        }
+        v
+    }
+
+    /// Returns the locals and temporaries this instruction reads. Borrowing a local counts as
+    /// reading it.
+    pub fn sources(&self) -> Vec<TempIndex> {
+        use StacklessBytecode::*;
+        match self {
+            MoveLoc(_, l) | CopyLoc(_, l) | BorrowLoc(_, l) => vec![*l as TempIndex],
+            StLoc(_, t)
+            | ReadRef(_, t)
+            | FreezeRef(_, t)
+            | Unpack(_, _, t)
+            | BorrowField(_, t, _)
+            | MoveToSender(t, _)
+            | MoveFrom(_, t, _)
+            | BorrowGlobal(_, t, _)
+            | Exists(_, t, _)
+            | CreateAccount(t)
+            | Not(_, t)
+            | BrTrue(_, t)
+            | BrFalse(_, t)
+            | Abort(t) => vec![*t],
+            WriteRef(t1, t2)
+            | Add(_, t1, t2)
+            | Sub(_, t1, t2)
+            | Mul(_, t1, t2)
+            | Div(_, t1, t2)
+            | Mod(_, t1, t2)
+            | BitOr(_, t1, t2)
+            | BitAnd(_, t1, t2)
+            | Xor(_, t1, t2)
+            | Lt(_, t1, t2)
+            | Gt(_, t1, t2)
+            | Le(_, t1, t2)
+            | Ge(_, t1, t2)
+            | Or(_, t1, t2)
+            | And(_, t1, t2)
+            | Eq(_, t1, t2)
+            | Neq(_, t1, t2) => vec![*t1, *t2],
+            Call(_, _, args) | Pack(_, _, args) | Ret(args) => args.clone(),
+            GetGasRemaining(_)
+            | GetTxnSequenceNumber(_)
+            | GetTxnPublicKey(_)
+            | GetTxnSenderAddress(_)
+            | GetTxnMaxGasUnits(_)
+            | GetTxnGasUnitPrice(_)
+            | LdTrue(_)
+            | LdFalse(_)
+            | LdConst(_, _)
+            | LdAddr(_, _)
+            | LdByteArray(_, _)
+            | LdStr(_, _)
+            | Branch(_)
+            | NoOp => vec![],
+        }
+    }
+
+    /// Returns the locals and temporaries this instruction assigns. Writing through a reference
+    /// does not assign the reference itself.
+    pub fn destinations(&self) -> Vec<TempIndex> {
+        use StacklessBytecode::*;
+        match self {
+            StLoc(l, _) => vec![*l as TempIndex],
+            MoveLoc(t, _)
+            | CopyLoc(t, _)
+            | BorrowLoc(t, _)
+            | ReadRef(t, _)
+            | FreezeRef(t, _)
+            | Pack(t, _, _)
+            | BorrowField(t, _, _)
+            | MoveFrom(t, _, _)
+            | BorrowGlobal(t, _, _)
+            | Exists(t, _, _)
+            | GetGasRemaining(t)
+            | GetTxnSequenceNumber(t)
+            | GetTxnPublicKey(t)
+            | GetTxnSenderAddress(t)
+            | GetTxnMaxGasUnits(t)
+            | GetTxnGasUnitPrice(t)
+            | LdTrue(t)
+            | LdFalse(t)
+            | LdConst(t, _)
+            | LdAddr(t, _)
+            | LdByteArray(t, _)
+            | LdStr(t, _)
+            | Not(t, _)
+            | Add(t, _, _)
+            | Sub(t, _, _)
+            | Mul(t, _, _)
+            | Div(t, _, _)
+            | Mod(t, _, _)
+            | BitOr(t, _, _)
+            | BitAnd(t, _, _)
+            | Xor(t, _, _)
+            | Lt(t, _, _)
+            | Gt(t, _, _)
+            | Le(t, _, _)
+            | Ge(t, _, _)
+            | Or(t, _, _)
+            | And(t, _, _)
+            | Eq(t, _, _)
+            | Neq(t, _, _) => vec![*t],
+            Call(rets, _, _) | Unpack(rets, _, _) => rets.clone(),
+            WriteRef(_, _)
+            | Ret(_)
+            | MoveToSender(_, _)
+            | CreateAccount(_)
+            | Branch(_)
+            | BrTrue(_, _)
+            | BrFalse(_, _)
+            | Abort(_)
+            | NoOp => vec![],
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates the table of stdlib abort code descriptions from the `@error` annotations of the
+//! modules under `modules/`, see `src/error_codes.rs`.
+//!
+//! A malformed annotation, or two annotations documenting the same code of a module, fail the
+//! build.
+
+use std::{collections::HashSet, env, fs, path::Path};
+
+const MODULES_DIR: &str = "modules";
+const ERROR_ANNOTATION: &str = "// @error ";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", MODULES_DIR);
+
+    let mut paths: Vec<_> = fs::read_dir(MODULES_DIR)
+        .expect("failed to read the stdlib modules")
+        .map(|entry| entry.expect("failed to read the stdlib modules").path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "mvir"))
+        .collect();
+    paths.sort();
+
+    let mut seen = HashSet::new();
+    let mut table = String::from("[\n");
+    for path in paths {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let source = fs::read_to_string(&path).expect("failed to read a stdlib module");
+        let module = module_name(&source)
+            .unwrap_or_else(|| panic!("{}: no module definition", path.display()));
+        for line in source.lines().map(str::trim) {
+            if !line.starts_with(ERROR_ANNOTATION) {
+                continue;
+            }
+            let (code, name, category, description) = parse(&line[ERROR_ANNOTATION.len()..])
+                .unwrap_or_else(|| {
+                    panic!("{}: malformed error annotation: {}", path.display(), line)
+                });
+            if !seen.insert((module.to_string(), code)) {
+                panic!(
+                    "{}: duplicate abort code {} in module {}",
+                    path.display(),
+                    code,
+                    module
+                );
+            }
+            table.push_str(&format!(
+                "    ErrorDescription {{ module: {:?}, code: {}, name: {:?}, category: \
+                 ErrorCategory::{}, description: {:?} }},\n",
+                module, code, name, category, description
+            ));
+        }
+    }
+    table.push_str("]\n");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(&out_dir).join("error_descriptions.rs"), table)
+        .expect("failed to write the error descriptions");
+}
+
+/// Extracts the name of the module defined in `source`.
+fn module_name(source: &str) -> Option<&str> {
+    source
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("module "))
+        .and_then(|line| line["module ".len()..].split_whitespace().next())
+}
+
+/// Parses the part of an annotation following `@error`, i.e.
+/// `<code> <name> <category>: <description>`, into the code, the name, the `ErrorCategory`
+/// variant and the description.
+fn parse(annotation: &str) -> Option<(u64, &str, &str, &str)> {
+    let mut parts = annotation.splitn(2, ':');
+    let mut header = parts.next()?.split_whitespace();
+    let description = parts.next()?.trim();
+
+    let code = header.next()?.parse().ok()?;
+    let name = header.next()?;
+    let category = match header.next()? {
+        "prologue" => "Prologue",
+        "invalid_argument" => "InvalidArgument",
+        "insufficient_funds" => "InsufficientFunds",
+        "permission_denied" => "PermissionDenied",
+        _ => return None,
+    };
+    if header.next().is_some() {
+        return None;
+    }
+    Some((code, name, category, description))
+}
@@ -0,0 +1,90 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Descriptions of the abort codes raised by the standard library modules.
+//!
+//! Every stdlib module documents the codes it aborts with in comments of the form
+//!
+//! ```text
+//! // @error <code> <name> <category>: <description>
+//! ```
+//!
+//! placed at the top of the module, one line per code. `<category>` is one of `prologue`,
+//! `invalid_argument`, `insufficient_funds` and `permission_denied`. A code that is raised but
+//! not documented, or documented with a different meaning, is not caught by the build, so keep
+//! these lines in sync with the module's `assert`s and `abort`s.
+//!
+//! The table of descriptions is generated from those comments by the build script of this crate,
+//! which rejects malformed annotations and codes documented twice in a module.
+
+use std::fmt;
+use types::{account_config, language_storage::ModuleId, vm_error::VMStatus};
+
+/// The descriptions of all documented abort codes, generated by `build.rs`.
+static ERROR_DESCRIPTIONS: &[ErrorDescription] =
+    &include!(concat!(env!("OUT_DIR"), "/error_descriptions.rs"));
+
+/// The broad class of failure an abort code belongs to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorCategory {
+    /// Raised by the transaction prologue. The VM maps these to dedicated validation statuses.
+    Prologue,
+    /// An argument passed to the function is not acceptable.
+    InvalidArgument,
+    /// An account or coin does not hold enough funds for the operation.
+    InsufficientFunds,
+    /// The sender is not allowed to perform the operation.
+    PermissionDenied,
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let category = match self {
+            ErrorCategory::Prologue => "prologue",
+            ErrorCategory::InvalidArgument => "invalid argument",
+            ErrorCategory::InsufficientFunds => "insufficient funds",
+            ErrorCategory::PermissionDenied => "permission denied",
+        };
+        write!(f, "{}", category)
+    }
+}
+
+/// The documentation of a single abort code of a stdlib module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ErrorDescription {
+    pub module: &'static str,
+    pub code: u64,
+    pub name: &'static str,
+    pub category: ErrorCategory,
+    pub description: &'static str,
+}
+
+impl fmt::Display for ErrorDescription {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.name, self.category, self.description)
+    }
+}
+
+/// Returns the description of abort `code` of the stdlib module `module`, if it is documented.
+pub fn error_description(module: &ModuleId, code: u64) -> Option<&'static ErrorDescription> {
+    if *module.address() != account_config::core_code_address() {
+        return None;
+    }
+    ERROR_DESCRIPTIONS.iter().find(|description| {
+        description.module == module.name().as_str() && description.code == code
+    })
+}
+
+/// Returns a human-readable explanation of an `ABORTED` status raised by a stdlib module.
+///
+/// Returns `None` for any other status, including aborts raised by code outside of the stdlib.
+pub fn explain_vm_status(status: &VMStatus) -> Option<String> {
+    let module = status.abort_module.as_ref()?;
+    let description = error_description(module, status.sub_status?)?;
+    Some(format!("{}::{}", module.name(), description))
+}
+
+/// Returns the descriptions of all documented abort codes.
+pub fn error_descriptions() -> &'static [ErrorDescription] {
+    ERROR_DESCRIPTIONS
+}
@@ -1,17 +1,34 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod error_codes;
 pub mod stdlib;
 pub mod transaction_scripts;
 
 use bytecode_verifier::{verify_module_dependencies, VerifiedModule};
-use ir_to_bytecode::compiler::compile_module;
+use ir_to_bytecode::compiler::{compile_module, compile_module_with_source_map, ModuleSourceMap};
 use lazy_static::lazy_static;
 use types::{account_address::AccountAddress, account_config};
 
 lazy_static! {
     static ref STDLIB_MODULES: Vec<VerifiedModule> =
         { build_stdlib(account_config::core_code_address()) };
+    static ref STDLIB_SOURCE_MAPS: Vec<ModuleSourceMap> = {
+        let stdlib_modules = stdlib_modules();
+        stdlib::module_defs()
+            .iter()
+            .enumerate()
+            .map(|(idx, module_def)| {
+                compile_module_with_source_map(
+                    account_config::core_code_address(),
+                    (*module_def).clone(),
+                    &stdlib_modules[..idx],
+                )
+                .expect("stdlib module failed to compile")
+                .1
+            })
+            .collect()
+    };
 }
 
 /// Returns a reference to the standard library, compiled with the
@@ -22,6 +39,13 @@ pub fn stdlib_modules() -> &'static [VerifiedModule] {
     &*STDLIB_MODULES
 }
 
+/// Returns the locations in the source of the instructions of each module in
+/// [`stdlib_modules`], in the same order. The sources are available through
+/// [`stdlib::module_sources`].
+pub fn stdlib_source_maps() -> &'static [ModuleSourceMap] {
+    &*STDLIB_SOURCE_MAPS
+}
+
 /// Builds and returns a copy of the standard library with this address as the self address.
 ///
 /// A copy of the stdlib built with the [default address](account_config::core_code_address) is
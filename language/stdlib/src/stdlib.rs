@@ -5,34 +5,63 @@ use ir_to_bytecode::parser::{ast::ModuleDefinition, parse_module};
 use lazy_static::lazy_static;
 
 macro_rules! make_module_definition {
-    ($source_path: literal) => {{
-        let struct_body = include_str!($source_path);
-        parse_module(struct_body).unwrap()
+    ($source: expr) => {{
+        parse_module($source).unwrap()
     }};
 }
 
+const ACCOUNT_SOURCE: &str = include_str!("../modules/libra_account.mvir");
+const BLOCK_SOURCE: &str = include_str!("../modules/block.mvir");
+const COIN_SOURCE: &str = include_str!("../modules/libra_coin.mvir");
+const NATIVE_HASH_SOURCE: &str = include_str!("../modules/hash.mvir");
+const SIGNATURE_SOURCE: &str = include_str!("../modules/signature.mvir");
+const VALIDATOR_SET_SOURCE: &str = include_str!("../modules/validator_set.mvir");
+const ADDRESS_UTIL_SOURCE: &str = include_str!("../modules/address_util.mvir");
+const U64_UTIL_SOURCE: &str = include_str!("../modules/u64_util.mvir");
+const VECTOR_SOURCE: &str = include_str!("../modules/vector.mvir");
+const BYTEARRAY_UTIL_SOURCE: &str = include_str!("../modules/bytearray_util.mvir");
+const EVENT_SOURCE: &str = include_str!("../modules/event.mvir");
+const GAS_SCHEDULE_SOURCE: &str = include_str!("../modules/gas_schedule.mvir");
+
+/// The sources of the modules returned by `module_defs`, in the same order.
+const MODULE_SOURCES: &[&str] = &[
+    ADDRESS_UTIL_SOURCE,
+    BLOCK_SOURCE,
+    BYTEARRAY_UTIL_SOURCE,
+    COIN_SOURCE,
+    GAS_SCHEDULE_SOURCE,
+    NATIVE_HASH_SOURCE,
+    SIGNATURE_SOURCE,
+    U64_UTIL_SOURCE,
+    VECTOR_SOURCE,
+    EVENT_SOURCE,
+    ACCOUNT_SOURCE,
+];
+
 lazy_static! {
     static ref ACCOUNT_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/libra_account.mvir");
+        make_module_definition!(ACCOUNT_SOURCE);
     static ref BLOCK_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/block.mvir");
+        make_module_definition!(BLOCK_SOURCE);
     static ref COIN_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/libra_coin.mvir");
+        make_module_definition!(COIN_SOURCE);
     static ref NATIVE_HASH_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/hash.mvir");
+        make_module_definition!(NATIVE_HASH_SOURCE);
     static ref SIGNATURE_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/signature.mvir");
+        make_module_definition!(SIGNATURE_SOURCE);
     static ref VALIDATOR_SET_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/validator_set.mvir");
+        make_module_definition!(VALIDATOR_SET_SOURCE);
     static ref ADDRESS_UTIL_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/address_util.mvir");
+        make_module_definition!(ADDRESS_UTIL_SOURCE);
     static ref U64_UTIL_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/u64_util.mvir");
+        make_module_definition!(U64_UTIL_SOURCE);
     static ref VECTOR_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/vector.mvir");
+        make_module_definition!(VECTOR_SOURCE);
     static ref BYTEARRAY_UTIL_MODULE: ModuleDefinition =
-        make_module_definition!("../modules/bytearray_util.mvir");
-    static ref EVENT_MODULE: ModuleDefinition = make_module_definition!("../modules/event.mvir");
+        make_module_definition!(BYTEARRAY_UTIL_SOURCE);
+    static ref EVENT_MODULE: ModuleDefinition = make_module_definition!(EVENT_SOURCE);
+    static ref GAS_SCHEDULE_MODULE: ModuleDefinition =
+        make_module_definition!(GAS_SCHEDULE_SOURCE);
     static ref MODULE_DEFS: Vec<&'static ModuleDefinition> = {
         // Note: a module can depend on earlier modules in the list, but not vice versa. Don't try
         // to rearrange without considering this!
@@ -41,12 +70,13 @@ lazy_static! {
             &*BLOCK_MODULE,
             &*BYTEARRAY_UTIL_MODULE,
             &*COIN_MODULE,
+            &*GAS_SCHEDULE_MODULE,
             &*NATIVE_HASH_MODULE,
             &*SIGNATURE_MODULE,
             &*U64_UTIL_MODULE,
             &*VECTOR_MODULE,
             &*EVENT_MODULE, // depends on AddressUtil, BytearrayUtil, Hash, U64Util
-            &*ACCOUNT_MODULE, // depends on Coin, Event, AddressUtil, BytearrayUtil, U64Util
+            &*ACCOUNT_MODULE, // depends on Block, Coin, Event, AddressUtil, BytearrayUtil, U64Util
         ]
     };
 }
@@ -87,6 +117,15 @@ pub fn event_module() -> ModuleDefinition {
     EVENT_MODULE.clone()
 }
 
+pub fn gas_schedule_module() -> ModuleDefinition {
+    GAS_SCHEDULE_MODULE.clone()
+}
+
 pub fn module_defs() -> &'static [&'static ModuleDefinition] {
     &*MODULE_DEFS
 }
+
+/// Returns the source of each module in `module_defs`, in the same order.
+pub fn module_sources() -> &'static [&'static str] {
+    MODULE_SOURCES
+}
@@ -6,6 +6,11 @@
 //! * Global-memory independent instructions;
 //! * Global-memory dependent instructions; and
 //! * Native operations.
+//!
+//! Optionally, the averaged measurements can be written out as a proposed gas schedule, serialized
+//! in the format of the on-chain `GasSchedule.T` cost table and ready to be submitted with the
+//! `update_gas_schedule` transaction script.
+use canonical_serialization::SimpleSerializer;
 use cost_synthesis::{
     global_state::{account::Account, inhabitor::RandomInhabitor},
     module_generator::ModuleGenerator,
@@ -18,7 +23,8 @@ use language_e2e_tests::data_store::FakeDataStore;
 use std::{
     collections::{HashMap, VecDeque},
     convert::TryFrom,
-    path::Path,
+    fs,
+    path::{Path, PathBuf},
     time::Instant,
     u64,
 };
@@ -30,7 +36,10 @@ use vm::{
         FunctionDefinitionIndex, FunctionHandleIndex, StructDefinitionIndex, UserStringIndex,
         NO_TYPE_ACTUALS,
     },
-    gas_schedule::{AbstractMemorySize, GasAlgebra, GasCarrier},
+    gas_schedule::{
+        AbstractMemorySize, CostTable, GasAlgebra, GasCarrier, GasUnits, InstructionKey,
+        NativeCostIndex, DEFAULT_GAS_SCHEDULE,
+    },
     transaction_metadata::TransactionMetadata,
 };
 use vm_cache_map::Arena;
@@ -54,6 +63,10 @@ struct Opt {
     /// The maximum stack size generated.
     #[structopt(short = "ms", long = "max-stack-size", default_value = "100")]
     max_stack_size: u64,
+
+    /// If set, write the averaged costs as a serialized cost table to this file.
+    #[structopt(short = "g", long = "gas-schedule", parse(from_os_str))]
+    gas_schedule: Option<PathBuf>,
 }
 
 fn output_to_csv(path: &Path, data: HashMap<String, Vec<u64>>) {
@@ -92,7 +105,15 @@ fn size_normalize_cost(instr: &Bytecode, cost: u64, size: AbstractMemorySize<Gas
     }
 }
 
-fn stack_instructions(options: &Opt) {
+fn average(costs: &[u64]) -> u64 {
+    if costs.is_empty() {
+        0
+    } else {
+        costs.iter().sum::<u64>() / costs.len() as u64
+    }
+}
+
+fn stack_instructions(options: &Opt) -> Vec<(Bytecode, Vec<u64>)> {
     use Bytecode::*;
     let stack_opcodes: Vec<Bytecode> = vec![
         ReadRef,
@@ -154,7 +175,7 @@ fn stack_instructions(options: &Opt) {
     let mod_gen: ModuleGenerator = ModuleGenerator::new(options.num_iters as u16, 3);
     let mut account = Account::new();
     with_loaded_vm! (mod_gen, account => vm, loaded_module, module_cache);
-    let costs: HashMap<String, Vec<u64>> = stack_opcodes
+    let costs: Vec<(Bytecode, Vec<u64>)> = stack_opcodes
         .into_iter()
         .map(|instruction| {
             println!("Running: {:?}", instruction);
@@ -198,11 +219,18 @@ fn stack_instructions(options: &Opt) {
                     size_normalize_cost(&instruction, u64::try_from(time).unwrap(), size)
                 })
                 .collect();
-            (format!("{:?}", instruction), instr_costs)
+            (instruction, instr_costs)
         })
         .collect();
 
-    output_to_csv(Path::new("data/bytecode_instruction_costs.csv"), costs);
+    output_to_csv(
+        Path::new("data/bytecode_instruction_costs.csv"),
+        costs
+            .iter()
+            .map(|(instruction, instr_costs)| (format!("{:?}", instruction), instr_costs.clone()))
+            .collect(),
+    );
+    costs
 }
 
 macro_rules! bench_native {
@@ -215,7 +243,7 @@ macro_rules! bench_native {
                     let before = Instant::now();
                     let mut args = VecDeque::new();
                     args.push_front(Value::byte_array(stack_access.next_bytearray()));
-                    let _ = $function(args);
+                    let _ = $function(args, &DEFAULT_GAS_SCHEDULE);
                     acc + before.elapsed().as_nanos()
                 });
                 // Time per byte averaged over the number of iterations that we performed.
@@ -226,7 +254,7 @@ macro_rules! bench_native {
     };
 }
 
-fn natives(options: &Opt) {
+fn natives(options: &Opt) -> Vec<(NativeCostIndex, Vec<u64>)> {
     let mut cost_table = HashMap::new();
     bench_native!(
         "native_sha2_256".to_string(),
@@ -240,11 +268,48 @@ fn natives(options: &Opt) {
         cost_table,
         options.num_iters
     );
+    let costs = vec![
+        (
+            NativeCostIndex::SHA2_256,
+            cost_table["native_sha2_256"].clone(),
+        ),
+        (
+            NativeCostIndex::SHA3_256,
+            cost_table["native_sha3_256"].clone(),
+        ),
+    ];
     output_to_csv(Path::new("data/native_function_costs.csv"), cost_table);
+    costs
+}
+
+/// Builds a proposed cost table from the measurements. Instructions and natives that were not
+/// measured keep their cost from the default gas schedule.
+fn gas_schedule(
+    instruction_costs: &[(Bytecode, Vec<u64>)],
+    native_costs: &[(NativeCostIndex, Vec<u64>)],
+) -> CostTable {
+    let mut cost_table = DEFAULT_GAS_SCHEDULE.clone();
+    for (instruction, costs) in instruction_costs {
+        let code = InstructionKey::new(instruction);
+        cost_table
+            .compute_table
+            .insert(code, GasUnits::new(average(costs)));
+        cost_table.memory_table.insert(code, GasUnits::new(1));
+    }
+    for (native, costs) in native_costs {
+        cost_table.native_table[*native as usize] = GasUnits::new(average(costs));
+    }
+    cost_table
 }
 
 pub fn main() {
     let opt = Opt::from_args();
-    stack_instructions(&opt);
-    natives(&opt);
+    let instruction_costs = stack_instructions(&opt);
+    let native_costs = natives(&opt);
+    if let Some(path) = &opt.gas_schedule {
+        let cost_table = gas_schedule(&instruction_costs, &native_costs);
+        let bytes = SimpleSerializer::<Vec<u8>>::serialize(&cost_table)
+            .expect("cost table serialization must succeed");
+        fs::write(path, bytes).unwrap();
+    }
 }
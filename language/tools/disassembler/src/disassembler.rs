@@ -0,0 +1,417 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prints the declarations of a compiled module or script: imports, structs and function
+//! signatures, with handles resolved to the names the IR refers to them by.
+
+use crate::structurer::Structurer;
+use failure::prelude::*;
+use vm::{
+    access::ModuleAccess,
+    file_format::{
+        Bytecode, CompiledModule, CompiledScript, FieldDefinitionIndex, FunctionDefinition,
+        FunctionHandleIndex, Kind, ModuleHandleIndex, SignatureToken, StructDefinitionIndex,
+        StructFieldInformation,
+    },
+};
+
+#[derive(Clone, Debug)]
+pub struct DisassemblerOptions {
+    /// Print the bytecode of every function, with the offset of each instruction, as a comment
+    /// at the start of the function body
+    pub print_code_offsets: bool,
+}
+
+impl Default for DisassemblerOptions {
+    fn default() -> Self {
+        Self {
+            print_code_offsets: true,
+        }
+    }
+}
+
+/// Disassembles a module into the source of a Move IR module.
+pub fn disassemble_module(
+    module: &CompiledModule,
+    options: &DisassemblerOptions,
+) -> Result<String> {
+    Disassembler::new(module, options).print_module()
+}
+
+/// Disassembles a script into the source of a Move IR transaction script.
+pub fn disassemble_script(
+    script: &CompiledScript,
+    options: &DisassemblerOptions,
+) -> Result<String> {
+    let module = script.clone().into_module();
+    Disassembler::new(&module, options).print_script()
+}
+
+pub(crate) struct Disassembler<'a> {
+    module: &'a CompiledModule,
+    options: &'a DisassemblerOptions,
+    /// The name every module handle is referred to by: `Self` for the module being disassembled,
+    /// the module name or an alias for imported modules
+    module_names: Vec<String>,
+}
+
+impl<'a> Disassembler<'a> {
+    fn new(module: &'a CompiledModule, options: &'a DisassemblerOptions) -> Self {
+        let handle_names: Vec<&str> = module
+            .module_handles()
+            .iter()
+            .map(|handle| module.identifier_at(handle.name).as_str())
+            .collect();
+        let module_names = handle_names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                if idx == 0 {
+                    "Self".to_string()
+                } else if *name == "Self"
+                    || handle_names[1..].iter().filter(|n| n == &name).count() > 1
+                {
+                    // Modules with the same name published under different addresses need an alias
+                    format!("{}{}", name, idx)
+                } else {
+                    name.to_string()
+                }
+            })
+            .collect();
+        Self {
+            module,
+            options,
+            module_names,
+        }
+    }
+
+    fn print_module(&self) -> Result<String> {
+        let mut items = vec![];
+        let imports = self.imports();
+        if !imports.is_empty() {
+            items.push(indent_lines(&imports, "    "));
+        }
+        for struct_def_idx in 0..self.module.struct_defs().len() {
+            items.push(self.struct_definition(StructDefinitionIndex::new(struct_def_idx as u16))?);
+        }
+        for function_def in self.module.function_defs() {
+            items.push(self.function_definition(function_def, "    ", false)?);
+        }
+        Ok(format!(
+            "module {} {{\n{}}}\n",
+            self.module.name(),
+            items.join("\n")
+        ))
+    }
+
+    fn print_script(&self) -> Result<String> {
+        let mut items = vec![];
+        let imports = self.imports();
+        if !imports.is_empty() {
+            items.push(indent_lines(&imports, ""));
+        }
+        let main = self
+            .module
+            .function_defs()
+            .first()
+            .ok_or_else(|| format_err!("script has no main function"))?;
+        items.push(self.function_definition(main, "", true)?);
+        Ok(items.join("\n"))
+    }
+
+    fn imports(&self) -> Vec<String> {
+        self.module
+            .module_handles()
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(idx, handle)| {
+                let name = self.module.identifier_at(handle.name);
+                let alias = &self.module_names[idx];
+                if alias.as_str() == name.as_str() {
+                    format!(
+                        "import 0x{}.{};",
+                        self.module.address_at(handle.address),
+                        name
+                    )
+                } else {
+                    format!(
+                        "import 0x{}.{} as {};",
+                        self.module.address_at(handle.address),
+                        name,
+                        alias
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn struct_definition(&self, idx: StructDefinitionIndex) -> Result<String> {
+        let struct_def = self.module.struct_def_at(idx);
+        let handle = self.module.struct_handle_at(struct_def.struct_handle);
+        let kind = if handle.is_nominal_resource {
+            "resource"
+        } else {
+            "struct"
+        };
+        let name = self.module.identifier_at(handle.name);
+        let type_formals = type_formals(&handle.type_formals);
+        match &struct_def.field_information {
+            StructFieldInformation::Native => {
+                Ok(format!("    native {} {}{};\n", kind, name, type_formals))
+            }
+            StructFieldInformation::Declared {
+                field_count,
+                fields,
+            } => {
+                let mut lines = vec![format!("    {} {}{} {{", kind, name, type_formals)];
+                for field in self.module.field_def_range(*field_count, *fields) {
+                    lines.push(format!(
+                        "        {}: {},",
+                        self.module.identifier_at(field.name),
+                        self.type_name(&self.module.type_signature_at(field.signature).0)?
+                    ));
+                }
+                lines.push("    }".to_string());
+                Ok(indent_lines(&lines, ""))
+            }
+        }
+    }
+
+    fn function_definition(
+        &self,
+        function_def: &FunctionDefinition,
+        indent: &str,
+        is_main: bool,
+    ) -> Result<String> {
+        let handle = self.module.function_handle_at(function_def.function);
+        let signature = self.module.function_signature_at(handle.signature);
+        let formals = signature
+            .arg_types
+            .iter()
+            .enumerate()
+            .map(|(idx, type_)| Ok(format!("arg{}: {}", idx, self.type_name(type_)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let header = if is_main {
+            format!("main({})", formals.join(", "))
+        } else {
+            let mut header = String::new();
+            if function_def.is_native() {
+                header.push_str("native ");
+            }
+            if function_def.is_public() {
+                header.push_str("public ");
+            }
+            header.push_str(self.module.identifier_at(handle.name).as_str());
+            header.push_str(&type_formals(&signature.type_formals));
+            header.push_str(&format!("({})", formals.join(", ")));
+            if !signature.return_types.is_empty() {
+                let return_types = signature
+                    .return_types
+                    .iter()
+                    .map(|type_| self.type_name(type_))
+                    .collect::<Result<Vec<_>>>()?;
+                header.push_str(&format!(": {}", return_types.join(" * ")));
+            }
+            if !function_def.acquires_global_resources.is_empty() {
+                let acquires: Vec<String> = function_def
+                    .acquires_global_resources
+                    .iter()
+                    .map(|idx| self.struct_name(*idx))
+                    .collect();
+                header.push_str(&format!(" acquires {}", acquires.join(", ")));
+            }
+            header
+        };
+        if function_def.is_native() {
+            return Ok(format!("{}{};\n", indent, header));
+        }
+
+        let num_formals = signature.arg_types.len();
+        let locals = &self.module.locals_signature_at(function_def.code.locals).0;
+        let local_names: Vec<String> = (0..locals.len())
+            .map(|idx| {
+                if idx < num_formals {
+                    format!("arg{}", idx)
+                } else {
+                    format!("loc{}", idx)
+                }
+            })
+            .collect();
+        let code = &function_def.code.code;
+
+        let mut lines = vec![];
+        for (name, type_) in local_names.iter().zip(locals).skip(num_formals) {
+            lines.push(format!("let {}: {};", name, self.type_name(type_)?));
+        }
+        let statements = Structurer::new(self, code, &local_names).structure();
+        if let Err(err) = &statements {
+            lines.push(format!("// unable to reconstruct structured code: {}", err));
+        }
+        if self.options.print_code_offsets || statements.is_err() {
+            for (offset, instr) in code.iter().enumerate() {
+                lines.push(format!(
+                    "// {}: {}",
+                    offset,
+                    self.instruction(instr, &local_names)
+                ));
+            }
+        }
+        if let Ok(statements) = statements {
+            lines.extend(statements);
+        }
+
+        let mut result = format!("{}{} {{\n", indent, header);
+        result.push_str(&indent_lines(&lines, &format!("{}    ", indent)));
+        result.push_str(&format!("{}}}\n", indent));
+        Ok(result)
+    }
+
+    /// Formats an instruction with its operands resolved to names.
+    fn instruction(&self, instr: &Bytecode, local_names: &[String]) -> String {
+        use Bytecode::*;
+        match instr {
+            CopyLoc(idx) => format!("CopyLoc({})", local_names[*idx as usize]),
+            MoveLoc(idx) => format!("MoveLoc({})", local_names[*idx as usize]),
+            StLoc(idx) => format!("StLoc({})", local_names[*idx as usize]),
+            MutBorrowLoc(idx) => format!("MutBorrowLoc({})", local_names[*idx as usize]),
+            ImmBorrowLoc(idx) => format!("ImmBorrowLoc({})", local_names[*idx as usize]),
+            LdAddr(idx) => format!("LdAddr(0x{})", self.module.address_at(*idx)),
+            LdByteArray(idx) => format!(
+                "LdByteArray(h\"{}\")",
+                hex::encode(self.module.byte_array_at(*idx).as_bytes())
+            ),
+            LdStr(idx) => format!("LdStr(\"{}\")", self.module.user_string_at(*idx)),
+            Call(idx, _) => format!("Call({})", self.function_name(*idx)),
+            MutBorrowField(idx) => format!("MutBorrowField({})", self.qualified_field_name(*idx)),
+            ImmBorrowField(idx) => format!("ImmBorrowField({})", self.qualified_field_name(*idx)),
+            Pack(idx, _) => format!("Pack({})", self.struct_name(*idx)),
+            Unpack(idx, _) => format!("Unpack({})", self.struct_name(*idx)),
+            MutBorrowGlobal(idx, _) => format!("MutBorrowGlobal({})", self.struct_name(*idx)),
+            ImmBorrowGlobal(idx, _) => format!("ImmBorrowGlobal({})", self.struct_name(*idx)),
+            Exists(idx, _) => format!("Exists({})", self.struct_name(*idx)),
+            MoveFrom(idx, _) => format!("MoveFrom({})", self.struct_name(*idx)),
+            MoveToSender(idx, _) => format!("MoveToSender({})", self.struct_name(*idx)),
+            _ => format!("{:?}", instr),
+        }
+    }
+
+    pub(crate) fn module(&self) -> &CompiledModule {
+        self.module
+    }
+
+    fn module_name(&self, idx: ModuleHandleIndex) -> &str {
+        &self.module_names[idx.0 as usize]
+    }
+
+    /// The name of a function as it is called, qualified by its module.
+    pub(crate) fn function_name(&self, idx: FunctionHandleIndex) -> String {
+        let handle = self.module.function_handle_at(idx);
+        format!(
+            "{}.{}",
+            self.module_name(handle.module),
+            self.module.identifier_at(handle.name)
+        )
+    }
+
+    /// The unqualified name of a struct defined in the module.
+    pub(crate) fn struct_name(&self, idx: StructDefinitionIndex) -> String {
+        let struct_def = self.module.struct_def_at(idx);
+        let handle = self.module.struct_handle_at(struct_def.struct_handle);
+        self.module.identifier_at(handle.name).to_string()
+    }
+
+    /// The names of the fields of a struct defined in the module, in declaration order.
+    pub(crate) fn field_names(&self, idx: StructDefinitionIndex) -> Result<Vec<String>> {
+        match &self.module.struct_def_at(idx).field_information {
+            StructFieldInformation::Native => bail!(
+                "struct {} is native and has no fields",
+                self.struct_name(idx)
+            ),
+            StructFieldInformation::Declared {
+                field_count,
+                fields,
+            } => Ok(self
+                .module
+                .field_def_range(*field_count, *fields)
+                .iter()
+                .map(|field| self.module.identifier_at(field.name).to_string())
+                .collect()),
+        }
+    }
+
+    pub(crate) fn field_name(&self, idx: FieldDefinitionIndex) -> String {
+        self.module
+            .identifier_at(self.module.field_def_at(idx).name)
+            .to_string()
+    }
+
+    fn qualified_field_name(&self, idx: FieldDefinitionIndex) -> String {
+        let field_def = self.module.field_def_at(idx);
+        let handle = self.module.struct_handle_at(field_def.struct_);
+        format!(
+            "{}.{}",
+            self.module.identifier_at(handle.name),
+            self.module.identifier_at(field_def.name)
+        )
+    }
+
+    pub(crate) fn type_name(&self, type_: &SignatureToken) -> Result<String> {
+        Ok(match type_ {
+            SignatureToken::Bool => "bool".to_string(),
+            SignatureToken::U64 => "u64".to_string(),
+            SignatureToken::ByteArray => "bytearray".to_string(),
+            SignatureToken::Address => "address".to_string(),
+            SignatureToken::String => bail!("string types cannot be expressed in Move IR"),
+            SignatureToken::Struct(idx, type_actuals) => {
+                let handle = self.module.struct_handle_at(*idx);
+                format!(
+                    "{}.{}{}",
+                    self.module_name(handle.module),
+                    self.module.identifier_at(handle.name),
+                    self.type_actuals(type_actuals)?
+                )
+            }
+            SignatureToken::Reference(inner) => format!("&{}", self.type_name(inner)?),
+            SignatureToken::MutableReference(inner) => format!("&mut {}", self.type_name(inner)?),
+            SignatureToken::TypeParameter(idx) => format!("T{}", idx),
+        })
+    }
+
+    /// Formats type actuals as they follow a struct or function name, e.g. `<u64, T0>`.
+    pub(crate) fn type_actuals(&self, types: &[SignatureToken]) -> Result<String> {
+        if types.is_empty() {
+            return Ok(String::new());
+        }
+        let names = types
+            .iter()
+            .map(|type_| self.type_name(type_))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(format!("<{}>", names.join(", ")))
+    }
+}
+
+fn type_formals(kinds: &[Kind]) -> String {
+    if kinds.is_empty() {
+        return String::new();
+    }
+    let formals: Vec<String> = kinds
+        .iter()
+        .enumerate()
+        .map(|(idx, kind)| match kind {
+            Kind::All => format!("T{}", idx),
+            Kind::Resource => format!("T{}: resource", idx),
+            Kind::Unrestricted => format!("T{}: unrestricted", idx),
+        })
+        .collect();
+    format!("<{}>", formals.join(", "))
+}
+
+/// Joins the lines, each prefixed with `indent` and terminated by a newline.
+pub(crate) fn indent_lines(lines: &[String], indent: &str) -> String {
+    lines
+        .iter()
+        .map(|line| format!("{}{}\n", indent, line))
+        .collect()
+}
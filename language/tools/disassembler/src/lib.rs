@@ -0,0 +1,11 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prints compiled Move modules and scripts back as Move IR.
+//!
+//! Function bodies are reconstructed into structured statements when the bytecode has the shape
+//! the IR compiler emits, so that the output can be compiled again. Bodies that cannot be
+//! structured are printed as an annotated bytecode listing instead.
+
+pub mod disassembler;
+mod structurer;
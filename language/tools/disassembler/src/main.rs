@@ -0,0 +1,51 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use disassembler::disassembler::{disassemble_module, disassemble_script, DisassemblerOptions};
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+use vm::file_format::{CompiledModule, CompiledScript};
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "Move Disassembler",
+    author = "Libra",
+    about = "Prints a serialized Move module or script as Move IR."
+)]
+struct Args {
+    /// Treat the input as a serialized transaction script instead of a module
+    #[structopt(short = "s", long = "script")]
+    pub is_script: bool,
+    /// Do not print the bytecode of each function with its instruction offsets
+    #[structopt(long = "no-code-offsets")]
+    pub no_code_offsets: bool,
+    /// Path to the serialized module or script
+    #[structopt(parse(from_os_str))]
+    pub input_path: PathBuf,
+}
+
+fn main() {
+    let args = Args::from_args();
+    let options = DisassemblerOptions {
+        print_code_offsets: !args.no_code_offsets,
+    };
+    let binary = fs::read(&args.input_path)
+        .unwrap_or_else(|err| panic!("Unable to read {:?}: {}", args.input_path, err));
+
+    let source = if args.is_script {
+        let script = CompiledScript::deserialize(&binary)
+            .unwrap_or_else(|err| panic!("Unable to deserialize script: {:?}", err));
+        disassemble_script(&script, &options)
+    } else {
+        let module = CompiledModule::deserialize(&binary)
+            .unwrap_or_else(|err| panic!("Unable to deserialize module: {:?}", err));
+        disassemble_module(&module, &options)
+    };
+    match source {
+        Ok(source) => print!("{}", source),
+        Err(err) => {
+            eprintln!("Unable to disassemble {:?}: {}", args.input_path, err);
+            std::process::exit(1);
+        }
+    }
+}
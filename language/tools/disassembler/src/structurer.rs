@@ -0,0 +1,542 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reconstructs the statements of a function body from its bytecode.
+//!
+//! Expressions are rebuilt by symbolically executing the operand stack, and control flow is
+//! matched against the patterns the IR compiler emits for `if`, `while` and `loop`: a
+//! conditional branch over the `then` block, optionally followed by a branch over the `else`
+//! block, and a branch back to the head at the end of a loop body.
+
+use crate::disassembler::Disassembler;
+use failure::prelude::*;
+use vm::{
+    access::ModuleAccess,
+    file_format::{Bytecode, LocalsSignatureIndex, StructDefinitionIndex},
+};
+
+/// An expression on the symbolic operand stack.
+struct Exp {
+    text: String,
+    /// The number of values the expression pushes on the stack
+    arity: usize,
+    /// Whether the expression can be used as the operand of an operator, a field borrow or a
+    /// dereference without parentheses
+    is_atom: bool,
+    /// For the values unpacked from a struct, the struct name and its field names
+    unpack: Option<(String, Vec<String>)>,
+}
+
+impl Exp {
+    fn atom(text: String) -> Self {
+        Self {
+            text,
+            arity: 1,
+            is_atom: true,
+            unpack: None,
+        }
+    }
+
+    fn compound(text: String) -> Self {
+        Self {
+            text,
+            arity: 1,
+            is_atom: false,
+            unpack: None,
+        }
+    }
+
+    fn values(text: String, arity: usize) -> Self {
+        Self {
+            text,
+            arity,
+            is_atom: false,
+            unpack: None,
+        }
+    }
+
+    fn as_operand(&self) -> String {
+        if self.is_atom {
+            self.text.clone()
+        } else {
+            format!("({})", self.text)
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct LoopContext {
+    head: usize,
+    /// The offset after the branch back to the head, which `break` jumps to
+    exit: usize,
+}
+
+pub(crate) struct Structurer<'a> {
+    disassembler: &'a Disassembler<'a>,
+    code: &'a [Bytecode],
+    local_names: &'a [String],
+}
+
+impl<'a> Structurer<'a> {
+    pub(crate) fn new(
+        disassembler: &'a Disassembler<'a>,
+        code: &'a [Bytecode],
+        local_names: &'a [String],
+    ) -> Self {
+        Self {
+            disassembler,
+            code,
+            local_names,
+        }
+    }
+
+    /// Returns the statements of the function body, one line each, with nested blocks indented.
+    pub(crate) fn structure(&self) -> Result<Vec<String>> {
+        self.block(0, self.code.len(), None)
+    }
+
+    /// Structures the code in `[start, end)`, which must leave the operand stack empty.
+    fn block(
+        &self,
+        start: usize,
+        end: usize,
+        loop_context: Option<LoopContext>,
+    ) -> Result<Vec<String>> {
+        let mut lines = vec![];
+        let mut stack = vec![];
+        let mut pc = start;
+        while pc < end {
+            let is_current_head = loop_context.map_or(false, |context| context.head == pc);
+            if stack.is_empty() && !is_current_head {
+                if let Some(back_edge) = self.back_edge(pc, end) {
+                    let context = LoopContext {
+                        head: pc,
+                        exit: back_edge + 1,
+                    };
+                    match self.while_condition(pc, back_edge) {
+                        Some((condition, body_start)) => {
+                            lines.push(format!("while ({}) {{", condition));
+                            lines.extend(indent(self.block(
+                                body_start,
+                                back_edge,
+                                Some(context),
+                            )?));
+                        }
+                        None => {
+                            lines.push("loop {".to_string());
+                            lines.extend(indent(self.block(pc, back_edge, Some(context))?));
+                        }
+                    }
+                    lines.push("}".to_string());
+                    pc = context.exit;
+                    continue;
+                }
+            }
+
+            let instr = &self.code[pc];
+            if self.push_expression(&mut stack, instr)? {
+                if stack.last().map_or(false, |exp| exp.arity == 0) {
+                    let exp = stack.pop().expect("stack is not empty");
+                    expect_empty(&stack, pc)?;
+                    lines.push(match exp.unpack {
+                        Some((name, _)) => format!("{} {{}} = {};", name, exp.text),
+                        None => format!("{};", exp.text),
+                    });
+                }
+                pc += 1;
+                continue;
+            }
+
+            match instr {
+                Bytecode::StLoc(_) | Bytecode::Pop => {
+                    let (line, next_pc) = self.assignment(&mut stack, pc, end)?;
+                    lines.push(line);
+                    pc = next_pc;
+                    continue;
+                }
+                Bytecode::WriteRef => {
+                    let reference = pop_operand(&mut stack, pc)?;
+                    let value = pop_operand(&mut stack, pc)?;
+                    expect_empty(&stack, pc)?;
+                    lines.push(format!("*{} = {};", reference.as_operand(), value.text));
+                }
+                Bytecode::Ret => {
+                    let values: Vec<String> = stack.drain(..).map(|exp| exp.text).collect();
+                    if values.is_empty() {
+                        lines.push("return;".to_string());
+                    } else {
+                        lines.push(format!("return {};", values.join(", ")));
+                    }
+                }
+                Bytecode::Abort => {
+                    let error = pop_operand(&mut stack, pc)?;
+                    expect_empty(&stack, pc)?;
+                    lines.push(format!("abort {};", error.text));
+                }
+                Bytecode::Branch(target) => {
+                    expect_empty(&stack, pc)?;
+                    let target = *target as usize;
+                    match loop_context {
+                        Some(context) if target == context.exit => lines.push("break;".to_string()),
+                        Some(context) if target == context.head => {
+                            lines.push("continue;".to_string())
+                        }
+                        _ => bail!("branch at offset {} does not match a loop", pc),
+                    }
+                }
+                Bytecode::BrFalse(target) => {
+                    let condition = pop_operand(&mut stack, pc)?;
+                    expect_empty(&stack, pc)?;
+                    let target = *target as usize;
+                    if target <= pc || target > end {
+                        bail!("branch at offset {} does not match an if statement", pc);
+                    }
+                    lines.push(format!("if ({}) {{", condition.text));
+                    match self.else_end(pc, target, end, loop_context) {
+                        Some(else_end) => {
+                            lines.extend(indent(self.block(pc + 1, target - 1, loop_context)?));
+                            lines.push("} else {".to_string());
+                            lines.extend(indent(self.block(target, else_end, loop_context)?));
+                            pc = else_end;
+                        }
+                        None => {
+                            lines.extend(indent(self.block(pc + 1, target, loop_context)?));
+                            pc = target;
+                        }
+                    }
+                    lines.push("}".to_string());
+                    continue;
+                }
+                _ => bail!("instruction {:?} at offset {} is not supported", instr, pc),
+            }
+            pc += 1;
+        }
+        if !stack.is_empty() {
+            bail!("values are left on the stack at offset {}", end);
+        }
+        Ok(lines)
+    }
+
+    /// The offset of the last branch back to `head` before `end`, which closes the loop starting
+    /// at `head`.
+    fn back_edge(&self, head: usize, end: usize) -> Option<usize> {
+        (head..end).rev().find(|offset| match self.code[*offset] {
+            Bytecode::Branch(target) => target as usize == head,
+            _ => false,
+        })
+    }
+
+    /// If the loop between `head` and `back_edge` starts by evaluating a condition and exiting
+    /// the loop when it is false, returns the condition and the offset the loop body starts at.
+    fn while_condition(&self, head: usize, back_edge: usize) -> Option<(String, usize)> {
+        let mut stack = vec![];
+        for offset in head..back_edge {
+            let instr = &self.code[offset];
+            match self.push_expression(&mut stack, instr) {
+                Ok(true) if stack.iter().all(|exp| exp.arity > 0) => continue,
+                Ok(true) | Err(_) => return None,
+                Ok(false) => (),
+            }
+            return match instr {
+                Bytecode::BrFalse(target) if *target as usize == back_edge + 1 => {
+                    match stack.as_slice() {
+                        [condition] if condition.arity == 1 && condition.unpack.is_none() => {
+                            Some((condition.text.clone(), offset + 1))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+        }
+        None
+    }
+
+    /// If the `if` statement whose condition branches at `pc` to `target` has an `else` block,
+    /// returns the offset the `else` block ends at. The compiler ends the `then` block with a
+    /// branch over the `else` block unless the `then` block never completes.
+    fn else_end(
+        &self,
+        pc: usize,
+        target: usize,
+        end: usize,
+        loop_context: Option<LoopContext>,
+    ) -> Option<usize> {
+        if target - 1 <= pc {
+            return None;
+        }
+        match self.code[target - 1] {
+            Bytecode::Branch(else_end) => {
+                let else_end = else_end as usize;
+                let is_break = loop_context.map_or(false, |context| context.exit == else_end);
+                if else_end >= target && else_end <= end && !is_break {
+                    Some(else_end)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Builds the assignment made by the stores starting at `pc`, which consume every value on
+    /// the stack. Returns the statement and the offset after the stores.
+    fn assignment(&self, stack: &mut Vec<Exp>, pc: usize, end: usize) -> Result<(String, usize)> {
+        let num_values: usize = stack.iter().map(|exp| exp.arity).sum();
+        if num_values == 0 || pc + num_values > end {
+            bail!("assignment at offset {} has no matching values", pc);
+        }
+        // The compiler stores the values of `a, b = ...` into `b` first
+        let mut lvalues = self.code[pc..pc + num_values]
+            .iter()
+            .map(|instr| match instr {
+                Bytecode::StLoc(idx) => Ok(self.local_names[*idx as usize].clone()),
+                Bytecode::Pop => Ok("_".to_string()),
+                _ => bail!("assignment at offset {} is not supported", pc),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        lvalues.reverse();
+
+        let mut exps: Vec<Exp> = stack.drain(..).collect();
+        let line = if exps.len() == 1 {
+            let exp = exps.pop().expect("one expression");
+            match exp.unpack {
+                Some((name, fields)) => {
+                    if lvalues.iter().any(|lvalue| lvalue == "_") {
+                        bail!("unpack at offset {} discards a field", pc);
+                    }
+                    let bindings: Vec<String> = fields
+                        .iter()
+                        .zip(&lvalues)
+                        .map(|(field, lvalue)| format!("{}: {}", field, lvalue))
+                        .collect();
+                    format!("{} {{ {} }} = {};", name, bindings.join(", "), exp.text)
+                }
+                None => format!("{} = {};", lvalues.join(", "), exp.text),
+            }
+        } else {
+            if exps.iter().any(|exp| exp.unpack.is_some()) {
+                bail!("unpack at offset {} is not supported", pc);
+            }
+            let values: Vec<String> = exps.into_iter().map(|exp| exp.text).collect();
+            format!("{} = ({});", lvalues.join(", "), values.join(", "))
+        };
+        Ok((line, pc + num_values))
+    }
+
+    /// Executes `instr` on the symbolic stack if it only computes values. Returns false for
+    /// instructions that store values or transfer control.
+    fn push_expression(&self, stack: &mut Vec<Exp>, instr: &Bytecode) -> Result<bool> {
+        use Bytecode::*;
+        let module = self.disassembler.module();
+        let exp = match instr {
+            LdConst(value) => Exp::atom(value.to_string()),
+            LdAddr(idx) => Exp::atom(format!("0x{}", module.address_at(*idx))),
+            LdByteArray(idx) => Exp::atom(format!(
+                "h\"{}\"",
+                hex::encode(module.byte_array_at(*idx).as_bytes())
+            )),
+            LdTrue => Exp::atom("true".to_string()),
+            LdFalse => Exp::atom("false".to_string()),
+            CopyLoc(idx) => Exp::atom(format!("copy({})", self.local_names[*idx as usize])),
+            MoveLoc(idx) => Exp::atom(format!("move({})", self.local_names[*idx as usize])),
+            MutBorrowLoc(idx) => Exp::compound(format!("&mut {}", self.local_names[*idx as usize])),
+            ImmBorrowLoc(idx) => Exp::compound(format!("&{}", self.local_names[*idx as usize])),
+            MutBorrowField(idx) | ImmBorrowField(idx) => {
+                let reference = pop_one(stack)?;
+                let borrow = if let MutBorrowField(_) = instr {
+                    "&mut "
+                } else {
+                    "&"
+                };
+                Exp::compound(format!(
+                    "{}{}.{}",
+                    borrow,
+                    reference.as_operand(),
+                    self.disassembler.field_name(*idx)
+                ))
+            }
+            ReadRef => Exp::compound(format!("*{}", pop_one(stack)?.as_operand())),
+            FreezeRef => Exp::compound(format!("freeze({})", pop_one(stack)?.text)),
+            Call(idx, type_actuals) => {
+                let handle = module.function_handle_at(*idx);
+                let signature = module.function_signature_at(handle.signature);
+                let args = pop_args(stack, signature.arg_types.len())?;
+                Exp::values(
+                    format!(
+                        "{}{}({})",
+                        self.disassembler.function_name(*idx),
+                        self.disassembler
+                            .type_actuals(&module.locals_signature_at(*type_actuals).0)?,
+                        args.join(", ")
+                    ),
+                    signature.return_types.len(),
+                )
+            }
+            Pack(idx, type_actuals) => {
+                let fields = self.disassembler.field_names(*idx)?;
+                let values = pop_args(stack, fields.len())?;
+                let field_values: Vec<String> = fields
+                    .iter()
+                    .zip(values)
+                    .map(|(field, value)| format!("{}: {}", field, value))
+                    .collect();
+                Exp::compound(format!(
+                    "{}{{{}}}",
+                    self.struct_name(*idx, *type_actuals)?,
+                    field_values.join(", ")
+                ))
+            }
+            Unpack(idx, type_actuals) => {
+                let fields = self.disassembler.field_names(*idx)?;
+                let exp = pop_one(stack)?;
+                Exp {
+                    text: exp.text,
+                    arity: fields.len(),
+                    is_atom: false,
+                    unpack: Some((self.struct_name(*idx, *type_actuals)?, fields)),
+                }
+            }
+            Add | Sub | Mul | Mod | Div | BitOr | BitAnd | Xor | Or | And | Eq | Neq | Lt | Gt
+            | Le | Ge => {
+                let rhs = pop_one(stack)?;
+                let lhs = pop_one(stack)?;
+                Exp::compound(format!(
+                    "{} {} {}",
+                    lhs.as_operand(),
+                    binop(instr),
+                    rhs.as_operand()
+                ))
+            }
+            Not => Exp::compound(format!("!{}", pop_one(stack)?.as_operand())),
+            MutBorrowGlobal(idx, type_actuals) => {
+                self.builtin("borrow_global_mut", *idx, *type_actuals, stack)?
+            }
+            ImmBorrowGlobal(idx, type_actuals) => {
+                self.builtin("borrow_global", *idx, *type_actuals, stack)?
+            }
+            Exists(idx, type_actuals) => self.builtin("exists", *idx, *type_actuals, stack)?,
+            MoveFrom(idx, type_actuals) => self.builtin("move_from", *idx, *type_actuals, stack)?,
+            MoveToSender(idx, type_actuals) => {
+                let mut exp = self.builtin("move_to_sender", *idx, *type_actuals, stack)?;
+                exp.arity = 0;
+                exp
+            }
+            CreateAccount => Exp::values(format!("create_account({})", pop_one(stack)?.text), 0),
+            GetTxnGasUnitPrice => Exp::compound("get_txn_gas_unit_price()".to_string()),
+            GetTxnMaxGasUnits => Exp::compound("get_txn_max_gas_units()".to_string()),
+            GetGasRemaining => Exp::compound("get_gas_remaining()".to_string()),
+            GetTxnSenderAddress => Exp::compound("get_txn_sender()".to_string()),
+            GetTxnSequenceNumber => Exp::compound("get_txn_sequence_number()".to_string()),
+            GetTxnPublicKey => Exp::compound("get_txn_public_key()".to_string()),
+            _ => return Ok(false),
+        };
+        stack.push(exp);
+        Ok(true)
+    }
+
+    /// A builtin taking a struct type and one argument, like `exists<T>(addr)`.
+    fn builtin(
+        &self,
+        name: &str,
+        idx: StructDefinitionIndex,
+        type_actuals: LocalsSignatureIndex,
+        stack: &mut Vec<Exp>,
+    ) -> Result<Exp> {
+        let arg = pop_one(stack)?;
+        Ok(Exp::compound(format!(
+            "{}<{}>({})",
+            name,
+            self.struct_name(idx, type_actuals)?,
+            arg.text
+        )))
+    }
+
+    /// The name of a struct defined in the module with its type actuals, e.g. `T<u64>`.
+    fn struct_name(
+        &self,
+        idx: StructDefinitionIndex,
+        type_actuals: LocalsSignatureIndex,
+    ) -> Result<String> {
+        let module = self.disassembler.module();
+        Ok(format!(
+            "{}{}",
+            self.disassembler.struct_name(idx),
+            self.disassembler
+                .type_actuals(&module.locals_signature_at(type_actuals).0)?
+        ))
+    }
+}
+
+fn binop(instr: &Bytecode) -> &'static str {
+    match instr {
+        Bytecode::Add => "+",
+        Bytecode::Sub => "-",
+        Bytecode::Mul => "*",
+        Bytecode::Mod => "%",
+        Bytecode::Div => "/",
+        Bytecode::BitOr => "|",
+        Bytecode::BitAnd => "&",
+        Bytecode::Xor => "^",
+        Bytecode::Or => "||",
+        Bytecode::And => "&&",
+        Bytecode::Eq => "==",
+        Bytecode::Neq => "!=",
+        Bytecode::Lt => "<",
+        Bytecode::Gt => ">",
+        Bytecode::Le => "<=",
+        Bytecode::Ge => ">=",
+        _ => unreachable!("not a binary operator: {:?}", instr),
+    }
+}
+
+/// Pops an expression computing a single value.
+fn pop_one(stack: &mut Vec<Exp>) -> Result<Exp> {
+    match stack.pop() {
+        Some(exp) if exp.arity == 1 && exp.unpack.is_none() => Ok(exp),
+        Some(_) => bail!("expected an expression computing a single value"),
+        None => bail!("operand stack underflow"),
+    }
+}
+
+/// Pops the expressions computing the `count` arguments of a call, in argument order. A call
+/// returning several values can provide several arguments.
+fn pop_args(stack: &mut Vec<Exp>, count: usize) -> Result<Vec<String>> {
+    let mut args = vec![];
+    let mut num_values = 0;
+    while num_values < count {
+        let exp = match stack.pop() {
+            Some(exp) => exp,
+            None => bail!("operand stack underflow"),
+        };
+        if exp.unpack.is_some() {
+            bail!("unpacked values cannot be used as arguments");
+        }
+        num_values += exp.arity;
+        args.push(exp.text);
+    }
+    if num_values != count {
+        bail!("call arguments do not match the values on the stack");
+    }
+    args.reverse();
+    Ok(args)
+}
+
+/// Pops the operand of the statement at offset `pc`.
+fn pop_operand(stack: &mut Vec<Exp>, pc: usize) -> Result<Exp> {
+    pop_one(stack).map_err(|err| format_err!("{} at offset {}", err, pc))
+}
+
+fn expect_empty(stack: &[Exp], pc: usize) -> Result<()> {
+    if !stack.is_empty() {
+        bail!("statement at offset {} leaves values on the stack", pc);
+    }
+    Ok(())
+}
+
+fn indent(lines: Vec<String>) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|line| format!("    {}", line))
+        .collect()
+}
@@ -0,0 +1,185 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use disassembler::disassembler::{disassemble_module, disassemble_script, DisassemblerOptions};
+use ir_to_bytecode::{
+    compiler::{compile_module, compile_script},
+    parser::{parse_module, parse_script},
+};
+use stdlib::stdlib_modules;
+use types::account_address::AccountAddress;
+use vm::{access::ModuleAccess, file_format::CompiledModule};
+
+fn options() -> DisassemblerOptions {
+    DisassemblerOptions {
+        print_code_offsets: false,
+    }
+}
+
+fn compile(code: &str) -> CompiledModule {
+    compile_module(
+        AccountAddress::default(),
+        parse_module(code).unwrap(),
+        stdlib_modules(),
+    )
+    .unwrap()
+}
+
+/// Disassembles `module`, compiles the output again and checks that disassembling the result
+/// gives the same source. Returns the source.
+fn assert_round_trip(module: &CompiledModule, deps: &[CompiledModule]) -> String {
+    let source = disassemble_module(module, &options()).unwrap();
+    assert!(
+        !source.contains("unable to reconstruct"),
+        "unstructured code in:\n{}",
+        source
+    );
+    let recompiled = compile_module(
+        AccountAddress::default(),
+        parse_module(&source).unwrap_or_else(|err| panic!("{}\nin:\n{}", err, source)),
+        deps,
+    )
+    .unwrap_or_else(|err| panic!("{}\nin:\n{}", err, source));
+    assert_eq!(source, disassemble_module(&recompiled, &options()).unwrap());
+    source
+}
+
+fn stdlib_deps() -> Vec<CompiledModule> {
+    stdlib_modules()
+        .iter()
+        .map(|module| module.as_module().clone())
+        .collect()
+}
+
+#[test]
+fn round_trip_structs_and_control_flow() {
+    let module = compile(
+        "
+        module M {
+            import 0x0.LibraCoin;
+
+            resource Counter { value: u64 }
+            struct Pair { a: u64, b: bool }
+
+            public make(a: u64): Self.Pair {
+                return Pair{a: move(a), b: true};
+            }
+
+            public split(p: Self.Pair): u64 * bool {
+                let a: u64;
+                let b: bool;
+                Pair { a, b } = move(p);
+                return move(a), move(b);
+            }
+
+            public count(n: u64): u64 {
+                let i: u64;
+                let total: u64;
+                i = 0;
+                total = 0;
+                while (copy(i) < copy(n)) {
+                    if (copy(i) % 2 == 0) {
+                        total = copy(total) + copy(i);
+                    } else {
+                        total = copy(total) + 1;
+                    }
+                    i = copy(i) + 1;
+                }
+                loop {
+                    if (copy(total) > 10) {
+                        break;
+                    }
+                    total = copy(total) + 1;
+                }
+                return move(total);
+            }
+
+            public check(coin: &LibraCoin.T): u64 {
+                let v: u64;
+                v = LibraCoin.value(move(coin));
+                assert(copy(v) > 0, 42);
+                return move(v);
+            }
+
+            public publish() {
+                move_to_sender<Counter>(Counter{value: 0});
+                return;
+            }
+
+            public increment(addr: address) acquires Counter {
+                let c: &mut Self.Counter;
+                c = borrow_global_mut<Counter>(move(addr));
+                *(&mut copy(c).value) = *(&copy(c).value) + 1;
+                _ = move(c);
+                return;
+            }
+        }
+        ",
+    );
+    let source = assert_round_trip(&module, &stdlib_deps());
+    assert!(source.contains("resource Counter {"));
+    assert!(source.contains("public split(arg0: Self.Pair): u64 * bool {"));
+    assert!(source.contains("Pair { a: loc1, b: loc2 } = move(arg0);"));
+    assert!(source.contains("while (copy(loc1) < copy(arg0)) {"));
+    assert!(source.contains("} else {"));
+    assert!(source.contains("break;"));
+    assert!(source.contains("public increment(arg0: address) acquires Counter {"));
+}
+
+#[test]
+fn round_trip_stdlib_module() {
+    let deps = stdlib_deps();
+    let idx = deps
+        .iter()
+        .position(|module| module.name().as_str() == "LibraCoin")
+        .unwrap();
+    assert_round_trip(&deps[idx], &deps[..idx]);
+}
+
+#[test]
+fn round_trip_script() {
+    let script = compile_script(
+        AccountAddress::default(),
+        parse_script(
+            "
+            import 0x0.LibraAccount;
+            main(payee: address, amount: u64) {
+                LibraAccount.pay_from_sender(move(payee), move(amount));
+                return;
+            }
+            ",
+        )
+        .unwrap(),
+        stdlib_modules(),
+    )
+    .unwrap();
+    let source = disassemble_script(&script, &options()).unwrap();
+    assert!(source.contains("main(arg0: address, arg1: u64) {"));
+    assert!(source.contains("LibraAccount.pay_from_sender(move(arg0), move(arg1));"));
+
+    let recompiled = compile_script(
+        AccountAddress::default(),
+        parse_script(&source).unwrap(),
+        stdlib_modules(),
+    )
+    .unwrap();
+    assert_eq!(source, disassemble_script(&recompiled, &options()).unwrap());
+}
+
+#[test]
+fn code_offsets_are_printed_as_comments() {
+    let module = compile(
+        "
+        module M {
+            public id(x: u64): u64 {
+                return move(x);
+            }
+        }
+        ",
+    );
+    let source = disassemble_module(&module, &DisassemblerOptions::default()).unwrap();
+    assert!(source.contains("// 0: MoveLoc(arg0)"));
+    assert!(source.contains("// 1: Ret"));
+    // The listing is made of comments, so the output still compiles
+    parse_module(&source).unwrap();
+}
@@ -0,0 +1,22 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A unit-test runner for Move modules.
+//!
+//! Tests are functions of a module marked with `#[test]`. They take no arguments other than
+//! addresses, one fresh account per parameter, and return nothing. A test passes if its
+//! transaction executes successfully, or, if it is also marked with `#[expected_failure]`, if the
+//! transaction fails. `#[expected_failure(abort_code = c)]` requires it to abort with code `c`:
+//!
+//! ```text
+//! module M {
+//!     #[test]
+//!     #[expected_failure(abort_code = 7)]
+//!     aborts() {
+//!         abort 7;
+//!     }
+//! }
+//! ```
+
+pub mod test_report;
+pub mod test_runner;
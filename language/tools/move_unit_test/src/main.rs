@@ -0,0 +1,57 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_unit_test::test_runner::{TestConfig, TestRunner};
+use std::{fs, path::PathBuf, process};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "Move Unit Test",
+    author = "Libra",
+    about = "Runs the functions of Move modules marked with #[test]."
+)]
+struct Args {
+    /// Only run the tests whose name contains this string
+    #[structopt(short = "f", long = "filter")]
+    pub filter: Option<String>,
+    /// The balance of the sender and of every account given to a test
+    #[structopt(long = "balance", default_value = "1000000")]
+    pub balance: u64,
+    /// The maximum amount of gas each test can use
+    #[structopt(long = "max-gas", default_value = "100000")]
+    pub max_gas_amount: u64,
+    /// Paths to the Move IR modules, in dependency order
+    #[structopt(parse(from_os_str))]
+    pub module_paths: Vec<PathBuf>,
+}
+
+fn main() {
+    let args = Args::from_args();
+    let sources = args
+        .module_paths
+        .iter()
+        .map(|path| {
+            fs::read_to_string(path)
+                .unwrap_or_else(|err| panic!("Unable to read {:?}: {}", path, err))
+        })
+        .collect::<Vec<_>>();
+    let config = TestConfig {
+        balance: args.balance,
+        max_gas_amount: args.max_gas_amount,
+        filter: args.filter,
+        ..TestConfig::default()
+    };
+    let runner = match TestRunner::new(config, &sources) {
+        Ok(runner) => runner,
+        Err(err) => {
+            eprintln!("Unable to build the tests: {}", err);
+            process::exit(1);
+        }
+    };
+    let report = runner.run();
+    print!("{}", report);
+    if !report.is_success() {
+        process::exit(101);
+    }
+}
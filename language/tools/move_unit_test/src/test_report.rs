@@ -0,0 +1,103 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The results of a test run, printed like those of `cargo test`.
+
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TestOutcome {
+    Passed,
+    /// The test failed, for the given reason.
+    Failed(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct TestResult {
+    /// The name of the test, `Module::function`.
+    pub name: String,
+    pub outcome: TestOutcome,
+    /// The gas charged for the transaction running the test.
+    pub gas_used: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TestReport {
+    /// The results of the tests that ran, in the order they ran.
+    pub results: Vec<TestResult>,
+    /// The number of tests skipped by the filter.
+    pub filtered_out: usize,
+}
+
+impl TestReport {
+    pub fn passed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.outcome == TestOutcome::Passed)
+            .count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.passed()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+
+    /// The result of the test with the given name, if it ran.
+    pub fn result(&self, name: &str) -> Option<&TestResult> {
+        self.results.iter().find(|result| result.name == name)
+    }
+}
+
+impl fmt::Display for TestReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln!(f, "running {} tests", self.results.len())?;
+        for result in &self.results {
+            let status = match result.outcome {
+                TestOutcome::Passed => "ok",
+                TestOutcome::Failed(_) => "FAILED",
+            };
+            writeln!(
+                f,
+                "test {} ... {} (gas used: {})",
+                result.name, status, result.gas_used
+            )?;
+        }
+
+        let failures = self
+            .results
+            .iter()
+            .filter_map(|result| match &result.outcome {
+                TestOutcome::Passed => None,
+                TestOutcome::Failed(reason) => Some((&result.name, reason)),
+            })
+            .collect::<Vec<_>>();
+        if !failures.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "failures:")?;
+            for (name, reason) in &failures {
+                writeln!(f)?;
+                writeln!(f, "---- {} ----", name)?;
+                writeln!(f, "{}", reason)?;
+            }
+            writeln!(f)?;
+            writeln!(f, "failures:")?;
+            for (name, _) in &failures {
+                writeln!(f, "    {}", name)?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "test result: {}. {} passed; {} failed; {} filtered out",
+            if self.is_success() { "ok" } else { "FAILED" },
+            self.passed(),
+            self.failed(),
+            self.filtered_out
+        )
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Discovery and execution of the unit tests of Move modules.
+
+use crate::test_report::{TestOutcome, TestReport, TestResult};
+use bytecode_verifier::VerifiedModule;
+use config::config::VMPublishingOption;
+use failure::prelude::*;
+use ir_to_bytecode::{
+    compiler::{compile_module, compile_script},
+    parser::{
+        ast::{FunctionAttribute, FunctionBody, FunctionVisibility, ModuleDefinition, Type},
+        parse_module, parse_script,
+    },
+};
+use language_e2e_tests::{account::AccountData, executor::FakeExecutor};
+use stdlib::stdlib_modules;
+use types::{
+    transaction::{TransactionArgument, TransactionStatus},
+    vm_error::{StatusCode, VMStatus},
+};
+use vm::access::ModuleAccess;
+
+/// The accounts and gas available to every test.
+#[derive(Clone, Debug)]
+pub struct TestConfig {
+    /// The balance of the sender, and of every other account given to a test.
+    pub balance: u64,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    /// Only run the tests whose name contains this string.
+    pub filter: Option<String>,
+}
+
+impl Default for TestConfig {
+    fn default() -> Self {
+        Self {
+            balance: 1_000_000,
+            max_gas_amount: 100_000,
+            gas_unit_price: 1,
+            filter: None,
+        }
+    }
+}
+
+/// A function marked with `#[test]`.
+#[derive(Clone, Debug)]
+pub struct TestCase {
+    /// The module defining the test.
+    pub module: String,
+    pub function: String,
+    /// The number of `address` parameters of the test. Each one is given a fresh account.
+    pub accounts: usize,
+    /// `None` if the test must succeed, otherwise the abort code it must abort with, if any.
+    pub expected_failure: Option<Option<u64>>,
+}
+
+impl TestCase {
+    pub fn name(&self) -> String {
+        format!("{}::{}", self.module, self.function)
+    }
+}
+
+/// Runs the unit tests of a set of Move modules.
+///
+/// All modules are published under the address of the sender account, and may depend on the
+/// modules before them. Each test runs in its own transaction sent by the sender, against the
+/// genesis state with only the sender, the accounts of the test and the modules added to it.
+pub struct TestRunner {
+    config: TestConfig,
+    sender: AccountData,
+    modules: Vec<VerifiedModule>,
+    tests: Vec<TestCase>,
+}
+
+impl TestRunner {
+    /// Compiles the modules in `sources` and collects their tests.
+    pub fn new(config: TestConfig, sources: &[String]) -> Result<Self> {
+        let sender = AccountData::new(config.balance, 0);
+        let mut modules = vec![];
+        let mut tests = vec![];
+        for source in sources {
+            let mut module_def = parse_module(source)?;
+            tests.extend(collect_tests(&mut module_def)?);
+            let deps = stdlib_modules().iter().chain(&modules);
+            let compiled_module = compile_module(*sender.address(), module_def, deps)?;
+            let module = VerifiedModule::new(compiled_module)
+                .map_err(|(_, errors)| format_err!("Module failed to verify: {:?}", errors))?;
+            modules.push(module);
+        }
+        Ok(Self {
+            config,
+            sender,
+            modules,
+            tests,
+        })
+    }
+
+    /// All the tests found in the modules, whether filtered out or not.
+    pub fn tests(&self) -> &[TestCase] {
+        &self.tests
+    }
+
+    /// Runs the tests selected by the filter, in the order they are defined.
+    pub fn run(&self) -> TestReport {
+        let mut report = TestReport::default();
+        for test in &self.tests {
+            match &self.config.filter {
+                Some(filter) if !test.name().contains(filter.as_str()) => report.filtered_out += 1,
+                _ => report.results.push(self.run_test(test)),
+            }
+        }
+        report
+    }
+
+    fn run_test(&self, test: &TestCase) -> TestResult {
+        let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::Open);
+        executor.add_account_data(&self.sender);
+        for module in &self.modules {
+            executor.add_module(&module.self_id(), module.as_inner());
+        }
+        let mut args = vec![];
+        for _ in 0..test.accounts {
+            let account = AccountData::new(self.config.balance, 0);
+            executor.add_account_data(&account);
+            args.push(TransactionArgument::Address(*account.address()));
+        }
+
+        let script = match self.test_script(test) {
+            Ok(script) => script,
+            Err(err) => {
+                return TestResult {
+                    name: test.name(),
+                    outcome: TestOutcome::Failed(format!("failed to build the test: {}", err)),
+                    gas_used: 0,
+                }
+            }
+        };
+        let txn = self.sender.account().create_signed_txn_with_args(
+            script,
+            args,
+            self.sender.sequence_number(),
+            self.config.max_gas_amount,
+            self.config.gas_unit_price,
+        );
+        let output = executor.execute_transaction(txn);
+        let outcome = match output.status() {
+            TransactionStatus::Keep(status) => check_status(status, test.expected_failure),
+            TransactionStatus::Discard(status) => {
+                TestOutcome::Failed(format!("transaction discarded: {:?}", status))
+            }
+        };
+        TestResult {
+            name: test.name(),
+            outcome,
+            gas_used: output.gas_used(),
+        }
+    }
+
+    /// A serialized script calling `test` with the accounts passed to it as arguments.
+    fn test_script(&self, test: &TestCase) -> Result<Vec<u8>> {
+        let formals = (0..test.accounts)
+            .map(|idx| format!("account{}: address", idx))
+            .collect::<Vec<_>>();
+        let actuals = (0..test.accounts)
+            .map(|idx| format!("move(account{})", idx))
+            .collect::<Vec<_>>();
+        let source = format!(
+            "import 0x{}.{};\nmain({}) {{\n  {}.{}({});\n  return;\n}}\n",
+            self.sender.address(),
+            test.module,
+            formals.join(", "),
+            test.module,
+            test.function,
+            actuals.join(", "),
+        );
+        let script = parse_script(&source)?;
+        let deps = stdlib_modules().iter().chain(&self.modules);
+        let compiled_script = compile_script(*self.sender.address(), script, deps)?;
+        let mut blob = vec![];
+        compiled_script.serialize(&mut blob)?;
+        Ok(blob)
+    }
+}
+
+/// Returns the tests defined in `module_def`. The test functions are made public so
+/// that the test scripts can call them.
+fn collect_tests(module_def: &mut ModuleDefinition) -> Result<Vec<TestCase>> {
+    let module = module_def.name.as_inner().to_string();
+    let mut tests = vec![];
+    for (name, function) in &mut module_def.functions {
+        let is_test = function.attributes.contains(&FunctionAttribute::Test);
+        let expected_failure = function
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                FunctionAttribute::ExpectedFailure(code) => Some(*code),
+                FunctionAttribute::Test => None,
+            });
+        if !is_test {
+            ensure!(
+                expected_failure.is_none(),
+                "{}::{} expects a failure but is not a test",
+                module,
+                name
+            );
+            continue;
+        }
+
+        let signature = &function.signature;
+        ensure!(
+            signature.return_type.is_empty() && signature.type_formals.is_empty(),
+            "Test {}::{} must not return values or take type parameters",
+            module,
+            name
+        );
+        ensure!(
+            signature
+                .formals
+                .iter()
+                .all(|(_, type_)| *type_ == Type::Address),
+            "Test {}::{} can only take address parameters",
+            module,
+            name
+        );
+        ensure!(
+            function.body != FunctionBody::Native,
+            "Test {}::{} cannot be native",
+            module,
+            name
+        );
+        function.visibility = FunctionVisibility::Public;
+        tests.push(TestCase {
+            module: module.clone(),
+            function: name.as_inner().to_string(),
+            accounts: signature.formals.len(),
+            expected_failure,
+        });
+    }
+    Ok(tests)
+}
+
+fn check_status(status: &VMStatus, expected_failure: Option<Option<u64>>) -> TestOutcome {
+    let executed = status.major_status == StatusCode::EXECUTED;
+    let abort_code = if status.major_status == StatusCode::ABORTED {
+        status.sub_status
+    } else {
+        None
+    };
+    match expected_failure {
+        None if executed => TestOutcome::Passed,
+        None => TestOutcome::Failed(failure_message(status)),
+        Some(_) if executed => {
+            TestOutcome::Failed("test succeeded but a failure was expected".to_string())
+        }
+        Some(None) => TestOutcome::Passed,
+        Some(Some(expected)) if abort_code == Some(expected) => TestOutcome::Passed,
+        Some(Some(expected)) => TestOutcome::Failed(format!(
+            "{}, but an abort with code {} was expected",
+            failure_message(status),
+            expected
+        )),
+    }
+}
+
+fn failure_message(status: &VMStatus) -> String {
+    match (status.major_status, status.sub_status) {
+        (StatusCode::ABORTED, Some(code)) => format!("test aborted with code {}", code),
+        _ => format!("test failed with {:?}", status),
+    }
+}
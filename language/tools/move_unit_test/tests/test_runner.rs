@@ -0,0 +1,150 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_unit_test::{
+    test_report::TestOutcome,
+    test_runner::{TestConfig, TestRunner},
+};
+
+const MODULE: &str = "
+module M {
+    import 0x0.LibraAccount;
+
+    resource R { value: u64 }
+
+    public value(): u64 acquires R {
+        let r: &mut Self.R;
+        r = borrow_global<R>(get_txn_sender());
+        return *(&move(r).value);
+    }
+
+    #[test]
+    passes() {
+        assert(1 + 1 == 2, 1);
+        return;
+    }
+
+    #[test]
+    fails() {
+        assert(1 + 1 == 3, 42);
+        return;
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 42)]
+    aborts_as_expected() {
+        abort 42;
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 42)]
+    aborts_with_wrong_code() {
+        abort 7;
+    }
+
+    #[test]
+    #[expected_failure]
+    fails_as_expected() {
+        let x: u64;
+        x = 0;
+        x = 1 / move(x);
+        return;
+    }
+
+    #[test]
+    publishes() acquires R {
+        move_to_sender<R>(R { value: 5 });
+        assert(Self.value() == 5, 1);
+        return;
+    }
+
+    #[test]
+    #[expected_failure]
+    starts_from_genesis() acquires R {
+        // The resource published by the previous test does not exist anymore
+        assert(Self.value() == 5, 1);
+        return;
+    }
+
+    #[test]
+    pays(payee: address) {
+        LibraAccount.pay_from_sender(copy(payee), 100);
+        assert(LibraAccount.balance(move(payee)) == 1000100, 1);
+        return;
+    }
+}
+";
+
+fn run(filter: Option<&str>) -> move_unit_test::test_report::TestReport {
+    let config = TestConfig {
+        filter: filter.map(str::to_string),
+        ..TestConfig::default()
+    };
+    TestRunner::new(config, &[MODULE.to_string()])
+        .expect("tests must build")
+        .run()
+}
+
+#[test]
+fn runs_tests() {
+    let report = run(None);
+    assert_eq!(report.results.len(), 8);
+    for name in &[
+        "M::passes",
+        "M::aborts_as_expected",
+        "M::fails_as_expected",
+        "M::publishes",
+        "M::starts_from_genesis",
+        "M::pays",
+    ] {
+        assert_eq!(
+            report.result(name).unwrap().outcome,
+            TestOutcome::Passed,
+            "{}",
+            name
+        );
+    }
+    for name in &["M::fails", "M::aborts_with_wrong_code"] {
+        assert_ne!(
+            report.result(name).unwrap().outcome,
+            TestOutcome::Passed,
+            "{}",
+            name
+        );
+    }
+    assert!(report.results.iter().all(|result| result.gas_used > 0));
+    assert!(!report.is_success());
+    assert!(report
+        .to_string()
+        .contains("test result: FAILED. 6 passed; 2 failed; 0 filtered out"));
+}
+
+#[test]
+fn filters_tests() {
+    let report = run(Some("aborts"));
+    assert_eq!(report.results.len(), 2);
+    assert_eq!(report.filtered_out, 6);
+}
+
+#[test]
+fn rejects_invalid_tests() {
+    let module = "
+module M {
+    #[test]
+    takes_u64(x: u64) {
+        return;
+    }
+}
+";
+    assert!(TestRunner::new(TestConfig::default(), &[module.to_string()]).is_err());
+
+    let module = "
+module M {
+    #[expected_failure]
+    not_a_test() {
+        return;
+    }
+}
+";
+    assert!(TestRunner::new(TestConfig::default(), &[module.to_string()]).is_err());
+}
@@ -8,10 +8,14 @@ use crate::{
     summaries,
 };
 use rand::{rngs::StdRng, FromEntropy, Rng, SeedableRng};
-use vm::file_format::{
-    AddressPoolIndex, ByteArrayPoolIndex, Bytecode, CodeOffset, CompiledModuleMut,
-    FieldDefinitionIndex, FunctionHandleIndex, FunctionSignature, LocalIndex, LocalsSignatureIndex,
-    SignatureToken, StructDefinitionIndex, TableIndex, UserStringIndex,
+use std::collections::HashMap;
+use vm::{
+    file_format::{
+        AddressPoolIndex, ByteArrayPoolIndex, Bytecode, CodeOffset, CompiledModuleMut,
+        FieldDefinitionIndex, FunctionHandleIndex, FunctionSignature, LocalIndex,
+        LocalsSignatureIndex, SignatureToken, StructDefinitionIndex, TableIndex, UserStringIndex,
+    },
+    gas_schedule::InstructionKey,
 };
 
 /// This type represents bytecode instructions that take a `LocalIndex`
@@ -94,10 +98,13 @@ enum StackEffect {
 /// This generator has:
 /// - `instructions`: A list of bytecode instructions to use for generation
 /// - `rng`: A random number generator for uniform random choice of next instruction
+/// - `weights`: The relative weights of the instructions, keyed by opcode. When empty, the next
+///   instruction is chosen uniformly
 #[derive(Debug, Clone)]
 pub struct BytecodeGenerator {
     instructions: Vec<(StackEffect, BytecodeType)>,
     rng: StdRng,
+    weights: HashMap<InstructionKey, f32>,
 }
 
 impl BytecodeGenerator {
@@ -242,9 +249,42 @@ impl BytecodeGenerator {
         Self {
             instructions,
             rng: generator,
+            weights: HashMap::new(),
         }
     }
 
+    /// Set the relative weights used to choose the next instruction. Instructions without a
+    /// weight have a weight of 1.
+    pub fn set_weights(&mut self, weights: HashMap<InstructionKey, f32>) {
+        self.weights = weights;
+    }
+
+    /// Choose one of the `candidates`, according to the weights of the instructions.
+    fn choose(&mut self, candidates: &[(StackEffect, Bytecode)]) -> Bytecode {
+        if self.weights.is_empty() {
+            let index = self.rng.gen_range(0, candidates.len());
+            return candidates[index].1.clone();
+        }
+        let weights: Vec<f32> = candidates
+            .iter()
+            .map(|(_, instruction)| {
+                *self
+                    .weights
+                    .get(&InstructionKey::new(instruction))
+                    .unwrap_or(&1.0)
+            })
+            .collect();
+        let mut choice = self.rng.gen_range(0.0, weights.iter().sum::<f32>());
+        for ((_, instruction), weight) in candidates.iter().zip(weights) {
+            if choice < weight {
+                return instruction.clone();
+            }
+            choice -= weight;
+        }
+        // Rounding errors can leave a tiny remainder after the last candidate
+        candidates[candidates.len() - 1].1.clone()
+    }
+
     /// Given an `AbstractState`, `state`, and a the number of locals the function has,
     /// this function returns a list of instructions whose preconditions are satisfied for
     /// the state.
@@ -351,7 +391,6 @@ impl BytecodeGenerator {
             1.0
         };
         debug!("Pr[add] = {:?}", prob_add);
-        let next_instruction_index;
         if self.rng.gen_range(0.0, 1.0) <= prob_add {
             let add_candidates: Vec<(StackEffect, Bytecode)> = candidates
                 .iter()
@@ -365,8 +404,7 @@ impl BytecodeGenerator {
             if add_candidates.is_empty() {
                 panic!("Could not find valid candidate");
             }
-            next_instruction_index = self.rng.gen_range(0, add_candidates.len());
-            add_candidates[next_instruction_index].1.clone()
+            self.choose(&add_candidates)
         } else {
             let sub_candidates: Vec<(StackEffect, Bytecode)> = candidates
                 .iter()
@@ -380,8 +418,7 @@ impl BytecodeGenerator {
             if sub_candidates.is_empty() {
                 panic!("Could not find valid candidate");
             }
-            next_instruction_index = self.rng.gen_range(0, sub_candidates.len());
-            sub_candidates[next_instruction_index].1.clone()
+            self.choose(&sub_candidates)
         }
     }
 
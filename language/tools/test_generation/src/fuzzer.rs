@@ -0,0 +1,372 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coverage-guided fuzzing of the VM with generated bytecode.
+//!
+//! Every generated module that passes the verifier is loaded into a fresh VM, and each of its
+//! functions is executed twice with the same random arguments. A run is reported as a violation
+//! when the VM panics, or when the two runs disagree on their result or on the gas they used.
+//!
+//! The instructions executed by all runs so far are fed back into the generator: instructions
+//! that rarely ran, or that ran in functions ending with an error, are chosen more often.
+
+use crate::bytecode_generator::BytecodeGenerator;
+use bytecode_verifier::VerifiedModule;
+use cost_synthesis::module_generator::ModuleBuilder;
+use language_e2e_tests::data_store::FakeDataStore;
+use rand::{rngs::StdRng, FromEntropy, Rng, SeedableRng};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
+};
+use types::{
+    account_address::AccountAddress,
+    byte_array::ByteArray,
+    identifier::Identifier,
+    vm_error::{StatusCode, VMStatus},
+};
+use vm::{
+    access::ModuleAccess,
+    file_format::{
+        Bytecode, CompiledModuleMut, FunctionDefinition, FunctionSignature, SignatureToken,
+    },
+    gas_schedule::{GasAlgebra, InstructionKey},
+    transaction_metadata::TransactionMetadata,
+    vm_string::VMString,
+};
+use vm_cache_map::Arena;
+use vm_runtime::{
+    code_cache::module_cache::{ModuleCache, VMModuleCache},
+    coverage::CoverageMap,
+    txn_executor::TransactionExecutor,
+};
+use vm_runtime_types::value::Value;
+
+/// The maximum length of the generated byte array and string arguments.
+const MAX_ARGUMENT_LENGTH: usize = 32;
+
+/// A behavior of the VM that should never happen, whatever the code it runs.
+#[derive(Debug)]
+pub enum Violation {
+    /// The VM panicked instead of returning an error.
+    Panic {
+        function: Identifier,
+        message: String,
+    },
+    /// Two runs with the same arguments returned different results.
+    NonDeterministic {
+        function: Identifier,
+        first: String,
+        second: String,
+    },
+    /// Two runs with the same arguments used different amounts of gas.
+    GasMismatch {
+        function: Identifier,
+        first: u64,
+        second: u64,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::Panic { function, message } => {
+                write!(f, "{}: VM panicked: {}", function, message)
+            }
+            Violation::NonDeterministic {
+                function,
+                first,
+                second,
+            } => write!(
+                f,
+                "{}: non-deterministic result: {} then {}",
+                function, first, second
+            ),
+            Violation::GasMismatch {
+                function,
+                first,
+                second,
+            } => write!(
+                f,
+                "{}: non-deterministic gas: {} then {}",
+                function, first, second
+            ),
+        }
+    }
+}
+
+/// The results of a fuzzing session.
+#[derive(Debug, Default)]
+pub struct FuzzReport {
+    pub modules_generated: u64,
+    pub modules_verified: u64,
+    pub functions_executed: u64,
+    /// The number of times each instruction was executed, keyed by instruction name.
+    pub instructions_executed: BTreeMap<String, u64>,
+    /// The number of runs ending with each status.
+    pub statuses: BTreeMap<StatusCode, u64>,
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for FuzzReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Modules generated: {}, verified: {}, functions executed: {}",
+            self.modules_generated, self.modules_verified, self.functions_executed
+        )?;
+        writeln!(f, "Instructions executed:")?;
+        for (instruction, count) in &self.instructions_executed {
+            writeln!(f, "  {:<24} {}", instruction, count)?;
+        }
+        writeln!(f, "Statuses:")?;
+        for (status, count) in &self.statuses {
+            writeln!(f, "  {:<24} {}", format!("{:?}", status), count)?;
+        }
+        writeln!(f, "Violations: {}", self.violations.len())?;
+        for violation in &self.violations {
+            writeln!(f, "  {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of executing a function once.
+struct Run {
+    result: Result<Vec<Value>, VMStatus>,
+    gas_used: u64,
+    coverage: CoverageMap,
+}
+
+/// Generates modules and executes them, steering the generation toward the instructions that
+/// were exercised the least.
+pub struct Fuzzer {
+    generator: Rc<RefCell<BytecodeGenerator>>,
+    rng: StdRng,
+    /// The number of times each instruction was executed.
+    hits: HashMap<InstructionKey, u64>,
+    /// The number of runs ending with an error that executed each instruction.
+    error_hits: HashMap<InstructionKey, u64>,
+    report: FuzzReport,
+}
+
+impl Fuzzer {
+    pub fn new(seed: Option<[u8; 32]>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::from_seed(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            generator: Rc::new(RefCell::new(BytecodeGenerator::new(seed))),
+            rng,
+            hits: HashMap::new(),
+            error_hits: HashMap::new(),
+            report: FuzzReport::default(),
+        }
+    }
+
+    /// Generates and executes `iterations` modules and returns what was found.
+    pub fn run(mut self, iterations: usize) -> FuzzReport {
+        for _ in 0..iterations {
+            self.fuzz_one();
+        }
+        self.report
+    }
+
+    fn fuzz_one(&mut self) {
+        let generator = Rc::clone(&self.generator);
+        let module = ModuleBuilder::new(
+            1,
+            Some(Box::new(
+                move |locals: &[SignatureToken],
+                      signature: &FunctionSignature,
+                      module: CompiledModuleMut| {
+                    generator.borrow_mut().generate(locals, signature, module)
+                },
+            )),
+        )
+        .materialize_unverified();
+        self.report.modules_generated += 1;
+        let module = match VerifiedModule::new(module) {
+            Ok(module) => module,
+            Err((_, errors)) => {
+                debug!("Module verification failed: {:?}", errors);
+                return;
+            }
+        };
+        self.report.modules_verified += 1;
+
+        for function_def in module.function_defs() {
+            let handle = module.function_handle_at(function_def.function);
+            let name = module.identifier_at(handle.name).to_owned();
+            let signature = module.function_signature_at(handle.signature);
+            let args = match self.arguments(&signature.arg_types) {
+                Some(args) => args,
+                None => continue,
+            };
+            let return_count = signature.return_types.len();
+            self.report.functions_executed += 1;
+            self.execute(&module, function_def, name, return_count, args);
+        }
+        self.update_weights();
+    }
+
+    /// Executes a function twice and checks that both runs agree.
+    fn execute(
+        &mut self,
+        module: &VerifiedModule,
+        function_def: &FunctionDefinition,
+        name: Identifier,
+        return_count: usize,
+        args: Vec<Value>,
+    ) {
+        let first = match catch_panic(|| run_function(module, &name, return_count, args.clone())) {
+            Ok(run) => run,
+            Err(message) => {
+                self.report.violations.push(Violation::Panic {
+                    function: name,
+                    message,
+                });
+                return;
+            }
+        };
+        let second = match catch_panic(|| run_function(module, &name, return_count, args)) {
+            Ok(run) => run,
+            Err(message) => {
+                self.report.violations.push(Violation::Panic {
+                    function: name,
+                    message,
+                });
+                return;
+            }
+        };
+
+        let status = match &first.result {
+            Ok(_) => StatusCode::EXECUTED,
+            Err(status) => status.major_status,
+        };
+        *self.report.statuses.entry(status).or_insert(0) += 1;
+        if let Some(offsets) = first.coverage.function(&module.self_id(), &name) {
+            let code = &function_def.code.code;
+            for (offset, count) in offsets {
+                let instruction = &code[*offset as usize];
+                let key = InstructionKey::new(instruction);
+                *self.hits.entry(key).or_insert(0) += count;
+                if first.result.is_err() {
+                    *self.error_hits.entry(key).or_insert(0) += 1;
+                }
+                *self
+                    .report
+                    .instructions_executed
+                    .entry(instruction_name(instruction))
+                    .or_insert(0) += count;
+            }
+        }
+
+        if first.result != second.result {
+            self.report.violations.push(Violation::NonDeterministic {
+                function: name,
+                first: format!("{:?}", first.result),
+                second: format!("{:?}", second.result),
+            });
+        } else if first.gas_used != second.gas_used {
+            self.report.violations.push(Violation::GasMismatch {
+                function: name,
+                first: first.gas_used,
+                second: second.gas_used,
+            });
+        }
+    }
+
+    /// Random arguments of the types `arg_types`, or `None` if one of them can't be built from
+    /// outside the VM.
+    fn arguments(&mut self, arg_types: &[SignatureToken]) -> Option<Vec<Value>> {
+        arg_types
+            .iter()
+            .map(|token| {
+                let len = self.rng.gen_range(0, MAX_ARGUMENT_LENGTH);
+                match token {
+                    SignatureToken::Bool => Some(Value::bool(self.rng.gen())),
+                    SignatureToken::U64 => Some(Value::u64(self.rng.gen())),
+                    SignatureToken::Address => {
+                        Some(Value::address(AccountAddress::new(self.rng.gen())))
+                    }
+                    SignatureToken::ByteArray => Some(Value::byte_array(ByteArray::new(
+                        (0..len).map(|_| self.rng.gen()).collect(),
+                    ))),
+                    SignatureToken::String => Some(Value::string(VMString::from(
+                        (0..len).map(|_| self.rng.gen::<char>()).collect::<String>(),
+                    ))),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Favors the instructions that ran the least, and those that ran in functions ending with
+    /// an error.
+    fn update_weights(&mut self) {
+        let weights = self
+            .hits
+            .iter()
+            .map(|(key, hits)| {
+                let error_hits = self.error_hits.get(key).cloned().unwrap_or(0);
+                let weight = (1.0 + error_hits as f32).sqrt() / (1.0 + *hits as f32).sqrt();
+                (*key, weight)
+            })
+            .collect();
+        self.generator.borrow_mut().set_weights(weights);
+    }
+}
+
+/// Executes `function` of `module` with `args` in a fresh VM, and returns the `return_count`
+/// values it returned along with the gas it used and the instructions it executed.
+fn run_function(
+    module: &VerifiedModule,
+    function: &Identifier,
+    return_count: usize,
+    args: Vec<Value>,
+) -> Run {
+    let allocator = Arena::new();
+    let module_cache = VMModuleCache::new(&allocator);
+    module_cache.cache_module(module.clone());
+    let data_cache = FakeDataStore::default();
+    let txn_data = TransactionMetadata::default();
+    let max_gas = txn_data.max_gas_amount().get();
+
+    let mut vm = TransactionExecutor::new(&module_cache, &data_cache, txn_data);
+    vm.enable_coverage();
+    let result = vm
+        .execute_function(&module.self_id(), function, args)
+        .and_then(|()| (0..return_count).map(|_| vm.pop_stack()).collect());
+    Run {
+        result,
+        gas_used: max_gas - vm.remaining_gas().get(),
+        coverage: vm.take_coverage().unwrap_or_default(),
+    }
+}
+
+/// Runs `f`, turning a panic into its message.
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        }
+    })
+}
+
+/// The name of an instruction, without its arguments.
+fn instruction_name(instruction: &Bytecode) -> String {
+    let name = format!("{:?}", instruction);
+    match name.find('(') {
+        Some(paren) => name[..paren].to_string(),
+        None => name,
+    }
+}
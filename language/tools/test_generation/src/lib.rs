@@ -5,6 +5,7 @@ pub mod abstract_state;
 pub mod bytecode_generator;
 pub mod common;
 pub mod control_flow_graph;
+pub mod fuzzer;
 pub mod summaries;
 pub mod transitions;
 
@@ -17,6 +18,7 @@ extern crate env_logger;
 use bytecode_generator::BytecodeGenerator;
 use bytecode_verifier::VerifiedModule;
 use cost_synthesis::module_generator::ModuleBuilder;
+use fuzzer::Fuzzer;
 use vm::{
     file_format::{Bytecode, CompiledModuleMut, FunctionSignature, SignatureToken},
     CompiledModule,
@@ -66,3 +68,15 @@ pub fn run_generation(iterations: usize) {
         (valid_programs as f64) / (iterations as f64) * 100.0
     );
 }
+
+/// Generate and execute 'iterations' modules, steering generation toward the instructions
+/// that were executed the least, and report the VM invariant violations found.
+pub fn run_fuzzer(iterations: usize, seed: Option<[u8; 32]>) {
+    env_logger::init();
+
+    let report = Fuzzer::new(seed).run(iterations);
+    println!("{}", report);
+    if !report.violations.is_empty() {
+        std::process::exit(1);
+    }
+}
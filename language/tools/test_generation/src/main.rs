@@ -1,8 +1,38 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use test_generation::run_generation;
+use structopt::StructOpt;
+use test_generation::{run_fuzzer, run_generation};
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "Bytecode Test Generator",
+    author = "Libra",
+    about = "Generate random bytecode to test the verifier and the VM."
+)]
+struct Args {
+    /// The number of modules to generate
+    #[structopt(short = "i", long = "iterations", default_value = "1000")]
+    pub iterations: usize,
+    /// Execute the generated modules and steer generation toward unexercised instructions,
+    /// instead of only running the verifier
+    #[structopt(short = "f", long = "fuzz")]
+    pub fuzz: bool,
+    /// The seed of the generator, as a number
+    #[structopt(short = "s", long = "seed")]
+    pub seed: Option<u64>,
+}
 
 pub fn main() {
-    run_generation(1000);
+    let args = Args::from_args();
+    if args.fuzz {
+        let seed = args.seed.map(|seed| {
+            let mut bytes = [0u8; 32];
+            bytes[..8].copy_from_slice(&seed.to_le_bytes());
+            bytes
+        });
+        run_fuzzer(args.iterations, seed);
+    } else {
+        run_generation(args.iterations);
+    }
 }
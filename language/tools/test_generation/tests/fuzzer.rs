@@ -0,0 +1,16 @@
+extern crate test_generation;
+use test_generation::fuzzer::Fuzzer;
+
+#[test]
+fn fuzzer_executes_generated_modules() {
+    let report = Fuzzer::new(Some([0; 32])).run(10);
+    assert_eq!(report.modules_generated, 10);
+    assert!(report.modules_verified <= report.modules_generated);
+    // The seed is fixed, so the generated modules are too: some of them must make it through
+    // the verifier and run, or the fuzzer is not exercising the VM at all.
+    assert!(report.modules_verified > 0, "{}", report);
+    assert!(report.functions_executed > 0, "{}", report);
+    assert!(report.violations.is_empty(), "{}", report);
+    let runs: u64 = report.statuses.values().sum();
+    assert_eq!(runs, report.functions_executed);
+}
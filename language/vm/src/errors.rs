@@ -4,6 +4,7 @@
 use crate::IndexKind;
 use types::{
     account_address::AccountAddress,
+    language_storage::ModuleId,
     transaction::TransactionStatus,
     vm_error::{StatusCode, VMStatus},
 };
@@ -33,6 +34,7 @@ pub const ESEQUENCE_NUMBER_TOO_OLD: u64 = 3; // transaction sequence number is t
 pub const ESEQUENCE_NUMBER_TOO_NEW: u64 = 4; // transaction sequence number is too new
 pub const EACCOUNT_DOES_NOT_EXIST: u64 = 5; // transaction sender's account does not exist
 pub const ECANT_PAY_GAS_DEPOSIT: u64 = 6; // insufficient balance to pay for gas deposit
+pub const ETRANSACTION_EXPIRED: u64 = 8; // transaction expired before the current block time
 
 /// Generic error codes. These codes don't have any special meaning for the VM, but they are useful
 /// conventions for debugging
@@ -80,6 +82,8 @@ pub fn convert_prologue_runtime_error(err: &VMStatus, txn_sender: &AccountAddres
             Some(ECANT_PAY_GAS_DEPOSIT) => {
                 VMStatus::new(StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE)
             }
+            // Expiration time is before the time of the block being executed
+            Some(ETRANSACTION_EXPIRED) => VMStatus::new(StatusCode::TRANSACTION_EXPIRED),
             // This should never happen...
             _ => err.clone(),
         }
@@ -88,6 +92,16 @@ pub fn convert_prologue_runtime_error(err: &VMStatus, txn_sender: &AccountAddres
     }
 }
 
+/// Builds the status of an `Abort` with `code` raised at `location` by a function in `module`.
+///
+/// The status records the aborting module, so that the meaning of the abort code can be looked up
+/// later on.
+pub fn abort_error(location: Location, module: &ModuleId, code: u64) -> VMStatus {
+    vm_error(location, StatusCode::ABORTED)
+        .with_sub_status(code)
+        .with_abort_module(module.clone())
+}
+
 pub fn vm_error(location: Location, err: StatusCode) -> VMStatus {
     let msg = format!("At location {:#?}", location);
     VMStatus::new(err).with_message(msg)
@@ -1,11 +1,12 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-//! This module lays out the basic abstract costing schedule for bytecode instructions.
+//! This module lays out the basic abstract costing schedule for bytecode instructions and native
+//! functions.
 //!
-//! It is important to note that the cost schedule defined in this file does not track hashing
-//! operations or other native operations; the cost of each native operation will be returned by the
-//! native function itself.
+//! The cost table is stored on chain in the `GasSchedule.T` resource of the Association account,
+//! and is read by the VM at the start of every block. The table defined in this file is the one
+//! published at genesis, and the one used when no table has been published yet.
 use crate::{
     file_format::{
         AddressPoolIndex, ByteArrayPoolIndex, Bytecode, FieldDefinitionIndex, FunctionHandleIndex,
@@ -13,13 +14,24 @@ use crate::{
     },
     serializer::serialize_instruction,
 };
+use canonical_serialization::{
+    CanonicalDeserialize, CanonicalDeserializer, CanonicalSerialize, CanonicalSerializer,
+    SimpleDeserializer,
+};
+use failure::prelude::*;
 use lazy_static::lazy_static;
 use std::{
     collections::HashMap,
     ops::{Add, Div, Mul, Sub},
     u64,
 };
-use types::transaction::MAX_TRANSACTION_SIZE_IN_BYTES;
+use types::{
+    access_path::{AccessPath, Accesses},
+    account_config::{association_address, core_code_address},
+    identifier::{IdentStr, Identifier},
+    language_storage::StructTag,
+    transaction::MAX_TRANSACTION_SIZE_IN_BYTES,
+};
 
 /// The underlying carrier for gas-related units and costs. Data with this type should not be
 /// manipulated directly, but instead be manipulated using the newtype wrappers defined around
@@ -142,6 +154,27 @@ define_gas_unit! {
 #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
 pub struct InstructionKey(pub u8);
 
+/// The native functions costed by the cost table. Natives are charged their cost once per unit of
+/// work they perform, e.g. per byte hashed.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+pub enum NativeCostIndex {
+    SHA2_256 = 0,
+    SHA3_256 = 1,
+    ED25519_VERIFY = 2,
+    ED25519_THRESHOLD_VERIFY = 3,
+    ADDRESS_TO_BYTES = 4,
+    U64_TO_BYTES = 5,
+    BYTEARRAY_CONCAT = 6,
+    LENGTH = 7,
+    EMPTY = 8,
+    BORROW = 9,
+    PUSH_BACK = 10,
+}
+
+/// The number of native functions in `NativeCostIndex`.
+pub const NUMBER_OF_NATIVE_FUNCTIONS: usize = 11;
+
 lazy_static! {
     /// The cost per-byte written to global storage.
     /// TODO: Fill this in with a proper number once it's determined.
@@ -194,10 +227,12 @@ lazy_static! {
 /// The cost tables, keyed by the serialized form of the bytecode instruction.  We use the
 /// serialized form as opposed to the instruction enum itself as the key since this will be the
 /// on-chain representation of bytecode instructions in the future.
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CostTable {
     pub compute_table: HashMap<InstructionKey, GasUnits<GasCarrier>>,
     pub memory_table: HashMap<InstructionKey, GasUnits<GasCarrier>>,
+    /// The cost per unit of work of each native function, indexed by `NativeCostIndex`.
+    pub native_table: Vec<GasUnits<GasCarrier>>,
 }
 
 impl InstructionKey {
@@ -211,7 +246,7 @@ impl InstructionKey {
 }
 
 impl CostTable {
-    pub fn new(instrs: Vec<(Bytecode, u64, u64)>) -> Self {
+    pub fn new(instrs: Vec<(Bytecode, u64, u64)>, natives: Vec<(NativeCostIndex, u64)>) -> Self {
         let mut compute_table = HashMap::new();
         let mut memory_table = HashMap::new();
         let mut instructions_covered = 0;
@@ -227,9 +262,22 @@ impl CostTable {
             instructions_covered == NUMBER_OF_BYTECODE_INSTRUCTIONS,
             "all instructions must be in the cost table"
         );
+        let mut native_table = vec![None; NUMBER_OF_NATIVE_FUNCTIONS];
+        for (native, cost) in natives.into_iter() {
+            native_table[native as usize] = Some(GasUnits::new(cost));
+        }
+        debug_assert!(
+            native_table.iter().all(Option::is_some),
+            "all natives must be in the cost table"
+        );
+        let native_table = native_table
+            .into_iter()
+            .map(|cost| cost.unwrap_or_else(|| GasUnits::new(0)))
+            .collect();
         Self {
             compute_table,
             memory_table,
+            native_table,
         }
     }
 
@@ -256,10 +304,128 @@ impl CostTable {
         assume!(compute_cost.is_some());
         compute_cost.unwrap().map2(size_provider, Mul::mul)
     }
+
+    /// Statically cost a bytecode instruction with this table.
+    ///
+    /// Don't take into account current stack or memory size. Don't track whether references are
+    /// to global or local storage.
+    pub fn instruction_cost(
+        &self,
+        instr: &Bytecode,
+        size_provider: AbstractMemorySize<GasCarrier>,
+    ) -> GasCost {
+        GasCost {
+            instruction_gas: self.comp_gas(instr, size_provider),
+            memory_gas: self.memory_gas(instr, size_provider),
+        }
+    }
+
+    /// The cost of running `native` on `size` units of work.
+    pub fn native_gas(
+        &self,
+        native: NativeCostIndex,
+        size: AbstractMemorySize<GasCarrier>,
+    ) -> GasUnits<GasCarrier> {
+        // CostTable initialization checks that every native is included in the native_table
+        self.native_table[native as usize].map2(size, Mul::mul)
+    }
+
+    /// Decodes the cost table held by the blob of a `GasSchedule.T` resource.
+    pub fn from_resource_blob(blob: &[u8]) -> Result<Self> {
+        let table_bytes = SimpleDeserializer::new(blob).decode_bytes()?;
+        SimpleDeserializer::deserialize(&table_bytes)
+    }
+}
+
+/// The cost table is serialized as the list of `(instruction key, compute cost, memory cost)`
+/// ordered by instruction key, followed by the list of native costs ordered by `NativeCostIndex`.
+impl CanonicalSerialize for CostTable {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        let mut instrs = self
+            .compute_table
+            .iter()
+            .map(|(code, compute_cost)| {
+                let memory_cost = self.memory_table.get(code).map_or(0, |cost| cost.get());
+                (code.0, compute_cost.get(), memory_cost)
+            })
+            .collect::<Vec<_>>();
+        instrs.sort();
+        let natives = self
+            .native_table
+            .iter()
+            .map(|cost| cost.get())
+            .collect::<Vec<_>>();
+        serializer.encode_vec(&instrs)?.encode_vec(&natives)?;
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for CostTable {
+    fn deserialize(deserializer: &mut impl CanonicalDeserializer) -> Result<Self> {
+        let instrs: Vec<(u8, u64, u64)> = deserializer.decode_vec()?;
+        let natives: Vec<u64> = deserializer.decode_vec()?;
+        // Instruction keys are the opcodes, which are numbered from 1 without gaps
+        ensure!(
+            instrs
+                .iter()
+                .map(|(code, _, _)| usize::from(*code))
+                .eq(1..=NUMBER_OF_BYTECODE_INSTRUCTIONS),
+            "cost table must contain every instruction exactly once, ordered by instruction key"
+        );
+        ensure!(
+            natives.len() == NUMBER_OF_NATIVE_FUNCTIONS,
+            "cost table must contain {} native costs, found {}",
+            NUMBER_OF_NATIVE_FUNCTIONS,
+            natives.len()
+        );
+        let mut compute_table = HashMap::new();
+        let mut memory_table = HashMap::new();
+        for (code, compute_cost, memory_cost) in instrs {
+            compute_table.insert(InstructionKey(code), GasUnits::new(compute_cost));
+            memory_table.insert(InstructionKey(code), GasUnits::new(memory_cost));
+        }
+        Ok(Self {
+            compute_table,
+            memory_table,
+            native_table: natives.into_iter().map(GasUnits::new).collect(),
+        })
+    }
+}
+
+lazy_static! {
+    static ref GAS_SCHEDULE_MODULE_NAME: Identifier = Identifier::new("GasSchedule").unwrap();
+    static ref GAS_SCHEDULE_STRUCT_NAME: Identifier = Identifier::new("T").unwrap();
+}
+
+pub fn gas_schedule_module_name() -> &'static IdentStr {
+    &*GAS_SCHEDULE_MODULE_NAME
+}
+
+pub fn gas_schedule_struct_name() -> &'static IdentStr {
+    &*GAS_SCHEDULE_STRUCT_NAME
+}
+
+pub fn gas_schedule_tag() -> StructTag {
+    StructTag {
+        name: gas_schedule_struct_name().to_owned(),
+        address: core_code_address(),
+        module: gas_schedule_module_name().to_owned(),
+        type_params: vec![],
+    }
+}
+
+/// The access path of the `GasSchedule.T` resource, which is published under the Association
+/// account.
+pub fn gas_schedule_access_path() -> AccessPath {
+    AccessPath::new(
+        association_address(),
+        AccessPath::resource_access_vec(&gas_schedule_tag(), &Accesses::empty()),
+    )
 }
 
 lazy_static! {
-    static ref GAS_SCHEDULE: CostTable = {
+    /// The cost table published at genesis.
+    pub static ref DEFAULT_GAS_SCHEDULE: CostTable = {
         use Bytecode::*;
         // Arguments to the instructions don't matter -- these will be removed in the
         // `encode_instruction` function.
@@ -324,7 +490,24 @@ lazy_static! {
             (Gt, 46, 1),
             (Pack(StructDefinitionIndex::new(0), NO_TYPE_ACTUALS), 73, 1),
         ];
-        CostTable::new(instrs)
+        // The cost of each native per unit of work: per byte of input for the hashes, signature
+        // checks and concatenation, per byte of output for the conversions, and per call for the
+        // vector operations.
+        // TODO: Fill in the signature and vector costs with synthesized numbers.
+        let natives = vec![
+            (NativeCostIndex::SHA2_256, 30),
+            (NativeCostIndex::SHA3_256, 30),
+            (NativeCostIndex::ED25519_VERIFY, 35),
+            (NativeCostIndex::ED25519_THRESHOLD_VERIFY, 30),
+            (NativeCostIndex::ADDRESS_TO_BYTES, 1),
+            (NativeCostIndex::U64_TO_BYTES, 1),
+            (NativeCostIndex::BYTEARRAY_CONCAT, 1),
+            (NativeCostIndex::LENGTH, 30),
+            (NativeCostIndex::EMPTY, 30),
+            (NativeCostIndex::BORROW, 30),
+            (NativeCostIndex::PUSH_BACK, 30),
+        ];
+        CostTable::new(instrs, natives)
     };
 }
 
@@ -337,7 +520,7 @@ pub struct GasCost {
     pub memory_gas: GasUnits<GasCarrier>,
 }
 
-/// Statically cost a bytecode instruction.
+/// Statically cost a bytecode instruction with the default cost table.
 ///
 /// Don't take into account current stack or memory size. Don't track whether references are to
 /// global or local storage.
@@ -345,10 +528,7 @@ pub fn static_cost_instr(
     instr: &Bytecode,
     size_provider: AbstractMemorySize<GasCarrier>,
 ) -> GasCost {
-    GasCost {
-        instruction_gas: GAS_SCHEDULE.comp_gas(instr, size_provider),
-        memory_gas: GAS_SCHEDULE.memory_gas(instr, size_provider),
-    }
+    DEFAULT_GAS_SCHEDULE.instruction_cost(instr, size_provider)
 }
 
 /// Computes the number of words rounded up
@@ -3,6 +3,7 @@
 
 use crate::gas_schedule::{AbstractMemorySize, GasAlgebra, GasCarrier, GasPrice, GasUnits};
 use crypto::ed25519::{compat, Ed25519PublicKey};
+use std::time::Duration;
 use types::{account_address::AccountAddress, transaction::SignedTransaction};
 
 pub struct TransactionMetadata {
@@ -12,6 +13,7 @@ pub struct TransactionMetadata {
     pub max_gas_amount: GasUnits<GasCarrier>,
     pub gas_unit_price: GasPrice<GasCarrier>,
     pub transaction_size: AbstractMemorySize<GasCarrier>,
+    pub expiration_time: Duration,
 }
 
 impl TransactionMetadata {
@@ -23,6 +25,7 @@ impl TransactionMetadata {
             max_gas_amount: GasUnits::new(txn.max_gas_amount()),
             gas_unit_price: GasPrice::new(txn.gas_unit_price()),
             transaction_size: AbstractMemorySize::new(txn.raw_txn_bytes_len() as u64),
+            expiration_time: txn.expiration_time(),
         }
     }
 
@@ -49,6 +52,10 @@ impl TransactionMetadata {
     pub fn transaction_size(&self) -> AbstractMemorySize<GasCarrier> {
         self.transaction_size
     }
+
+    pub fn expiration_time(&self) -> Duration {
+        self.expiration_time
+    }
 }
 
 impl Default for TransactionMetadata {
@@ -61,6 +68,7 @@ impl Default for TransactionMetadata {
             max_gas_amount: GasUnits::new(100_000_000),
             gas_unit_price: GasPrice::new(0),
             transaction_size: AbstractMemorySize::new(0),
+            expiration_time: Duration::from_secs(u64::max_value()),
         }
     }
 }
@@ -0,0 +1,29 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::errors::{abort_error, vm_error, Location};
+use types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    vm_error::StatusCode,
+};
+
+#[test]
+fn abort_error_records_location_and_module() {
+    let module = ModuleId::new(
+        AccountAddress::new([1; 32]),
+        Identifier::new("LibraAccount").unwrap(),
+    );
+    let status = abort_error(Location::default(), &module, 7);
+    assert_eq!(status.major_status, StatusCode::ABORTED);
+    assert_eq!(status.sub_status, Some(7));
+    assert!(status.message.as_ref().unwrap().starts_with("At location "));
+    assert_eq!(status.abort_module, Some(module));
+}
+
+#[test]
+fn other_errors_have_no_abort_module() {
+    let status = vm_error(Location::default(), StatusCode::ABORTED);
+    assert_eq!(status.abort_module, None);
+    let status = vm_error(Location::default(), StatusCode::ARITHMETIC_ERROR);
+    assert_eq!(status.abort_module, None);
+}
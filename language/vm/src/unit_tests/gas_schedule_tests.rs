@@ -0,0 +1,39 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::gas_schedule::{CostTable, DEFAULT_GAS_SCHEDULE};
+use canonical_serialization::{CanonicalSerializer, SimpleDeserializer, SimpleSerializer};
+
+#[test]
+fn cost_table_round_trip() {
+    let blob: Vec<u8> = SimpleSerializer::serialize(&*DEFAULT_GAS_SCHEDULE).unwrap();
+    let cost_table: CostTable = SimpleDeserializer::deserialize(&blob).unwrap();
+    assert_eq!(cost_table, *DEFAULT_GAS_SCHEDULE);
+}
+
+#[test]
+fn cost_table_from_resource_blob() {
+    let table_bytes: Vec<u8> = SimpleSerializer::serialize(&*DEFAULT_GAS_SCHEDULE).unwrap();
+    // A `GasSchedule.T` resource holds the serialized table in its only field
+    let mut serializer = SimpleSerializer::<Vec<u8>>::new();
+    serializer.encode_bytes(&table_bytes).unwrap();
+    let cost_table = CostTable::from_resource_blob(&serializer.get_output()).unwrap();
+    assert_eq!(cost_table, *DEFAULT_GAS_SCHEDULE);
+}
+
+#[test]
+fn cost_table_missing_instruction() {
+    let mut cost_table = DEFAULT_GAS_SCHEDULE.clone();
+    let code = *cost_table.compute_table.keys().next().unwrap();
+    cost_table.compute_table.remove(&code);
+    let blob: Vec<u8> = SimpleSerializer::serialize(&cost_table).unwrap();
+    assert!(SimpleDeserializer::deserialize::<CostTable>(&blob).is_err());
+}
+
+#[test]
+fn cost_table_missing_native() {
+    let mut cost_table = DEFAULT_GAS_SCHEDULE.clone();
+    cost_table.native_table.pop();
+    let blob: Vec<u8> = SimpleSerializer::serialize(&cost_table).unwrap();
+    assert!(SimpleDeserializer::deserialize::<CostTable>(&blob).is_err());
+}
@@ -3,6 +3,8 @@
 
 mod binary_tests;
 mod deserializer_tests;
+mod errors_tests;
 mod fixture_tests;
+mod gas_schedule_tests;
 mod number_tests;
 mod vm_string_tests;
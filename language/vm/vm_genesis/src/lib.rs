@@ -1,6 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use canonical_serialization::SimpleSerializer;
 use config::config::{VMConfig, VMPublishingOption};
 use crypto::{ed25519::*, HashValue};
 use failure::prelude::*;
@@ -13,7 +14,7 @@ use stdlib::{
     stdlib_modules,
     transaction_scripts::{
         CREATE_ACCOUNT_TXN_BODY, MINT_TXN_BODY, PEER_TO_PEER_TRANSFER_TXN_BODY,
-        ROTATE_AUTHENTICATION_KEY_TXN_BODY,
+        ROTATE_AUTHENTICATION_KEY_TXN_BODY, UPDATE_GAS_SCHEDULE_TXN_BODY,
     },
 };
 use types::{
@@ -28,7 +29,11 @@ use types::{
     },
     validator_public_keys::ValidatorPublicKeys,
 };
-use vm::{access::ModuleAccess, transaction_metadata::TransactionMetadata};
+use vm::{
+    access::ModuleAccess,
+    gas_schedule::{CostTable, DEFAULT_GAS_SCHEDULE},
+    transaction_metadata::TransactionMetadata,
+};
 use vm_cache_map::Arena;
 use vm_runtime::{
     code_cache::{
@@ -36,7 +41,9 @@ use vm_runtime::{
         module_cache::{BlockModuleCache, VMModuleCache},
     },
     data_cache::BlockDataCache,
-    txn_executor::{TransactionExecutor, ACCOUNT_MODULE, BLOCK_MODULE, COIN_MODULE},
+    txn_executor::{
+        TransactionExecutor, ACCOUNT_MODULE, BLOCK_MODULE, COIN_MODULE, GAS_SCHEDULE_MODULE,
+    },
 };
 use vm_runtime_types::value::Value;
 
@@ -171,6 +178,7 @@ lazy_static! {
     static ref ROTATE_AUTHENTICATION_KEY_TXN: Vec<u8> =
         { compile_script(&ROTATE_AUTHENTICATION_KEY_TXN_BODY) };
     static ref MINT_TXN: Vec<u8> = { compile_script(&MINT_TXN_BODY) };
+    static ref UPDATE_GAS_SCHEDULE_TXN: Vec<u8> = { compile_script(&UPDATE_GAS_SCHEDULE_TXN_BODY) };
     static ref GENESIS_ACCOUNT: Accounts = {
         let mut account = Accounts::empty();
         account.new_account();
@@ -243,6 +251,19 @@ pub fn encode_mint_program(sender: &AccountAddress, amount: u64) -> Program {
     )
 }
 
+/// Encode a program that replaces the gas schedule with `cost_table`. Only the Association can
+/// send it, and the new costs apply from the next block on.
+pub fn encode_update_gas_schedule_program(cost_table: &CostTable) -> Program {
+    Program::new(
+        UPDATE_GAS_SCHEDULE_TXN.clone(),
+        vec![],
+        vec![TransactionArgument::ByteArray(ByteArray::new(
+            SimpleSerializer::<Vec<u8>>::serialize(cost_table)
+                .expect("Serializing a cost table cannot fail"),
+        ))],
+    )
+}
+
 /// Returns a user friendly mnemonic for the transaction type if the transaction is
 /// for a known, white listed, transaction.
 pub fn get_transaction_name(code: &[u8]) -> String {
@@ -254,6 +275,8 @@ pub fn get_transaction_name(code: &[u8]) -> String {
         return "mint_transaction".to_string();
     } else if code == &ROTATE_AUTHENTICATION_KEY_TXN[..] {
         return "rotate_authentication_key_transaction".to_string();
+    } else if code == &UPDATE_GAS_SCHEDULE_TXN[..] {
+        return "update_gas_schedule_transaction".to_string();
     }
     "<unknown transaction>".to_string()
 }
@@ -264,6 +287,7 @@ pub fn allowing_script_hashes() -> Vec<[u8; SCRIPT_HASH_LENGTH]> {
         PEER_TO_PEER_TXN.clone(),
         ROTATE_AUTHENTICATION_KEY_TXN.clone(),
         CREATE_ACCOUNT_TXN.clone(),
+        UPDATE_GAS_SCHEDULE_TXN.clone(),
     ]
     .into_iter()
     .map(|s| *HashValue::from_sha3_256(&s).as_ref())
@@ -318,6 +342,10 @@ pub fn encode_genesis_transaction_with_validator(
     let vm_cache = VMModuleCache::new(&arena);
     let genesis_addr = account_config::association_address();
     let genesis_auth_key = ByteArray::new(AccountAddress::from_public_key(&public_key).to_vec());
+    let genesis_cost_table = ByteArray::new(
+        SimpleSerializer::<Vec<u8>>::serialize(&*DEFAULT_GAS_SCHEDULE)
+            .expect("Serializing a cost table cannot fail"),
+    );
 
     let genesis_write_set = {
         let fake_fetcher = FakeFetcher::new(modules.iter().map(|m| m.as_inner().clone()).collect());
@@ -338,6 +366,13 @@ pub fn encode_genesis_transaction_with_validator(
             txn_executor
                 .execute_function(&COIN_MODULE, &INITIALIZE, vec![])
                 .unwrap();
+            txn_executor
+                .execute_function(
+                    &GAS_SCHEDULE_MODULE,
+                    &INITIALIZE,
+                    vec![Value::byte_array(genesis_cost_table)],
+                )
+                .unwrap();
 
             txn_executor
                 .execute_function(
@@ -7,12 +7,13 @@ use crate::{
         module_cache::{BlockModuleCache, ModuleCache, VMModuleCache},
         script_cache::ScriptCache,
     },
-    counters::{report_block_count, report_execution_status},
+    counters::{report_block_count, report_execution_status, report_gas_schedule_fallback},
     data_cache::BlockDataCache,
     process_txn::{execute::ExecutedTransaction, validate::ValidationMode, ProcessTransaction},
 };
 use config::config::VMPublishingOption;
-use logger::prelude::*;
+use crypto::hash::CryptoHash;
+use logger::{log_context::LogContext, prelude::*};
 use rayon::prelude::*;
 use state_view::StateView;
 use types::{
@@ -22,6 +23,10 @@ use types::{
     vm_error::{StatusCode, VMStatus},
     write_set::WriteSet,
 };
+use vm::{
+    errors::VMResult,
+    gas_schedule::{gas_schedule_access_path, CostTable, DEFAULT_GAS_SCHEDULE},
+};
 use vm_cache_map::Arena;
 
 pub fn execute_block<'alloc>(
@@ -56,6 +61,16 @@ pub fn execute_block<'alloc>(
         ValidationMode::Executing
     };
 
+    // The gas schedule is read once per block, so an update only applies from the next block on.
+    let gas_schedule = match load_gas_schedule(data_view) {
+        Ok(gas_schedule) => gas_schedule,
+        Err(vm_status) => {
+            return txn_block
+                .iter()
+                .map(|_| ExecutedTransaction::discard_error_output(vm_status.clone()))
+                .collect();
+        }
+    };
     let module_cache = BlockModuleCache::new(code_cache, ModuleFetcherImpl::new(data_view));
     let mut data_cache = BlockDataCache::new(data_view);
     let mut result = vec![];
@@ -70,14 +85,17 @@ pub fn execute_block<'alloc>(
 
     for transaction in signature_verified_block {
         let output = match transaction {
-            Ok(t) => transaction_flow(
-                t,
-                &module_cache,
-                script_cache,
-                &data_cache,
-                mode,
-                publishing_option,
-            ),
+            Ok(t) => LogContext::new().txn_hash(t.hash()).scope(|| {
+                transaction_flow(
+                    t,
+                    &module_cache,
+                    script_cache,
+                    &data_cache,
+                    &gas_schedule,
+                    mode,
+                    publishing_option,
+                )
+            }),
             Err(vm_status) => ExecutedTransaction::discard_error_output(vm_status),
         };
         report_execution_status(output.status());
@@ -110,6 +128,7 @@ fn transaction_flow<'alloc, P>(
     module_cache: P,
     script_cache: &ScriptCache<'alloc>,
     data_cache: &BlockDataCache<'_>,
+    gas_schedule: &CostTable,
     mode: ValidationMode,
     publishing_option: &VMPublishingOption,
 ) -> TransactionOutput
@@ -117,7 +136,7 @@ where
     P: ModuleCache<'alloc>,
 {
     let arena = Arena::new();
-    let process_txn = ProcessTransaction::new(txn, &module_cache, data_cache, &arena);
+    let process_txn = ProcessTransaction::new(txn, &module_cache, data_cache, gas_schedule, &arena);
 
     let validated_txn = match process_txn.validate(mode, publishing_option) {
         Ok(validated_txn) => validated_txn,
@@ -144,3 +163,30 @@ where
     };
     output
 }
+
+/// Reads the gas schedule published on chain. Before genesis, and in tests that start from an
+/// empty state, no gas schedule has been published yet and the default one is used instead.
+///
+/// A published gas schedule that cannot be decoded is ignored in favor of the default one, so
+/// that a bad update does not halt the chain and can be fixed with another update. Every such
+/// fallback is logged and counted, so that a bad update is noticed.
+pub(crate) fn load_gas_schedule(data_view: &dyn StateView) -> VMResult<CostTable> {
+    match data_view.get(&gas_schedule_access_path()) {
+        Ok(Some(blob)) => match CostTable::from_resource_blob(&blob) {
+            Ok(gas_schedule) => Ok(gas_schedule),
+            Err(err) => {
+                error!(
+                    "[VM] Invalid gas schedule on chain, using the default one: {}",
+                    err
+                );
+                report_gas_schedule_fallback();
+                Ok(DEFAULT_GAS_SCHEDULE.clone())
+            }
+        },
+        Ok(None) => Ok(DEFAULT_GAS_SCHEDULE.clone()),
+        Err(err) => {
+            error!("[VM] Failed to read the gas schedule: {}", err);
+            Err(VMStatus::new(StatusCode::STORAGE_ERROR))
+        }
+    }
+}
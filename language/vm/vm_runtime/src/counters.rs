@@ -16,6 +16,7 @@ const TXN_EXECUTION_DISCARD: &str = "txn.execution.discard";
 const TXN_VERIFICATION_SUCCESS: &str = "txn.verification.success";
 const TXN_VERIFICATION_FAIL: &str = "txn.verification.fail";
 const TXN_BLOCK_COUNT: &str = "txn.block.count";
+const GAS_SCHEDULE_FALLBACK: &str = "gas_schedule.fallback";
 
 lazy_static::lazy_static! {
     // the main metric (move_vm)
@@ -23,6 +24,7 @@ lazy_static::lazy_static! {
 
     static ref VERIFIED_TRANSACTION: IntCounter = VM_COUNTERS.counter(TXN_VERIFICATION_SUCCESS);
     static ref BLOCK_TRANSACTION_COUNT: IntGauge = VM_COUNTERS.gauge(TXN_BLOCK_COUNT);
+    static ref GAS_SCHEDULE_FALLBACK_COUNT: IntCounter = VM_COUNTERS.counter(GAS_SCHEDULE_FALLBACK);
 }
 
 /// Reports the number of transactions in a block.
@@ -33,6 +35,12 @@ pub fn report_block_count(count: usize) {
     }
 }
 
+/// Reports that the gas schedule published on chain could not be decoded and the default one was
+/// used instead.
+pub fn report_gas_schedule_fallback() {
+    GAS_SCHEDULE_FALLBACK_COUNT.inc();
+}
+
 /// Reports the result of a transaction execution.
 ///
 /// Counters are prefixed with `TXN_EXECUTION_KEEP` or `TXN_EXECUTION_DISCARD`.
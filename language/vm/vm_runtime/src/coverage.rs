@@ -0,0 +1,123 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording of the instructions executed by the VM.
+//!
+//! A single `TransactionExecutor` records its instructions once `enable_coverage` is called on it.
+//! To record every transaction the VM executes, call `start_recording` first: executors created
+//! afterwards on the same thread record their instructions, and hand them over when the
+//! transaction produces its output. Transactions discarded during validation are not recorded.
+
+use std::{cell::RefCell, collections::BTreeMap};
+use types::{
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+};
+use vm::file_format::CodeOffset;
+
+/// The number of times each instruction was executed, per function.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CoverageMap {
+    // Keyed by module, then by function, so that recording an instruction of a function that
+    // already ran looks it up without allocating.
+    modules: BTreeMap<ModuleId, BTreeMap<Identifier, BTreeMap<CodeOffset, u64>>>,
+}
+
+impl CoverageMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one execution of the instruction at `offset` in `function` of `module`.
+    pub fn record(&mut self, module: &ModuleId, function: &IdentStr, offset: CodeOffset) {
+        if !self.modules.contains_key(module) {
+            self.modules.insert(module.clone(), BTreeMap::new());
+        }
+        let functions = self
+            .modules
+            .get_mut(module)
+            .expect("module was just inserted");
+        if !functions.contains_key(function) {
+            functions.insert(function.to_owned(), BTreeMap::new());
+        }
+        *functions
+            .get_mut(function)
+            .expect("function was just inserted")
+            .entry(offset)
+            .or_insert(0) += 1;
+    }
+
+    /// Adds the executions recorded in `other` to this map.
+    pub fn merge(&mut self, other: CoverageMap) {
+        for (module, functions) in other.modules {
+            let self_functions = self.modules.entry(module).or_insert_with(BTreeMap::new);
+            for (function, offsets) in functions {
+                let counts = self_functions.entry(function).or_insert_with(BTreeMap::new);
+                for (offset, count) in offsets {
+                    *counts.entry(offset).or_insert(0) += count;
+                }
+            }
+        }
+    }
+
+    /// The execution counts of the instructions of `function` in `module` that ran at least once.
+    pub fn function(
+        &self,
+        module: &ModuleId,
+        function: &IdentStr,
+    ) -> Option<&BTreeMap<CodeOffset, u64>> {
+        self.modules
+            .get(module)
+            .and_then(|functions| functions.get(function))
+    }
+
+    /// All the functions that ran, with the execution counts of their instructions.
+    pub fn functions(
+        &self,
+    ) -> impl Iterator<Item = (&ModuleId, &Identifier, &BTreeMap<CodeOffset, u64>)> {
+        self.modules.iter().flat_map(|(module, functions)| {
+            functions
+                .iter()
+                .map(move |(function, offsets)| (module, function, offsets))
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<CoverageMap>> = RefCell::new(None);
+}
+
+/// Starts recording the instructions executed by the transactions run on the current thread.
+pub fn start_recording() {
+    RECORDING.with(|recording| {
+        let mut recording = recording.borrow_mut();
+        if recording.is_none() {
+            *recording = Some(CoverageMap::new());
+        }
+    })
+}
+
+/// Stops recording on the current thread, and returns the instructions executed since
+/// `start_recording`.
+pub fn stop_recording() -> CoverageMap {
+    RECORDING
+        .with(|recording| recording.borrow_mut().take())
+        .unwrap_or_default()
+}
+
+pub(crate) fn is_recording() -> bool {
+    RECORDING.with(|recording| recording.borrow().is_some())
+}
+
+/// Adds the instructions executed by a transaction to the recording, if one is in progress.
+pub(crate) fn record_transaction(coverage: CoverageMap) {
+    RECORDING.with(|recording| {
+        if let Some(recording) = recording.borrow_mut().as_mut() {
+            recording.merge(coverage);
+        }
+    })
+}
@@ -8,7 +8,10 @@ use crate::{
     IndexKind,
 };
 use std::{fmt, marker::PhantomData};
-use types::vm_error::{StatusCode, VMStatus};
+use types::{
+    language_storage::TypeTag,
+    vm_error::{StatusCode, VMStatus},
+};
 use vm::errors::*;
 use vm_runtime_types::value::{Locals, Value};
 
@@ -43,14 +46,19 @@ where
         }
     }
 
-    pub fn push_call(&mut self, function: FunctionRef<'txn>) -> VMResult<()> {
+    pub fn push_call(
+        &mut self,
+        function: FunctionRef<'txn>,
+        type_actual_tags: Vec<TypeTag>,
+    ) -> VMResult<()> {
         let mut locals = Locals::new(function.local_count());
         let arg_count = function.arg_count();
         for i in 0..arg_count {
             locals.store_loc(arg_count - i - 1, self.pop()?)?;
         }
         if self.function_stack.len() < (FUNCTION_STACK_SIZE_LIMIT as usize) {
-            self.function_stack.push(Frame::new(function, locals));
+            self.function_stack
+                .push(Frame::new(function, type_actual_tags, locals));
             Ok(())
         } else {
             Err(vm_error(self.location()?, StatusCode::CALL_STACK_OVERFLOW))
@@ -169,7 +177,8 @@ where
 
     pub fn push_frame(&mut self, func: FunctionRef<'txn>) -> VMResult<()> {
         if self.function_stack.len() < (FUNCTION_STACK_SIZE_LIMIT as usize) {
-            self.function_stack.push(Frame::new(func, Locals::new(0)));
+            self.function_stack
+                .push(Frame::new(func, vec![], Locals::new(0)));
             Ok(())
         } else {
             Err(vm_error(self.location()?, StatusCode::CALL_STACK_OVERFLOW))
@@ -3,6 +3,7 @@
 
 use crate::loaded_data::{function::FunctionReference, loaded_module::LoadedModule};
 use std::{fmt, marker::PhantomData};
+use types::{identifier::IdentStr, language_storage::TypeTag};
 use vm::{
     errors::{Location, VMResult},
     file_format::{Bytecode, CodeOffset, LocalIndex},
@@ -13,6 +14,7 @@ pub struct Frame<'txn, F: 'txn> {
     pc: u16,
     locals: Locals,
     function: F,
+    type_actual_tags: Vec<TypeTag>,
     phantom: PhantomData<&'txn F>,
 }
 
@@ -20,11 +22,12 @@ impl<'txn, F> Frame<'txn, F>
 where
     F: FunctionReference<'txn>,
 {
-    pub fn new(function: F, locals: Locals) -> Self {
+    pub fn new(function: F, type_actual_tags: Vec<TypeTag>, locals: Locals) -> Self {
         Frame {
             pc: 0,
             locals,
             function,
+            type_actual_tags,
             phantom: PhantomData,
         }
     }
@@ -45,6 +48,15 @@ where
         self.function.module()
     }
 
+    pub fn function_name(&self) -> &'txn IdentStr {
+        self.function.name()
+    }
+
+    /// The types the type parameters of the function are instantiated with.
+    pub fn type_actual_tags(&self) -> &[TypeTag] {
+        &self.type_actual_tags
+    }
+
     pub fn copy_loc(&self, idx: LocalIndex) -> VMResult<Value> {
         self.locals.copy_loc(idx as usize)
     }
@@ -5,9 +5,12 @@
 use types::{
     access_path::{AccessPath, Accesses},
     account_address::AccountAddress,
-    language_storage::{ResourceKey, StructTag},
+    language_storage::{ResourceKey, StructTag, TypeTag},
+};
+use vm::{
+    access::ModuleAccess,
+    file_format::{SignatureToken, StructDefinitionIndex},
 };
-use vm::{access::ModuleAccess, file_format::StructDefinitionIndex};
 
 /// Get the StructTag for a StructDefinition defined in a published module.
 pub fn resource_storage_key(module: &impl ModuleAccess, idx: StructDefinitionIndex) -> StructTag {
@@ -25,6 +28,37 @@ pub fn resource_storage_key(module: &impl ModuleAccess, idx: StructDefinitionInd
     }
 }
 
+/// Get the TypeTag of the type `token` in `module`, where `TypeParameter(i)` stands for the type
+/// `type_actual_tags[i]`. Returns `None` for references, which have no TypeTag.
+pub fn type_tag_from_token(
+    module: &impl ModuleAccess,
+    token: &SignatureToken,
+    type_actual_tags: &[TypeTag],
+) -> Option<TypeTag> {
+    Some(match token {
+        SignatureToken::Bool => TypeTag::Bool,
+        SignatureToken::U64 => TypeTag::U64,
+        SignatureToken::String => TypeTag::String,
+        SignatureToken::ByteArray => TypeTag::ByteArray,
+        SignatureToken::Address => TypeTag::Address,
+        SignatureToken::Struct(idx, type_actuals) => {
+            let struct_handle = module.struct_handle_at(*idx);
+            let struct_module = module.module_handle_at(struct_handle.module);
+            TypeTag::Struct(StructTag {
+                module: module.identifier_at(struct_module.name).into(),
+                address: *module.address_at(struct_module.address),
+                name: module.identifier_at(struct_handle.name).into(),
+                type_params: type_actuals
+                    .iter()
+                    .map(|token| type_tag_from_token(module, token, type_actual_tags))
+                    .collect::<Option<_>>()?,
+            })
+        }
+        SignatureToken::TypeParameter(idx) => type_actual_tags.get(*idx as usize)?.clone(),
+        SignatureToken::Reference(_) | SignatureToken::MutableReference(_) => return None,
+    })
+}
+
 /// Get the AccessPath to a resource stored under `address` with type name `tag`
 pub fn create_access_path(address: &AccountAddress, tag: StructTag) -> AccessPath {
     let resource_tag = ResourceKey::new(*address, tag);
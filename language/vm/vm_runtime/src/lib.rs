@@ -121,6 +121,7 @@ mod runtime;
 mod unit_tests;
 
 pub mod code_cache;
+pub mod coverage;
 pub mod data_cache;
 pub mod identifier;
 pub mod loaded_data;
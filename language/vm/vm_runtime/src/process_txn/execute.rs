@@ -203,6 +203,20 @@ where
                 },
             }
         }
+        TransactionPayload::BlockMetadata(block_metadata) => {
+            let VerifiedTransactionState {
+                mut txn_executor, ..
+            } = txn_state.expect("block metadata transactions should always have associated state");
+            // A block whose metadata is rejected by the `Block` module cannot be applied on top
+            // of the current state, so there is nothing to keep.
+            match txn_executor.run_block_prologue(&block_metadata) {
+                Ok(output) => output,
+                Err(err) => {
+                    error!("[VM] Error in block prologue: {:?}", err);
+                    ExecutedTransaction::discard_error_output(err)
+                }
+            }
+        }
     }
 }
 
@@ -5,7 +5,7 @@ use crate::{
 use config::config::VMPublishingOption;
 use std::marker::PhantomData;
 use types::transaction::SignatureCheckedTransaction;
-use vm::errors::VMResult;
+use vm::{errors::VMResult, gas_schedule::CostTable};
 use vm_cache_map::Arena;
 
 pub mod execute;
@@ -24,6 +24,7 @@ where
     txn: SignatureCheckedTransaction,
     module_cache: P,
     data_cache: &'txn dyn RemoteCache,
+    gas_schedule: &'txn CostTable,
     allocator: &'txn Arena<LoadedModule>,
     phantom: PhantomData<&'alloc ()>,
 }
@@ -33,17 +34,20 @@ where
     'alloc: 'txn,
     P: ModuleCache<'alloc>,
 {
-    /// Creates a new instance of `ProcessTransaction`.
+    /// Creates a new instance of `ProcessTransaction`. Gas is charged according to
+    /// `gas_schedule`.
     pub fn new(
         txn: SignatureCheckedTransaction,
         module_cache: P,
         data_cache: &'txn dyn RemoteCache,
+        gas_schedule: &'txn CostTable,
         allocator: &'txn Arena<LoadedModule>,
     ) -> Self {
         Self {
             txn,
             module_cache,
             data_cache,
+            gas_schedule,
             allocator,
             phantom: PhantomData,
         }
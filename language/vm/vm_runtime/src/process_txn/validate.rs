@@ -17,7 +17,7 @@ use types::{
 };
 use vm::{
     errors::convert_prologue_runtime_error,
-    gas_schedule::{self, AbstractMemorySize, GasAlgebra, GasCarrier},
+    gas_schedule::{self, AbstractMemorySize, CostTable, GasAlgebra, GasCarrier},
     transaction_metadata::TransactionMetadata,
 };
 use vm_cache_map::Arena;
@@ -74,6 +74,7 @@ where
             txn,
             module_cache,
             data_cache,
+            gas_schedule,
             allocator,
             ..
         } = process_txn;
@@ -84,6 +85,7 @@ where
                     &txn,
                     module_cache,
                     data_cache,
+                    gas_schedule,
                     allocator,
                     mode,
                     || {
@@ -109,6 +111,7 @@ where
                     &txn,
                     module_cache,
                     data_cache,
+                    gas_schedule,
                     allocator,
                     mode,
                     || {
@@ -127,6 +130,7 @@ where
                     &txn,
                     module_cache,
                     data_cache,
+                    gas_schedule,
                     allocator,
                     mode,
                     || {
@@ -159,6 +163,26 @@ where
 
                 None
             }
+            TransactionPayload::BlockMetadata(_) => {
+                // Block metadata transactions are created by the execution pipeline at the start
+                // of a block and must never be accepted from clients.
+                if mode != ValidationMode::Executing {
+                    warn!("[VM] Attempt to submit block metadata outside of block execution");
+                    return Err(VMStatus::new(StatusCode::REJECTED_BLOCK_METADATA));
+                }
+
+                let mut metadata = TransactionMetadata::new(&txn);
+                // The metadata carries the votes of the previous block, which may exceed the
+                // size limit for user transactions. It is not charged for, so ignore its size.
+                metadata.transaction_size = AbstractMemorySize::new(0);
+                Some(ValidatedTransactionState::new(
+                    metadata,
+                    module_cache,
+                    data_cache,
+                    gas_schedule,
+                    allocator,
+                ))
+            }
         };
 
         Ok(Self { txn, txn_state })
@@ -192,6 +216,7 @@ where
         txn: &SignatureCheckedTransaction,
         module_cache: P,
         data_cache: &'txn dyn RemoteCache,
+        gas_schedule: &'txn CostTable,
         allocator: &'txn Arena<LoadedModule>,
         mode: ValidationMode,
         payload_check: impl Fn() -> Result<(), VMStatus>,
@@ -300,8 +325,13 @@ where
         payload_check()?;
 
         let metadata = TransactionMetadata::new(&txn);
-        let mut txn_state =
-            ValidatedTransactionState::new(metadata, module_cache, data_cache, allocator);
+        let mut txn_state = ValidatedTransactionState::new(
+            metadata,
+            module_cache,
+            data_cache,
+            gas_schedule,
+            allocator,
+        );
 
         // Run the prologue to ensure that clients have enough gas and aren't tricking us by
         // sending us garbage.
@@ -350,11 +380,17 @@ where
         metadata: TransactionMetadata,
         module_cache: P,
         data_cache: &'txn dyn RemoteCache,
+        gas_schedule: &'txn CostTable,
         allocator: &'txn Arena<LoadedModule>,
     ) -> Self {
         // This temporary cache is used for modules published by a single transaction.
         let txn_module_cache = TransactionModuleCache::new(module_cache, allocator);
-        let txn_executor = TransactionExecutor::new(txn_module_cache, data_cache, metadata);
+        let txn_executor = TransactionExecutor::new_with_gas_schedule(
+            txn_module_cache,
+            data_cache,
+            metadata,
+            gas_schedule,
+        );
         Self { txn_executor }
     }
 }
@@ -3,9 +3,12 @@ use crate::{
         module_cache::{ModuleCache, TransactionModuleCache},
         script_cache::ScriptCache,
     },
-    loaded_data::function::{FunctionRef, FunctionReference},
+    loaded_data::{
+        function::{FunctionRef, FunctionReference},
+        loaded_module::LoadedModule,
+    },
     process_txn::{execute::ExecutedTransaction, validate::ValidatedTransaction},
-    txn_executor::TransactionExecutor,
+    txn_executor::{TransactionExecutor, VECTOR_MODULE, VECTOR_STRUCT_NAME},
 };
 use bytecode_verifier::{VerifiedModule, VerifiedScript};
 use logger::prelude::*;
@@ -20,7 +23,7 @@ use types::{
 use vm::{
     access::ModuleAccess,
     errors::{verification_error, VMResult},
-    file_format::{CompiledModule, CompiledScript, FunctionSignature, SignatureToken},
+    file_format::{CompiledModule, CompiledScript, SignatureToken, StructHandleIndex},
     IndexKind,
 };
 
@@ -87,6 +90,17 @@ where
                     verified_txn: VerTxn::Script(main),
                 })
             }
+            TransactionPayload::BlockMetadata(_) => {
+                let txn_state = txn_state
+                    .expect("block metadata transactions should always have associated state");
+
+                // Block metadata only invokes the `Block` module already on chain, so there is no
+                // code to verify.
+                Some(VerifiedTransactionState {
+                    txn_executor: txn_state.txn_executor,
+                    verified_txn: VerTxn::BlockMetadata,
+                })
+            }
         };
 
         Ok(Self {
@@ -106,7 +120,7 @@ where
             Err(err) => return Err(err),
         };
 
-        if !verify_actuals(main.signature(), program.args()) {
+        if !verify_actuals(&main, program.args()) {
             return Err(VMStatus::new(StatusCode::TYPE_MISMATCH)
                 .with_message("Actual Type Mismatch".to_string()));
         }
@@ -186,7 +200,7 @@ where
         // Ensure the script can correctly be resolved into main.
         let main = script_cache.cache_script(&script.code())?;
 
-        if !verify_actuals(main.signature(), script.args()) {
+        if !verify_actuals(&main, script.args()) {
             return Err(VMStatus::new(StatusCode::TYPE_MISMATCH)
                 .with_message("Actual Type Mismatch".to_string()));
         }
@@ -243,6 +257,7 @@ pub enum VerTxn<'alloc> {
     Program(VerProgram<'alloc>),
     Script(FunctionRef<'alloc>),
     Module(Box<VerifiedModule>),
+    BlockMetadata,
 }
 
 fn static_verify_modules(
@@ -336,7 +351,8 @@ pub fn static_verify_program(
 }
 
 /// Verify if the transaction arguments match the type signature of the main function.
-fn verify_actuals(signature: &FunctionSignature, args: &[TransactionArgument]) -> bool {
+fn verify_actuals(main: &FunctionRef, args: &[TransactionArgument]) -> bool {
+    let signature = main.signature();
     if signature.arg_types.len() != args.len() {
         warn!(
             "[VM] different argument length: actuals {}, formals {}",
@@ -351,6 +367,9 @@ fn verify_actuals(signature: &FunctionSignature, args: &[TransactionArgument]) -
             (SignatureToken::Address, TransactionArgument::Address(_)) => (),
             (SignatureToken::ByteArray, TransactionArgument::ByteArray(_)) => (),
             (SignatureToken::String, TransactionArgument::String(_)) => (),
+            (SignatureToken::Bool, TransactionArgument::Bool(_)) => (),
+            (SignatureToken::Struct(idx, type_actuals), TransactionArgument::AddressVector(_))
+                if is_address_vector(main.module(), *idx, type_actuals) => {}
             _ => {
                 warn!(
                     "[VM] different argument type: formal {:?}, actual {:?}",
@@ -362,3 +381,17 @@ fn verify_actuals(signature: &FunctionSignature, args: &[TransactionArgument]) -
     }
     true
 }
+
+/// Returns whether the struct type with handle `idx` in `module` and type actuals `type_actuals`
+/// is the stdlib `Vector.T<address>`.
+fn is_address_vector(
+    module: &LoadedModule,
+    idx: StructHandleIndex,
+    type_actuals: &[SignatureToken],
+) -> bool {
+    let struct_handle = module.struct_handle_at(idx);
+    let module_id = module.module_id_for_handle(module.module_handle_at(struct_handle.module));
+    module_id == *VECTOR_MODULE
+        && module.identifier_at(struct_handle.name) == VECTOR_STRUCT_NAME.as_ident_str()
+        && type_actuals == [SignatureToken::Address]
+}
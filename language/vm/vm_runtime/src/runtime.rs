@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_processor::execute_block,
+    block_processor::{execute_block, load_gas_schedule},
     code_cache::{
         module_adapter::ModuleFetcherImpl,
         module_cache::{BlockModuleCache, VMModuleCache},
@@ -54,7 +54,8 @@ impl<'alloc> VMRuntime<'alloc> {
     ///    transaction
     /// 2. The script to be executed is in the whitelist.
     /// 3. Invokes `LibraAccount.prologue`, which checks properties such as the transaction has the
-    /// right sequence number and the sender has enough balance to pay for the gas. 4.
+    /// right sequence number, the sender has enough balance to pay for the gas and the
+    /// transaction has not expired as of the latest committed block. 4.
     /// Transaction arguments matches the main function's type signature. 5. Script and modules
     /// in the transaction pass the bytecode static verifier.
     ///
@@ -71,6 +72,10 @@ impl<'alloc> VMRuntime<'alloc> {
         let module_cache =
             BlockModuleCache::new(&self.code_cache, ModuleFetcherImpl::new(data_view));
         let data_cache = BlockDataCache::new(data_view);
+        let gas_schedule = match load_gas_schedule(data_view) {
+            Ok(gas_schedule) => gas_schedule,
+            Err(vm_status) => return Some(vm_status),
+        };
 
         let arena = Arena::new();
         let signature_verified_txn = match txn.check_signature() {
@@ -78,8 +83,13 @@ impl<'alloc> VMRuntime<'alloc> {
             Err(_) => return Some(VMStatus::new(StatusCode::INVALID_SIGNATURE)),
         };
 
-        let process_txn =
-            ProcessTransaction::new(signature_verified_txn, module_cache, &data_cache, &arena);
+        let process_txn = ProcessTransaction::new(
+            signature_verified_txn,
+            module_cache,
+            &data_cache,
+            &gas_schedule,
+            &arena,
+        );
         let mode = if data_view.is_genesis() {
             ValidationMode::Genesis
         } else {
@@ -4,10 +4,11 @@
 
 use crate::{
     code_cache::module_cache::{ModuleCache, VMModuleCache},
+    coverage::{self, CoverageMap},
     data_cache::{RemoteCache, TransactionDataCache},
     execution_stack::ExecutionStack,
     gas_meter::GasMeter,
-    identifier::{create_access_path, resource_storage_key},
+    identifier::{create_access_path, resource_storage_key, type_tag_from_token},
     loaded_data::{
         function::{FunctionRef, FunctionReference},
         loaded_module::LoadedModule,
@@ -19,11 +20,12 @@ use types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_config,
+    block_metadata::BlockMetadata,
     byte_array::ByteArray,
     contract_event::ContractEvent,
     event::EventKey,
     identifier::{IdentStr, Identifier},
-    language_storage::ModuleId,
+    language_storage::{ModuleId, TypeTag},
     transaction::{
         TransactionArgument, TransactionOutput, TransactionStatus, MAX_TRANSACTION_SIZE_IN_BYTES,
     },
@@ -33,8 +35,13 @@ use types::{
 use vm::{
     access::ModuleAccess,
     errors::*,
-    file_format::{Bytecode, CodeOffset, CompiledScript, StructDefinitionIndex},
-    gas_schedule::{AbstractMemorySize, GasAlgebra, GasUnits},
+    file_format::{
+        Bytecode, CodeOffset, CompiledScript, LocalsSignatureIndex, StructDefinitionIndex,
+    },
+    gas_schedule::{
+        gas_schedule_module_name, AbstractMemorySize, CostTable, GasAlgebra, GasCarrier, GasUnits,
+        DEFAULT_GAS_SCHEDULE,
+    },
     transaction_metadata::TransactionMetadata,
     vm_string::VMString,
 };
@@ -58,6 +65,12 @@ lazy_static! {
     /// The ModuleId for the Event
     pub static ref EVENT_MODULE: ModuleId =
         { ModuleId::new(account_config::core_code_address(), Identifier::new("Event").unwrap()) };
+    /// The ModuleId for the GasSchedule module
+    pub static ref GAS_SCHEDULE_MODULE: ModuleId =
+        { ModuleId::new(account_config::core_code_address(), gas_schedule_module_name().to_owned()) };
+    /// The ModuleId for the Vector module
+    pub static ref VECTOR_MODULE: ModuleId =
+        { ModuleId::new(account_config::core_code_address(), Identifier::new("Vector").unwrap()) };
 
 }
 
@@ -67,6 +80,7 @@ lazy_static! {
     static ref EPILOGUE_NAME: Identifier = Identifier::new("epilogue").unwrap();
     static ref CREATE_ACCOUNT_NAME: Identifier = Identifier::new("make").unwrap();
     static ref ACCOUNT_STRUCT_NAME: Identifier = Identifier::new("T").unwrap();
+    pub static ref VECTOR_STRUCT_NAME: Identifier = Identifier::new("T").unwrap();
     static ref EMIT_EVENT_NAME: Identifier = Identifier::new("write_to_event_store").unwrap();
 }
 
@@ -87,6 +101,7 @@ fn make_access_path(
 /// `txn_data` contains the information of this transaction, such as sender, sequence number, etc.
 /// `event_data` is the vector that stores all events emitted during execution.
 /// `data_view` is the scratchpad for the local writes emitted by this transaction.
+/// `coverage` records the executed instructions when coverage is enabled.
 pub struct TransactionExecutor<'alloc, 'txn, P>
 where
     'alloc: 'txn,
@@ -97,10 +112,11 @@ where
 
     #[cfg(not(any(test, feature = "instruction_synthesis")))]
     execution_stack: ExecutionStack<'alloc, 'txn, P>,
-    gas_meter: GasMeter,
+    gas_meter: GasMeter<'txn>,
     txn_data: TransactionMetadata,
     event_data: Vec<ContractEvent>,
     data_view: TransactionDataCache<'txn>,
+    coverage: Option<CoverageMap>,
 }
 
 impl<'alloc, 'txn, P> TransactionExecutor<'alloc, 'txn, P>
@@ -111,21 +127,54 @@ where
     /// Create a new `TransactionExecutor` to execute a single transaction. `module_cache` is the
     /// cache that stores the modules previously read from the blockchain. `data_cache` is the cache
     /// that holds read-only connection to the state store as well as the changes made by previous
-    /// transactions within the same block.
+    /// transactions within the same block. Gas is charged according to the default cost table.
     pub fn new(
         module_cache: P,
         data_cache: &'txn dyn RemoteCache,
         txn_data: TransactionMetadata,
+    ) -> Self {
+        Self::new_with_gas_schedule(module_cache, data_cache, txn_data, &DEFAULT_GAS_SCHEDULE)
+    }
+
+    /// Create a new `TransactionExecutor` that charges gas according to `gas_schedule`, usually
+    /// the cost table published on chain.
+    pub fn new_with_gas_schedule(
+        module_cache: P,
+        data_cache: &'txn dyn RemoteCache,
+        txn_data: TransactionMetadata,
+        gas_schedule: &'txn CostTable,
     ) -> Self {
         TransactionExecutor {
             execution_stack: ExecutionStack::new(module_cache),
-            gas_meter: GasMeter::new(txn_data.max_gas_amount()),
+            gas_meter: GasMeter::new(txn_data.max_gas_amount(), gas_schedule),
             txn_data,
             event_data: Vec::new(),
             data_view: TransactionDataCache::new(data_cache),
+            coverage: if coverage::is_recording() {
+                Some(CoverageMap::new())
+            } else {
+                None
+            },
         }
     }
 
+    /// Start recording the instructions executed from now on.
+    pub fn enable_coverage(&mut self) {
+        if self.coverage.is_none() {
+            self.coverage = Some(CoverageMap::new());
+        }
+    }
+
+    /// Returns the instructions executed since coverage was enabled, and stops recording.
+    pub fn take_coverage(&mut self) -> Option<CoverageMap> {
+        self.coverage.take()
+    }
+
+    /// Returns the gas left for this transaction.
+    pub fn remaining_gas(&self) -> GasUnits<GasCarrier> {
+        self.gas_meter.remaining_gas()
+    }
+
     /// Returns the module cache for this executor.
     pub fn module_cache(&self) -> &P {
         &self.execution_stack.module_cache
@@ -178,7 +227,20 @@ where
         beginning_offset: CodeOffset,
     ) -> VMResult<CodeOffset> {
         let mut pc = beginning_offset;
+        // The block belongs to a single function, so it is looked up once
+        let covered_function = match self.coverage {
+            Some(_) => {
+                let frame = self.execution_stack.top_frame()?;
+                Some((frame.module().self_id(), frame.function_name()))
+            }
+            None => None,
+        };
         for instruction in &code[beginning_offset as usize..] {
+            if let (Some(coverage), Some((module, function))) =
+                (&mut self.coverage, &covered_function)
+            {
+                coverage.record(module, function, pc);
+            }
             // FIXME: Once we add in memory ops, we will need to pass in the current memory size to
             // this function.
             self.gas_meter.calculate_and_consume(
@@ -251,13 +313,14 @@ where
                         .top_frame_mut()?
                         .store_loc(*idx, value)?;
                 }
-                Bytecode::Call(idx, _) => {
+                Bytecode::Call(idx, type_actuals_idx) => {
                     let self_module = &self.execution_stack.top_frame()?.module();
                     let callee_function_ref = self
                         .execution_stack
                         .module_cache
                         .resolve_function_ref(self_module, *idx)?
                         .ok_or_else(|| VMStatus::new(StatusCode::LINKER_ERROR))?;
+                    let type_actual_tags = self.resolve_type_actual_tags(*type_actuals_idx)?;
 
                     if callee_function_ref.is_native() {
                         let module = callee_function_ref.module();
@@ -276,13 +339,22 @@ where
                             let key = self.execution_stack.pop_as::<ByteArray>()?;
                             let guid = EventKey::try_from(key.as_bytes())
                                 .map_err(|_| VMStatus::new(StatusCode::EVENT_KEY_MISMATCH))?;
+                            // The single type actual is the type of the event
+                            let type_tag = type_actual_tags
+                                .into_iter()
+                                .next()
+                                .ok_or_else(|| VMStatus::new(StatusCode::LINKER_ERROR))?;
 
                             // TODO:
                             // 1. Rename the AccessPath here to a new type that represents such
                             //    globally unique id for event streams.
                             // 2. Charge gas for the msg emitted.
-                            self.event_data
-                                .push(ContractEvent::new(guid, count, msg.into_inner()))
+                            self.event_data.push(ContractEvent::new(
+                                guid,
+                                count,
+                                type_tag,
+                                msg.into_inner(),
+                            ))
                         } else {
                             let mut arguments = VecDeque::new();
                             let expected_args = native_function.num_args();
@@ -296,26 +368,29 @@ where
                             for _ in 0..expected_args {
                                 arguments.push_front(self.execution_stack.pop()?);
                             }
-                            let (cost, return_values) = match (native_function.dispatch)(arguments)
-                            {
-                                NativeReturnStatus::InvalidArguments => {
-                                    // TODO: better error
-                                    return Err(VMStatus::new(StatusCode::LINKER_ERROR));
-                                }
-                                NativeReturnStatus::Aborted { cost, error_code } => {
-                                    self.gas_meter
-                                        .consume_gas(GasUnits::new(cost), &self.execution_stack)?;
-                                    return Err(vm_error(
-                                        self.execution_stack.location()?,
-                                        StatusCode::ABORTED,
-                                    )
-                                    .with_sub_status(error_code));
-                                }
-                                NativeReturnStatus::Success {
-                                    cost,
-                                    return_values,
-                                } => (cost, return_values),
-                            };
+                            let gas_schedule = self.gas_meter.gas_schedule();
+                            let (cost, return_values) =
+                                match (native_function.dispatch)(arguments, gas_schedule) {
+                                    NativeReturnStatus::InvalidArguments => {
+                                        // TODO: better error
+                                        return Err(VMStatus::new(StatusCode::LINKER_ERROR));
+                                    }
+                                    NativeReturnStatus::Aborted { cost, error_code } => {
+                                        self.gas_meter.consume_gas(
+                                            GasUnits::new(cost),
+                                            &self.execution_stack,
+                                        )?;
+                                        return Err(vm_error(
+                                            self.execution_stack.location()?,
+                                            StatusCode::ABORTED,
+                                        )
+                                        .with_sub_status(error_code));
+                                    }
+                                    NativeReturnStatus::Success {
+                                        cost,
+                                        return_values,
+                                    } => (cost, return_values),
+                                };
                             self.gas_meter
                                 .consume_gas(GasUnits::new(cost), &self.execution_stack)?;
                             for value in return_values {
@@ -326,7 +401,8 @@ where
                     // proceed on to next instruction.
                     } else {
                         self.execution_stack.top_frame_mut()?.save_pc(pc);
-                        self.execution_stack.push_call(callee_function_ref)?;
+                        self.execution_stack
+                            .push_call(callee_function_ref, type_actual_tags)?;
                         // Call stack is reconstructed, the next instruction to execute will be the
                         // first instruction of the callee function. Thus we should break here to
                         // restart the instruction sequence from there.
@@ -392,10 +468,12 @@ where
                 Bytecode::Ge => self.binop_bool(|l: u64, r| l >= r)?,
                 Bytecode::Abort => {
                     let error_code = self.execution_stack.pop_as::<u64>()?;
-                    return Err(
-                        vm_error(self.execution_stack.location()?, StatusCode::ABORTED)
-                            .with_sub_status(error_code),
-                    );
+                    let module_id = self.execution_stack.top_frame()?.module().self_id();
+                    return Err(abort_error(
+                        self.execution_stack.location()?,
+                        &module_id,
+                        error_code,
+                    ));
                 }
 
                 // TODO: Should we emit different eq for different primitive type values?
@@ -549,6 +627,11 @@ where
                 TransactionArgument::Address(a) => Value::address(a),
                 TransactionArgument::ByteArray(b) => Value::byte_array(b),
                 TransactionArgument::String(s) => Value::string(VMString::new(s)),
+                TransactionArgument::Bool(b) => Value::bool(b),
+                // Vectors hold their elements as the fields of a struct.
+                TransactionArgument::AddressVector(addresses) => Value::struct_(Struct::new(
+                    addresses.into_iter().map(Value::address).collect(),
+                )),
             });
             assume!(push_result.is_ok());
             push_result.expect("Stack should be empty at beginning of function");
@@ -595,13 +678,41 @@ where
 
     /// Run the prologue of a transaction by calling into `PROLOGUE_NAME` function stored
     /// in the `ACCOUNT_MODULE` on chain.
+    ///
+    /// The expiration time of the transaction is passed in microseconds so that the prologue can
+    /// compare it against the timestamp of the block the transaction is executed in.
     pub(crate) fn run_prologue(&mut self) -> VMResult<()> {
+        let expiration_time_usecs =
+            u64::try_from(self.txn_data.expiration_time().as_micros()).unwrap_or(u64::max_value());
         self.gas_meter.disable_metering();
-        let result = self.execute_function(&ACCOUNT_MODULE, &PROLOGUE_NAME, vec![]);
+        let result = self.execute_function(
+            &ACCOUNT_MODULE,
+            &PROLOGUE_NAME,
+            vec![Value::u64(expiration_time_usecs)],
+        );
         self.gas_meter.enable_metering();
         result
     }
 
+    /// Run the prologue of a block by calling into `PROLOGUE_NAME` function stored in the
+    /// `BLOCK_MODULE` on chain, and produce the output recording the new block metadata.
+    pub(crate) fn run_block_prologue(
+        &mut self,
+        block_metadata: &BlockMetadata,
+    ) -> VMResult<TransactionOutput> {
+        let args = vec![
+            Value::u64(block_metadata.timestamp_usecs()),
+            Value::byte_array(ByteArray::new(block_metadata.id().to_vec())),
+            Value::byte_array(block_metadata.voters()),
+            Value::address(block_metadata.proposer()),
+        ];
+        self.gas_meter.disable_metering();
+        let result = self.execute_function(&BLOCK_MODULE, &PROLOGUE_NAME, args);
+        self.gas_meter.enable_metering();
+        result?;
+        self.make_write_set(vec![], Ok(()))
+    }
+
     /// Run the epilogue of a transaction by calling into `EPILOGUE_NAME` function stored
     /// in the `ACCOUNT_MODULE` on chain.
     fn run_epilogue(&mut self) -> VMResult<()> {
@@ -659,6 +770,27 @@ where
         }
     }
 
+    /// Resolves the type actuals at `idx` of the module of the current frame to TypeTags, in terms
+    /// of the type actuals of the current frame.
+    fn resolve_type_actual_tags(&self, idx: LocalsSignatureIndex) -> VMResult<Vec<TypeTag>> {
+        let frame = self.execution_stack.top_frame()?;
+        let module = frame.module();
+        let mut type_actual_tags = vec![];
+        for token in &module.locals_signature_at(idx).0 {
+            match type_tag_from_token(module, token, frame.type_actual_tags()) {
+                Some(type_tag) => type_actual_tags.push(type_tag),
+                // The bytecode verifier rejects references as type actuals
+                None => {
+                    return Err(vm_error(
+                        self.execution_stack.location()?,
+                        StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                    ))
+                }
+            }
+        }
+        Ok(type_actual_tags)
+    }
+
     /// Execute a function given a FunctionRef.
     pub(crate) fn execute_function_impl(&mut self, func: FunctionRef<'txn>) -> VMResult<()> {
         // We charge an intrinsic amount of gas based upon the size of the transaction submitted
@@ -670,7 +802,7 @@ where
         self.gas_meter
             .charge_transaction_gas(txn_size, &self.execution_stack)?;
         let beginning_height = self.execution_stack.call_stack_height();
-        self.execution_stack.push_call(func)?;
+        self.execution_stack.push_call(func, vec![])?;
         // We always start execution from the first instruction.
         let mut pc = 0;
 
@@ -742,6 +874,12 @@ where
             .sub(self.gas_meter.remaining_gas())
             .mul(self.txn_data.gas_unit_price)
             .get();
+        // Hand the instructions executed so far over to the recording in progress, if any
+        if coverage::is_recording() {
+            if let Some(coverage) = self.coverage.replace(CoverageMap::new()) {
+                coverage::record_transaction(coverage);
+            }
+        }
         let write_set = self.data_view.make_write_set(to_be_published_modules)?;
 
         Ok(TransactionOutput::new(
@@ -786,10 +924,11 @@ pub fn execute_function(
     }
     let mut vm = TransactionExecutor {
         execution_stack: ExecutionStack::new(&module_cache),
-        gas_meter: GasMeter::new(txn_metadata.max_gas_amount()),
+        gas_meter: GasMeter::new(txn_metadata.max_gas_amount(), &DEFAULT_GAS_SCHEDULE),
         txn_data: txn_metadata,
         event_data: Vec::new(),
         data_view: TransactionDataCache::new(data_cache),
+        coverage: None,
     };
     vm.execute_function_impl(entry_func)
 }
@@ -24,7 +24,7 @@ use types::{
 use vm::{
     access::ModuleAccess,
     file_format::*,
-    gas_schedule::{GasAlgebra, GasUnits},
+    gas_schedule::{GasAlgebra, GasUnits, DEFAULT_GAS_SCHEDULE},
 };
 use vm_cache_map::Arena;
 use vm_runtime_types::loaded_data::{struct_def::StructDef, types::Type};
@@ -525,7 +525,7 @@ fn test_same_module_struct_resolution() {
     {
         let module_id = ModuleId::new(AccountAddress::default(), ident("M1"));
         let module_ref = block_cache.get_loaded_module(&module_id).unwrap().unwrap();
-        let gas = GasMeter::new(GasUnits::new(100_000_000));
+        let gas = GasMeter::new(GasUnits::new(100_000_000), &DEFAULT_GAS_SCHEDULE);
         let struct_x = block_cache
             .resolve_struct_def(module_ref, StructDefinitionIndex::new(0), &gas)
             .unwrap()
@@ -575,7 +575,7 @@ fn test_multi_module_struct_resolution() {
             .unwrap()
             .unwrap();
 
-        let gas = GasMeter::new(GasUnits::new(100_000_000));
+        let gas = GasMeter::new(GasUnits::new(100_000_000), &DEFAULT_GAS_SCHEDULE);
         let struct_t = block_cache
             .resolve_struct_def(module2_ref, StructDefinitionIndex::new(0), &gas)
             .unwrap()
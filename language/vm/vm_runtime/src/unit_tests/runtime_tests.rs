@@ -13,7 +13,7 @@ use crate::{
 };
 use bytecode_verifier::{VerifiedModule, VerifiedScript};
 use crypto::ed25519::compat;
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 use types::{
     access_path::AccessPath, account_address::AccountAddress, byte_array::ByteArray,
     vm_error::StatusCode,
@@ -770,6 +770,7 @@ fn test_transaction_info() {
             max_gas_amount: GasUnits::new(100_000_009),
             gas_unit_price: GasPrice::new(5),
             transaction_size: AbstractMemorySize::new(100),
+            expiration_time: Duration::from_secs(u64::max_value()),
         }
     };
     let data_cache = FakeDataCache::new();
@@ -809,3 +810,51 @@ fn test_transaction_info() {
         1,
     );
 }
+
+#[test]
+fn test_coverage() {
+    let module = fake_module_with_calls(vec![(
+        vec![],
+        FunctionSignature {
+            arg_types: vec![],
+            return_types: vec![],
+            type_formals: vec![],
+        },
+    )]);
+    let mut module = module.into_inner().into_inner();
+    module.function_defs[0].code.code = vec![
+        Bytecode::LdTrue,
+        Bytecode::BrTrue(3),
+        Bytecode::Branch(3),
+        Bytecode::Ret,
+    ];
+    let module = VerifiedModule::bypass_verifier_DANGEROUS_FOR_TESTING_ONLY(
+        module
+            .freeze()
+            .expect("test module should satisfy the bounds checker"),
+    );
+
+    let mod_id = module.self_id();
+    let allocator = Arena::new();
+    let module_cache = VMModuleCache::new_from_module(module, &allocator).unwrap();
+    let data_cache = FakeDataCache::new();
+    let mut vm =
+        TransactionExecutor::new(module_cache, &data_cache, TransactionMetadata::default());
+    vm.enable_coverage();
+    vm.execute_function(&mod_id, &ident("func0"), vec![])
+        .expect("function should execute");
+
+    let coverage = vm.take_coverage().expect("coverage is enabled");
+    let offsets = coverage
+        .function(&mod_id, &ident("func0"))
+        .expect("function ran");
+    // The branch skipped by BrTrue is not covered
+    assert_eq!(
+        offsets
+            .iter()
+            .map(|(offset, count)| (*offset, *count))
+            .collect::<Vec<_>>(),
+        vec![(0, 1), (1, 1), (3, 1)]
+    );
+    assert!(vm.take_coverage().is_none());
+}
@@ -10,7 +10,10 @@ use types::{
     identifier::{IdentStr, Identifier},
     language_storage::ModuleId,
 };
-use vm::file_format::{FunctionSignature, Kind, SignatureToken};
+use vm::{
+    file_format::{FunctionSignature, Kind, SignatureToken},
+    gas_schedule::CostTable,
+};
 
 /// Enum representing the result of running a native function
 pub enum NativeReturnStatus {
@@ -34,8 +37,9 @@ pub enum NativeReturnStatus {
 
 /// Struct representing the expected definition for a native function
 pub struct NativeFunction {
-    /// Given the vector of aguments, it executes the native function
-    pub dispatch: fn(VecDeque<Value>) -> NativeReturnStatus,
+    /// Given the vector of aguments and the cost table to charge with, it executes the native
+    /// function
+    pub dispatch: fn(VecDeque<Value>, &CostTable) -> NativeReturnStatus,
     /// The signature as defined in it's declaring module.
     /// It should NOT be generally inspected outside of it's declaring module as the various
     /// struct handle indexes are not remapped into the local context
@@ -180,7 +184,8 @@ lazy_static! {
         );
         // Event
         add!(m, addr, "Event", "write_to_event_store",
-            |_| { NativeReturnStatus::InvalidArguments },
+            |_, _| { NativeReturnStatus::InvalidArguments },
+            vec![Kind::All],
             vec![ByteArray, U64, ByteArray],
             vec![]
         );
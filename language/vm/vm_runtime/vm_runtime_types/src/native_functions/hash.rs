@@ -7,16 +7,22 @@ use crypto::HashValue;
 use sha2::{Digest, Sha256};
 use std::collections::VecDeque;
 use types::byte_array::ByteArray;
+use vm::gas_schedule::{AbstractMemorySize, CostTable, GasAlgebra, NativeCostIndex};
 
-const SHA2_COST: u64 = 30;
-const SHA3_COST: u64 = 30;
-
-pub fn native_sha2_256(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_sha2_256(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
     if arguments.len() != 1 {
         return NativeReturnStatus::InvalidArguments;
     }
     let hash_arg = pop_arg!(arguments, ByteArray);
-    let cost = SHA2_COST * hash_arg.len() as u64;
+    let cost = cost_table
+        .native_gas(
+            NativeCostIndex::SHA2_256,
+            AbstractMemorySize::new(hash_arg.len() as u64),
+        )
+        .get();
 
     let hash_vec = Sha256::digest(hash_arg.as_bytes()).to_vec();
     let return_values = vec![Value::byte_array(ByteArray::new(hash_vec))];
@@ -26,12 +32,20 @@ pub fn native_sha2_256(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
     }
 }
 
-pub fn native_sha3_256(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_sha3_256(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
     if arguments.len() != 1 {
         return NativeReturnStatus::InvalidArguments;
     }
     let hash_arg = pop_arg!(arguments, ByteArray);
-    let cost = SHA3_COST * hash_arg.len() as u64;
+    let cost = cost_table
+        .native_gas(
+            NativeCostIndex::SHA3_256,
+            AbstractMemorySize::new(hash_arg.len() as u64),
+        )
+        .get();
 
     let hash_vec = HashValue::from_sha3_256(hash_arg.as_bytes()).to_vec();
     let return_values = vec![Value::byte_array(ByteArray::new(hash_vec))];
@@ -2,8 +2,12 @@ use super::dispatch::NativeReturnStatus;
 use crate::value::Value;
 use std::collections::VecDeque;
 use types::{account_address::AccountAddress, byte_array::ByteArray};
+use vm::gas_schedule::{AbstractMemorySize, CostTable, GasAlgebra, NativeCostIndex};
 
-pub fn native_bytearray_concat(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_bytearray_concat(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
     if arguments.len() != 2 {
         return NativeReturnStatus::InvalidArguments;
     }
@@ -12,8 +16,12 @@ pub fn native_bytearray_concat(mut arguments: VecDeque<Value>) -> NativeReturnSt
     let mut return_val = arg1.as_bytes().to_vec();
     return_val.extend_from_slice(arg2.as_bytes());
 
-    // TODO: Figure out the gas cost for concatenation.
-    let cost = return_val.len() as u64;
+    let cost = cost_table
+        .native_gas(
+            NativeCostIndex::BYTEARRAY_CONCAT,
+            AbstractMemorySize::new(return_val.len() as u64),
+        )
+        .get();
     let return_values = vec![Value::byte_array(ByteArray::new(return_val))];
     NativeReturnStatus::Success {
         cost,
@@ -21,15 +29,22 @@ pub fn native_bytearray_concat(mut arguments: VecDeque<Value>) -> NativeReturnSt
     }
 }
 
-pub fn native_address_to_bytes(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_address_to_bytes(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
     if arguments.len() != 1 {
         return NativeReturnStatus::InvalidArguments;
     }
     let arg = pop_arg!(arguments, AccountAddress);
     let return_val = arg.to_vec();
 
-    // TODO: Figure out the gas cost for conversion.
-    let cost = return_val.len() as u64;
+    let cost = cost_table
+        .native_gas(
+            NativeCostIndex::ADDRESS_TO_BYTES,
+            AbstractMemorySize::new(return_val.len() as u64),
+        )
+        .get();
     let return_values = vec![Value::byte_array(ByteArray::new(return_val))];
     NativeReturnStatus::Success {
         cost,
@@ -37,15 +52,22 @@ pub fn native_address_to_bytes(mut arguments: VecDeque<Value>) -> NativeReturnSt
     }
 }
 
-pub fn native_u64_to_bytes(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_u64_to_bytes(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
     if arguments.len() != 1 {
         return NativeReturnStatus::InvalidArguments;
     }
     let arg = pop_arg!(arguments, u64);
     let return_val: Vec<u8> = arg.to_le_bytes().to_vec();
 
-    // TODO: Figure out the gas cost for conversion.
-    let cost = return_val.len() as u64;
+    let cost = cost_table
+        .native_gas(
+            NativeCostIndex::U64_TO_BYTES,
+            AbstractMemorySize::new(return_val.len() as u64),
+        )
+        .get();
     let return_values = vec![Value::byte_array(ByteArray::new(return_val))];
     NativeReturnStatus::Success {
         cost,
@@ -11,10 +11,7 @@ use crypto::{
 };
 use std::{collections::VecDeque, convert::TryFrom};
 use types::byte_array::ByteArray;
-
-// TODO: Talk to Crypto to determine these costs
-const ED25519_COST: u64 = 35;
-const BATCH_ED25519_COST: u64 = 30;
+use vm::gas_schedule::{AbstractMemorySize, CostTable, GasAlgebra, NativeCostIndex};
 
 const BITMAP_SIZE: usize = 32;
 
@@ -39,7 +36,10 @@ const OVERSIZED_PUBLIC_KEY_SIZE_FAILURE: u64 = DEFAULT_ERROR_CODE + 8;
 /// Concatenated Ed25519 public keys should be a multiple of 32 bytes
 const INVALID_PUBLIC_KEY_SIZE_FAILURE: u64 = DEFAULT_ERROR_CODE + 9;
 
-pub fn native_ed25519_signature_verification(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_ed25519_signature_verification(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
     if arguments.len() != 3 {
         return NativeReturnStatus::InvalidArguments;
     }
@@ -47,7 +47,12 @@ pub fn native_ed25519_signature_verification(mut arguments: VecDeque<Value>) ->
     let pubkey = pop_arg!(arguments, ByteArray);
     let signature = pop_arg!(arguments, ByteArray);
 
-    let cost = ED25519_COST * msg.len() as u64;
+    let cost = cost_table
+        .native_gas(
+            NativeCostIndex::ED25519_VERIFY,
+            AbstractMemorySize::new(msg.len() as u64),
+        )
+        .get();
 
     let sig = match ed25519::Ed25519Signature::try_from(signature.as_bytes()) {
         Ok(sig) => sig,
@@ -82,6 +87,7 @@ pub fn native_ed25519_signature_verification(mut arguments: VecDeque<Value>) ->
 /// Batch verify a collection of signatures using a bitmap for matching signatures to keys.
 pub fn native_ed25519_threshold_signature_verification(
     mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
 ) -> NativeReturnStatus {
     if arguments.len() != 4 {
         return NativeReturnStatus::InvalidArguments;
@@ -96,13 +102,14 @@ pub fn native_ed25519_threshold_signature_verification(
         &signatures,
         &public_keys,
         &message,
-        BATCH_ED25519_COST,
+        cost_table,
     ) {
         Ok(num_of_sigs) => num_of_sigs,
         Err(e) => return e,
     };
 
-    let cost = ed25519_threshold_signature_verification_cost(num_of_sigs, message.len());
+    let cost =
+        ed25519_threshold_signature_verification_cost(cost_table, num_of_sigs, message.len());
     let return_values = vec![Value::u64(num_of_sigs)];
     NativeReturnStatus::Success {
         cost,
@@ -110,8 +117,17 @@ pub fn native_ed25519_threshold_signature_verification(
     }
 }
 
-fn ed25519_threshold_signature_verification_cost(num_of_sigs: u64, message_len: usize) -> u64 {
-    BATCH_ED25519_COST * num_of_sigs * message_len as u64
+fn ed25519_threshold_signature_verification_cost(
+    cost_table: &CostTable,
+    num_of_sigs: u64,
+    message_len: usize,
+) -> u64 {
+    cost_table
+        .native_gas(
+            NativeCostIndex::ED25519_THRESHOLD_VERIFY,
+            AbstractMemorySize::new(num_of_sigs * message_len as u64),
+        )
+        .get()
 }
 
 fn ed25519_threshold_signature_verification(
@@ -119,12 +135,15 @@ fn ed25519_threshold_signature_verification(
     signatures: &ByteArray,
     public_keys: &ByteArray,
     message: &ByteArray,
-    abort_cost: u64,
+    cost_table: &CostTable,
 ) -> std::result::Result<u64, NativeReturnStatus> {
     let bitvec = BitVec::from_bytes(bitmap.as_bytes());
 
+    // Failing the sanity check is charged like checking a single byte
+    let abort_cost = ed25519_threshold_signature_verification_cost(cost_table, 1, 1);
     let num_of_sigs = sanity_check(&bitvec, &signatures, &public_keys, abort_cost)?;
-    let abort_cost = ed25519_threshold_signature_verification_cost(num_of_sigs, message.len());
+    let abort_cost =
+        ed25519_threshold_signature_verification_cost(cost_table, num_of_sigs, message.len());
 
     let sig_chunks: ::std::result::Result<Vec<_>, _> = signatures
         .as_bytes()
@@ -4,16 +4,14 @@
 use super::dispatch::NativeReturnStatus;
 use crate::value::{Struct, Value};
 use std::collections::VecDeque;
-
-const BORROW_COST: u64 = 30; // TODO: determine experimentally
-const EMPTY_COST: u64 = 30; // TODO: determine experimentally
-const LENGTH_COST: u64 = 30; // TODO: determine experimentally
-const PUSH_BACK_COST: u64 = 30; // TODO: determine experimentally
+use vm::gas_schedule::{AbstractMemorySize, CostTable, GasAlgebra, NativeCostIndex};
 
 #[allow(unreachable_code)]
-pub fn native_length(_arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_length(_arguments: VecDeque<Value>, cost_table: &CostTable) -> NativeReturnStatus {
     unimplemented!("Computing length of a vector collection");
-    let cost = LENGTH_COST;
+    let cost = cost_table
+        .native_gas(NativeCostIndex::LENGTH, AbstractMemorySize::new(1))
+        .get();
     let return_values = vec![Value::u64(0)];
     NativeReturnStatus::Success {
         cost,
@@ -22,9 +20,11 @@ pub fn native_length(_arguments: VecDeque<Value>) -> NativeReturnStatus {
 }
 
 #[allow(unreachable_code)]
-pub fn native_empty(_arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_empty(_arguments: VecDeque<Value>, cost_table: &CostTable) -> NativeReturnStatus {
     unimplemented!("Creating an empty vector");
-    let cost = EMPTY_COST;
+    let cost = cost_table
+        .native_gas(NativeCostIndex::EMPTY, AbstractMemorySize::new(1))
+        .get();
     // TODO: implement returning empty vector native struct here
     let empty_vector = Value::struct_(Struct::new(vec![]));
     let return_values = vec![empty_vector];
@@ -35,9 +35,11 @@ pub fn native_empty(_arguments: VecDeque<Value>) -> NativeReturnStatus {
 }
 
 #[allow(unreachable_code)]
-pub fn native_borrow(_arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_borrow(_arguments: VecDeque<Value>, cost_table: &CostTable) -> NativeReturnStatus {
     unimplemented!("borrowing an element from a vector");
-    let cost = BORROW_COST;
+    let cost = cost_table
+        .native_gas(NativeCostIndex::BORROW, AbstractMemorySize::new(1))
+        .get();
     // TODO: bounds check + implement retrieving reference to element of vector here
     let vector_element = Value::struct_(Struct::new(vec![]));
     let return_values = vec![vector_element];
@@ -48,9 +50,11 @@ pub fn native_borrow(_arguments: VecDeque<Value>) -> NativeReturnStatus {
 }
 
 #[allow(unreachable_code)]
-pub fn native_push_back(_arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_push_back(_arguments: VecDeque<Value>, cost_table: &CostTable) -> NativeReturnStatus {
     unimplemented!("Adding an element to a vector");
-    let cost = PUSH_BACK_COST;
+    let cost = cost_table
+        .native_gas(NativeCostIndex::PUSH_BACK, AbstractMemorySize::new(1))
+        .get();
     let return_values = vec![];
     NativeReturnStatus::Success {
         cost,
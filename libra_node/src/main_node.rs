@@ -6,18 +6,29 @@ use admission_control_proto::proto::admission_control_grpc::{
 };
 use admission_control_service::admission_control_service::AdmissionControlService;
 use config::config::{NetworkConfig, NodeConfig, RoleType};
-use consensus::consensus_provider::{make_consensus_provider, ConsensusProvider};
+use consensus::consensus_provider::{
+    last_committed_round, make_consensus_provider, ConsensusProvider,
+};
 use crypto::{ed25519::*, ValidKey};
-use debug_interface::{node_debug_service::NodeDebugService, proto::node_debug_interface_grpc};
+use debug_interface::{
+    failpoint, libra_trace, node_debug_service::NodeDebugService, proto::node_debug_interface_grpc,
+};
 use execution_proto::proto::execution_grpc;
 use execution_service::ExecutionService;
+use failure::prelude::*;
 use futures::future::{FutureExt, TryFutureExt};
 use grpc_helpers::ServerHandle;
-use grpcio::{ChannelBuilder, EnvBuilder, ServerBuilder};
+use grpcio::{CallOption, ChannelBuilder, EnvBuilder, ServerBuilder};
 use grpcio_sys;
 use logger::prelude::*;
-use mempool::{proto::mempool_grpc::MempoolClient, MempoolRuntime};
-use metrics::metric_server;
+use mempool::{
+    proto::{mempool::HealthCheckRequest, mempool_grpc::MempoolClient},
+    MempoolRuntime,
+};
+use metrics::{
+    health::{progress_check, register_health_check, CheckKind},
+    metric_server,
+};
 use network::{
     validator_network::{
         network_builder::{NetworkBuilder, TransportType},
@@ -26,16 +37,17 @@ use network::{
     },
     NetworkPublicKeys, ProtocolId,
 };
-use state_synchronizer::StateSynchronizer;
+use state_synchronizer::{version_lag, StateSynchronizer};
 use std::{
     cmp::min,
     convert::{TryFrom, TryInto},
     str::FromStr,
     sync::Arc,
     thread,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use storage_client::{StorageRead, StorageReadServiceClient, StorageWriteServiceClient};
+use storage_proto::proto::{storage::GetStartupInfoRequest, storage_grpc::StorageClient};
 use storage_service::start_storage_service;
 use tokio::runtime::{Builder, Runtime};
 use types::account_address::AccountAddress as PeerId;
@@ -147,6 +159,69 @@ fn setup_debug_interface(config: &NodeConfig) -> ::grpcio::Server {
         .expect("Unable to create grpc server")
 }
 
+fn setup_health_checks(config: &NodeConfig) {
+    // A hung service must fail its check rather than block the health endpoint, so the checks
+    // call the services with a deadline.
+    let timeout = Duration::from_millis(config.debug_interface.health_check_timeout_ms);
+    let storage_client = StorageClient::new(
+        ChannelBuilder::new(Arc::new(
+            EnvBuilder::new().name_prefix("grpc-health-sto-").build(),
+        ))
+        .connect(&format!(
+            "{}:{}",
+            config.storage.address, config.storage.port
+        )),
+    );
+    register_health_check("storage", CheckKind::Liveness, move || {
+        storage_client
+            .get_startup_info_opt(
+                &GetStartupInfoRequest::new(),
+                CallOption::default().timeout(timeout),
+            )
+            .map_err(|e| format_err!("storage did not answer: {}", e))?;
+        Ok(())
+    });
+
+    let max_lag = config.debug_interface.max_state_sync_lag;
+    register_health_check("state_sync", CheckKind::Readiness, move || {
+        let lag = version_lag();
+        ensure!(
+            lag <= max_lag,
+            "state sync is {} versions behind, more than {}",
+            lag,
+            max_lag
+        );
+        Ok(())
+    });
+
+    if config.is_validator() {
+        let window = Duration::from_millis(config.debug_interface.consensus_progress_window_ms);
+        register_health_check(
+            "consensus",
+            CheckKind::Readiness,
+            progress_check("last committed round", last_committed_round, window),
+        );
+
+        let mempool_client = MempoolClient::new(
+            ChannelBuilder::new(Arc::new(
+                EnvBuilder::new().name_prefix("grpc-health-mem-").build(),
+            ))
+            .connect(&format!(
+                "localhost:{}",
+                config.mempool.mempool_service_port
+            )),
+        );
+        register_health_check("mempool", CheckKind::Readiness, move || {
+            let response = mempool_client.health_check_opt(
+                &HealthCheckRequest::new(),
+                CallOption::default().timeout(timeout),
+            )?;
+            ensure!(response.get_is_healthy(), "mempool is not healthy");
+            Ok(())
+        });
+    }
+}
+
 // TODO(abhayb): Move to network crate (similar to consensus).
 pub fn setup_network(
     peer_id: PeerId,
@@ -225,8 +300,28 @@ pub fn setup_network(
     (runtime, network_provider)
 }
 
+fn setup_tracing(config: &NodeConfig) {
+    if !config.trace.enabled {
+        return;
+    }
+    libra_trace::set_enabled(true);
+    if let Some(path) = &config.trace.output_path {
+        let service_name = match config.networks.get(0) {
+            Some(network) => format!("libra-node-{}", network.peer_id),
+            None => "libra-node".to_string(),
+        };
+        libra_trace::start_file_exporter(
+            service_name,
+            path.clone(),
+            Duration::from_millis(config.trace.flush_interval_ms),
+        );
+    }
+}
+
 pub fn setup_environment(node_config: &mut NodeConfig) -> (AdmissionControlClient, LibraHandle) {
     crash_handler::setup_panic_handler();
+    setup_tracing(&node_config);
+    failpoint::configure_all(&node_config.failpoints).expect("Invalid failpoints in config");
 
     let mut instant = Instant::now();
     let storage = start_storage_service(&node_config);
@@ -336,6 +431,7 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> (AdmissionControlClien
 
     let debug_if = ServerHandle::setup(setup_debug_interface(&node_config));
 
+    setup_health_checks(&node_config);
     let metrics_port = node_config.debug_interface.metrics_server_port;
     let metric_host = node_config.debug_interface.address.clone();
     thread::spawn(move || metric_server::start_server((metric_host.as_str(), metrics_port)));
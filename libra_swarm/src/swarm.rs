@@ -128,6 +128,16 @@ impl LibraNode {
         }
     }
 
+    /// Configures a failpoint inside the node process, e.g. `("libradb::commit", "panic")`.
+    pub fn set_failpoint(&self, name: &str, actions: &str) -> Result<()> {
+        self.debug_client.set_failpoint(name, actions)
+    }
+
+    /// Client of the debug interface of the node, e.g. to change its log levels.
+    pub fn debug_client(&self) -> &NodeDebugClient {
+        &self.debug_client
+    }
+
     pub fn check_connectivity(&self, expected_peers: i64) -> bool {
         if let Some(num_connected_peers) = self.get_metric("network_gauge{op=connected_peers}") {
             if num_connected_peers != expected_peers {
@@ -504,6 +514,22 @@ impl LibraSwarm {
         self.validator_nodes.get(peer_id)
     }
 
+    /// Waits for the node to exit on its own, e.g. after a failpoint made it panic. Returns false
+    /// if it is still running after `num_attempts` seconds.
+    pub fn wait_for_node_crash(&mut self, peer_id: &str, num_attempts: usize) -> bool {
+        let node = match self.validator_nodes.get_mut(peer_id) {
+            Some(node) => node,
+            None => return false,
+        };
+        for _ in 0..num_attempts {
+            if let HealthStatus::Crashed(_) = node.health_check() {
+                return true;
+            }
+            ::std::thread::sleep(::std::time::Duration::from_millis(1000));
+        }
+        false
+    }
+
     pub fn kill_node(&mut self, peer_id: &str) {
         self.validator_nodes.remove(peer_id);
     }
@@ -16,7 +16,12 @@ use crate::{
 };
 use chrono::Utc;
 use config::config::NodeConfig;
-use logger::prelude::*;
+use crypto::hash::CryptoHash;
+use debug_interface::{
+    libra_trace::{self, SpanGuard},
+    trace_span,
+};
+use logger::{log_context::LogContext, prelude::*};
 use lru_cache::LruCache;
 use std::{
     cmp::{max, min},
@@ -105,6 +110,26 @@ impl Mempool {
         db_sequence_number: u64,
         balance: u64,
         timeline_state: TimelineState,
+    ) -> MempoolAddTransactionStatus {
+        let txn_hash = txn.hash();
+        let _span = trace_span!(
+            "mempool::add_txn",
+            txn_hash.as_ref(),
+            "sender": txn.sender().short_str(),
+            "sequence_number": txn.sequence_number(),
+        );
+        LogContext::new()
+            .txn_hash(txn_hash)
+            .scope(|| self.insert_txn(txn, gas_amount, db_sequence_number, balance, timeline_state))
+    }
+
+    fn insert_txn(
+        &mut self,
+        txn: SignedTransaction,
+        gas_amount: u64,
+        db_sequence_number: u64,
+        balance: u64,
+        timeline_state: TimelineState,
     ) -> MempoolAddTransactionStatus {
         debug!(
             "[Mempool] Adding transaction to mempool: {}:{}",
@@ -218,6 +243,21 @@ impl Mempool {
             .into_iter()
             .filter_map(|(address, seq)| self.transactions.get(&address, seq))
             .collect();
+        // One span for the whole batch, in the trace of its first transaction and linked to the
+        // traces of the other ones.
+        let mut span = match block.first() {
+            Some(first) => trace_span!(
+                "mempool::get_block",
+                first.hash().as_ref(),
+                "txns": block.len(),
+            ),
+            None => SpanGuard::disabled(),
+        };
+        if libra_trace::is_enabled() {
+            for transaction in block.iter().skip(1) {
+                span.add_link(transaction.hash().as_ref());
+            }
+        }
         for transaction in &block {
             self.log_latency(
                 transaction.sender(),
@@ -50,8 +50,10 @@
 //! Transactions in Mempool have two types of expirations: systemTTL and client-specified
 //! expiration. Once we hit either of those, the transaction is removed from Mempool. SystemTTL is
 //! checked periodically in the background, while the client-specified expiration is checked on
-//! every Consensus commit request. We use a separate system TTL to ensure that a transaction won't
-//! remain stuck in Mempool forever, even if Consensus doesn't make progress
+//! every Consensus commit request against the timestamp of the committed block. This matches the
+//! VM, which rejects transactions that expired before the block they are executed in. We use a
+//! separate system TTL to ensure that a transaction won't remain stuck in Mempool forever, even if
+//! Consensus doesn't make progress
 pub mod proto;
 pub use runtime::MempoolRuntime;
 
@@ -67,6 +67,7 @@ use crate::{
 use bounded_executor::BoundedExecutor;
 use bytes::Bytes;
 use channel;
+use debug_interface::failpoint::fail_point;
 use error::RpcError;
 use futures::{
     channel::oneshot,
@@ -346,6 +347,8 @@ where
     TSubstream: AsyncRead + AsyncWrite + Send + Unpin,
 {
     let _timer = counters::RPC_LATENCY.start_timer();
+    // An injected failure is surfaced the same way as an rpc that never got a response.
+    fail_point("network::rpc_send").map_err(|_| RpcError::TimedOut)?;
     // Request a new substream with the peer.
     let substream = peer_mgr_tx.open_substream(peer_id, protocol).await?;
     // Rpc messages are length-prefixed.
@@ -9,6 +9,7 @@ use crate::{
 };
 use config::config::StateSyncConfig;
 use crypto::ed25519::*;
+use debug_interface::failpoint::fail_point;
 use execution_proto::proto::execution::{ExecuteChunkRequest, ExecuteChunkResponse};
 use failure::prelude::*;
 use futures::{
@@ -17,7 +18,7 @@ use futures::{
     stream::{futures_unordered::FuturesUnordered, select_all},
     StreamExt,
 };
-use logger::prelude::*;
+use logger::{log_context::update_node_log_context, prelude::*};
 use network::{
     proto::{GetChunkRequest, GetChunkResponse, StateSynchronizerMsg},
     validator_network::{Event, StateSynchronizerEvents, StateSynchronizerSender},
@@ -96,6 +97,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             .get_latest_version()
             .await
             .expect("[start sync] failed to fetch latest version from storage");
+        counters::KNOWN_VERSION.set(self.known_version as i64);
 
         let mut interval =
             Interval::new_interval(Duration::from_millis(self.config.tick_interval_ms))
@@ -180,6 +182,8 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             .get_latest_version()
             .await
             .expect("[state sync] failed to fetch latest version from storage");
+        counters::KNOWN_VERSION.set(self.known_version as i64);
+        update_target_version(requested_version);
 
         debug!(
             "[state sync] sync requested. Known version: {}, requested_version: {}",
@@ -214,6 +218,8 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         );
         let is_update = version > self.known_version;
         self.known_version = std::cmp::max(version, self.known_version);
+        counters::KNOWN_VERSION.set(self.known_version as i64);
+        update_node_log_context(|context| context.version = Some(self.known_version));
         if is_update {
             self.last_commit = Some(SystemTime::now());
             if let Err(err) = self.check_subscriptions().await {
@@ -369,6 +375,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
 
         let target = LedgerInfo::from_proto(response.take_ledger_info_with_sigs())?;
         self.executor_proxy.validate_ledger_info(&target)?;
+        update_target_version(target.ledger_info().version());
 
         self.store_transactions(target, txn_list_with_proof).await?;
 
@@ -435,6 +442,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         ledger_info: LedgerInfoWithSignatures<Ed25519Signature>,
         txn_list_with_proof: TransactionListWithProof,
     ) -> Result<ExecuteChunkResponse> {
+        fail_point("state_synchronizer::apply_chunk")?;
         let mut req = ExecuteChunkRequest::new();
         req.set_txn_list_with_proof(txn_list_with_proof);
         req.set_ledger_info_with_sigs(ledger_info.into_proto());
@@ -480,3 +488,9 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         Ok(())
     }
 }
+
+fn update_target_version(version: u64) {
+    if version as i64 > counters::TARGET_VERSION.get() {
+        counters::TARGET_VERSION.set(version as i64);
+    }
+}
@@ -3,7 +3,7 @@
 
 use lazy_static;
 use metrics::OpMetrics;
-use prometheus::IntCounter;
+use prometheus::{IntCounter, IntGauge};
 
 lazy_static::lazy_static! {
     pub static ref OP_COUNTERS: OpMetrics = OpMetrics::new_and_registered("state_sync");
@@ -14,4 +14,10 @@ lazy_static::lazy_static! {
 /// Large values mean that a node has been significantly behind and had to replay a lot of txns.
 pub static ref STATE_SYNC_TXN_REPLAYED: IntCounter = OP_COUNTERS.counter("state_sync_txns_replayed");
 
+/// Latest version committed locally.
+pub static ref KNOWN_VERSION: IntGauge = OP_COUNTERS.gauge("known_version");
+
+/// Highest version of the ledger infos requested by consensus or received from peers.
+pub static ref TARGET_VERSION: IntGauge = OP_COUNTERS.gauge("target_version");
+
 }
@@ -18,5 +18,11 @@ mod synchronizer;
 type PeerId = AccountAddress;
 type LedgerInfo = LedgerInfoWithSignatures<Ed25519Signature>;
 
+/// Number of versions between the latest one committed locally and the highest one known from
+/// consensus or peers.
+pub fn version_lag() -> u64 {
+    (counters::TARGET_VERSION.get() - counters::KNOWN_VERSION.get()).max(0) as u64
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,184 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline inspection of the databases of a node that is not running. It opens ConsensusDB and
+//! LibraDB read-only, so it can also be pointed at a copy of the storage directory of a stuck
+//! validator.
+
+use consensus::inspect_consensus_db;
+use failure::prelude::*;
+use libradb::LibraDB;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use types::{
+    account_address::AccountAddress, account_config::get_account_resource_or_default,
+    crypto_proxies::LedgerInfoWithSignatures, transaction::Version,
+};
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "db_inspector",
+    author = "Libra",
+    about = "Prints the consensus and ledger state stored in the databases of a node."
+)]
+struct Args {
+    /// Storage directory of the node, i.e. `storage.dir` of its config, which contains the
+    /// `consensusdb` and `libradb` directories
+    #[structopt(short = "d", long = "db-dir", parse(from_os_str))]
+    pub db_dir: PathBuf,
+    #[structopt(subcommand)]
+    pub cmd: Command,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Print the consensus state with the last vote, the highest certificates and the tree of
+    /// pending blocks
+    #[structopt(name = "consensus")]
+    Consensus,
+    /// Print the latest ledger info of every epoch
+    #[structopt(name = "ledger-infos")]
+    LedgerInfos,
+    /// Print the range of versions of the transactions in the ledger
+    #[structopt(name = "transactions")]
+    Transactions,
+    /// Print the state of an account
+    #[structopt(name = "account")]
+    Account {
+        /// Address of the account, in hex
+        #[structopt(short = "a", long = "address")]
+        address: AccountAddress,
+        /// Version of the state, the latest one by default
+        #[structopt(short = "v", long = "version")]
+        version: Option<Version>,
+    },
+    /// Print the root hash of the Jellyfish Merkle tree of the state, and check that it matches
+    /// the one in the transaction info
+    #[structopt(name = "state-root")]
+    StateRoot {
+        /// Version of the state, the latest one by default
+        #[structopt(short = "v", long = "version")]
+        version: Option<Version>,
+    },
+    /// Check that the transaction accumulator root hash matches every stored ledger info
+    #[structopt(name = "check")]
+    Check,
+}
+
+fn main() {
+    let args = Args::from_args();
+    if let Err(err) = run(args) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<()> {
+    match args.cmd {
+        Command::Consensus => {
+            print!("{}", inspect_consensus_db(&args.db_dir)?);
+            Ok(())
+        }
+        cmd => inspect_libradb(&LibraDB::open_readonly(&args.db_dir)?, cmd),
+    }
+}
+
+fn inspect_libradb(db: &LibraDB, cmd: Command) -> Result<()> {
+    let latest_version = db
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("LibraDB is not bootstrapped."))?
+        .latest_version;
+    match cmd {
+        Command::Consensus => unreachable!("ConsensusDB is inspected separately"),
+        Command::LedgerInfos => {
+            for ledger_info in get_ledger_infos(db)? {
+                println!("{}", ledger_info);
+            }
+        }
+        Command::Transactions => {
+            let (first_version, last_version) = db
+                .get_transaction_version_range()?
+                .ok_or_else(|| format_err!("No transaction in LibraDB."))?;
+            println!("Transactions: [{}, {}]", first_version, last_version);
+            if let Some(ledger_info) = get_ledger_infos(db)?.last() {
+                println!(
+                    "Latest ledger info version: {}",
+                    ledger_info.ledger_info().version()
+                );
+            }
+        }
+        Command::Account { address, version } => {
+            let version = version.unwrap_or(latest_version);
+            let (blob, _proof) = db.get_account_state_with_proof_by_version(address, version)?;
+            match blob {
+                Some(_) => println!(
+                    "Account {} at version {}: {:?}",
+                    address,
+                    version,
+                    get_account_resource_or_default(&blob)?
+                ),
+                None => println!("Account {} does not exist at version {}", address, version),
+            }
+        }
+        Command::StateRoot { version } => {
+            let version = version.unwrap_or(latest_version);
+            let root_hash = db.get_state_root_hash(version)?;
+            let txn_info_root_hash = db.get_transaction_info(version)?.state_root_hash();
+            println!("State root hash at version {}: {}", version, root_hash);
+            ensure!(
+                root_hash == txn_info_root_hash,
+                "MISMATCH: the transaction info at version {} has state root hash {}",
+                version,
+                txn_info_root_hash
+            );
+        }
+        Command::Check => check_ledger_infos(db)?,
+    }
+    Ok(())
+}
+
+fn get_ledger_infos(db: &LibraDB) -> Result<Vec<LedgerInfoWithSignatures>> {
+    let mut ledger_infos = db.get_latest_ledger_infos_per_epoch(0 /* start_epoch */)?;
+    ledger_infos.sort_by_key(|ledger_info| ledger_info.ledger_info().epoch_num());
+    Ok(ledger_infos)
+}
+
+fn check_ledger_infos(db: &LibraDB) -> Result<()> {
+    let mut num_failures = 0;
+    for ledger_info_with_sigs in get_ledger_infos(db)? {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let result = db
+            .get_transaction_accumulator_root_hash(ledger_info.version())
+            .and_then(|root_hash| {
+                ensure!(
+                    root_hash == ledger_info.transaction_accumulator_hash(),
+                    "transaction accumulator root hash is {}",
+                    root_hash
+                );
+                Ok(())
+            });
+        match result {
+            Ok(()) => println!(
+                "OK: epoch {}, version {}",
+                ledger_info.epoch_num(),
+                ledger_info.version()
+            ),
+            Err(err) => {
+                num_failures += 1;
+                println!(
+                    "FAILED: epoch {}, version {}, ledger info transaction accumulator hash {}: {}",
+                    ledger_info.epoch_num(),
+                    ledger_info.version(),
+                    ledger_info.transaction_accumulator_hash(),
+                    err
+                );
+            }
+        }
+    }
+    ensure!(
+        num_failures == 0,
+        "{} ledger infos do not match the transaction accumulator.",
+        num_failures
+    );
+    Ok(())
+}
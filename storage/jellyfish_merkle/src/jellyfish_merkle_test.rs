@@ -30,13 +30,14 @@ fn test_insert_to_empty_tree() {
     let key = HashValue::random();
     let value = AccountStateBlob::from(vec![1u8, 2u8, 3u8, 4u8]);
 
-    let (_new_root_hash, batch) = tree
+    let (new_root_hash, batch) = tree
         .put_blob_set(vec![(key, value.clone())], 0 /* version */)
         .unwrap();
     assert!(batch.stale_node_index_batch.is_empty());
     db.write_tree_update_batch(batch).unwrap();
 
     assert_eq!(tree.get(key, 0).unwrap().unwrap(), value);
+    assert_eq!(tree.get_root_hash(0).unwrap(), new_root_hash);
 }
 
 #[test]
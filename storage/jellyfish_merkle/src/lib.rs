@@ -524,6 +524,15 @@ where
         bail!("Jellyfish Merkle tree has cyclic graph inside.");
     }
 
+    /// Returns the root hash of the tree at `version`, computed from the root node stored for that
+    /// version.
+    pub fn get_root_hash(&self, version: Version) -> Result<HashValue> {
+        Ok(self
+            .reader
+            .get_node(&NodeKey::new_empty_path(version))?
+            .hash())
+    }
+
     #[cfg(test)]
     pub fn get(&self, key: HashValue, version: Version) -> Result<Option<AccountStateBlob>> {
         Ok(self.get_with_proof(key, version)?.0)
@@ -0,0 +1,182 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Crash-consistency tests: a workload of commits and pruning is cut short at every write to
+//! RocksDB by failing the `schemadb::write` failpoint, which is what a crash at that point would
+//! leave on disk. The DB is then reopened, checked to be consistent and to hold exactly the
+//! blocks committed before the crash, and the rest of the workload is replayed on it.
+//!
+//! Failpoints are global to the process, hence the test runs in its own process.
+
+use super::*;
+use crate::{
+    mock_genesis::{db_with_mock_genesis, GENESIS_INFO},
+    pruner::prune_state,
+    schema::stale_node_index::StaleNodeIndexSchema,
+    test_helper::arb_blocks_to_commit,
+};
+use debug_interface::failpoint;
+use proptest::test_runner::{Config, TestRunner};
+use rusty_fork::{rusty_fork_id, rusty_fork_test, rusty_fork_test_name};
+use schemadb::WRITE_FAILPOINT;
+use std::collections::HashMap;
+use tools::tempdir::TempPath;
+use types::proof::verify_sparse_merkle_element;
+
+type BlockToCommit = (
+    Vec<TransactionToCommit>,
+    LedgerInfoWithSignatures<Ed25519Signature>,
+);
+
+/// Saves the blocks from `blocks[num_committed]` on, then prunes every version but the latest
+/// one, in batches as small as possible so that there are many writes to crash at.
+fn run_workload(db: &LibraDB, blocks: &[BlockToCommit], num_committed: usize) -> Result<()> {
+    for (txns_to_commit, ledger_info_with_sigs) in &blocks[num_committed..] {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        db.save_transactions(
+            txns_to_commit,
+            ledger_info.version() + 1 - txns_to_commit.len() as u64, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )?;
+    }
+
+    // The pruner restarts from scratch after a crash, as the worker does.
+    let latest_version = db
+        .ledger_store
+        .get_latest_ledger_info()?
+        .ledger_info()
+        .version();
+    let mut least_readable_version = 0;
+    loop {
+        let new_least_readable_version = prune_state(
+            Arc::clone(&db.db),
+            least_readable_version,
+            latest_version,
+            2, /* max_versions */
+        )?;
+        if new_least_readable_version == least_readable_version {
+            break;
+        }
+        least_readable_version = new_least_readable_version;
+    }
+    db.db.range_delete::<StaleNodeIndexSchema, Version>(
+        &0,
+        &(least_readable_version + 1), // end is exclusive
+    )?;
+    Ok(())
+}
+
+/// Checks that the DB is consistent and returns the number of blocks it holds.
+fn verify_consistency(db: &LibraDB, blocks: &[BlockToCommit]) -> Result<usize> {
+    let startup_info = db
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("DB is not bootstrapped."))?;
+    let ledger_info = &startup_info.ledger_info;
+    let latest_version = startup_info.latest_version;
+
+    // Every block is committed with its ledger info in one batch, so no transaction can be
+    // persisted without the ledger info covering it.
+    ensure!(
+        latest_version == ledger_info.version(),
+        "Latest version {} is not the one of the latest ledger info {}.",
+        latest_version,
+        ledger_info.version(),
+    );
+    let num_committed = std::iter::once(&GENESIS_INFO.1)
+        .chain(
+            blocks
+                .iter()
+                .map(|(_, ledger_info_with_sigs)| ledger_info_with_sigs),
+        )
+        .position(|ledger_info_with_sigs| ledger_info_with_sigs.ledger_info() == ledger_info)
+        .ok_or_else(|| format_err!("Unexpected latest ledger info {}.", ledger_info))?;
+
+    // The accumulator and the transactions agree with the ledger info.
+    ensure!(
+        db.ledger_store.get_root_hash(latest_version)?
+            == ledger_info.transaction_accumulator_hash(),
+        "Transaction accumulator root hash does not match the ledger info at version {}.",
+        latest_version,
+    );
+    db.get_transactions(
+        0, /* start_version */
+        latest_version + 1,
+        latest_version,
+        false, /* fetch_events */
+    )?
+    .verify(ledger_info, Some(0))?;
+
+    // The latest state tree is complete, i.e. no live node has been pruned, and holds the
+    // latest state of every account written by the committed blocks.
+    let state_root_hash = db
+        .ledger_store
+        .get_transaction_info(latest_version)?
+        .state_root_hash();
+    ensure!(
+        state_root_hash == startup_info.account_state_root_hash,
+        "State root hash does not match the startup info."
+    );
+    let mut account_states: HashMap<AccountAddress, AccountStateBlob> = HashMap::new();
+    for txn_to_commit in std::iter::once(&GENESIS_INFO.2).chain(
+        blocks[..num_committed]
+            .iter()
+            .flat_map(|(txns_to_commit, _)| txns_to_commit),
+    ) {
+        account_states.extend(txn_to_commit.account_states().clone());
+    }
+    for (address, expected_blob) in account_states {
+        let (blob, proof) = db.get_account_state_with_proof_by_version(address, latest_version)?;
+        ensure!(
+            blob.as_ref() == Some(&expected_blob),
+            "Unexpected state of account {} at version {}.",
+            address,
+            latest_version,
+        );
+        verify_sparse_merkle_element(state_root_hash, address.hash(), &blob, &proof)?;
+    }
+
+    Ok(num_committed)
+}
+
+fn test_crash_consistency_impl(blocks: Vec<BlockToCommit>) -> Result<()> {
+    // Crash right before the `crash_point`-th write, until the workload completes.
+    for crash_point in 0.. {
+        let tmp_dir = TempPath::new();
+        let db = db_with_mock_genesis(&tmp_dir)?;
+        let actions = match crash_point {
+            0 => "return".to_string(),
+            n => format!("{}*off->return", n),
+        };
+        failpoint::configure(WRITE_FAILPOINT, &actions)?;
+        let result = run_workload(&db, &blocks, 0 /* num_committed */);
+        failpoint::clear_all();
+
+        // Reopen the DB as a restarted node would.
+        drop(db);
+        let db = LibraDB::new(&tmp_dir);
+        let num_committed = verify_consistency(&db, &blocks)?;
+        // Saving a block is a single write.
+        assert_eq!(num_committed, std::cmp::min(crash_point, blocks.len()));
+        if result.is_ok() {
+            return Ok(());
+        }
+
+        // The DB is recoverable: the workload can go on from where it crashed.
+        run_workload(&db, &blocks, num_committed)?;
+        assert_eq!(verify_consistency(&db, &blocks)?, blocks.len());
+    }
+    unreachable!("The workload has a finite number of writes.")
+}
+
+rusty_fork_test! {
+#[test]
+fn test_crash_consistency() {
+    let mut runner = TestRunner::new(Config::with_cases(3));
+    runner
+        .run(&arb_blocks_to_commit(), |blocks| {
+            test_crash_consistency_impl(blocks).unwrap();
+            Ok(())
+        })
+        .unwrap();
+}
+}
@@ -12,7 +12,7 @@ use crate::{
     ledger_counters::LedgerCounter,
     schema::{
         event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema,
+        event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
     },
 };
 use accumulator::{HashReader, MerkleAccumulator};
@@ -27,6 +27,7 @@ use types::{
     account_address::AccountAddress,
     contract_event::ContractEvent,
     event::EventKey,
+    language_storage::{StructTag, TypeTag},
     proof::{position::Position, AccumulatorProof, EventProof},
     transaction::Version,
 };
@@ -181,6 +182,56 @@ impl EventStore {
         Ok(result)
     }
 
+    /// Given `event_type` and the position designated by `start_version` and `start_event_index`,
+    /// returns up to `limit` events of that type identified by transaction version and index among
+    /// all events emitted by the same transaction. Result won't contain records with a transaction
+    /// version > `ledger_version` and is in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        event_type: &StructTag,
+        start_version: Version,
+        start_event_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, u64)>> {
+        let event_type_hash = event_type.hash();
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(event_type_hash, start_version, start_event_index))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((type_hash, ver, idx), ()) = res?;
+            if type_hash != event_type_hash || ver > ledger_version {
+                break;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
+    /// Index contract events yielded by the transaction at `version` by their struct types.
+    /// Events of other types are not indexed.
+    pub fn put_event_types(
+        &self,
+        version: u64,
+        events: &[ContractEvent],
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        events
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, event)| match event.type_tag() {
+                TypeTag::Struct(event_type) => Some((idx, event_type)),
+                _ => None,
+            })
+            .map(|(idx, event_type)| {
+                cs.batch
+                    .put::<EventByTypeSchema>(&(event_type.hash(), version, idx as u64), &())
+            })
+            .collect::<Result<()>>()
+    }
+
     /// Save contract events yielded by the transaction at `version` and return root hash of the
     /// event accumulator formed by these events.
     pub fn put_events(
@@ -16,8 +16,10 @@ use std::collections::HashMap;
 use tools::tempdir::TempPath;
 use types::{
     account_address::AccountAddress,
+    account_config::{received_event_struct_tag, sent_event_struct_tag},
     contract_event::ContractEvent,
     event::EventKey,
+    identifier::Identifier,
     proof::verify_event_accumulator_element,
     proptest_types::{AccountInfoUniverse, ContractEventGen},
 };
@@ -215,3 +217,55 @@ fn test_get_events_by_access_path_impl(event_batches: Vec<Vec<ContractEvent>>) {
         assert_eq!(events, traversed);
     });
 }
+
+#[test]
+fn test_get_events_by_type() {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new(&tmp_dir);
+    let store = &db.event_store;
+
+    let (sent_key, received_key) = (EventKey::random(), EventKey::random());
+    let sent_tag = TypeTag::Struct(sent_event_struct_tag());
+    let received_tag = TypeTag::Struct(received_event_struct_tag());
+
+    // Each transaction emits a payment event pair and an event of a non-struct type.
+    let mut cs = ChangeSet::new();
+    for ver in 0..3 {
+        let events = vec![
+            ContractEvent::new(EventKey::random(), 0, TypeTag::ByteArray, vec![]),
+            ContractEvent::new(sent_key, ver, sent_tag.clone(), vec![]),
+            ContractEvent::new(received_key, ver, received_tag.clone(), vec![]),
+        ];
+        store.put_event_types(ver, &events, &mut cs).unwrap();
+        store.put_events(ver, &events, &mut cs).unwrap();
+    }
+    store.db.write_schemas(cs.batch).unwrap();
+
+    let sent_type = sent_event_struct_tag();
+    assert_eq!(
+        store
+            .lookup_events_by_type(&sent_type, 0, 0, 10, 2)
+            .unwrap(),
+        vec![(0, 1), (1, 1), (2, 1)]
+    );
+    // Paging resumes at the given position and stops at the ledger version.
+    assert_eq!(
+        store
+            .lookup_events_by_type(&sent_type, 0, 2, 10, 1)
+            .unwrap(),
+        vec![(1, 1)]
+    );
+    assert_eq!(
+        store
+            .lookup_events_by_type(&received_event_struct_tag(), 1, 0, 1, 2)
+            .unwrap(),
+        vec![(1, 2)]
+    );
+    // Events of other types are not mixed in.
+    let mut other_type = sent_type;
+    other_type.name = Identifier::new("OtherEvent").unwrap();
+    assert!(store
+        .lookup_events_by_type(&other_type, 0, 0, 10, 2)
+        .unwrap()
+        .is_empty());
+}
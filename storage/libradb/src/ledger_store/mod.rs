@@ -26,6 +26,7 @@ use std::{ops::Deref, sync::Arc};
 use types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{
+        accumulator::Accumulator as InMemoryAccumulator,
         position::{FrozenSubTreeIterator, Position},
         AccumulatorProof,
     },
@@ -117,6 +118,20 @@ impl LedgerStore {
             .ok_or_else(|| LibraDbError::NotFound(String::from("Genesis TransactionInfo.")).into())
     }
 
+    /// Get the versions of the first and the last transaction infos.
+    pub fn get_transaction_info_version_range(&self) -> Result<Option<(Version, Version)>> {
+        let mut iter = self
+            .db
+            .iter::<TransactionInfoSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        let first_version = match iter.next().transpose()? {
+            Some((version, _)) => version,
+            None => return Ok(None),
+        };
+        let (last_version, _) = self.get_latest_transaction_info()?;
+        Ok(Some((first_version, last_version)))
+    }
+
     /// Get transaction info at `version` with proof towards root of ledger at `ledger_version`.
     pub fn get_transaction_info_with_proof(
         &self,
@@ -178,6 +193,16 @@ impl LedgerStore {
         )
     }
 
+    /// Get root hash of the transaction accumulator after the transaction at `version`.
+    pub fn get_root_hash(&self, version: Version) -> Result<HashValue> {
+        let frozen_subtree_hashes = self.get_ledger_frozen_subtree_hashes(version)?;
+        Ok(InMemoryAccumulator::<TransactionAccumulatorHasher>::new(
+            frozen_subtree_hashes,
+            version + 1, /* num_leaves */
+        )?
+        .root_hash())
+    }
+
     /// From left to right, get frozen subtree root hashes of the transaction accumulator.
     pub fn get_ledger_frozen_subtree_hashes(&self, version: Version) -> Result<Vec<HashValue>> {
         FrozenSubTreeIterator::new(version + 1)
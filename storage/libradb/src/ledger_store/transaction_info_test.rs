@@ -64,5 +64,13 @@ proptest! {
 
         // retrieve batch1 and verify against root_hash after batch1 was interted
         verify(store, &batch1, 0, ledger_version1, root_hash1);
+
+        // root hashes are recomputed from the stored accumulator nodes
+        prop_assert_eq!(store.get_root_hash(ledger_version1).unwrap(), root_hash1);
+        prop_assert_eq!(store.get_root_hash(ledger_version2).unwrap(), root_hash2);
+        prop_assert_eq!(
+            store.get_transaction_info_version_range().unwrap(),
+            Some((0, ledger_version2))
+        );
     }
 }
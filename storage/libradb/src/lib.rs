@@ -25,6 +25,8 @@ mod state_store;
 mod system_store;
 mod transaction_store;
 
+#[cfg(test)]
+mod crash_consistency_test;
 #[cfg(test)]
 mod libradb_test;
 
@@ -41,6 +43,7 @@ use crate::{
     transaction_store::TransactionStore,
 };
 use crypto::{ed25519::*, hash::CryptoHash, HashValue};
+use debug_interface::{failpoint::fail_point, libra_trace::SpanGuard, trace_span};
 use failure::prelude::*;
 use itertools::{izip, zip_eq};
 use lazy_static::lazy_static;
@@ -56,6 +59,7 @@ use types::{
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
     contract_event::EventWithProof,
     get_with_proof::{RequestItem, ResponseItem},
+    language_storage::StructTag,
     ledger_info::LedgerInfoWithSignatures,
     proof::{AccountStateProof, EventProof, SignedTransactionProof, SparseMerkleProof},
     transaction::{
@@ -96,15 +100,15 @@ impl LibraDB {
     /// Config parameter for the pruner.
     const NUM_HISTORICAL_VERSIONS_TO_KEEP: u64 = 1_000_000;
 
-    /// This creates an empty LibraDB instance on disk or opens one if it already exists.
-    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let cf_opts_map: ColumnFamilyOptionsMap = [
+    fn column_families() -> ColumnFamilyOptionsMap {
+        [
             (
                 /* LedgerInfo CF = */ DEFAULT_CF_NAME,
                 ColumnFamilyOptions::default(),
             ),
             (EVENT_ACCUMULATOR_CF_NAME, ColumnFamilyOptions::default()),
             (EVENT_BY_KEY_CF_NAME, ColumnFamilyOptions::default()),
+            (EVENT_BY_TYPE_CF_NAME, ColumnFamilyOptions::default()),
             (EVENT_CF_NAME, ColumnFamilyOptions::default()),
             (
                 JELLYFISH_MERKLE_NODE_CF_NAME,
@@ -126,12 +130,15 @@ impl LibraDB {
         ]
         .iter()
         .cloned()
-        .collect();
+        .collect()
+    }
 
+    /// This creates an empty LibraDB instance on disk or opens one if it already exists.
+    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
         let path = db_root_path.as_ref().join("libradb");
         let instant = Instant::now();
         let db = Arc::new(
-            DB::open(path.clone(), cf_opts_map)
+            DB::open(path.clone(), Self::column_families())
                 .unwrap_or_else(|e| panic!("LibraDB open failed: {:?}", e)),
         );
 
@@ -141,6 +148,18 @@ impl LibraDB {
             instant.elapsed().as_millis()
         );
 
+        Self::new_with_db(db)
+    }
+
+    /// This opens an existing LibraDB instance read-only, e.g. to inspect the DB of a node that is
+    /// not running. Writes to the returned instance fail.
+    pub fn open_readonly<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let path = db_root_path.as_ref().join("libradb");
+        let db = Arc::new(DB::open_readonly(path, Self::column_families())?);
+        Ok(Self::new_with_db(db))
+    }
+
+    fn new_with_db(db: Arc<DB>) -> Self {
         LibraDB {
             db: Arc::clone(&db),
             event_store: EventStore::new(Arc::clone(&db)),
@@ -208,7 +227,9 @@ impl LibraDB {
         let account_resource = if let Some(account_blob) = &account_state.blob {
             AccountResource::make_from(&(&account_blob.try_into()?))?
         } else {
-            bail!("Nothing stored under address: {}", query_path.address);
+            // No event was ever emitted under a non-existent account, which the proof of the
+            // account's absence shows.
+            return Ok((Vec::new(), account_state));
         };
         let event_key = account_resource
             .get_event_handle_by_query_path(&query_path.path)?
@@ -272,10 +293,45 @@ impl LibraDB {
         }
 
         // We always need to return the account blob to prove that this is indeed the event that was
-        // being queried.
+        // being queried, and that the events returned are all the events in the requested range.
         Ok((events_with_proof, account_state))
     }
 
+    /// Returns up to `limit` events of type `event_type` emitted by any account, starting with the
+    /// event at index `start_event_index` of the transaction at `start_version` (inclusive) and in
+    /// the order they were committed.
+    fn get_events_by_type(
+        &self,
+        event_type: &StructTag,
+        start_version: Version,
+        start_event_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithProof>> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+        self.event_store
+            .lookup_events_by_type(
+                event_type,
+                start_version,
+                start_event_index,
+                limit,
+                ledger_version,
+            )?
+            .into_iter()
+            .map(|(ver, idx)| {
+                let (event, event_proof) = self
+                    .event_store
+                    .get_event_with_proof_by_version_and_index(ver, idx)?;
+                let (txn_info, txn_info_proof) = self
+                    .ledger_store
+                    .get_transaction_info_with_proof(ver, ledger_version)?;
+                let proof = EventProof::new(txn_info_proof, txn_info, event_proof);
+                Ok(EventWithProof::new(ver, idx, event, proof))
+            })
+            .collect()
+    }
+
     /// Returns a signed transaction that is the `seq_num`-th one associated with the given account.
     /// If the signed transaction with given `seq_num` doesn't exist, returns `None`.
     fn get_txn_by_account(
@@ -346,6 +402,16 @@ impl LibraDB {
                 claimed_last_version,
             );
         }
+        // Only commits driven by consensus belong to a block trace.
+        let _span = match ledger_info_with_sigs {
+            Some(x) => trace_span!(
+                "libradb::save_transactions",
+                x.ledger_info().consensus_block_id().as_ref(),
+                "first_version": first_version,
+                "txns": num_txns,
+            ),
+            None => SpanGuard::disabled(),
+        };
 
         // Gather db mutations to `batch`.
         let mut cs = ChangeSet::new();
@@ -409,6 +475,8 @@ impl LibraDB {
         // Event updates. Gather event accumulator root hashes.
         let event_root_hashes = zip_eq(first_version..=last_version, txns_to_commit)
             .map(|(ver, txn_to_commit)| {
+                self.event_store
+                    .put_event_types(ver, txn_to_commit.events(), &mut cs)?;
                 self.event_store
                     .put_events(ver, txn_to_commit.events(), &mut cs)
             })
@@ -499,7 +567,7 @@ impl LibraDB {
                     ascending,
                     limit,
                 } => {
-                    let (events_with_proof, proof_of_latest_event) = self
+                    let (events_with_proof, proof_of_event_handle) = self
                         .get_events_by_query_path(
                             &access_path,
                             start_event_seq_num,
@@ -509,7 +577,7 @@ impl LibraDB {
                         )?;
                     Ok(ResponseItem::GetEventsByEventAccessPath {
                         events_with_proof,
-                        proof_of_latest_event,
+                        proof_of_event_handle,
                     })
                 }
                 RequestItem::GetTransactions {
@@ -524,6 +592,22 @@ impl LibraDB {
                         txn_list_with_proof,
                     })
                 }
+                RequestItem::GetEventsByEventType {
+                    event_type,
+                    start_version,
+                    start_event_index,
+                    limit,
+                } => {
+                    let events_with_proof = self.get_events_by_type(
+                        &event_type,
+                        start_version,
+                        start_event_index,
+                        limit,
+                        ledger_version,
+                    )?;
+
+                    Ok(ResponseItem::GetEventsByEventType { events_with_proof })
+                }
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -634,6 +718,39 @@ impl LibraDB {
         ))
     }
 
+    // ================================ Inspection APIs ===============================
+    /// Returns the first and the last versions of the transaction infos in the DB, or `None` if
+    /// the DB is not bootstrapped.
+    ///
+    /// This is used by offline tools inspecting the DB.
+    pub fn get_transaction_version_range(&self) -> Result<Option<(Version, Version)>> {
+        self.ledger_store.get_transaction_info_version_range()
+    }
+
+    /// Gets the transaction info at `version`, which carries the root hash of the state Merkle
+    /// tree after that transaction.
+    ///
+    /// This is used by offline tools inspecting the DB.
+    pub fn get_transaction_info(&self, version: Version) -> Result<TransactionInfo> {
+        self.ledger_store.get_transaction_info(version)
+    }
+
+    /// Gets the root hash of the state Merkle tree after the transaction at `version`, computed
+    /// from the tree nodes stored in the DB rather than read from the transaction info.
+    ///
+    /// This is used by offline tools inspecting the DB.
+    pub fn get_state_root_hash(&self, version: Version) -> Result<HashValue> {
+        self.state_store.get_root_hash(version)
+    }
+
+    /// Gets the root hash of the transaction accumulator after the transaction at `version`,
+    /// computed from the accumulator nodes stored in the DB.
+    ///
+    /// This is used by offline tools inspecting the DB.
+    pub fn get_transaction_accumulator_root_hash(&self, version: Version) -> Result<HashValue> {
+        self.ledger_store.get_root_hash(version)
+    }
+
     // ================================== Private APIs ==================================
     /// Convert a `ChangeSet` to `SealedChangeSet`.
     ///
@@ -664,6 +781,7 @@ impl LibraDB {
     /// state of some transaction by leveraging rocksdb atomicity support. Also committed are the
     /// LedgerCounters.
     fn commit(&self, sealed_cs: SealedChangeSet) -> Result<()> {
+        fail_point("libradb::commit")?;
         self.db.write_schemas(sealed_cs.batch)?;
 
         match self.db.get_approximate_sizes_cf() {
@@ -13,7 +13,8 @@ use std::collections::HashMap;
 use tools::tempdir::TempPath;
 use types::{
     account_config::get_account_resource_or_default, contract_event::ContractEvent,
-    ledger_info::LedgerInfo,
+    get_with_proof::verify_update_to_latest_ledger_response, ledger_info::LedgerInfo,
+    validator_verifier::ValidatorVerifier,
 };
 
 fn test_save_blocks_impl(
@@ -134,7 +135,7 @@ fn get_events_by_query_path(
 
     let mut ret = Vec::new();
     loop {
-        let (events_with_proof, proof_of_latest_event) = db.get_events_by_query_path(
+        let (events_with_proof, proof_of_event_handle) = db.get_events_by_query_path(
             query_path,
             cursor,
             ascending,
@@ -142,13 +143,13 @@ fn get_events_by_query_path(
             ledger_info.version(),
         )?;
 
-        let account_resource = get_account_resource_or_default(&proof_of_latest_event.blob)?;
+        let account_resource = get_account_resource_or_default(&proof_of_event_handle.blob)?;
         let expected_event_key = account_resource
             .get_event_handle_by_query_path(&query_path.path)?
             .key();
 
         let num_events = events_with_proof.len() as u64;
-        proof_of_latest_event.verify(ledger_info, ledger_info.version(), query_path.address)?;
+        proof_of_event_handle.verify(ledger_info, ledger_info.version(), query_path.address)?;
 
         if cursor == u64::max_value() {
             cursor = last_seq_num;
@@ -426,6 +427,39 @@ fn test_bootstrapping_already_bootstrapped_db() {
     );
 }
 
+#[test]
+fn test_get_events_of_nonexistent_account() {
+    let tmp_dir = TempPath::new();
+    let db = db_with_mock_genesis(&tmp_dir).unwrap();
+
+    let request_items = vec![RequestItem::GetEventsByEventAccessPath {
+        access_path: AccessPath::new_for_sent_event(AccountAddress::random()),
+        start_event_seq_num: 0,
+        ascending: true,
+        limit: 10,
+    }];
+    let (response_items, ledger_info_with_sigs, _validator_change_events) = db
+        .update_to_latest_ledger(0, request_items.clone())
+        .unwrap();
+    verify_update_to_latest_ledger_response(
+        Arc::new(ValidatorVerifier::new(HashMap::new())),
+        0,
+        &request_items,
+        &response_items,
+        &ledger_info_with_sigs,
+    )
+    .unwrap();
+
+    let (events_with_proof, proof_of_event_handle) = response_items
+        .into_iter()
+        .next()
+        .unwrap()
+        .into_get_events_by_access_path_response()
+        .unwrap();
+    assert!(events_with_proof.is_empty());
+    assert!(proof_of_event_handle.blob.is_none());
+}
+
 #[test]
 fn test_get_first_seq_num_and_limit() {
     assert!(get_first_seq_num_and_limit(true, 0, 0).is_err());
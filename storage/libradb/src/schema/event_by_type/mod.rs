@@ -0,0 +1,71 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an event index via which all ContractEvents of
+//! a given type (represented by a <txn_version, event_idx> tuple so that they can be fetched from
+//! `EventSchema`) can be found in the order they were committed, regardless of the account they
+//! were emitted to.
+//!
+//! The event type is identified by the hash of its struct tag.
+//!
+//! ```text
+//! |<-----------------key----------------->|
+//! | event_type_hash | txn_ver | idx |
+//! ```
+//!
+//! `txn_ver` and `idx` are serialized in big endian so that records in RocksDB will be in order of
+//! their numeric values.
+
+use crate::schema::{ensure_slice_len_eq, EVENT_BY_TYPE_CF_NAME};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use crypto::HashValue;
+use failure::prelude::*;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+use types::transaction::Version;
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (HashValue, Version, Index);
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref event_type_hash, version, index) = *self;
+
+        let mut encoded = event_type_hash.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        const HASH_LEN: usize = HashValue::LENGTH;
+        const VERSION_SIZE: usize = size_of::<Version>();
+        let event_type_hash = HashValue::from_slice(&data[..HASH_LEN])?;
+        let version = (&data[HASH_LEN..HASH_LEN + VERSION_SIZE]).read_u64::<BigEndian>()?;
+        let index = (&data[HASH_LEN + VERSION_SIZE..]).read_u64::<BigEndian>()?;
+
+        Ok((event_type_hash, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
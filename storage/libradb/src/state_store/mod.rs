@@ -46,6 +46,11 @@ impl StateStore {
         Ok((blob, proof))
     }
 
+    /// Get the root hash of the state Merkle tree at `version` from the stored tree nodes.
+    pub fn get_root_hash(&self, version: Version) -> Result<HashValue> {
+        JellyfishMerkleTree::new(self).get_root_hash(version)
+    }
+
     /// Put the results generated by `account_state_sets` to `batch` and return the result root
     /// hashes for each write set.
     pub fn put_account_state_sets(
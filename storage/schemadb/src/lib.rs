@@ -46,6 +46,22 @@ pub type ColumnFamilyOptionsMap = HashMap<ColumnFamilyName, ColumnFamilyOptions>
 /// [`LedgerInfo`](../types/ledger_info/struct.LedgerInfo.html).
 pub const DEFAULT_CF_NAME: ColumnFamilyName = "default";
 
+/// Name of the failpoint evaluated right before every write to RocksDB when the `fail-on-write`
+/// feature is enabled. Failing it makes the write, and every later one while it keeps failing,
+/// never reach the DB, like a crash would.
+pub const WRITE_FAILPOINT: &str = "schemadb::write";
+
+#[cfg(feature = "fail-on-write")]
+fn fail_on_write() -> Result<()> {
+    debug_interface::failpoint::fail_point(WRITE_FAILPOINT)
+}
+
+#[cfg(not(feature = "fail-on-write"))]
+#[inline]
+fn fail_on_write() -> Result<()> {
+    Ok(())
+}
+
 #[derive(Debug)]
 enum WriteOp {
     Value(Vec<u8>),
@@ -209,6 +225,31 @@ impl DB {
         Ok(db)
     }
 
+    /// Open the existing db at `path` with all the column families provided, read-only. Unlike
+    /// `open`, it never creates the db and does not take the db lock, so it can be used to
+    /// inspect the db of a node that is not running.
+    pub fn open_readonly<P: AsRef<Path>>(
+        path: P,
+        cf_opts_map: ColumnFamilyOptionsMap,
+    ) -> Result<Self> {
+        ensure!(
+            db_exists(path.as_ref()),
+            "No db found at {:?}.",
+            path.as_ref()
+        );
+        let inner = rocksdb::DB::open_cf_for_read_only(
+            DBOptions::new(),
+            path.as_ref().to_str().ok_or_else(|| {
+                format_err!("Path {:?} can not be converted to string.", path.as_ref())
+            })?,
+            cf_opts_map.into_iter().collect(),
+            false, /* error_if_log_file_exist */
+        )
+        .map_err(convert_rocksdb_err)?;
+
+        Ok(DB { inner })
+    }
+
     fn open_cf<'a, P, T>(opts: DBOptions, path: P, cfds: Vec<T>) -> Result<DB>
     where
         P: AsRef<Path>,
@@ -252,6 +293,7 @@ impl DB {
         let v = <S::Value as ValueCodec<S>>::encode_value(&value)?;
         let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
 
+        fail_on_write()?;
         self.inner
             .put_cf_opt(cf_handle, &k, &v, &default_write_options())
             .map_err(convert_rocksdb_err)
@@ -270,6 +312,7 @@ impl DB {
         let raw_end = end.encode_seek_key()?;
         let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
 
+        fail_on_write()?;
         self.inner
             .delete_range_cf(&cf_handle, &raw_begin, &raw_end)
             .map_err(convert_rocksdb_err)
@@ -295,6 +338,7 @@ impl DB {
             }
         }
 
+        fail_on_write()?;
         self.inner
             .write_opt(&db_batch, &default_write_options())
             .map_err(convert_rocksdb_err)?;
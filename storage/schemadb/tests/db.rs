@@ -71,8 +71,8 @@ impl ValueCodec<TestSchema2> for TestField {
     }
 }
 
-fn open_db(dir: &tools::tempdir::TempPath) -> DB {
-    let cf_opts_map: ColumnFamilyOptionsMap = [
+fn get_cf_opts_map() -> ColumnFamilyOptionsMap {
+    [
         (DEFAULT_CF_NAME, ColumnFamilyOptions::default()),
         (
             TestSchema1::COLUMN_FAMILY_NAME,
@@ -85,8 +85,11 @@ fn open_db(dir: &tools::tempdir::TempPath) -> DB {
     ]
     .iter()
     .cloned()
-    .collect();
-    DB::open(&dir.path(), cf_opts_map).expect("Failed to open DB.")
+    .collect()
+}
+
+fn open_db(dir: &tools::tempdir::TempPath) -> DB {
+    DB::open(&dir.path(), get_cf_opts_map()).expect("Failed to open DB.")
 }
 
 struct TestDB {
@@ -323,3 +326,23 @@ fn test_report_size() {
     assert!(*cf_sizes.get("TestCF2").unwrap() > 0);
     assert_eq!(*cf_sizes.get("default").unwrap(), 0);
 }
+
+#[test]
+fn test_open_readonly() {
+    let tmpdir = tools::tempdir::TempPath::new();
+    assert!(DB::open_readonly(&tmpdir.path(), get_cf_opts_map()).is_err());
+
+    let db = open_db(&tmpdir);
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    db.flush_all(/* sync = */ true).unwrap();
+
+    // The db can be read while it is open for writing, but not written.
+    let readonly_db = DB::open_readonly(&tmpdir.path(), get_cf_opts_map()).unwrap();
+    assert_eq!(
+        readonly_db.get::<TestSchema1>(&TestField(0)).unwrap(),
+        Some(TestField(0)),
+    );
+    assert!(readonly_db
+        .put::<TestSchema1>(&TestField(1), &TestField(1))
+        .is_err());
+}
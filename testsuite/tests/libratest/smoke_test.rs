@@ -322,6 +322,97 @@ fn test_basic_state_synchronization() {
     );
 }
 
+/// Makes the first validator panic at `failpoint` while the others keep committing, then restarts
+/// it and checks that it recovers from its persisted state and catches up.
+fn test_crash_recovery_at_failpoint(failpoint: &str) {
+    let (mut swarm, mut client_proxy) = setup_swarm_and_client_proxy(4, 1);
+    client_proxy.create_next_account(false).unwrap();
+    client_proxy.create_next_account(false).unwrap();
+    client_proxy.mint_coins(&["mb", "0", "100"], true).unwrap();
+
+    let node_to_crash = swarm.get_validators_ids()[0].clone();
+    swarm
+        .get_validator(&node_to_crash)
+        .unwrap()
+        .set_failpoint(failpoint, "panic")
+        .unwrap();
+    client_proxy
+        .transfer_coins(&["tb", "0", "1", "10"], true)
+        .unwrap();
+    assert!(swarm.wait_for_node_crash(&node_to_crash, 60));
+
+    // The remaining 3 validators still form a quorum.
+    for _ in 0..3 {
+        client_proxy
+            .transfer_coins(&["tb", "0", "1", "1"], true)
+            .unwrap();
+    }
+
+    // Failpoints set at runtime do not survive a restart.
+    swarm.kill_node(&node_to_crash);
+    assert!(swarm.add_node(node_to_crash, false).is_ok());
+    assert!(swarm.wait_for_all_nodes_to_catchup());
+
+    client_proxy
+        .transfer_coins(&["tb", "0", "1", "1"], true)
+        .unwrap();
+    assert_eq!(
+        Decimal::from_f64(86.0),
+        Decimal::from_str(&client_proxy.get_balance(&["b", "0"]).unwrap()).ok()
+    );
+    assert_eq!(
+        Decimal::from_f64(14.0),
+        Decimal::from_str(&client_proxy.get_balance(&["b", "1"]).unwrap()).ok()
+    );
+}
+
+#[test]
+fn test_crash_recovery_from_storage_commit() {
+    test_crash_recovery_at_failpoint("libradb::commit");
+}
+
+#[test]
+fn test_crash_recovery_from_consensus_storage() {
+    test_crash_recovery_at_failpoint("consensus::save_tree");
+}
+
+#[test]
+fn test_transient_execution_failure() {
+    // A validator failing to execute a couple of blocks must not stall the others, and must be
+    // able to catch up once execution succeeds again.
+    let (mut swarm, mut client_proxy) = setup_swarm_and_client_proxy(4, 1);
+    let faulty_node = swarm.get_validators_ids()[0].clone();
+    swarm
+        .get_validator(&faulty_node)
+        .unwrap()
+        .set_failpoint("executor::execute_block", "2*return")
+        .unwrap();
+    test_smoke_script(client_proxy);
+    assert!(swarm.wait_for_all_nodes_to_catchup());
+}
+
+#[test]
+fn test_runtime_log_level() {
+    let (swarm, _client_proxy) = setup_swarm_and_client_proxy(1, 0);
+    let validator = swarm.get_validators_ids()[0].clone();
+    let debug_client = swarm.get_validator(&validator).unwrap().debug_client();
+    assert!(debug_client.set_log_level("consensus", "verbose").is_err());
+    debug_client.set_log_level("consensus", "trace").unwrap();
+    let (_, modules) = debug_client.get_log_levels().unwrap();
+    assert_eq!(modules["consensus"], "trace");
+
+    // The pacemaker traces the timeout of every new round.
+    assert!(debug_client
+        .stream_logs()
+        .unwrap()
+        .take(10_000)
+        .any(|line| line.unwrap().contains("Scheduling timeout")));
+
+    debug_client.set_log_level("consensus", "").unwrap();
+    let (_, modules) = debug_client.get_log_levels().unwrap();
+    assert!(!modules.contains_key("consensus"));
+}
+
 #[test]
 fn test_external_transaction_signer() {
     let (_swarm, mut client_proxy) = setup_swarm_and_client_proxy(1, 0);
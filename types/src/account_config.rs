@@ -31,6 +31,10 @@ lazy_static! {
     // Account
     static ref ACCOUNT_MODULE_NAME: Identifier = Identifier::new("LibraAccount").unwrap();
     static ref ACCOUNT_STRUCT_NAME: Identifier = Identifier::new("T").unwrap();
+
+    // Account events
+    static ref SENT_EVENT_NAME: Identifier = Identifier::new("SentPaymentEvent").unwrap();
+    static ref RECEIVED_EVENT_NAME: Identifier = Identifier::new("ReceivedPaymentEvent").unwrap();
 }
 
 pub fn coin_module_name() -> &'static IdentStr {
@@ -49,6 +53,14 @@ pub fn account_struct_name() -> &'static IdentStr {
     &*ACCOUNT_STRUCT_NAME
 }
 
+pub fn sent_event_name() -> &'static IdentStr {
+    &*SENT_EVENT_NAME
+}
+
+pub fn received_event_name() -> &'static IdentStr {
+    &*RECEIVED_EVENT_NAME
+}
+
 pub fn core_code_address() -> AccountAddress {
     AccountAddress::default()
 }
@@ -67,6 +79,26 @@ pub fn account_struct_tag() -> StructTag {
     }
 }
 
+/// The type of the events emitted to the `sent_events` handle of an Account resource.
+pub fn sent_event_struct_tag() -> StructTag {
+    StructTag {
+        address: core_code_address(),
+        module: account_module_name().to_owned(),
+        name: sent_event_name().to_owned(),
+        type_params: vec![],
+    }
+}
+
+/// The type of the events emitted to the `received_events` handle of an Account resource.
+pub fn received_event_struct_tag() -> StructTag {
+    StructTag {
+        address: core_code_address(),
+        module: account_module_name().to_owned(),
+        name: received_event_name().to_owned(),
+        type_params: vec![],
+    }
+}
+
 /// A Rust representation of an Account resource.
 /// This is not how the Account is represented in the VM but it's a convenient representation.
 #[derive(Debug, Default)]
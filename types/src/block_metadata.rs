@@ -0,0 +1,133 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    byte_array::ByteArray,
+    transaction::{RawTransaction, SignedTransaction},
+};
+use canonical_serialization::{
+    CanonicalDeserialize, CanonicalDeserializer, CanonicalSerialize, CanonicalSerializer,
+};
+use crypto::{
+    ed25519::{compat, Ed25519Signature},
+    HashValue,
+};
+use failure::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::TryFrom};
+
+/// Metadata of a block that is persisted on chain before any of the block's transactions run.
+///
+/// Consensus derives this from the block it is about to execute and the execution pipeline
+/// prepends it to the block's transactions as a `TransactionPayload::BlockMetadata` transaction.
+/// The VM executes it by calling `Block.prologue`, which updates the on-chain `Block.T` resource
+/// so that Move modules can read the height, time and proposer of the block they run in.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockMetadata {
+    /// Id of the consensus block this metadata describes.
+    id: HashValue,
+    /// The physical time at which the block was proposed, in microseconds.
+    timestamp_usecs: u64,
+    /// Signatures of the validators that voted for the parent of this block, i.e. the signatures
+    /// in the block's quorum certificate.
+    previous_block_votes: BTreeMap<AccountAddress, Ed25519Signature>,
+    /// Author of the block. NIL blocks have no author and use the default address.
+    proposer: AccountAddress,
+}
+
+impl BlockMetadata {
+    pub fn new(
+        id: HashValue,
+        timestamp_usecs: u64,
+        previous_block_votes: BTreeMap<AccountAddress, Ed25519Signature>,
+        proposer: AccountAddress,
+    ) -> Self {
+        Self {
+            id,
+            timestamp_usecs,
+            previous_block_votes,
+            proposer,
+        }
+    }
+
+    pub fn id(&self) -> HashValue {
+        self.id
+    }
+
+    pub fn timestamp_usecs(&self) -> u64 {
+        self.timestamp_usecs
+    }
+
+    pub fn previous_block_votes(&self) -> &BTreeMap<AccountAddress, Ed25519Signature> {
+        &self.previous_block_votes
+    }
+
+    pub fn proposer(&self) -> AccountAddress {
+        self.proposer
+    }
+
+    /// Returns the addresses of the validators that voted for the parent block, concatenated in
+    /// ascending order. This is the representation `Block.prologue` stores on chain.
+    pub fn voters(&self) -> ByteArray {
+        ByteArray::new(
+            self.previous_block_votes
+                .keys()
+                .flat_map(|address| address.to_vec())
+                .collect(),
+        )
+    }
+
+    /// Wraps this metadata into a transaction that can be executed at the start of a block.
+    ///
+    /// Every validator has to produce a byte-identical transaction for the same block, so the
+    /// transaction is signed with the well-known genesis key rather than a validator key. The
+    /// signature carries no authority: the VM only accepts block metadata transactions while
+    /// executing a block and rejects them on the validation path used by admission control and
+    /// mempool.
+    pub fn into_signed_transaction(self) -> SignedTransaction {
+        let (private_key, public_key) = compat::generate_genesis_keypair();
+        RawTransaction::new_block_metadata(self)
+            .sign(&private_key, public_key)
+            .expect("Signing block metadata with the genesis key should never fail")
+            .into_inner()
+    }
+}
+
+impl CanonicalSerialize for BlockMetadata {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        serializer
+            .encode_bytes(self.id.as_ref())?
+            .encode_u64(self.timestamp_usecs)?
+            .encode_u32(self.previous_block_votes.len() as u32)?;
+        for (voter, signature) in &self.previous_block_votes {
+            serializer
+                .encode_struct(voter)?
+                .encode_bytes(&signature.to_bytes())?;
+        }
+        serializer.encode_struct(&self.proposer)?;
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for BlockMetadata {
+    fn deserialize(deserializer: &mut impl CanonicalDeserializer) -> Result<Self> {
+        let id = HashValue::from_slice(&deserializer.decode_bytes()?)?;
+        let timestamp_usecs = deserializer.decode_u64()?;
+        let num_votes = deserializer.decode_u32()?;
+        let mut previous_block_votes = BTreeMap::new();
+        for _ in 0..num_votes {
+            let voter = deserializer.decode_struct()?;
+            let signature = Ed25519Signature::try_from(&deserializer.decode_bytes()?[..])?;
+            previous_block_votes.insert(voter, signature);
+        }
+        let proposer = deserializer.decode_struct()?;
+
+        Ok(BlockMetadata {
+            id,
+            timestamp_usecs,
+            previous_block_votes,
+            proposer,
+        })
+    }
+}
@@ -4,6 +4,7 @@
 use crate::{
     account_config::AccountEvent,
     event::EventKey,
+    language_storage::TypeTag,
     ledger_info::LedgerInfo,
     proof::{verify_event, EventProof},
     transaction::Version,
@@ -19,22 +20,30 @@ use proptest_derive::Arbitrary;
 use proto_conv::{FromProto, IntoProto};
 
 /// Entry produced via a call to the `emit_event` builtin.
-#[derive(Clone, Default, Eq, PartialEq, FromProto, IntoProto)]
+#[derive(Clone, Eq, PartialEq, FromProto, IntoProto)]
 #[ProtoType(crate::proto::events::Event)]
 pub struct ContractEvent {
     /// The unique key that the event was emitted to
     key: EventKey,
     /// The number of messages that have been emitted to the path previously
     sequence_number: u64,
+    /// The type of the event, as given by the type actual of the `emit_event` call
+    type_tag: TypeTag,
     /// The data payload of the event
     event_data: Vec<u8>,
 }
 
 impl ContractEvent {
-    pub fn new(key: EventKey, sequence_number: u64, event_data: Vec<u8>) -> Self {
+    pub fn new(
+        key: EventKey,
+        sequence_number: u64,
+        type_tag: TypeTag,
+        event_data: Vec<u8>,
+    ) -> Self {
         ContractEvent {
             key,
             sequence_number,
+            type_tag,
             event_data,
         }
     }
@@ -47,6 +56,10 @@ impl ContractEvent {
         self.sequence_number
     }
 
+    pub fn type_tag(&self) -> &TypeTag {
+        &self.type_tag
+    }
+
     pub fn event_data(&self) -> &[u8] {
         &self.event_data
     }
@@ -56,9 +69,10 @@ impl std::fmt::Debug for ContractEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "ContractEvent {{ key: {:?}, index: {:?}, event_data: {:?} }}",
+            "ContractEvent {{ key: {:?}, index: {:?}, type: {:?}, event_data: {:?} }}",
             self.key,
             self.sequence_number,
+            self.type_tag,
             hex::encode(&self.event_data)
         )
     }
@@ -69,8 +83,8 @@ impl std::fmt::Display for ContractEvent {
         if let Ok(payload) = AccountEvent::try_from(&self.event_data) {
             write!(
                 f,
-                "ContractEvent {{ key: {}, index: {:?}, event_data: {:?} }}",
-                self.key, self.sequence_number, payload,
+                "ContractEvent {{ key: {}, index: {:?}, type: {:?}, event_data: {:?} }}",
+                self.key, self.sequence_number, self.type_tag, payload,
             )
         } else {
             write!(f, "{:?}", self)
@@ -83,6 +97,7 @@ impl CanonicalSerialize for ContractEvent {
         serializer
             .encode_struct(&self.key)?
             .encode_u64(self.sequence_number)?
+            .encode_struct(&self.type_tag)?
             .encode_bytes(&self.event_data)?;
         Ok(())
     }
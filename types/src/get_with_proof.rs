@@ -7,17 +7,20 @@ use crate::{
     account_config::get_account_resource_or_default,
     account_state_blob::AccountStateWithProof,
     contract_event::EventWithProof,
+    language_storage::{StructTag, TypeTag},
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     proto::get_with_proof::{
         GetAccountStateRequest, GetAccountStateResponse,
         GetAccountTransactionBySequenceNumberRequest,
         GetAccountTransactionBySequenceNumberResponse, GetEventsByEventAccessPathRequest,
-        GetEventsByEventAccessPathResponse, GetTransactionsRequest, GetTransactionsResponse,
+        GetEventsByEventAccessPathResponse, GetEventsByEventTypeRequest,
+        GetEventsByEventTypeResponse, GetTransactionsRequest, GetTransactionsResponse,
     },
     transaction::{SignedTransactionWithProof, TransactionListWithProof, Version},
     validator_change::ValidatorChangeEventWithProof,
     validator_verifier::ValidatorVerifier,
 };
+use canonical_serialization::{SimpleDeserializer, SimpleSerializer};
 use crypto::{hash::CryptoHash, *};
 use failure::prelude::*;
 #[cfg(any(test, feature = "testing"))]
@@ -197,7 +200,7 @@ fn verify_response_item(
             },
             ResponseItem::GetEventsByEventAccessPath {
                 events_with_proof,
-                proof_of_latest_event,
+                proof_of_event_handle,
             },
         ) => verify_get_events_by_access_path_resp(
             ledger_info,
@@ -206,7 +209,24 @@ fn verify_response_item(
             *ascending,
             *limit,
             events_with_proof,
-            proof_of_latest_event,
+            proof_of_event_handle,
+        ),
+        // GetEventsByEventType
+        (
+            RequestItem::GetEventsByEventType {
+                event_type,
+                start_version,
+                start_event_index,
+                limit,
+            },
+            ResponseItem::GetEventsByEventType { events_with_proof },
+        ) => verify_get_events_by_type_resp(
+            ledger_info,
+            event_type,
+            *start_version,
+            *start_event_index,
+            *limit,
+            events_with_proof,
         ),
         // GetTransactions
         (
@@ -277,6 +297,10 @@ fn verify_get_txn_by_seq_num_resp(
     }
 }
 
+/// The event handle in the proven account state carries the number of events ever emitted to the
+/// access path as of the ledger version, so the exact list of sequence numbers the response must
+/// contain is known. This proves that the server neither omitted events within `limit` nor hid
+/// events beyond the last one returned. A non-existent account has no events.
 fn verify_get_events_by_access_path_resp(
     ledger_info: &LedgerInfo,
     req_access_path: &AccessPath,
@@ -284,11 +308,11 @@ fn verify_get_events_by_access_path_resp(
     req_ascending: bool,
     req_limit: u64,
     events_with_proof: &[EventWithProof],
-    proof_of_latest_event: &AccountStateWithProof,
+    proof_of_event_handle: &AccountStateWithProof,
 ) -> Result<()> {
-    let account_resource = get_account_resource_or_default(&proof_of_latest_event.blob)?;
+    let account_resource = get_account_resource_or_default(&proof_of_event_handle.blob)?;
     let (seq_num_upper_bound, expected_event_key) = {
-        proof_of_latest_event.verify(
+        proof_of_event_handle.verify(
             ledger_info,
             ledger_info.version(),
             req_access_path.address,
@@ -340,6 +364,59 @@ fn verify_get_events_by_access_path_resp(
     Ok(())
 }
 
+/// Verifies that each event returned exists, is of the requested type and that the page is in
+/// order. The ledger has no accumulator per event type, so the response is untrusted in that the
+/// client relies on the server for not skipping any event of the type.
+fn verify_get_events_by_type_resp(
+    ledger_info: &LedgerInfo,
+    req_event_type: &StructTag,
+    req_start_version: Version,
+    req_start_event_index: u64,
+    req_limit: u64,
+    events_with_proof: &[EventWithProof],
+) -> Result<()> {
+    ensure!(
+        events_with_proof.len() as u64 <= req_limit,
+        "Expecting at most {} events, got {}.",
+        req_limit,
+        events_with_proof.len(),
+    );
+
+    let req_type_tag = TypeTag::Struct(req_event_type.clone());
+    let mut prev_position = None;
+    for e in events_with_proof {
+        ensure!(
+            *e.event.type_tag() == req_type_tag,
+            "Event at version {} index {} is of type {:?}, expecting {:?}.",
+            e.transaction_version,
+            e.event_index,
+            e.event.type_tag(),
+            req_type_tag,
+        );
+        let position = (e.transaction_version, e.event_index);
+        let in_order = match prev_position {
+            Some(prev) => position > prev,
+            None => position >= (req_start_version, req_start_event_index),
+        };
+        ensure!(
+            in_order,
+            "Event at version {} index {} is out of order.",
+            e.transaction_version,
+            e.event_index,
+        );
+        e.verify(
+            ledger_info,
+            e.event.key(),
+            e.event.sequence_number(),
+            e.transaction_version,
+            e.event_index,
+        )?;
+        prev_position = Some(position);
+    }
+
+    Ok(())
+}
+
 fn verify_get_txns_resp(
     ledger_info: &LedgerInfo,
     req_start_version: Version,
@@ -393,6 +470,12 @@ pub enum RequestItem {
         limit: u64,
         fetch_events: bool,
     },
+    GetEventsByEventType {
+        event_type: StructTag,
+        start_version: Version,
+        start_event_index: u64,
+        limit: u64,
+    },
 }
 
 impl FromProto for RequestItem {
@@ -439,6 +522,19 @@ impl FromProto for RequestItem {
                 limit,
                 fetch_events,
             }
+        } else if object.has_get_events_by_event_type_request() {
+            let req = object.get_get_events_by_event_type_request();
+            let event_type = SimpleDeserializer::deserialize(req.get_event_type())?;
+            let start_version = req.get_start_version();
+            let start_event_index = req.get_start_event_index();
+            let limit = req.get_limit();
+
+            RequestItem::GetEventsByEventType {
+                event_type,
+                start_version,
+                start_event_index,
+                limit,
+            }
         } else {
             bail!("Unknown RequestItem type.")
         })
@@ -494,6 +590,23 @@ impl IntoProto for RequestItem {
 
                 out.set_get_transactions_request(req);
             }
+            RequestItem::GetEventsByEventType {
+                event_type,
+                start_version,
+                start_event_index,
+                limit,
+            } => {
+                let mut req = GetEventsByEventTypeRequest::new();
+                req.set_event_type(
+                    SimpleSerializer::<Vec<u8>>::serialize(&event_type)
+                        .expect("Struct tag serialization should not fail."),
+                );
+                req.set_start_version(start_version);
+                req.set_start_event_index(start_event_index);
+                req.set_limit(limit);
+
+                out.set_get_events_by_event_type_request(req);
+            }
         }
         out
     }
@@ -513,12 +626,14 @@ pub enum ResponseItem {
     },
     GetEventsByEventAccessPath {
         events_with_proof: Vec<EventWithProof>,
-        // TODO: Rename this field to proof_of_event_handle.
-        proof_of_latest_event: AccountStateWithProof,
+        proof_of_event_handle: AccountStateWithProof,
     },
     GetTransactions {
         txn_list_with_proof: TransactionListWithProof,
     },
+    GetEventsByEventType {
+        events_with_proof: Vec<EventWithProof>,
+    },
 }
 
 impl ResponseItem {
@@ -555,12 +670,19 @@ impl ResponseItem {
         match self {
             ResponseItem::GetEventsByEventAccessPath {
                 events_with_proof,
-                proof_of_latest_event,
-            } => Ok((events_with_proof, proof_of_latest_event)),
+                proof_of_event_handle,
+            } => Ok((events_with_proof, proof_of_event_handle)),
             _ => bail!("Not ResponseItem::GetEventsByEventAccessPath."),
         }
     }
 
+    pub fn into_get_events_by_type_response(self) -> Result<Vec<EventWithProof>> {
+        match self {
+            ResponseItem::GetEventsByEventType { events_with_proof } => Ok(events_with_proof),
+            _ => bail!("Not ResponseItem::GetEventsByEventType."),
+        }
+    }
+
     pub fn into_get_transactions_response(self) -> Result<TransactionListWithProof> {
         match self {
             ResponseItem::GetTransactions {
@@ -611,12 +733,12 @@ impl FromProto for ResponseItem {
                 .map(EventWithProof::from_proto)
                 .collect::<Result<Vec<_>>>()?;
 
-            let proof_of_latest_event =
-                AccountStateWithProof::from_proto(res.take_proof_of_latest_event())?;
+            let proof_of_event_handle =
+                AccountStateWithProof::from_proto(res.take_proof_of_event_handle())?;
 
             ResponseItem::GetEventsByEventAccessPath {
                 events_with_proof,
-                proof_of_latest_event,
+                proof_of_event_handle,
             }
         } else if object.has_get_transactions_response() {
             let mut res = object.take_get_transactions_response();
@@ -626,6 +748,16 @@ impl FromProto for ResponseItem {
             ResponseItem::GetTransactions {
                 txn_list_with_proof,
             }
+        } else if object.has_get_events_by_event_type_response() {
+            let mut res = object.take_get_events_by_event_type_response();
+
+            let events_with_proof = res
+                .take_events_with_proof()
+                .into_iter()
+                .map(EventWithProof::from_proto)
+                .collect::<Result<Vec<_>>>()?;
+
+            ResponseItem::GetEventsByEventType { events_with_proof }
         } else {
             bail!("Unknown ResponseItem type.")
         })
@@ -663,7 +795,7 @@ impl IntoProto for ResponseItem {
             }
             ResponseItem::GetEventsByEventAccessPath {
                 events_with_proof,
-                proof_of_latest_event,
+                proof_of_event_handle,
             } => {
                 let mut res = GetEventsByEventAccessPathResponse::new();
                 res.set_events_with_proof(::protobuf::RepeatedField::from_vec(
@@ -672,7 +804,7 @@ impl IntoProto for ResponseItem {
                         .map(EventWithProof::into_proto)
                         .collect(),
                 ));
-                res.set_proof_of_latest_event(proof_of_latest_event.into_proto());
+                res.set_proof_of_event_handle(proof_of_event_handle.into_proto());
 
                 out.set_get_events_by_event_access_path_response(res);
             }
@@ -684,6 +816,17 @@ impl IntoProto for ResponseItem {
 
                 out.set_get_transactions_response(res)
             }
+            ResponseItem::GetEventsByEventType { events_with_proof } => {
+                let mut res = GetEventsByEventTypeResponse::new();
+                res.set_events_with_proof(::protobuf::RepeatedField::from_vec(
+                    events_with_proof
+                        .into_iter()
+                        .map(EventWithProof::into_proto)
+                        .collect(),
+                ));
+
+                out.set_get_events_by_event_type_response(res);
+            }
         }
         out
     }
@@ -9,21 +9,36 @@ use crate::{
 };
 use canonical_serialization::{
     CanonicalDeserialize, CanonicalDeserializer, CanonicalSerialize, CanonicalSerializer,
-    SimpleSerializer,
+    SimpleDeserializer, SimpleSerializer,
 };
 use crypto::hash::{AccessPathHasher, CryptoHash, CryptoHasher, HashValue};
-use failure::Result;
+use failure::prelude::*;
 #[cfg(any(test, feature = "testing"))]
 use proptest_derive::Arbitrary;
 use proto_conv::{FromProto, IntoProto};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Hash, Eq, Clone, PartialOrd, Ord)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
 pub struct StructTag {
     pub address: AccountAddress,
     pub module: Identifier,
     pub name: Identifier,
-    pub type_params: Vec<StructTag>,
+    // Recursive type parameters are left empty when generating arbitrary tags.
+    #[cfg_attr(any(test, feature = "testing"), proptest(value = "vec![]"))]
+    pub type_params: Vec<TypeTag>,
+}
+
+/// The type of a Move value, such as the type actual of a generic function.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Hash, Eq, Clone, PartialOrd, Ord)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub enum TypeTag {
+    Bool,
+    U64,
+    String,
+    ByteArray,
+    Address,
+    Struct(StructTag),
 }
 
 /// Represents the intitial key into global storage where we first index by the address, and then
@@ -127,7 +142,7 @@ impl CanonicalDeserialize for StructTag {
         let address = deserializer.decode_struct::<AccountAddress>()?;
         let module = deserializer.decode_struct::<Identifier>()?;
         let name = deserializer.decode_struct::<Identifier>()?;
-        let type_params = deserializer.decode_vec::<StructTag>()?;
+        let type_params = deserializer.decode_vec::<TypeTag>()?;
         Ok(Self {
             address,
             name,
@@ -146,3 +161,79 @@ impl CryptoHash for StructTag {
         state.finish()
     }
 }
+
+impl CanonicalSerialize for TypeTag {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        match self {
+            TypeTag::Bool => serializer.encode_u32(TypeTagType::Bool as u32)?,
+            TypeTag::U64 => serializer.encode_u32(TypeTagType::U64 as u32)?,
+            TypeTag::String => serializer.encode_u32(TypeTagType::String as u32)?,
+            TypeTag::ByteArray => serializer.encode_u32(TypeTagType::ByteArray as u32)?,
+            TypeTag::Address => serializer.encode_u32(TypeTagType::Address as u32)?,
+            TypeTag::Struct(struct_tag) => {
+                serializer.encode_u32(TypeTagType::Struct as u32)?;
+                serializer.encode_struct(struct_tag)?
+            }
+        };
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for TypeTag {
+    fn deserialize(deserializer: &mut impl CanonicalDeserializer) -> Result<Self> {
+        let decoded_type_tag_type = deserializer.decode_u32()?;
+        let type_tag_type = TypeTagType::from_u32(decoded_type_tag_type);
+        match type_tag_type {
+            Some(TypeTagType::Bool) => Ok(TypeTag::Bool),
+            Some(TypeTagType::U64) => Ok(TypeTag::U64),
+            Some(TypeTagType::String) => Ok(TypeTag::String),
+            Some(TypeTagType::ByteArray) => Ok(TypeTag::ByteArray),
+            Some(TypeTagType::Address) => Ok(TypeTag::Address),
+            Some(TypeTagType::Struct) => Ok(TypeTag::Struct(deserializer.decode_struct()?)),
+            None => Err(format_err!(
+                "ParseError: Unable to decode TypeTagType, found {}",
+                decoded_type_tag_type
+            )),
+        }
+    }
+}
+
+impl FromProto for TypeTag {
+    type ProtoType = Vec<u8>;
+
+    fn from_proto(type_tag: Self::ProtoType) -> Result<Self> {
+        SimpleDeserializer::deserialize(&type_tag)
+    }
+}
+
+impl IntoProto for TypeTag {
+    type ProtoType = Vec<u8>;
+
+    fn into_proto(self) -> Self::ProtoType {
+        SimpleSerializer::<Vec<u8>>::serialize(&self).expect("Failed to serialize.")
+    }
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+enum TypeTagType {
+    Bool = 0,
+    U64 = 1,
+    String = 2,
+    ByteArray = 3,
+    Address = 4,
+    Struct = 5,
+}
+
+impl TypeTagType {
+    fn from_u32(value: u32) -> Option<TypeTagType> {
+        match value {
+            0 => Some(TypeTagType::Bool),
+            1 => Some(TypeTagType::U64),
+            2 => Some(TypeTagType::String),
+            3 => Some(TypeTagType::ByteArray),
+            4 => Some(TypeTagType::Address),
+            5 => Some(TypeTagType::Struct),
+            _ => None,
+        }
+    }
+}
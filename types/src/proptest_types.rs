@@ -4,12 +4,14 @@
 use crate::{
     access_path::AccessPath,
     account_address::AccountAddress,
-    account_config::AccountResource,
+    account_config::{received_event_struct_tag, sent_event_struct_tag, AccountResource},
     account_state_blob::AccountStateBlob,
+    block_metadata::BlockMetadata,
     byte_array::ByteArray,
     contract_event::ContractEvent,
     event::{EventHandle, EventKey},
     get_with_proof::{ResponseItem, UpdateToLatestLedgerResponse},
+    language_storage::TypeTag,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     proof::AccumulatorProof,
     transaction::{
@@ -268,6 +270,10 @@ fn new_raw_transaction(
             // not used, but it isn't a huge deal.
             RawTransaction::new_write_set(sender, sequence_number, write_set)
         }
+        TransactionPayload::BlockMetadata(block_metadata) => {
+            // Block metadata transactions are always sent by the block proposer.
+            RawTransaction::new_block_metadata(block_metadata)
+        }
     }
 }
 
@@ -394,6 +400,34 @@ impl TransactionPayload {
     pub fn genesis_strategy() -> impl Strategy<Value = Self> {
         WriteSet::genesis_strategy().prop_map(TransactionPayload::WriteSet)
     }
+
+    pub fn block_metadata_strategy() -> impl Strategy<Value = Self> {
+        any::<BlockMetadata>().prop_map(TransactionPayload::BlockMetadata)
+    }
+}
+
+prop_compose! {
+    fn arb_block_metadata()(
+        id in any::<HashValue>(),
+        timestamp_usecs in any::<u64>(),
+        voters in vec((any::<AccountAddress>(), keypair_strategy()), 0..4),
+        proposer in any::<AccountAddress>(),
+    ) -> BlockMetadata {
+        let previous_block_votes = voters
+            .into_iter()
+            .map(|(voter, (private_key, _))| (voter, private_key.sign_message(&id)))
+            .collect();
+        BlockMetadata::new(id, timestamp_usecs, previous_block_votes, proposer)
+    }
+}
+
+impl Arbitrary for BlockMetadata {
+    type Parameters = ();
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        arb_block_metadata().boxed()
+    }
+
+    type Strategy = BoxedStrategy<Self>;
 }
 
 /// The `Arbitrary` impl only generates validation statuses since the full enum is too large.
@@ -420,6 +454,7 @@ impl Arbitrary for StatusCode {
             Just(StatusCode::MAX_GAS_UNITS_BELOW_MIN_TRANSACTION_GAS_UNITS),
             Just(StatusCode::GAS_UNIT_PRICE_BELOW_MIN_BOUND),
             Just(StatusCode::GAS_UNIT_PRICE_ABOVE_MAX_BOUND),
+            Just(StatusCode::REJECTED_BLOCK_METADATA),
         ]
         .boxed()
     }
@@ -511,6 +546,8 @@ impl Arbitrary for TransactionArgument {
             any::<AccountAddress>().prop_map(TransactionArgument::Address),
             any::<ByteArray>().prop_map(TransactionArgument::ByteArray),
             ".*".prop_map(TransactionArgument::String),
+            any::<bool>().prop_map(TransactionArgument::Bool),
+            vec(any::<AccountAddress>(), 0..10).prop_map(TransactionArgument::AddressVector),
         ]
         .boxed()
     }
@@ -584,16 +621,24 @@ impl ContractEventGen {
         universe: &mut AccountInfoUniverse,
     ) -> ContractEvent {
         let account_info = universe.get_account_info_mut(account_index);
-        let event_handle = if self.use_sent_key {
-            &mut account_info.sent_event_handle
+        let (event_handle, event_type) = if self.use_sent_key {
+            (&mut account_info.sent_event_handle, sent_event_struct_tag())
         } else {
-            &mut account_info.received_event_handle
+            (
+                &mut account_info.received_event_handle,
+                received_event_struct_tag(),
+            )
         };
         let sequence_number = event_handle.count();
         *event_handle.count_mut() += 1;
         let event_key = event_handle.key();
 
-        ContractEvent::new(*event_key, sequence_number, self.payload)
+        ContractEvent::new(
+            *event_key,
+            sequence_number,
+            TypeTag::Struct(event_type),
+            self.payload,
+        )
     }
 }
 
@@ -644,9 +689,15 @@ impl ContractEvent {
     pub fn strategy_impl(
         event_key_strategy: impl Strategy<Value = EventKey>,
     ) -> impl Strategy<Value = Self> {
-        (event_key_strategy, any::<u64>(), vec(any::<u8>(), 1..10)).prop_map(
-            |(event_key, seq_num, event_data)| ContractEvent::new(event_key, seq_num, event_data),
+        (
+            event_key_strategy,
+            any::<u64>(),
+            any::<TypeTag>(),
+            vec(any::<u8>(), 1..10),
         )
+            .prop_map(|(event_key, seq_num, type_tag, event_data)| {
+                ContractEvent::new(event_key, seq_num, type_tag, event_data)
+            })
     }
 }
 
@@ -4,6 +4,7 @@
 use crate::{
     account_address::AccountAddress,
     account_state_blob::AccountStateBlob,
+    block_metadata::BlockMetadata,
     contract_event::ContractEvent,
     ledger_info::LedgerInfo,
     proof::{
@@ -159,6 +160,22 @@ impl RawTransaction {
         }
     }
 
+    /// Create a new `RawTransaction` carrying the metadata of a block.
+    ///
+    /// These transactions are produced by the execution pipeline at the start of every block and
+    /// are never submitted by clients. See [`BlockMetadata`] for details.
+    pub fn new_block_metadata(block_metadata: BlockMetadata) -> Self {
+        RawTransaction {
+            sender: block_metadata.proposer(),
+            sequence_number: 0,
+            payload: TransactionPayload::BlockMetadata(block_metadata),
+            // Block metadata transactions are not charged for gas.
+            max_gas_amount: 0,
+            gas_unit_price: 0,
+            expiration_time: Duration::new(u64::max_value(), 0),
+        }
+    }
+
     /// Signs the given `RawTransaction`. Note that this consumes the `RawTransaction` and turns it
     /// into a `SignatureCheckedTransaction`.
     ///
@@ -189,6 +206,7 @@ impl RawTransaction {
                 (get_transaction_name(script.code()), script.args())
             }
             TransactionPayload::Module(_) => ("module publishing".to_string(), &empty_vec[..]),
+            TransactionPayload::BlockMetadata(_) => ("block metadata".to_string(), &empty_vec[..]),
         };
         let mut f_args: String = "".to_string();
         for arg in args {
@@ -277,6 +295,8 @@ pub enum TransactionPayload {
     Module(Module),
     /// A transaction that executes code.
     Script(Script),
+    /// A transaction that records the metadata of the block it starts.
+    BlockMetadata(BlockMetadata),
 }
 
 impl CanonicalSerialize for TransactionPayload {
@@ -298,6 +318,10 @@ impl CanonicalSerialize for TransactionPayload {
                 serializer.encode_u32(TransactionPayloadType::Module as u32)?;
                 serializer.encode_struct(module)?;
             }
+            TransactionPayload::BlockMetadata(block_metadata) => {
+                serializer.encode_u32(TransactionPayloadType::BlockMetadata as u32)?;
+                serializer.encode_struct(block_metadata)?;
+            }
         };
         Ok(())
     }
@@ -320,6 +344,9 @@ impl CanonicalDeserialize for TransactionPayload {
             Some(TransactionPayloadType::Module) => {
                 Ok(TransactionPayload::Module(deserializer.decode_struct()?))
             }
+            Some(TransactionPayloadType::BlockMetadata) => Ok(TransactionPayload::BlockMetadata(
+                deserializer.decode_struct()?,
+            )),
             None => Err(format_err!(
                 "ParseError: Unable to decode TransactionPayloadType, found {}",
                 decoded_payload_type
@@ -334,6 +361,7 @@ enum TransactionPayloadType {
     WriteSet = 1,
     Script = 2,
     Module = 3,
+    BlockMetadata = 4,
 }
 
 impl TransactionPayloadType {
@@ -343,6 +371,7 @@ impl TransactionPayloadType {
             1 => Some(TransactionPayloadType::WriteSet),
             2 => Some(TransactionPayloadType::Script),
             3 => Some(TransactionPayloadType::Module),
+            4 => Some(TransactionPayloadType::BlockMetadata),
             _ => None,
         }
     }
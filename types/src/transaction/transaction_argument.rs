@@ -20,6 +20,10 @@ pub enum TransactionArgument {
     Address(AccountAddress),
     ByteArray(ByteArray),
     String(String),
+    Bool(bool),
+    /// A `Vector.T<address>`. Note that `vector<u8>` arguments are passed as `ByteArray`, which is
+    /// the VM's byte vector type.
+    AddressVector(Vec<AccountAddress>),
 }
 
 impl fmt::Debug for TransactionArgument {
@@ -31,6 +35,10 @@ impl fmt::Debug for TransactionArgument {
             TransactionArgument::ByteArray(byte_array) => {
                 write!(f, "{{ByteArray: 0x{}}}", byte_array)
             }
+            TransactionArgument::Bool(boolean) => write!(f, "{{BOOL: {}}}", boolean),
+            TransactionArgument::AddressVector(addresses) => {
+                write!(f, "{{ADDRESS_VECTOR: {:?}}}", addresses)
+            }
         }
     }
 }
@@ -43,6 +51,10 @@ pub enum ErrorKind {
 
 /// Parses the given string as address.
 pub fn parse_as_address(s: &str) -> Result<TransactionArgument> {
+    Ok(TransactionArgument::Address(parse_address(s)?))
+}
+
+fn parse_address(s: &str) -> Result<AccountAddress> {
     let mut s = s.to_ascii_lowercase();
     if !s.starts_with("0x") {
         return Err(ErrorKind::ParseError("address must start with '0x'".to_string()).into());
@@ -63,9 +75,32 @@ pub fn parse_as_address(s: &str) -> Result<TransactionArgument> {
             .chain(addr.into_iter())
             .collect();
     }
-    Ok(TransactionArgument::Address(AccountAddress::try_from(
-        addr,
-    )?))
+    AccountAddress::try_from(addr)
+}
+
+/// Parses the given string as a vector of addresses, written as `[0x1,0x2]`.
+///
+/// The elements are separated by commas without spaces, so that the vector can be passed as a
+/// single whitespace-delimited argument.
+pub fn parse_as_address_vector(s: &str) -> Result<TransactionArgument> {
+    if !s.starts_with('[') || !s.ends_with(']') || s.len() < 2 {
+        return Err(ErrorKind::ParseError(format!("\"{}\" is not a vector", s)).into());
+    }
+    let elements = &s[1..s.len() - 1];
+    let addresses = if elements.is_empty() {
+        vec![]
+    } else {
+        elements
+            .split(',')
+            .map(parse_address)
+            .collect::<Result<_>>()?
+    };
+    Ok(TransactionArgument::AddressVector(addresses))
+}
+
+/// Parses the given string as bool.
+pub fn parse_as_bool(s: &str) -> Result<TransactionArgument> {
+    Ok(TransactionArgument::Bool(s.parse::<bool>()?))
 }
 
 /// Parses the given string as bytearray.
@@ -106,6 +141,8 @@ pub fn parse_as_transaction_argument(s: &str) -> Result<TransactionArgument> {
     return_if_ok!(parse_as_address(s));
     return_if_ok!(parse_as_u64(s));
     return_if_ok!(parse_as_byte_array(s));
+    return_if_ok!(parse_as_bool(s));
+    return_if_ok!(parse_as_address_vector(s));
     Err(ErrorKind::ParseError(format!("cannot parse \"{}\" as transaction argument", s)).into())
 }
 
@@ -158,9 +195,40 @@ mod test_transaction_argument {
         }
     }
 
+    #[test]
+    fn parse_bool() {
+        assert_eq!(
+            parse_as_bool("true").unwrap(),
+            TransactionArgument::Bool(true)
+        );
+        assert_eq!(
+            parse_as_bool("false").unwrap(),
+            TransactionArgument::Bool(false)
+        );
+
+        for s in &["", "0", "True", "yes"] {
+            parse_as_bool(s).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn parse_address_vector() {
+        assert_eq!(
+            parse_as_address_vector("[]").unwrap(),
+            TransactionArgument::AddressVector(vec![])
+        );
+        for s in &["[0x1]", "[0x1,0x2]", "[0xA,0x00,0x100]"] {
+            parse_as_address_vector(s).unwrap();
+        }
+
+        for s in &["", "[", "0x1", "[0x1,]", "[0x1, 0x2]", "[1,2]", "[0xG]"] {
+            parse_as_address_vector(s).unwrap_err();
+        }
+    }
+
     #[test]
     fn parse_args() {
-        for s in &["123", "0xf", "b\"aaa\""] {
+        for s in &["123", "0xf", "b\"aaa\"", "true", "[0x1,0x2]"] {
             parse_as_transaction_argument(s).unwrap();
         }
 
@@ -189,6 +257,14 @@ impl CanonicalSerialize for TransactionArgument {
                 serializer.encode_u32(TransactionArgument_ArgType::BYTEARRAY as u32)?;
                 serializer.encode_struct(byte_array)?;
             }
+            TransactionArgument::Bool(boolean) => {
+                serializer.encode_u32(TransactionArgument_ArgType::BOOL as u32)?;
+                serializer.encode_bool(*boolean)?;
+            }
+            TransactionArgument::AddressVector(addresses) => {
+                serializer.encode_u32(TransactionArgument_ArgType::ADDRESS_VECTOR as u32)?;
+                serializer.encode_vec(addresses)?;
+            }
         }
 
         Ok(())
@@ -212,6 +288,12 @@ impl CanonicalDeserialize for TransactionArgument {
             Some(TransactionArgument_ArgType::BYTEARRAY) => Ok(TransactionArgument::ByteArray(
                 deserializer.decode_struct()?,
             )),
+            Some(TransactionArgument_ArgType::BOOL) => {
+                Ok(TransactionArgument::Bool(deserializer.decode_bool()?))
+            }
+            Some(TransactionArgument_ArgType::ADDRESS_VECTOR) => Ok(
+                TransactionArgument::AddressVector(deserializer.decode_vec()?),
+            ),
             None => Err(format_err!(
                 "ParseError: Unable to decode TransactionArgument_ArgType, found {}",
                 decoded_value
@@ -0,0 +1,38 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block_metadata::BlockMetadata,
+    transaction::{SignedTransaction, TransactionPayload},
+};
+use canonical_serialization::{
+    CanonicalDeserializer, CanonicalSerializer, SimpleDeserializer, SimpleSerializer,
+};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn block_metadata_roundtrip_canonical_serialization(block_metadata in any::<BlockMetadata>()) {
+        let mut serializer = SimpleSerializer::<Vec<u8>>::new();
+        serializer.encode_struct(&block_metadata).unwrap();
+        let serialized_bytes = serializer.get_output();
+
+        let mut deserializer = SimpleDeserializer::new(&serialized_bytes);
+        let output: BlockMetadata = deserializer.decode_struct().unwrap();
+        assert_eq!(block_metadata, output);
+    }
+
+    #[test]
+    fn block_metadata_transaction_is_deterministic(block_metadata in any::<BlockMetadata>()) {
+        let txn1 = block_metadata.clone().into_signed_transaction();
+        let txn2 = block_metadata.clone().into_signed_transaction();
+        assert_eq!(txn1, txn2);
+        assert_eq!(txn1.sender(), block_metadata.proposer());
+        assert_eq!(txn1.payload(), &TransactionPayload::BlockMetadata(block_metadata));
+
+        let serialized_bytes = SimpleSerializer::<Vec<u8>>::serialize(&txn1).unwrap();
+        let output: SignedTransaction = SimpleDeserializer::deserialize(&serialized_bytes).unwrap();
+        assert_eq!(txn1, output);
+        assert!(output.check_signature().is_ok());
+    }
+}
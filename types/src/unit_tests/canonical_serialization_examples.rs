@@ -203,6 +203,15 @@ fn test_transaction_argument_byte_array_canonical_serialization_example() {
     assert_eq!(expected_output, actual_output);
 }
 
+#[test]
+fn test_transaction_argument_bool_canonical_serialization_example() {
+    let input = TransactionArgument::Bool(true);
+    let expected_output: Vec<u8> = vec![0x04, 0x00, 0x00, 0x00, 0x01];
+
+    let actual_output = SimpleSerializer::<Vec<u8>>::serialize(&input).unwrap();
+    assert_eq!(expected_output, actual_output);
+}
+
 #[test]
 fn test_transaction_argument_string_canonical_serialization_example() {
     let input = TransactionArgument::String("Hello, World!".to_string());
@@ -1,4 +1,4 @@
-use crate::language_storage::ModuleId;
+use crate::language_storage::{ModuleId, TypeTag};
 use canonical_serialization::test_helper::assert_canonical_encode_decode;
 use proptest::prelude::*;
 use proto_conv::test_helper::assert_protobuf_encode_decode;
@@ -13,4 +13,9 @@ proptest! {
     fn test_module_id_canonical_roundtrip(module_id in any::<ModuleId>()) {
         assert_canonical_encode_decode(&module_id);
     }
+
+    #[test]
+    fn test_type_tag_canonical_roundtrip(type_tag in any::<TypeTag>()) {
+        assert_canonical_encode_decode(&type_tag);
+    }
 }
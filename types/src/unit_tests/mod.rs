@@ -3,6 +3,7 @@
 
 mod access_path_test;
 mod address_test;
+mod block_metadata_test;
 mod canonical_serialization_examples;
 mod contract_event_proto_conversion_test;
 mod get_with_proof_proto_conversion_test;
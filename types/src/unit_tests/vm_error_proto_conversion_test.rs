@@ -1,7 +1,12 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::vm_error::{StatusCode, VMStatus};
+use crate::{
+    account_config,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    vm_error::{StatusCode, VMStatus},
+};
 use proto_conv::test_helper::assert_protobuf_encode_decode_non_message;
 use std::convert::TryFrom;
 
@@ -21,3 +26,15 @@ fn status_roundtrip() {
         assert_protobuf_encode_decode_non_message(&status);
     }
 }
+
+#[test]
+fn abort_status_roundtrip() {
+    let module = ModuleId::new(
+        account_config::core_code_address(),
+        Identifier::new("LibraAccount").unwrap(),
+    );
+    let status = VMStatus::new(StatusCode::ABORTED)
+        .with_sub_status(7)
+        .with_abort_module(module);
+    assert_protobuf_encode_decode_non_message(&status);
+}
@@ -3,6 +3,7 @@
 
 #![allow(clippy::unit_arg)]
 
+use crate::language_storage::ModuleId;
 use failure::prelude::*;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 #[cfg(any(test, feature = "testing"))]
@@ -59,6 +60,10 @@ pub struct VMStatus {
     /// The optional message. Useful for verification errors, and for returning information in
     /// validation.
     pub message: Option<String>,
+
+    /// The module whose code raised the status. Only set for `ABORTED` statuses raised by an
+    /// abort, so that the meaning of the abort code can be looked up.
+    pub abort_module: Option<ModuleId>,
 }
 
 /// A status type is one of 5 different variants, along with a fallback variant in the case that we
@@ -96,6 +101,15 @@ impl fmt::Display for VMStatus {
             status = format!("{} with sub status {}", status, sub_status);
         }
 
+        if let Some(ref module) = self.abort_module {
+            status = format!(
+                "{} in module 0x{}.{}",
+                status,
+                module.address(),
+                module.name()
+            );
+        }
+
         if let Some(ref msg) = self.message {
             status = format!("{} and message {}", status, msg);
         }
@@ -113,6 +127,7 @@ impl VMStatus {
             major_status,
             sub_status: None,
             message: None,
+            abort_module: None,
         }
     }
 
@@ -128,6 +143,12 @@ impl VMStatus {
         self
     }
 
+    /// Adds the module that aborted to the VM status.
+    pub fn with_abort_module(mut self, abort_module: ModuleId) -> Self {
+        self.abort_module = Some(abort_module);
+        self
+    }
+
     /// Mutates the VMStatus sub status field to be the new `sub_status` passed in.
     pub fn set_sub_status(&mut self, sub_status: u64) {
         self.sub_status = Some(sub_status);
@@ -224,6 +245,11 @@ impl IntoProto for VMStatus {
             proto_status.set_message(string);
         }
 
+        // Set the aborting module if there is one
+        if let Some(module) = self.abort_module {
+            proto_status.set_abort_module(module.into_proto());
+        }
+
         proto_status
     }
 }
@@ -242,6 +268,10 @@ impl FromProto for VMStatus {
             status.set_message(proto_status.take_message());
         }
 
+        if proto_status.has_abort_module() {
+            status.abort_module = Some(ModuleId::from_proto(proto_status.take_abort_module())?);
+        }
+
         Ok(status)
     }
 }
@@ -324,6 +354,9 @@ pub enum StatusCode {
     // Gas unit price submitted with the transaction is above the maximum
     // gas price set in the VM.
     GAS_UNIT_PRICE_ABOVE_MAX_BOUND = 16,
+    // A block metadata transaction was submitted outside of block execution. These transactions
+    // are only ever created by the execution pipeline.
+    REJECTED_BLOCK_METADATA = 17,
 
     // When a code module/script is published it is verified. These are the
     // possible errors that can arise from the verification process.